@@ -0,0 +1,209 @@
+//! Simulates hundreds of rules finishing at once against the automation database
+//! while the dashboard keeps listing rules and logs.
+//!
+//! cargo bench --features ssr --bench database_throughput -- [--rules N] [--runs N]
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use torbox_companion::automation::revisions::RevisionSource;
+use torbox_companion::automation::types::*;
+use torbox_companion::automation::encryption::MasterKey;
+use torbox_companion::automation::Database;
+
+const API_KEYS: usize = 20;
+const DASHBOARD_CLIENTS: usize = 20;
+const DASHBOARD_REQUESTS: usize = 50;
+
+struct Report {
+    elapsed: Duration,
+    writes: usize,
+    reads: usize,
+    dashboard_latencies: Vec<Duration>,
+}
+
+fn arg(name: &str, default: usize) -> usize {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|index| args.get(index + 1))
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+fn rule(api_key_hash: &str, index: usize) -> AutomationRule {
+    AutomationRule {
+        id: None,
+        api_key_hash: api_key_hash.to_string(),
+        name: format!("Benchmark rule {}", index),
+        enabled: true,
+        download_types: default_download_types(),
+        trigger_config: TriggerConfig::Interval { minutes: 30 },
+        conditions: ConditionNode::Condition(Condition {
+            r#type: ConditionType::SeedingTime,
+            operator: Operator::GreaterThan,
+            value: 24.0,
+        }),
+        action_config: ActionConfig {
+            steps: vec![ActionStep {
+                action_type: ActionType::StopSeeding,
+                params: None,
+                on_failure: FailurePolicy::default(),
+            }],
+        },
+        notification: None,
+        schedule_window: None,
+        quota: None,
+        cooldown: None,
+        revision: None,
+        created_at: None,
+        updated_at: None,
+    }
+}
+
+fn execution_log(rule: &AutomationRule) -> ExecutionLog {
+    let processed_items: Vec<ProcessedItem> = (0..10)
+        .map(|id| ProcessedItem {
+            id,
+            download_type: Some(DownloadType::Torrent),
+            name: format!("Download {}", id),
+            action: "Stop Seeding".to_string(),
+            success: true,
+            error: None,
+            steps: Vec::new(),
+            size: Some(1_073_741_824),
+            hash: None,
+        })
+        .collect();
+
+    ExecutionLog {
+        id: None,
+        rule_id: rule.id.unwrap_or(0),
+        rule_name: rule.name.clone(),
+        api_key_hash: rule.api_key_hash.clone(),
+        execution_type: "scheduled".to_string(),
+        items_processed: processed_items.len() as i32,
+        total_items: Some(processed_items.len() as i32),
+        success: true,
+        error_message: None,
+        processed_items: Some(processed_items),
+        executed_at: None,
+        partial: Some(false),
+        rule_revision: rule.revision,
+    }
+}
+
+async fn run(read_connections: usize, rule_count: usize, runs_per_rule: usize) -> Result<Report, String> {
+    let dir = std::env::temp_dir().join(format!("torbox-db-bench-{}-{}", std::process::id(), read_connections));
+    let _ = std::fs::remove_dir_all(&dir);
+    let master_key = MasterKey::from_hex(&"42".repeat(32))?;
+    let database = Arc::new(Database::new(dir.join("torbox.db"), read_connections, Some(master_key)).await?);
+
+    let mut api_key_hashes = Vec::new();
+    for index in 0..API_KEYS {
+        api_key_hashes.push(database.save_api_key(&format!("benchmark-api-key-{}", index)).await?);
+    }
+
+    let mut rules = Vec::new();
+    for index in 0..rule_count {
+        let mut rule = rule(&api_key_hashes[index % API_KEYS], index);
+        let (rule_id, revision) = database.save_rule(&rule, &RevisionSource::new("created", None)).await?;
+        rule.id = Some(rule_id);
+        rule.revision = Some(revision);
+        rules.push(rule);
+    }
+
+    let started = Instant::now();
+
+    // Each rule run looks up its API key (a write, it bumps last_used_at),
+    // re-reads its rule and logs the outcome.
+    let executions: Vec<_> = rules
+        .into_iter()
+        .map(|rule| {
+            let database = database.clone();
+            tokio::spawn(async move {
+                for _ in 0..runs_per_rule {
+                    database.get_api_key(&rule.api_key_hash).await?;
+                    database.get_rule_by_id(rule.id.unwrap_or(0), &rule.api_key_hash).await?;
+                    database.log_execution(&execution_log(&rule)).await?;
+                }
+                Ok::<_, String>(())
+            })
+        })
+        .collect();
+
+    let dashboards: Vec<_> = (0..DASHBOARD_CLIENTS)
+        .map(|client| {
+            let database = database.clone();
+            let api_key_hash = api_key_hashes[client % API_KEYS].clone();
+            tokio::spawn(async move {
+                let mut latencies = Vec::new();
+                for _ in 0..DASHBOARD_REQUESTS {
+                    let request = Instant::now();
+                    database.get_rules_by_api_key(&api_key_hash).await?;
+                    database.get_execution_logs(None, &api_key_hash, 100).await?;
+                    latencies.push(request.elapsed());
+                }
+                Ok::<_, String>(latencies)
+            })
+        })
+        .collect();
+
+    for execution in executions {
+        execution.await.map_err(|e| e.to_string())??;
+    }
+    let mut dashboard_latencies = Vec::new();
+    for dashboard in dashboards {
+        dashboard_latencies.extend(dashboard.await.map_err(|e| e.to_string())??);
+    }
+    let elapsed = started.elapsed();
+
+    drop(database);
+    let _ = std::fs::remove_dir_all(&dir);
+
+    dashboard_latencies.sort();
+    Ok(Report {
+        elapsed,
+        writes: rule_count * runs_per_rule * 2,
+        reads: rule_count * runs_per_rule + DASHBOARD_CLIENTS * DASHBOARD_REQUESTS * 2,
+        dashboard_latencies,
+    })
+}
+
+fn percentile(sorted: &[Duration], percentile: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let index = ((sorted.len() - 1) as f64 * percentile).round() as usize;
+    sorted[index]
+}
+
+fn main() {
+    let rule_count = arg("--rules", 300);
+    let runs_per_rule = arg("--runs", 10);
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("Failed to build tokio runtime");
+
+    println!("{} rules x {} runs, {} dashboard clients x {} requests", rule_count, runs_per_rule, DASHBOARD_CLIENTS, DASHBOARD_REQUESTS);
+    println!("{:>16} {:>10} {:>12} {:>12} {:>16} {:>16}", "read connections", "elapsed", "writes/s", "reads/s", "dashboard p50", "dashboard p95");
+
+    for read_connections in [1, 4, 8] {
+        match runtime.block_on(run(read_connections, rule_count, runs_per_rule)) {
+            Ok(report) => {
+                let secs = report.elapsed.as_secs_f64();
+                println!(
+                    "{:>16} {:>9.2}s {:>12.0} {:>12.0} {:>14.1}ms {:>14.1}ms",
+                    read_connections,
+                    secs,
+                    report.writes as f64 / secs,
+                    report.reads as f64 / secs,
+                    percentile(&report.dashboard_latencies, 0.5).as_secs_f64() * 1000.0,
+                    percentile(&report.dashboard_latencies, 0.95).as_secs_f64() * 1000.0,
+                );
+            }
+            Err(e) => eprintln!("{:>16} failed: {}", read_connections, e),
+        }
+    }
+}
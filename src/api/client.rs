@@ -112,9 +112,58 @@ impl TorboxClient {
             request = request.json(body_data);
         }
 
-        request.send().await.map_err(|_| {
-            ApiError::NetworkError
-        })
+        self.send(request).await
+    }
+
+    /// Sends a request to TorBox. On the server each request first takes a token
+    /// from the shared per-key rate limiter, and a 429 is retried once when its
+    /// `Retry-After` is short enough to wait out.
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<Response, ApiError> {
+        #[cfg(feature = "ssr")]
+        {
+            use crate::api::rate_limiter::{self, RateLimiter, MAX_QUEUE_WAIT};
+
+            if self.config.api_key.is_empty() {
+                return request.send().await.map_err(|_| ApiError::NetworkError);
+            }
+
+            let limiter = rate_limiter::global();
+            let key = RateLimiter::key_for(&self.config.api_key);
+            let mut request = request;
+            let mut retried = false;
+            loop {
+                let retry = request.try_clone();
+                limiter.acquire(&key, MAX_QUEUE_WAIT).await.map_err(|wait| {
+                    let retry_after_secs = wait.as_secs().max(1);
+                    ApiError::RateLimitError(UpstreamError {
+                        status_code: 429,
+                        code: None,
+                        message: format!("Request budget for this API key is exhausted, retry in {}s", retry_after_secs),
+                        retryable: true,
+                        retry_after_secs: Some(retry_after_secs),
+                    })
+                })?;
+
+                let response = request.send().await.map_err(|_| ApiError::NetworkError)?;
+                if response.status().as_u16() != 429 {
+                    return Ok(response);
+                }
+
+                let retry_after = limiter.record_rate_limited(&key, parse_retry_after(response.headers()));
+                match retry {
+                    Some(next) if !retried && retry_after <= MAX_QUEUE_WAIT => {
+                        request = next;
+                        retried = true;
+                    }
+                    _ => return Ok(response),
+                }
+            }
+        }
+
+        #[cfg(not(feature = "ssr"))]
+        {
+            request.send().await.map_err(|_| ApiError::NetworkError)
+        }
     }
 
     async fn handle_response<T>(response: Response) -> Result<ApiResponse<T>, ApiError>
@@ -126,54 +175,20 @@ impl TorboxClient {
         
         if status.is_success() {
             let text = response.text().await.map_err(|e| {
-                ApiError::HttpError { 
-                    status_code, 
-                    message: format!("Failed to read response: {}", e) 
-                }
+                ApiError::InvalidResponse(format!("Failed to read response: {}", e))
             })?;
             
             let api_response: ApiResponse<T> = serde_json::from_str(&text).map_err(|e| {
-                ApiError::HttpError { 
-                    status_code, 
-                    message: format!("JSON parse error: {}", e) 
-                }
+                ApiError::InvalidResponse(format!("JSON parse error: {}", e))
             })?;
             
             if api_response.success {
                 Ok(api_response)
             } else {
-                let error_msg = api_response.error
-                    .unwrap_or_else(|| api_response.detail.clone());
-                Err(ApiError::HttpError { 
-                    status_code, 
-                    message: error_msg
-                })
+                Err(ApiError::from_response(status_code, api_response.error, api_response.detail, None))
             }
         } else {
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            
-            match status_code {
-                401 => Err(ApiError::HttpError { 
-                    status_code, 
-                    message: format!("Authentication failed: {}", error_text) 
-                }),
-                404 => Err(ApiError::HttpError { 
-                    status_code, 
-                    message: format!("Resource not found: {}", error_text) 
-                }),
-                422 => Err(ApiError::HttpError { 
-                    status_code, 
-                    message: format!("Validation error: {}", error_text) 
-                }),
-                429 => Err(ApiError::HttpError { 
-                    status_code, 
-                    message: format!("Rate limit exceeded: {}", error_text) 
-                }),
-                _ => Err(ApiError::HttpError { 
-                    status_code, 
-                    message: format!("Server error ({}): {}", status_code, error_text) 
-                }),
-            }
+            Err(Self::error_response(response).await)
         }
     }
 
@@ -186,55 +201,47 @@ impl TorboxClient {
         
         if status.is_success() {
             let text = response.text().await.map_err(|e| {
-                ApiError::HttpError { 
-                    status_code, 
-                    message: format!("Failed to read response: {}", e) 
-                }
+                ApiError::InvalidResponse(format!("Failed to read response: {}", e))
             })?;
             
             let search_response: SearchApiResponse<T> = serde_json::from_str(&text).map_err(|e| {
-                ApiError::HttpError { 
-                    status_code, 
-                    message: format!("JSON parse error: {}", e) 
-                }
+                ApiError::InvalidResponse(format!("JSON parse error: {}", e))
             })?;
             
             if search_response.success {
                 Ok(search_response)
             } else {
-                Err(ApiError::HttpError { 
-                    status_code, 
-                    message: search_response.message.clone()
-                })
+                Err(ApiError::from_response(status_code, search_response.error, search_response.message, None))
             }
         } else {
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            
-            match status_code {
-                401 => Err(ApiError::HttpError { 
-                    status_code, 
-                    message: format!("Authentication failed: {}", error_text) 
-                }),
-                404 => Err(ApiError::HttpError { 
-                    status_code, 
-                    message: format!("Resource not found: {}", error_text) 
-                }),
-                422 => Err(ApiError::HttpError { 
-                    status_code, 
-                    message: format!("Validation error: {}", error_text) 
-                }),
-                429 => Err(ApiError::HttpError { 
-                    status_code, 
-                    message: format!("Rate limit exceeded: {}", error_text) 
-                }),
-                _ => Err(ApiError::HttpError { 
-                    status_code, 
-                    message: format!("Server error ({}): {}", status_code, error_text) 
-                }),
-            }
+            Err(Self::error_response(response).await)
         }
     }
 
+    /// Classifies a non-2xx response, taking TorBox's `error` code and its
+    /// `detail` (or `message`) from the body when the body is JSON.
+    async fn error_response(response: Response) -> ApiError {
+        let status_code = response.status().as_u16();
+        let retry_after_secs = parse_retry_after(response.headers()).map(|delay| delay.as_secs());
+        let text = response.text().await.unwrap_or_default();
+
+        let body: Option<serde_json::Value> = serde_json::from_str(&text).ok();
+        let field = |key: &str| {
+            body.as_ref()
+                .and_then(|body| body.get(key))
+                .and_then(|value| value.as_str())
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        let code = field("error");
+        let message = field("detail")
+            .or_else(|| field("message"))
+            .unwrap_or_else(|| text.trim().chars().take(500).collect());
+
+        ApiError::from_response(status_code, code, message, retry_after_secs)
+    }
+
     pub async fn get_user(&self, settings: Option<bool>) -> Result<ApiResponse<User>, ApiError> {
         let mut url = self.build_api_url("/v1/api/user/me");
         if let Some(include_settings) = settings {
@@ -352,19 +359,17 @@ impl TorboxClient {
             
             let status = resp.status();
             let api_response: ApiResponse<serde_json::Value> = serde_json::from_str(&text).map_err(|e| {
-                ApiError::HttpError { 
-                    status_code: status, 
-                    message: format!("JSON parse error: {}", e) 
+                if (200..300).contains(&status) {
+                    ApiError::InvalidResponse(format!("JSON parse error: {}", e))
+                } else {
+                    ApiError::from_response(status, None, text.trim().chars().take(500).collect(), None)
                 }
             })?;
             
             if api_response.success {
                 Ok(api_response)
             } else {
-                Err(ApiError::HttpError { 
-                    status_code: status, 
-                    message: api_response.detail.clone()
-                })
+                Err(ApiError::from_response(status, api_response.error, api_response.detail, None))
             }
         }
         #[cfg(not(target_arch = "wasm32"))]
@@ -397,13 +402,11 @@ impl TorboxClient {
                 form = form.text("add_only_if_cached", add_only_if_cached.to_string());
             }
             
-            let response = self.client.post(&url)
+            let upload = self.client.post(&url)
                 .header("Authorization", format!("Bearer {}", self.config.api_key))
                 .header("User-Agent", "TorboxCompanion/1.0")
-                .multipart(form)
-                .send()
-                .await
-                .map_err(|e| ApiError::NetworkError)?;
+                .multipart(form);
+            let response = self.send(upload).await?;
             
             Self::handle_response(response).await
         }
@@ -605,19 +608,17 @@ impl TorboxClient {
             
             let status = resp.status();
             let api_response: ApiResponse<serde_json::Value> = serde_json::from_str(&text).map_err(|e| {
-                ApiError::HttpError { 
-                    status_code: status, 
-                    message: format!("JSON parse error: {}", e) 
+                if (200..300).contains(&status) {
+                    ApiError::InvalidResponse(format!("JSON parse error: {}", e))
+                } else {
+                    ApiError::from_response(status, None, text.trim().chars().take(500).collect(), None)
                 }
             })?;
             
             if api_response.success {
                 Ok(api_response)
             } else {
-                Err(ApiError::HttpError { 
-                    status_code: status, 
-                    message: api_response.detail.clone()
-                })
+                Err(ApiError::from_response(status, api_response.error, api_response.detail, None))
             }
         }
         #[cfg(not(target_arch = "wasm32"))]
@@ -650,13 +651,11 @@ impl TorboxClient {
                 form = form.text("add_only_if_cached", add_only_if_cached.to_string());
             }
             
-            let response = self.client.post(&url)
+            let upload = self.client.post(&url)
                 .header("Authorization", format!("Bearer {}", self.config.api_key))
                 .header("User-Agent", "TorboxCompanion/1.0")
-                .multipart(form)
-                .send()
-                .await
-                .map_err(|e| ApiError::NetworkError)?;
+                .multipart(form);
+            let response = self.send(upload).await?;
             
             Self::handle_response(response).await
         }
@@ -767,35 +766,29 @@ impl TorboxClient {
     pub async fn get_metadata(&self, id_type: String, id: String) -> Result<SearchApiResponse<SearchMetadata>, ApiError> {
         let url = self.build_search_api_url(&format!("/meta/{}:{}", id_type, id));
         
-        let response = self.client.get(&url)
-            .header("User-Agent", "TorboxCompanion/1.0")
-            .send()
-            .await
-            .map_err(|_| ApiError::NetworkError)?;
+        let request = self.client.get(&url)
+            .header("User-Agent", "TorboxCompanion/1.0");
+        let response = self.send(request).await?;
         Self::handle_search_response(response).await
     }
 
     pub async fn get_torrents_by_imdb(&self, imdb_id: String) -> Result<SearchApiResponse<SearchTorrentsResponse>, ApiError> {
         let url = self.build_search_api_url(&format!("/torrents/imdb:{}", imdb_id));
         
-        let response = self.client.get(&url)
+        let request = self.client.get(&url)
             .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("User-Agent", "TorboxCompanion/1.0")
-            .send()
-            .await
-            .map_err(|_| ApiError::NetworkError)?;
+            .header("User-Agent", "TorboxCompanion/1.0");
+        let response = self.send(request).await?;
         Self::handle_search_response(response).await
     }
 
     pub async fn get_usenet_by_imdb(&self, imdb_id: String) -> Result<SearchApiResponse<SearchUsenetResponse>, ApiError> {
         let url = self.build_search_api_url(&format!("/usenet/imdb:{}", imdb_id));
         
-        let response = self.client.get(&url)
+        let request = self.client.get(&url)
             .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("User-Agent", "TorboxCompanion/1.0")
-            .send()
-            .await
-            .map_err(|_| ApiError::NetworkError)?;
+            .header("User-Agent", "TorboxCompanion/1.0");
+        let response = self.send(request).await?;
         Self::handle_search_response(response).await
     }
 
@@ -820,12 +813,10 @@ impl TorboxClient {
             url.push_str(&format!("?{}", params.join("&")));
         }
         
-        let response = self.client.get(&url)
+        let request = self.client.get(&url)
             .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("User-Agent", "TorboxCompanion/1.0")
-            .send()
-            .await
-            .map_err(|_| ApiError::NetworkError)?;
+            .header("User-Agent", "TorboxCompanion/1.0");
+        let response = self.send(request).await?;
         Self::handle_search_response(response).await
     }
 
@@ -856,45 +847,37 @@ impl TorboxClient {
             url.push_str(&format!("?{}", params.join("&")));
         }
         
-        let response = self.client.get(&url)
+        let request = self.client.get(&url)
             .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("User-Agent", "TorboxCompanion/1.0")
-            .send()
-            .await
-            .map_err(|_| ApiError::NetworkError)?;
+            .header("User-Agent", "TorboxCompanion/1.0");
+        let response = self.send(request).await?;
         Self::handle_search_response(response).await
     }
 
     pub async fn search_metadata(&self, query: String) -> Result<SearchApiResponse<Vec<SearchMetadata>>, ApiError> {
         let url = self.build_search_api_url(&format!("/search/{}", query));
         
-        let response = self.client.get(&url)
-            .header("User-Agent", "TorboxCompanion/1.0")
-            .send()
-            .await
-            .map_err(|_| ApiError::NetworkError)?;
+        let request = self.client.get(&url)
+            .header("User-Agent", "TorboxCompanion/1.0");
+        let response = self.send(request).await?;
         Self::handle_search_response(response).await
     }
 
     pub async fn get_relay_status(&self) -> Result<RelayStatus, ApiError> {
         let url = self.build_relay_api_url("/");
         
-        let response = self.client.get(&url)
-            .header("User-Agent", "TorboxCompanion/1.0")
-            .send()
-            .await
-            .map_err(|_| ApiError::NetworkError)?;
-        response.json().await.map_err(|_| ApiError::ServerError)
+        let request = self.client.get(&url)
+            .header("User-Agent", "TorboxCompanion/1.0");
+        let response = self.send(request).await?;
+        response.json().await.map_err(|e| ApiError::InvalidResponse(format!("JSON parse error: {}", e)))
     }
 
     pub async fn request_torrent_update(&self, user_id: String, torrent_id: i32) -> Result<ApiResponse<serde_json::Value>, ApiError> {
         let url = self.build_relay_api_url(&format!("/v1/inactivecheck/torrent/{}/{}", user_id, torrent_id));
         
-        let response = self.client.get(&url)
-            .header("User-Agent", "TorboxCompanion/1.0")
-            .send()
-            .await
-            .map_err(|_| ApiError::NetworkError)?;
+        let request = self.client.get(&url)
+            .header("User-Agent", "TorboxCompanion/1.0");
+        let response = self.send(request).await?;
         Self::handle_response(response).await
     }
 
@@ -1205,3 +1188,14 @@ impl TorboxClient {
         Self::handle_response(response).await
     }
 }
+
+/// Reads `Retry-After` as delay seconds or an HTTP date.
+pub fn parse_retry_after(headers: &reqwest::header::HeaderMap) -> Option<std::time::Duration> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<f64>() {
+        return (secs.is_finite() && secs >= 0.0).then(|| std::time::Duration::from_secs_f64(secs));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let secs = (date.timestamp() - chrono::Utc::now().timestamp()).max(0);
+    Some(std::time::Duration::from_secs(secs as u64))
+}
@@ -7,7 +7,7 @@ pub async fn complete_api_workflow_example(api_key: String) -> Result<(), ApiErr
  match handler.test_connection().await {
  Ok(true) => {},
  Ok(false) => {
- return Err(ApiError::AuthenticationError);
+ return Err(ApiError::from_response(401, None, "API key was rejected".to_string(), None));
  },
  Err(e) => {
  return Err(e);
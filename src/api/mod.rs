@@ -11,6 +11,9 @@ pub mod server_routes;
 #[cfg(feature = "ssr")]
 pub mod proxy;
 
+#[cfg(feature = "ssr")]
+pub mod rate_limiter;
+
 pub use client::TorboxClient;
 pub use types::*;
 pub use request_handler::{RequestHandler, create_handler, create_handler_with_config, create_handler_with_user_ip, demonstrate_all_apis};
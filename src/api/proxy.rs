@@ -8,6 +8,10 @@ use axum::{
 };
 #[cfg(feature = "ssr")]
 use crate::api::endpoints::{MAIN_API_BASE, SEARCH_API_BASE, RELAY_API_BASE};
+#[cfg(feature = "ssr")]
+use crate::api::client::parse_retry_after;
+#[cfg(feature = "ssr")]
+use crate::api::rate_limiter::{self, RateLimiter, MAX_QUEUE_WAIT};
 
 #[cfg(feature = "ssr")]
 fn extract_user_ip(headers: &HeaderMap) -> Option<String> {
@@ -43,6 +47,36 @@ fn extract_user_ip(headers: &HeaderMap) -> Option<String> {
     None
 }
 
+/// Sends a proxied request through the shared per-key rate limiter, so browser
+/// traffic and automation share one budget. 429s are passed back to the browser
+/// but still block the key for their `Retry-After`.
+#[cfg(feature = "ssr")]
+async fn send_upstream(headers: &HeaderMap, upstream_request: reqwest::RequestBuilder) -> Result<reqwest::Response, StatusCode> {
+    let key = headers.get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim_start_matches("Bearer ").trim())
+        .filter(|api_key| !api_key.is_empty())
+        .map(RateLimiter::key_for);
+
+    let limiter = rate_limiter::global();
+    if let Some(key) = &key {
+        limiter.acquire(key, MAX_QUEUE_WAIT).await.map_err(|_| StatusCode::TOO_MANY_REQUESTS)?;
+    }
+
+    let response = upstream_request
+        .send()
+        .await
+        .map_err(|_| StatusCode::BAD_GATEWAY)?;
+
+    if let Some(key) = &key {
+        if response.status().as_u16() == 429 {
+            limiter.record_rate_limited(key, parse_retry_after(response.headers()));
+        }
+    }
+
+    Ok(response)
+}
+
 #[cfg(feature = "ssr")]
 async fn proxy_main_api_get(
     Path(path): Path<String>,
@@ -78,10 +112,7 @@ async fn proxy_main_api_get(
     }
     upstream_request = upstream_request.header("User-Agent", "TorboxCompanion/1.0");
     
-    let response = upstream_request
-        .send()
-        .await
-        .map_err(|_| StatusCode::BAD_GATEWAY)?;
+    let response = send_upstream(&headers, upstream_request).await?;
     
     let status = response.status();
     let mut response_builder = Response::builder().status(status);
@@ -144,10 +175,7 @@ async fn proxy_search_api_get(
     }
     upstream_request = upstream_request.header("User-Agent", "TorboxCompanion/1.0");
     
-    let response = upstream_request
-        .send()
-        .await
-        .map_err(|_| StatusCode::BAD_GATEWAY)?;
+    let response = send_upstream(&headers, upstream_request).await?;
     
     let status = response.status();
     let mut response_builder = Response::builder().status(status);
@@ -207,10 +235,7 @@ async fn proxy_relay_api_get(
     
     upstream_request = upstream_request.header("User-Agent", "TorboxCompanion/1.0");
     
-    let response = upstream_request
-        .send()
-        .await
-        .map_err(|_| StatusCode::BAD_GATEWAY)?;
+    let response = send_upstream(&headers, upstream_request).await?;
     
     let status = response.status();
     let mut response_builder = Response::builder().status(status);
@@ -380,10 +405,7 @@ async fn proxy_main_api_with_body(
         upstream_request = upstream_request.body(body_bytes.to_vec());
     }
     
-    let response = upstream_request
-        .send()
-        .await
-        .map_err(|_| StatusCode::BAD_GATEWAY)?;
+    let response = send_upstream(&headers, upstream_request).await?;
     
     let status = response.status();
     let mut response_builder = Response::builder().status(status);
@@ -455,10 +477,7 @@ async fn proxy_search_api_with_body(
         upstream_request = upstream_request.body(body_bytes.to_vec());
     }
     
-    let response = upstream_request
-        .send()
-        .await
-        .map_err(|_| StatusCode::BAD_GATEWAY)?;
+    let response = send_upstream(&headers, upstream_request).await?;
     
     let status = response.status();
     let mut response_builder = Response::builder().status(status);
@@ -526,10 +545,7 @@ async fn proxy_relay_api_with_body(
         upstream_request = upstream_request.body(body_bytes.to_vec());
     }
     
-    let response = upstream_request
-        .send()
-        .await
-        .map_err(|_| StatusCode::BAD_GATEWAY)?;
+    let response = send_upstream(&headers, upstream_request).await?;
     
     let status = response.status();
     let mut response_builder = Response::builder().status(status);
@@ -0,0 +1,193 @@
+use crate::automation::encryption::EncryptionService;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_REQUESTS_PER_MINUTE: u32 = 120;
+pub const DEFAULT_BURST: u32 = 20;
+
+/// Longest a request will queue for a token before giving up with a rate limit error.
+pub const MAX_QUEUE_WAIT: Duration = Duration::from_secs(10);
+
+/// Assumed back-off when TorBox answers 429 without a usable `Retry-After`.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(5);
+
+/// Buckets that have been full and untouched for this long are dropped.
+const IDLE_BUCKET_TTL: Duration = Duration::from_secs(600);
+
+static RATE_LIMITER: OnceLock<RateLimiter> = OnceLock::new();
+
+/// Sets the limits of the shared limiter. Only the first call has an effect, so it
+/// must run before the first outbound request.
+pub fn init(requests_per_minute: u32, burst: u32) {
+    let _ = RATE_LIMITER.set(RateLimiter::new(requests_per_minute, burst));
+}
+
+/// The limiter every server-side call to TorBox goes through.
+pub fn global() -> &'static RateLimiter {
+    RATE_LIMITER.get_or_init(|| RateLimiter::new(DEFAULT_REQUESTS_PER_MINUTE, DEFAULT_BURST))
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    blocked_until: Option<Instant>,
+    rate_limited_responses: u64,
+}
+
+/// Limits and aggregate counts only, so it can be shown to anyone.
+#[derive(Debug, Clone, Serialize)]
+pub struct RateLimiterStatus {
+    pub requests_per_minute: u32,
+    pub burst: u32,
+    pub tracked_keys: usize,
+    pub blocked_keys: usize,
+}
+
+/// Remaining budget of one API key, for the owner of that key.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyBudget {
+    pub requests_per_minute: u32,
+    pub burst: u32,
+    pub available: u32,
+    pub queued: u32,
+    pub blocked_for_secs: Option<u64>,
+    pub rate_limited_responses: u64,
+}
+
+/// Token bucket per API key hash. Tokens refill continuously up to `burst`; a
+/// request that finds the bucket empty reserves the next token and sleeps until
+/// it is due, so concurrent callers are served in order. A 429 from TorBox blocks
+/// the key until its `Retry-After` has passed.
+pub struct RateLimiter {
+    requests_per_minute: u32,
+    burst: u32,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_minute: u32, burst: u32) -> Self {
+        Self {
+            requests_per_minute: requests_per_minute.max(1),
+            burst: burst.max(1),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn key_for(api_key: &str) -> String {
+        EncryptionService::hash_api_key(api_key)
+    }
+
+    fn refill_per_sec(&self) -> f64 {
+        self.requests_per_minute as f64 / 60.0
+    }
+
+    fn refill(&self, bucket: &mut Bucket, now: Instant) {
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec()).min(self.burst as f64);
+        bucket.updated = now;
+        if bucket.blocked_until.is_some_and(|until| until <= now) {
+            bucket.blocked_until = None;
+        }
+    }
+
+    /// Waits for a token for `key`. Returns the remaining wait instead of sleeping
+    /// when it would exceed `max_wait`.
+    pub async fn acquire(&self, key: &str, max_wait: Duration) -> Result<(), Duration> {
+        let wait = {
+            let mut buckets = self.buckets.lock().map_err(|_| DEFAULT_RETRY_AFTER)?;
+            let now = Instant::now();
+            if !buckets.contains_key(key) {
+                buckets.retain(|_, bucket| {
+                    bucket.blocked_until.is_some() || now.saturating_duration_since(bucket.updated) < IDLE_BUCKET_TTL
+                });
+            }
+            let bucket = buckets.entry(key.to_string()).or_insert_with(|| Bucket {
+                tokens: self.burst as f64,
+                updated: now,
+                blocked_until: None,
+                rate_limited_responses: 0,
+            });
+            self.refill(bucket, now);
+
+            let blocked = bucket.blocked_until.map(|until| until.saturating_duration_since(now)).unwrap_or_default();
+            let queued = if bucket.tokens >= 1.0 {
+                Duration::ZERO
+            } else {
+                Duration::from_secs_f64((1.0 - bucket.tokens) / self.refill_per_sec())
+            };
+            let wait = blocked.max(queued);
+            if wait > max_wait {
+                return Err(wait);
+            }
+            bucket.tokens -= 1.0;
+            wait
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        Ok(())
+    }
+
+    /// Records a 429 for `key`: nothing is sent for it until `retry_after` has passed.
+    pub fn record_rate_limited(&self, key: &str, retry_after: Option<Duration>) -> Duration {
+        let retry_after = retry_after.unwrap_or(DEFAULT_RETRY_AFTER);
+        if let Ok(mut buckets) = self.buckets.lock() {
+            let now = Instant::now();
+            if let Some(bucket) = buckets.get_mut(key) {
+                self.refill(bucket, now);
+                let until = now + retry_after;
+                bucket.blocked_until = Some(bucket.blocked_until.map_or(until, |existing| existing.max(until)));
+                bucket.tokens = bucket.tokens.min(0.0);
+                bucket.rate_limited_responses += 1;
+            }
+        }
+        retry_after
+    }
+
+    pub fn status(&self) -> RateLimiterStatus {
+        let now = Instant::now();
+        let (tracked_keys, blocked_keys) = self.buckets
+            .lock()
+            .map(|buckets| {
+                let blocked = buckets.values()
+                    .filter(|bucket| bucket.blocked_until.is_some_and(|until| until > now))
+                    .count();
+                (buckets.len(), blocked)
+            })
+            .unwrap_or_default();
+
+        RateLimiterStatus {
+            requests_per_minute: self.requests_per_minute,
+            burst: self.burst,
+            tracked_keys,
+            blocked_keys,
+        }
+    }
+
+    /// The budget of `key`; a key nothing has been sent for yet has the full burst.
+    pub fn budget(&self, key: &str) -> KeyBudget {
+        let now = Instant::now();
+        let mut budget = KeyBudget {
+            requests_per_minute: self.requests_per_minute,
+            burst: self.burst,
+            available: self.burst,
+            queued: 0,
+            blocked_for_secs: None,
+            rate_limited_responses: 0,
+        };
+
+        if let Ok(mut buckets) = self.buckets.lock() {
+            if let Some(bucket) = buckets.get_mut(key) {
+                self.refill(bucket, now);
+                budget.available = bucket.tokens.max(0.0).floor() as u32;
+                budget.queued = (-bucket.tokens).max(0.0).ceil() as u32;
+                budget.blocked_for_secs = bucket.blocked_until.map(|until| until.saturating_duration_since(now).as_secs().max(1));
+                budget.rate_limited_responses = bucket.rate_limited_responses;
+            }
+        }
+        budget
+    }
+}
@@ -578,30 +578,125 @@ pub struct VendorUser {
     pub created_at: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// TorBox error codes that mean the API key itself was rejected.
+const AUTH_ERROR_CODES: [&str; 3] = ["BAD_TOKEN", "AUTH_ERROR", "NO_AUTH"];
+
+/// TorBox error codes for temporary capacity problems worth retrying.
+const RETRYABLE_ERROR_CODES: [&str; 2] = ["NO_SERVERS_AVAILABLE_ERROR", "DOWNLOAD_SERVER_ERROR"];
+
+/// An error response from TorBox.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpstreamError {
+    pub status_code: u16,
+    /// TorBox's machine-readable code (e.g. `BAD_TOKEN`), when the body carried one.
+    pub code: Option<String>,
+    pub message: String,
+    /// Whether the same request may succeed if sent again later.
+    pub retryable: bool,
+    pub retry_after_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ApiError {
-    AuthenticationError,
-    RateLimitError,
-    ValidationError,
-    NotFoundError,
-    ServerError,
+    /// 401/403, or a TorBox code saying the API key was rejected.
+    AuthenticationError(UpstreamError),
+    /// 429, or the server-side rate limiter's budget for the key ran out.
+    RateLimitError(UpstreamError),
+    /// 400/422.
+    ValidationError(UpstreamError),
+    /// 404.
+    NotFoundError(UpstreamError),
+    /// 5xx, including the 530 Cloudflare returns when TorBox can't be reached.
+    ServerError(UpstreamError),
+    /// The request never got a response.
     NetworkError,
-    HttpError { status_code: u16, message: String },
+    /// A response that couldn't be read, or lacked the data it should carry.
+    InvalidResponse(String),
+    /// Any other failed response.
+    HttpError(UpstreamError),
+}
+
+impl ApiError {
+    /// Classifies a failed response by status code and TorBox error code.
+    pub fn from_response(status_code: u16, code: Option<String>, message: String, retry_after_secs: Option<u64>) -> Self {
+        let code_is = |codes: &[&str]| code.as_deref().map(|code| codes.contains(&code)).unwrap_or(false);
+        let is_auth = matches!(status_code, 401 | 403) || code_is(&AUTH_ERROR_CODES);
+        let retryable = matches!(status_code, 429 | 502 | 503 | 504 | 530) || code_is(&RETRYABLE_ERROR_CODES);
+
+        let upstream = UpstreamError { status_code, code, message, retryable, retry_after_secs };
+        match status_code {
+            _ if is_auth => ApiError::AuthenticationError(upstream),
+            429 => ApiError::RateLimitError(upstream),
+            400 | 422 => ApiError::ValidationError(upstream),
+            404 => ApiError::NotFoundError(upstream),
+            500..=599 => ApiError::ServerError(upstream),
+            _ => ApiError::HttpError(upstream),
+        }
+    }
+
+    pub fn missing_data() -> Self {
+        ApiError::InvalidResponse("Response contained no data".to_string())
+    }
+
+    pub fn upstream(&self) -> Option<&UpstreamError> {
+        match self {
+            ApiError::AuthenticationError(upstream)
+            | ApiError::RateLimitError(upstream)
+            | ApiError::ValidationError(upstream)
+            | ApiError::NotFoundError(upstream)
+            | ApiError::ServerError(upstream)
+            | ApiError::HttpError(upstream) => Some(upstream),
+            ApiError::NetworkError | ApiError::InvalidResponse(_) => None,
+        }
+    }
+
+    pub fn status_code(&self) -> Option<u16> {
+        self.upstream().map(|upstream| upstream.status_code)
+    }
+
+    pub fn code(&self) -> Option<&str> {
+        self.upstream().and_then(|upstream| upstream.code.as_deref())
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ApiError::NetworkError => true,
+            _ => self.upstream().map(|upstream| upstream.retryable).unwrap_or(false),
+        }
+    }
+
+    pub fn retry_after(&self) -> Option<std::time::Duration> {
+        self.upstream()
+            .and_then(|upstream| upstream.retry_after_secs)
+            .map(std::time::Duration::from_secs)
+    }
 }
 
 impl std::fmt::Display for ApiError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ApiError::AuthenticationError => write!(f, "Authentication failed"),
-            ApiError::RateLimitError => write!(f, "Rate limit exceeded"),
-            ApiError::ValidationError => write!(f, "Validation error"),
-            ApiError::NotFoundError => write!(f, "Resource not found"),
-            ApiError::ServerError => write!(f, "Server error"),
-            ApiError::NetworkError => write!(f, "Network error"),
-            ApiError::HttpError { status_code, message } => {
-                write!(f, "HTTP {}: {}", status_code, message)
-            }
+        let label = match self {
+            ApiError::AuthenticationError(_) => "Authentication failed",
+            ApiError::RateLimitError(_) => "Rate limit exceeded",
+            ApiError::ValidationError(_) => "Validation error",
+            ApiError::NotFoundError(_) => "Resource not found",
+            ApiError::ServerError(_) => "Server error",
+            ApiError::NetworkError => return write!(f, "Network error"),
+            ApiError::InvalidResponse(message) => return write!(f, "Invalid response: {}", message),
+            ApiError::HttpError(_) => "HTTP error",
+        };
+        let Some(upstream) = self.upstream() else {
+            return write!(f, "{}", label);
+        };
+
+        write!(f, "{} (HTTP {}", label, upstream.status_code)?;
+        if let Some(code) = &upstream.code {
+            write!(f, ", {}", code)?;
+        }
+        write!(f, ")")?;
+        if !upstream.message.trim().is_empty() {
+            write!(f, ": {}", upstream.message.trim())?;
         }
+        Ok(())
     }
 }
 
@@ -0,0 +1,179 @@
+use crate::automation::engine::action_label;
+use crate::automation::limiter::SKIPPED_ALREADY_RUNNING;
+use crate::automation::types::*;
+use crate::automation::window::SKIPPED_OUTSIDE_WINDOW;
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+pub const DEFAULT_ANALYTICS_DAYS: u32 = 30;
+pub const MAX_ANALYTICS_DAYS: u32 = 365;
+const MAX_FAILING_ITEMS: usize = 10;
+
+/// Log entries that follow up on an earlier run or note a change of status rather
+/// than being a run themselves.
+const NON_RUN_EXECUTION_TYPES: [&str; 2] = ["transfer", EXECUTION_TYPE_SUSPENDED];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AutomationAnalytics {
+    pub days: u32,
+    pub total_runs: i64,
+    pub successful_runs: i64,
+    pub partial_runs: i64,
+    pub failed_runs: i64,
+    /// Runs skipped because the previous run of the rule was still going or its
+    /// schedule window was closed.
+    pub skipped_runs: i64,
+    pub success_rate: f64,
+    pub partial_rate: f64,
+    pub items_processed: i64,
+    /// Bytes freed by successful deletions. Only counts runs that recorded item sizes.
+    pub reclaimed_bytes: i64,
+    pub runs_per_day: Vec<DailyRuns>,
+    pub actions: Vec<ActionStats>,
+    pub failing_items: Vec<FailingItem>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DailyRuns {
+    pub date: String,
+    pub runs: i64,
+    pub successful: i64,
+    pub partial: i64,
+    pub failed: i64,
+    pub items_processed: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionStats {
+    pub action: String,
+    pub succeeded: i64,
+    pub failed: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FailingItem {
+    pub id: i32,
+    pub download_type: Option<DownloadType>,
+    pub name: String,
+    pub failures: i64,
+    pub last_error: Option<String>,
+    pub last_failed_at: Option<String>,
+}
+
+/// Aggregates the log entries of the `days` days up to and including `today`.
+/// `logs` must be ordered oldest first.
+pub fn summarize(logs: &[ExecutionLog], days: u32, today: NaiveDate) -> AutomationAnalytics {
+    let first_day = today - Duration::days(days.saturating_sub(1) as i64);
+    let mut runs_per_day: BTreeMap<NaiveDate, DailyRuns> = (0..days)
+        .map(|offset| {
+            let date = first_day + Duration::days(offset as i64);
+            (date, DailyRuns { date: date.to_string(), ..Default::default() })
+        })
+        .collect();
+
+    let mut actions: HashMap<String, (i64, i64)> = HashMap::new();
+    let mut failing: HashMap<(Option<DownloadType>, i32), FailingItem> = HashMap::new();
+    let mut skipped_runs = 0;
+    let mut reclaimed_bytes = 0;
+    let delete_label = action_label(&ActionType::Delete);
+
+    for log in logs {
+        let Some(date) = log.executed_at.as_deref().and_then(log_date) else {
+            continue;
+        };
+        let Some(day) = runs_per_day.get_mut(&date) else {
+            continue;
+        };
+
+        if !NON_RUN_EXECUTION_TYPES.contains(&log.execution_type.as_str()) {
+            if matches!(log.error_message.as_deref(), Some(SKIPPED_ALREADY_RUNNING | SKIPPED_OUTSIDE_WINDOW)) {
+                skipped_runs += 1;
+                continue;
+            }
+            day.runs += 1;
+            day.items_processed += log.items_processed as i64;
+            if log.partial == Some(true) {
+                day.partial += 1;
+            } else if log.success {
+                day.successful += 1;
+            } else {
+                day.failed += 1;
+            }
+        }
+
+        for item in log.processed_items.as_deref().unwrap_or_default() {
+            let outcomes: Vec<(&str, bool)> = if item.steps.is_empty() {
+                vec![(item.action.as_str(), item.success)]
+            } else {
+                item.steps.iter().map(|step| (step.action.as_str(), step.success)).collect()
+            };
+
+            for (action, success) in &outcomes {
+                let counts = actions.entry(action.to_string()).or_default();
+                if *success {
+                    counts.0 += 1;
+                } else {
+                    counts.1 += 1;
+                }
+            }
+
+            if outcomes.iter().any(|(action, success)| *success && *action == delete_label) {
+                reclaimed_bytes += item.size.unwrap_or(0).max(0);
+            }
+
+            if !item.success {
+                let entry = failing.entry((item.download_type, item.id)).or_insert_with(|| FailingItem {
+                    id: item.id,
+                    download_type: item.download_type,
+                    name: item.name.clone(),
+                    failures: 0,
+                    last_error: None,
+                    last_failed_at: None,
+                });
+                entry.failures += 1;
+                entry.name = item.name.clone();
+                entry.last_error = item.error.clone();
+                entry.last_failed_at = log.executed_at.clone();
+            }
+        }
+    }
+
+    let runs_per_day: Vec<DailyRuns> = runs_per_day.into_values().collect();
+    let successful_runs: i64 = runs_per_day.iter().map(|day| day.successful).sum();
+    let partial_runs: i64 = runs_per_day.iter().map(|day| day.partial).sum();
+    let failed_runs: i64 = runs_per_day.iter().map(|day| day.failed).sum();
+    let total_runs = successful_runs + partial_runs + failed_runs;
+    let rate = |count: i64| if total_runs == 0 { 0.0 } else { count as f64 / total_runs as f64 };
+
+    let mut actions: Vec<ActionStats> = actions
+        .into_iter()
+        .map(|(action, (succeeded, failed))| ActionStats { action, succeeded, failed })
+        .collect();
+    actions.sort_by(|a, b| (b.succeeded + b.failed).cmp(&(a.succeeded + a.failed)).then_with(|| a.action.cmp(&b.action)));
+
+    let mut failing_items: Vec<FailingItem> = failing.into_values().collect();
+    failing_items.sort_by(|a, b| b.failures.cmp(&a.failures).then_with(|| b.last_failed_at.cmp(&a.last_failed_at)));
+    failing_items.truncate(MAX_FAILING_ITEMS);
+
+    AutomationAnalytics {
+        days,
+        total_runs,
+        successful_runs,
+        partial_runs,
+        failed_runs,
+        skipped_runs,
+        success_rate: rate(successful_runs),
+        partial_rate: rate(partial_runs),
+        items_processed: runs_per_day.iter().map(|day| day.items_processed).sum(),
+        reclaimed_bytes,
+        runs_per_day,
+        actions,
+        failing_items,
+    }
+}
+
+/// `executed_at` is SQLite's `YYYY-MM-DD HH:MM:SS` in UTC.
+fn log_date(executed_at: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(executed_at.get(..10)?, "%Y-%m-%d").ok()
+}
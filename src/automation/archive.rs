@@ -0,0 +1,126 @@
+use crate::api::types::{CreateTorrentRequest, Torrent};
+use crate::api::TorboxClient;
+use crate::automation::database::Database;
+use crate::automation::engine::AutomationEngine;
+use crate::automation::items::DownloadItem;
+use crate::automation::types::*;
+use leptos::logging::log;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedFile {
+    pub name: String,
+    pub size: i64,
+}
+
+/// What is kept of a deleted torrent so it can be added again until the archive
+/// expires.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedTorrent {
+    pub id: Option<i64>,
+    pub api_key_hash: String,
+    /// Id the torrent had before it was deleted.
+    pub torrent_id: i32,
+    pub hash: String,
+    pub name: String,
+    pub size: i64,
+    pub magnet: String,
+    pub files: Vec<ArchivedFile>,
+    /// The rule that deleted the torrent; `None` for deletions from the downloads table.
+    pub rule_id: Option<i64>,
+    pub rule_name: Option<String>,
+    pub deleted_at: Option<String>,
+    pub readded_at: Option<String>,
+    /// Id TorBox gave the torrent when it was added again.
+    pub readded_torrent_id: Option<i32>,
+}
+
+/// Collects what is needed to add `torrent` again. Call before deleting it: TorBox
+/// cannot export a magnet for a torrent that is gone. When the export fails the
+/// magnet from the torrent list, or one built from the info hash, is kept instead.
+pub async fn archive(client: &TorboxClient, torrent: &Torrent, api_key_hash: &str) -> Result<DeletedTorrent, String> {
+    let exported = match client.export_torrent_data(torrent.id, "magnet".to_string()).await {
+        Ok(response) => response.data.filter(|magnet| magnet.starts_with("magnet:")),
+        Err(e) => {
+            log!("Failed to export magnet for '{}' before deleting it: {}", torrent.name, e);
+            None
+        }
+    };
+
+    let magnet = exported
+        .or_else(|| torrent.magnet.clone().filter(|magnet| magnet.starts_with("magnet:")))
+        .or_else(|| (!torrent.hash.is_empty()).then(|| format!("magnet:?xt=urn:btih:{}", torrent.hash)))
+        .ok_or_else(|| format!("Cannot archive '{}': TorBox returned neither a magnet nor an info hash", torrent.name))?;
+
+    Ok(DeletedTorrent {
+        id: None,
+        api_key_hash: api_key_hash.to_string(),
+        torrent_id: torrent.id,
+        hash: torrent.hash.clone(),
+        name: torrent.name.clone(),
+        size: torrent.size,
+        magnet,
+        files: torrent.files.iter().flatten()
+            .map(|file| ArchivedFile { name: file.name.clone(), size: file.size })
+            .collect(),
+        rule_id: None,
+        rule_name: None,
+        deleted_at: None,
+        readded_at: None,
+        readded_torrent_id: None,
+    })
+}
+
+/// Stores `deleted` in the archive, then deletes the torrent on TorBox. The
+/// archive goes first so a run cancelled or crashing after the delete cannot
+/// lose it; it is dropped again when the delete fails.
+pub async fn delete_archived(database: &Database, client: &TorboxClient, mut deleted: DeletedTorrent) -> Result<DeletedTorrent, String> {
+    let id = database.save_deleted_torrent(&deleted).await?;
+    deleted.id = Some(id);
+
+    let engine = AutomationEngine::new();
+    if let Err(e) = engine.control_download(client, &ActionType::Delete, DownloadType::Torrent, deleted.torrent_id).await {
+        if let Err(forget_error) = database.forget_deleted_torrent(id, &deleted.api_key_hash).await {
+            log!("Failed to drop archive of '{}' after its delete failed: {}", deleted.name, forget_error);
+        }
+        return Err(e);
+    }
+
+    Ok(deleted)
+}
+
+/// Looks a torrent up by id in the full, uncached torrent list.
+pub async fn find_torrent(client: &TorboxClient, torrent_id: i32, context: &str) -> Result<Option<Torrent>, String> {
+    let items = AutomationEngine::new()
+        .fetch_items(client, &[DownloadType::Torrent], context)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(items.into_iter().find_map(|item| match item {
+        DownloadItem::Torrent(torrent) if torrent.id == torrent_id => Some(torrent),
+        _ => None,
+    }))
+}
+
+/// Adds an archived torrent again from its magnet. Returns the id of the new
+/// torrent when TorBox reports one.
+pub async fn readd(client: &TorboxClient, deleted: &DeletedTorrent) -> Result<Option<i32>, String> {
+    let request = CreateTorrentRequest {
+        file: None,
+        magnet: Some(deleted.magnet.clone()),
+        seed: None,
+        allow_zip: None,
+        name: Some(deleted.name.clone()),
+        as_queued: None,
+        add_only_if_cached: None,
+    };
+
+    let response = client.create_torrent(request).await
+        .map_err(|e| format!("Failed to add torrent: {}", e))?;
+
+    Ok(response.data
+        .as_ref()
+        .and_then(|data| data.get("torrent_id"))
+        .and_then(|id| id.as_i64())
+        .map(|id| id as i32))
+}
@@ -0,0 +1,183 @@
+use crate::automation::types::*;
+use serde::{Deserialize, Serialize};
+
+pub const RULE_BUNDLE_FORMAT: &str = "torbox-companion/automation-rules";
+pub const RULE_BUNDLE_VERSION: u32 = 1;
+pub const MAX_BUNDLE_RULES: usize = 500;
+
+/// Portable set of rules for moving them between accounts. Carries no ids,
+/// API key hashes, timestamps or notification header values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleBundle {
+    pub format: String,
+    pub version: u32,
+    #[serde(default)]
+    pub exported_at: Option<String>,
+    pub rules: Vec<BundledRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledRule {
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_download_types")]
+    pub download_types: Vec<DownloadType>,
+    pub trigger_config: TriggerConfig,
+    #[serde(deserialize_with = "deserialize_conditions")]
+    pub conditions: ConditionNode,
+    pub action_config: ActionConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notification: Option<NotificationConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule_window: Option<ScheduleWindow>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quota: Option<QuotaConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cooldown: Option<CooldownConfig>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BundleFormat {
+    Json,
+    Yaml,
+}
+
+impl BundleFormat {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "json" => Ok(BundleFormat::Json),
+            "yaml" | "yml" => Ok(BundleFormat::Yaml),
+            other => Err(format!("Unsupported bundle format: {}", other)),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            BundleFormat::Json => "json",
+            BundleFormat::Yaml => "yaml",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            BundleFormat::Json => "application/json",
+            BundleFormat::Yaml => "application/yaml",
+        }
+    }
+}
+
+impl From<&AutomationRule> for BundledRule {
+    fn from(rule: &AutomationRule) -> Self {
+        Self {
+            name: rule.name.clone(),
+            enabled: rule.enabled,
+            download_types: rule.download_types.clone(),
+            trigger_config: rule.trigger_config.clone(),
+            conditions: rule.conditions.clone(),
+            action_config: rule.action_config.clone(),
+            notification: rule.notification.clone(),
+            schedule_window: rule.schedule_window.clone(),
+            quota: rule.quota.clone(),
+            cooldown: rule.cooldown.clone(),
+        }
+    }
+}
+
+impl BundledRule {
+    /// Blanks the value of every notification header, of the rule and of its
+    /// Notify steps, since those usually carry auth tokens and a bundle is meant to
+    /// be shared. The header names stay so the values can be entered again after
+    /// importing.
+    pub fn without_secrets(mut self) -> Self {
+        if let Some(notification) = self.notification.as_mut() {
+            notification.headers.values_mut().for_each(String::clear);
+        }
+        for step in &mut self.action_config.steps {
+            if !matches!(step.action_type, ActionType::Notify) {
+                continue;
+            }
+            let headers = step.params.as_mut().and_then(|params| params.get_mut("headers"));
+            if let Some(serde_json::Value::Object(headers)) = headers {
+                headers.values_mut().for_each(|value| *value = serde_json::Value::String(String::new()));
+            }
+        }
+        self
+    }
+
+    /// Whether any notification header has no value, as in an exported bundle.
+    pub fn has_blank_secrets(&self) -> bool {
+        let rule_headers = self.notification.iter()
+            .any(|notification| notification.headers.values().any(String::is_empty));
+        let step_headers = self.action_config.steps.iter()
+            .filter(|step| matches!(step.action_type, ActionType::Notify))
+            .filter_map(|step| step.params.as_ref()?.get("headers")?.as_object())
+            .any(|headers| headers.values().any(|value| value.as_str() == Some("")));
+        rule_headers || step_headers
+    }
+}
+
+impl RuleBundle {
+    /// Bundles rules for export, without their notification header values.
+    pub fn from_rules(rules: &[AutomationRule]) -> Self {
+        Self {
+            format: RULE_BUNDLE_FORMAT.to_string(),
+            version: RULE_BUNDLE_VERSION,
+            exported_at: Some(chrono::Utc::now().to_rfc3339()),
+            rules: rules.iter().map(|rule| BundledRule::from(rule).without_secrets()).collect(),
+        }
+    }
+
+    /// Parses a JSON or YAML bundle. YAML goes through `serde_json::Value` so enums
+    /// are written as plain `{Variant: ...}` maps rather than YAML tags, which the
+    /// untagged back-compat helpers for conditions and actions can't read.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let trimmed = content.trim_start();
+        let bundle: RuleBundle = if trimmed.starts_with('{') {
+            serde_json::from_str(trimmed).map_err(|e| format!("Invalid JSON bundle: {}", e))?
+        } else {
+            let value: serde_json::Value = serde_yaml::from_str(content)
+                .map_err(|e| format!("Invalid YAML bundle: {}", e))?;
+            serde_json::from_value(value).map_err(|e| format!("Invalid YAML bundle: {}", e))?
+        };
+        bundle.check_header()?;
+        Ok(bundle)
+    }
+
+    pub fn check_header(&self) -> Result<(), String> {
+        if self.format != RULE_BUNDLE_FORMAT {
+            return Err(format!("Not a rule bundle (expected format '{}')", RULE_BUNDLE_FORMAT));
+        }
+        if self.version == 0 || self.version > RULE_BUNDLE_VERSION {
+            return Err(format!(
+                "Unsupported bundle version {} (this server reads up to version {})",
+                self.version, RULE_BUNDLE_VERSION
+            ));
+        }
+        if self.rules.is_empty() {
+            return Err("Bundle contains no rules".to_string());
+        }
+        if self.rules.len() > MAX_BUNDLE_RULES {
+            return Err(format!("Bundle cannot contain more than {} rules", MAX_BUNDLE_RULES));
+        }
+        Ok(())
+    }
+
+    pub fn serialize(&self, format: BundleFormat) -> Result<String, String> {
+        match format {
+            BundleFormat::Json => serde_json::to_string_pretty(self)
+                .map_err(|e| format!("Failed to serialize bundle: {}", e)),
+            BundleFormat::Yaml => {
+                let value = serde_json::to_value(self)
+                    .map_err(|e| format!("Failed to serialize bundle: {}", e))?;
+                serde_yaml::to_string(&value)
+                    .map_err(|e| format!("Failed to serialize bundle: {}", e))
+            }
+        }
+    }
+}
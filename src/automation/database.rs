@@ -1,226 +1,489 @@
-use crate::automation::encryption::EncryptionService;
+use crate::automation::archive::DeletedTorrent;
+use crate::automation::bundle::BundledRule;
+use crate::automation::encryption::{self, EncryptionService, MasterKey, ALLOW_UNWRAPPED_DATA_KEY_VAR, CIPHER_BOUND};
+use crate::automation::history::{ActionTally, ItemHistory, RuleTally};
+use crate::automation::logs::{like_pattern, LogFilter, SKIPPED_MESSAGES};
+use crate::automation::migrations;
+use crate::automation::pool::ConnectionPool;
+use crate::automation::revisions::{RevisionSource, RuleRevision};
 use crate::automation::types::*;
-use rusqlite::{params, Connection, Result as SqliteResult};
+use leptos::logging::log;
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
-use tokio::sync::Mutex;
 
-pub struct Database {
-    conn: Arc<Mutex<Connection>>,
-    encryption: Arc<EncryptionService>,
+const RULE_COLUMNS: &str =
+    "id, api_key_hash, name, enabled, trigger_config, conditions, action_config, created_at, updated_at, download_types, notification_config, schedule_window, quota, cooldown, revision";
+
+fn rule_from_row(row: &rusqlite::Row) -> rusqlite::Result<AutomationRule> {
+    let trigger_json: String = row.get(4)?;
+    let conditions_json: String = row.get(5)?;
+    let action_json: String = row.get(6)?;
+    let download_types_json: Option<String> = row.get(9)?;
+    let notification_json: Option<String> = row.get(10)?;
+    let schedule_window_json: Option<String> = row.get(11)?;
+    let quota_json: Option<String> = row.get(12)?;
+    let cooldown_json: Option<String> = row.get(13)?;
+
+    Ok(AutomationRule {
+        id: Some(row.get(0)?),
+        api_key_hash: row.get(1)?,
+        name: row.get(2)?,
+        enabled: row.get(3)?,
+        download_types: match download_types_json {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|_| rusqlite::Error::InvalidColumnType(9, "download_types".to_string(), rusqlite::types::Type::Text))?,
+            None => default_download_types(),
+        },
+        trigger_config: serde_json::from_str(&trigger_json)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(4, "trigger_config".to_string(), rusqlite::types::Type::Text))?,
+        conditions: parse_conditions(&conditions_json)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(5, "conditions".to_string(), rusqlite::types::Type::Text))?,
+        action_config: serde_json::from_str(&action_json)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(6, "action_config".to_string(), rusqlite::types::Type::Text))?,
+        notification: match notification_json {
+            Some(json) => Some(serde_json::from_str(&json)
+                .map_err(|_| rusqlite::Error::InvalidColumnType(10, "notification_config".to_string(), rusqlite::types::Type::Text))?),
+            None => None,
+        },
+        schedule_window: match schedule_window_json {
+            Some(json) => Some(serde_json::from_str(&json)
+                .map_err(|_| rusqlite::Error::InvalidColumnType(11, "schedule_window".to_string(), rusqlite::types::Type::Text))?),
+            None => None,
+        },
+        quota: match quota_json {
+            Some(json) => Some(serde_json::from_str(&json)
+                .map_err(|_| rusqlite::Error::InvalidColumnType(12, "quota".to_string(), rusqlite::types::Type::Text))?),
+            None => None,
+        },
+        cooldown: match cooldown_json {
+            Some(json) => Some(serde_json::from_str(&json)
+                .map_err(|_| rusqlite::Error::InvalidColumnType(13, "cooldown".to_string(), rusqlite::types::Type::Text))?),
+            None => None,
+        },
+        revision: row.get(14)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
 }
 
-impl Database {
-    pub async fn new<P: AsRef<Path>>(db_path: P) -> Result<Self, String> {
-        let db_dir = db_path.as_ref().parent().ok_or("Invalid database path")?;
-        std::fs::create_dir_all(db_dir).map_err(|e| format!("Failed to create data directory: {}", e))?;
+const TRANSFER_COLUMNS: &str =
+    "id, rule_id, rule_name, api_key_hash, batch_id, download_id, download_type, item_name, file_name, provider, job_id, status, on_success, created_at, completed_at, rule_revision";
+
+fn transfer_from_row(row: &rusqlite::Row) -> rusqlite::Result<TrackedTransfer> {
+    let download_type_json: String = row.get(6)?;
+    let provider_json: String = row.get(9)?;
+    let on_success_json: Option<String> = row.get(12)?;
+
+    Ok(TrackedTransfer {
+        id: Some(row.get(0)?),
+        rule_id: row.get(1)?,
+        rule_name: row.get(2)?,
+        rule_revision: row.get(15)?,
+        api_key_hash: row.get(3)?,
+        batch_id: row.get(4)?,
+        download_id: row.get(5)?,
+        download_type: serde_json::from_str(&download_type_json)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(6, "download_type".to_string(), rusqlite::types::Type::Text))?,
+        item_name: row.get(7)?,
+        file_name: row.get(8)?,
+        provider: serde_json::from_str(&provider_json)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(9, "provider".to_string(), rusqlite::types::Type::Text))?,
+        job_id: row.get(10)?,
+        status: row.get(11)?,
+        on_success: on_success_json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
+        created_at: row.get(13)?,
+        completed_at: row.get(14)?,
+    })
+}
 
-        let db_path_ref = db_path.as_ref();
-        let conn = Connection::open(db_path_ref)
-            .map_err(|e| format!("Failed to open database: {}", e))?;
-
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            if let Ok(metadata) = std::fs::metadata(db_path_ref) {
-                let mut perms = metadata.permissions();
-                perms.set_mode(0o600);
-                std::fs::set_permissions(db_path_ref, perms)
-                    .map_err(|e| format!("Failed to set database permissions: {}", e))?;
-            }
-        }
+const LOG_COLUMNS: &str =
+    "id, rule_id, rule_name, api_key_hash, execution_type, items_processed, total_items, success, error_message, processed_items, executed_at, partial, rule_revision";
+
+fn log_from_row(row: &rusqlite::Row) -> rusqlite::Result<ExecutionLog> {
+    let processed_items_json: Option<String> = row.get(9)?;
+
+    Ok(ExecutionLog {
+        id: Some(row.get(0)?),
+        rule_id: row.get(1)?,
+        rule_name: row.get(2)?,
+        api_key_hash: row.get(3)?,
+        execution_type: row.get(4)?,
+        items_processed: row.get(5)?,
+        total_items: row.get(6)?,
+        success: row.get(7)?,
+        error_message: row.get(8)?,
+        processed_items: processed_items_json.and_then(|json| serde_json::from_str(&json).ok()),
+        executed_at: row.get(10)?,
+        partial: row.get(11)?,
+        rule_revision: row.get(12)?,
+    })
+}
 
-        let _: String = conn
-            .query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))
-            .map_err(|e| format!("Failed to set WAL mode: {}", e))?;
+const DELETED_TORRENT_COLUMNS: &str =
+    "id, api_key_hash, torrent_id, hash, name, size, magnet, files, rule_id, rule_name, deleted_at, readded_at, readded_torrent_id";
+
+fn deleted_torrent_from_row(row: &rusqlite::Row) -> rusqlite::Result<DeletedTorrent> {
+    let files_json: String = row.get(7)?;
+
+    Ok(DeletedTorrent {
+        id: Some(row.get(0)?),
+        api_key_hash: row.get(1)?,
+        torrent_id: row.get(2)?,
+        hash: row.get(3)?,
+        name: row.get(4)?,
+        size: row.get(5)?,
+        magnet: row.get(6)?,
+        files: serde_json::from_str(&files_json)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(7, "files".to_string(), rusqlite::types::Type::Text))?,
+        rule_id: row.get(8)?,
+        rule_name: row.get(9)?,
+        deleted_at: row.get(10)?,
+        readded_at: row.get(11)?,
+        readded_torrent_id: row.get(12)?,
+    })
+}
 
-        conn.execute("PRAGMA foreign_keys = ON", [])
-            .map_err(|e| format!("Failed to enable foreign keys: {}", e))?;
+fn revision_from_row(row: &rusqlite::Row) -> rusqlite::Result<RuleRevision> {
+    let definition_json: String = row.get(5)?;
+
+    Ok(RuleRevision {
+        rule_id: row.get(0)?,
+        revision: row.get(1)?,
+        action: row.get(2)?,
+        client_ip: row.get(3)?,
+        created_at: row.get(4)?,
+        rule: serde_json::from_str(&definition_json)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(5, "definition".to_string(), rusqlite::types::Type::Text))?,
+    })
+}
 
-        let db = Self {
-            conn: Arc::new(Mutex::new(conn)),
-            encryption: Arc::new(EncryptionService::new()),
-        };
+/// Stores `definition_json` as the next revision of the rule and points the rule at it.
+fn insert_revision(tx: &Transaction, rule_id: i64, definition_json: &str, source: &RevisionSource) -> Result<i64, String> {
+    let revision: i64 = tx
+        .query_row(
+            "SELECT COALESCE(MAX(revision), 0) + 1 FROM rule_revisions WHERE rule_id = ?",
+            params![rule_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to number rule revision: {}", e))?;
 
-        db.create_tables().await?;
-        db.initialize_encryption_key().await?;
+    tx.execute(
+        "INSERT INTO rule_revisions (rule_id, revision, definition, action, client_ip) VALUES (?, ?, ?, ?, ?)",
+        params![rule_id, revision, definition_json, source.action, source.client_ip],
+    )
+    .map_err(|e| format!("Failed to record rule revision: {}", e))?;
 
-        Ok(db)
-    }
+    tx.execute(
+        "UPDATE automation_rules SET revision = ? WHERE id = ?",
+        params![revision, rule_id],
+    )
+    .map_err(|e| format!("Failed to record rule revision: {}", e))?;
 
-    async fn create_tables(&self) -> Result<(), String> {
-        let conn = self.conn.lock().await;
+    Ok(revision)
+}
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS server_key (
-                id INTEGER PRIMARY KEY CHECK (id = 1),
-                encryption_key BLOB NOT NULL,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-            [],
-        )
-        .map_err(|e| format!("Failed to create server_key table: {}", e))?;
-
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS api_keys (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                api_key_hash TEXT UNIQUE NOT NULL,
-                encrypted_api_key BLOB NOT NULL,
-                nonce BLOB NOT NULL,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                last_used_at DATETIME
-            )",
-            [],
-        )
-        .map_err(|e| format!("Failed to create api_keys table: {}", e))?;
-
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS automation_rules (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                api_key_hash TEXT NOT NULL,
-                name TEXT NOT NULL,
-                enabled BOOLEAN DEFAULT true,
-                trigger_config TEXT NOT NULL,
-                conditions TEXT NOT NULL,
-                action_config TEXT NOT NULL,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (api_key_hash) REFERENCES api_keys(api_key_hash)
-            )",
-            [],
-        )
-        .map_err(|e| format!("Failed to create automation_rules table: {}", e))?;
-
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS rule_execution_log (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                rule_id INTEGER NOT NULL,
-                rule_name TEXT NOT NULL,
-                api_key_hash TEXT NOT NULL,
-                execution_type TEXT NOT NULL,
-                items_processed INTEGER DEFAULT 0,
-                success BOOLEAN DEFAULT true,
-                error_message TEXT,
-                processed_items TEXT,
-                executed_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (rule_id) REFERENCES automation_rules(id)
-            )",
-            [],
-        )
-        .map_err(|e| format!("Failed to create rule_execution_log table: {}", e))?;
+/// Loaded data keys: the id new ciphertexts use and every key that may still be
+/// referenced by an `api_keys` row.
+struct LoadedDataKeys {
+    active: i64,
+    keys: HashMap<i64, [u8; 32]>,
+    stored_unwrapped: bool,
+}
 
-        let processed_items_column_exists: bool = conn
-            .query_row(
-                "SELECT COUNT(*) FROM pragma_table_info('rule_execution_log') WHERE name = 'processed_items'",
-                [],
-                |row| Ok(row.get::<_, i64>(0)? > 0),
-            )
-            .unwrap_or(false);
+#[derive(Debug, Clone)]
+pub struct KeyRotationReport {
+    pub data_key_id: i64,
+    pub reencrypted: usize,
+    /// Rows still on an older data key, e.g. because they failed to decrypt.
+    pub remaining: i64,
+    pub pruned_keys: usize,
+}
 
-        if !processed_items_column_exists {
-            conn.execute(
-                "ALTER TABLE rule_execution_log ADD COLUMN processed_items TEXT",
-                [],
-            )
-            .map_err(|e| format!("Failed to add processed_items column: {}", e))?;
+fn insert_data_key(tx: &Transaction, master_key: Option<&MasterKey>, key: &[u8; 32]) -> Result<i64, String> {
+    let (key_material, wrap_nonce, master_key_id) = match master_key {
+        Some(master_key) => {
+            let (wrapped, nonce) = master_key.wrap(key)?;
+            (wrapped, Some(nonce), Some(master_key.id()))
         }
+        None if encryption::unwrapped_data_keys_allowed() => (key.to_vec(), None, None),
+        None => {
+            return Err(format!(
+                "No master key configured; set TORBOX_MASTER_KEY or TORBOX_MASTER_KEY_FILE, or {}=true to keep the data key unwrapped in the database file",
+                ALLOW_UNWRAPPED_DATA_KEY_VAR
+            ))
+        }
+    };
 
-        let total_items_column_exists: bool = conn
-            .query_row(
-                "SELECT COUNT(*) FROM pragma_table_info('rule_execution_log') WHERE name = 'total_items'",
-                [],
-                |row| Ok(row.get::<_, i64>(0)? > 0),
-            )
-            .unwrap_or(false);
+    tx.execute(
+        "INSERT INTO data_keys (key_material, wrap_nonce, master_key_id, active) VALUES (?, ?, ?, true)",
+        params![key_material, wrap_nonce, master_key_id],
+    )
+    .map_err(|e| format!("Failed to store data key: {}", e))?;
 
-        if !total_items_column_exists {
-            let _ = conn.execute(
-                "ALTER TABLE rule_execution_log ADD COLUMN total_items INTEGER",
-                [],
-            );
-        }
+    Ok(tx.last_insert_rowid())
+}
 
-        let partial_column_exists: bool = conn
-            .query_row(
-                "SELECT COUNT(*) FROM pragma_table_info('rule_execution_log') WHERE name = 'partial'",
-                [],
-                |row| Ok(row.get::<_, i64>(0)? > 0),
-            )
-            .unwrap_or(false);
+/// Loads every data key, creating the first one (`new_key`) on an empty database.
+/// Keys still stored in the clear are wrapped in place once a master key is set;
+/// without one they keep loading, but no new unwrapped key is created unless allowed.
+fn load_data_keys(conn: &mut Connection, master_key: Option<&MasterKey>, new_key: [u8; 32]) -> Result<LoadedDataKeys, String> {
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    let stored: Vec<(i64, Vec<u8>, Option<Vec<u8>>, Option<String>, bool)> = tx
+        .prepare("SELECT id, key_material, wrap_nonce, master_key_id, active FROM data_keys ORDER BY id")
+        .and_then(|mut stmt| {
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)))?
+                .collect();
+            rows
+        })
+        .map_err(|e| format!("Failed to load data keys: {}", e))?;
+
+    let mut loaded = LoadedDataKeys {
+        active: 0,
+        keys: HashMap::new(),
+        stored_unwrapped: false,
+    };
+
+    if stored.is_empty() {
+        loaded.active = insert_data_key(&tx, master_key, &new_key)?;
+        loaded.keys.insert(loaded.active, new_key);
+        loaded.stored_unwrapped = master_key.is_none();
+    }
 
-        if !partial_column_exists {
-            let _ = conn.execute(
-                "ALTER TABLE rule_execution_log ADD COLUMN partial BOOLEAN",
-                [],
-            );
+    for (id, key_material, wrap_nonce, master_key_id, active) in stored {
+        let key = match wrap_nonce {
+            Some(nonce) => {
+                let master_key = master_key.ok_or(
+                    "Stored API keys are protected by a master key; set TORBOX_MASTER_KEY or TORBOX_MASTER_KEY_FILE",
+                )?;
+                if master_key_id.as_deref() != Some(master_key.id().as_str()) {
+                    return Err(format!(
+                        "The configured master key ({}) is not the one data key {} was wrapped with ({})",
+                        master_key.id(),
+                        id,
+                        master_key_id.unwrap_or_default()
+                    ));
+                }
+                master_key.unwrap(&key_material, &nonce)?
+            }
+            None => {
+                let key: [u8; 32] = key_material
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| "Invalid server key length".to_string())?;
+                match master_key {
+                    Some(master_key) => {
+                        let (wrapped, nonce) = master_key.wrap(&key)?;
+                        tx.execute(
+                            "UPDATE data_keys SET key_material = ?, wrap_nonce = ?, master_key_id = ? WHERE id = ?",
+                            params![wrapped, nonce, master_key.id(), id],
+                        )
+                        .map_err(|e| format!("Failed to wrap data key: {}", e))?;
+                        log!("Wrapped data key {} with the master key", id);
+                    }
+                    None => loaded.stored_unwrapped = true,
+                }
+                key
+            }
+        };
+
+        if active {
+            loaded.active = id;
         }
+        loaded.keys.insert(id, key);
+    }
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_rules_api_key_hash ON automation_rules(api_key_hash)",
-            [],
-        )
-        .map_err(|e| format!("Failed to create index: {}", e))?;
+    if loaded.active == 0 {
+        return Err("No active data key".to_string());
+    }
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_rules_enabled ON automation_rules(enabled)",
-            [],
-        )
-        .map_err(|e| format!("Failed to create index: {}", e))?;
+    tx.commit().map_err(|e| format!("Failed to save data keys: {}", e))?;
+    Ok(loaded)
+}
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_logs_rule_id ON rule_execution_log(rule_id)",
-            [],
-        )
-        .map_err(|e| format!("Failed to create index: {}", e))?;
+pub struct Database {
+    pool: ConnectionPool,
+    encryption: Arc<EncryptionService>,
+    master_key: Option<MasterKey>,
+}
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_logs_api_key_hash ON rule_execution_log(api_key_hash)",
-            [],
-        )
-        .map_err(|e| format!("Failed to create index: {}", e))?;
+impl Database {
+    pub async fn new<P: AsRef<Path>>(db_path: P, read_connections: usize, master_key: Option<MasterKey>) -> Result<Self, String> {
+        let db_dir = db_path.as_ref().parent().ok_or("Invalid database path")?;
+        std::fs::create_dir_all(db_dir).map_err(|e| format!("Failed to create data directory: {}", e))?;
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_logs_executed_at ON rule_execution_log(executed_at)",
-            [],
-        )
-        .map_err(|e| format!("Failed to create index: {}", e))?;
+        let db_path_ref = db_path.as_ref().to_path_buf();
+        let pool = tokio::task::spawn_blocking(move || {
+            ConnectionPool::open(&db_path_ref, read_connections, |conn| {
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    if let Ok(metadata) = std::fs::metadata(&db_path_ref) {
+                        let mut perms = metadata.permissions();
+                        perms.set_mode(0o600);
+                        std::fs::set_permissions(&db_path_ref, perms)
+                            .map_err(|e| format!("Failed to set database permissions: {}", e))?;
+                    }
+                }
+
+                migrations::run(conn).map(|_| ())
+            })
+        })
+        .await
+        .map_err(|e| format!("Failed to open database: {}", e))??;
 
-        Ok(())
+        let db = Self {
+            pool,
+            encryption: Arc::new(EncryptionService::new()),
+            master_key,
+        };
+
+        db.initialize_encryption_key().await?;
+
+        let baselined = db.record_baseline_revisions().await?;
+        if baselined > 0 {
+            log!("Recorded the current definition of {} rules as their first revision", baselined);
+        }
+
+        Ok(db)
+    }
+
+    pub fn read_connections(&self) -> usize {
+        self.pool.read_connections()
     }
 
     async fn initialize_encryption_key(&self) -> Result<(), String> {
-        let conn = self.conn.lock().await;
+        if self.reload_data_keys().await? {
+            log!("WARNING: stored API keys are encrypted with a key kept in the database file. Set TORBOX_MASTER_KEY or TORBOX_MASTER_KEY_FILE to protect it.");
+        }
 
-        let count: i64 = conn
-            .query_row("SELECT COUNT(*) FROM server_key", [], |row| row.get(0))
-            .map_err(|e| format!("Failed to check server_key: {}", e))?;
+        let upgraded = self.reencrypt_api_keys().await?;
+        if upgraded > 0 {
+            log!("Re-encrypted {} stored API keys under the current data key", upgraded);
+        }
 
-        if count == 0 {
-            let key = EncryptionService::generate_key().await;
-            conn.execute(
-                "INSERT INTO server_key (id, encryption_key) VALUES (1, ?)",
-                params![key.as_slice()],
-            )
-            .map_err(|e| format!("Failed to insert server key: {}", e))?;
+        Ok(())
+    }
 
-            self.encryption.initialize(key).await;
-        } else {
-            let key: Vec<u8> = conn
-                .query_row("SELECT encryption_key FROM server_key WHERE id = 1", [], |row| {
-                    row.get(0)
-                })
-                .map_err(|e| format!("Failed to get server key: {}", e))?;
+    /// Reads the data keys into the encryption service. Returns whether any of them
+    /// is stored unwrapped.
+    async fn reload_data_keys(&self) -> Result<bool, String> {
+        let master_key = self.master_key.clone();
+        let new_key = EncryptionService::generate_key().await;
+        let loaded = self.pool.write(move |conn| load_data_keys(conn, master_key.as_ref(), new_key)).await?;
 
-            if key.len() != 32 {
-                return Err("Invalid server key length".to_string());
-            }
+        self.encryption.initialize(loaded.active, loaded.keys).await;
+        Ok(loaded.stored_unwrapped)
+    }
+
+    /// Re-encrypts every stored API key that is not yet under the active data key
+    /// with its hash as associated data. Rows that changed meanwhile are left alone.
+    async fn reencrypt_api_keys(&self) -> Result<usize, String> {
+        let active = self.encryption.active_key_id().await.ok_or("Encryption key not initialized")?;
 
-            let mut key_array = [0u8; 32];
-            key_array.copy_from_slice(&key);
-            self.encryption.initialize(key_array).await;
+        let rows: Vec<(String, Vec<u8>, Vec<u8>, Option<i64>, i64)> = self.pool.read(move |conn| {
+            conn.prepare(
+                "SELECT api_key_hash, encrypted_api_key, nonce, data_key_id, cipher_version FROM api_keys
+                 WHERE data_key_id IS NOT ?1 OR cipher_version < ?2",
+            )
+            .and_then(|mut stmt| {
+                let rows = stmt
+                    .query_map(params![active, CIPHER_BOUND], |row| {
+                        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+                    })?
+                    .collect();
+                rows
+            })
+            .map_err(|e| format!("Failed to load API keys: {}", e))
+        }).await?;
+
+        let mut reencrypted = 0;
+        for (api_key_hash, ciphertext, nonce, data_key_id, cipher_version) in rows {
+            let Some(data_key_id) = data_key_id else {
+                log!("Skipping API key {}: no data key recorded", &api_key_hash[..api_key_hash.len().min(12)]);
+                continue;
+            };
+            let api_key = match self.encryption
+                .decrypt_api_key(data_key_id, cipher_version, &api_key_hash, &ciphertext, &nonce)
+                .await
+            {
+                Ok(api_key) => api_key,
+                Err(e) => {
+                    log!("Skipping API key {}: {}", &api_key_hash[..api_key_hash.len().min(12)], e);
+                    continue;
+                }
+            };
+
+            let encrypted = self.encryption.encrypt_api_key(&api_key, &api_key_hash).await?;
+            let updated = self.pool.write(move |conn| {
+                conn.execute(
+                    "UPDATE api_keys SET encrypted_api_key = ?1, nonce = ?2, data_key_id = ?3, cipher_version = ?4
+                     WHERE api_key_hash = ?5 AND nonce = ?6
+                       AND EXISTS (SELECT 1 FROM data_keys WHERE id = ?3 AND active)",
+                    params![encrypted.ciphertext, encrypted.nonce, encrypted.data_key_id, CIPHER_BOUND, api_key_hash, nonce],
+                )
+                .map_err(|e| format!("Failed to re-encrypt API key: {}", e))
+            }).await?;
+            reencrypted += updated;
         }
 
-        Ok(())
+        Ok(reencrypted)
+    }
+
+    /// Switches new encryptions to a fresh data key, re-encrypts every stored API key
+    /// under it and drops retired keys nothing refers to any more. Safe to run while
+    /// the server is up: readers keep decrypting with the old key until their row
+    /// has been rewritten.
+    pub async fn rotate_data_key(&self) -> Result<KeyRotationReport, String> {
+        let master_key = self.master_key.clone();
+        let new_key = EncryptionService::generate_key().await;
+        let data_key_id = self.pool.write(move |conn| {
+            let tx = conn
+                .transaction_with_behavior(TransactionBehavior::Immediate)
+                .map_err(|e| format!("Failed to start transaction: {}", e))?;
+            tx.execute("UPDATE data_keys SET active = false, retired_at = CURRENT_TIMESTAMP WHERE active", [])
+                .map_err(|e| format!("Failed to retire data key: {}", e))?;
+            let id = insert_data_key(&tx, master_key.as_ref(), &new_key)?;
+            tx.commit().map_err(|e| format!("Failed to save data key: {}", e))?;
+            Ok(id)
+        }).await?;
+
+        self.reload_data_keys().await?;
+        let reencrypted = self.reencrypt_api_keys().await?;
+
+        let (remaining, pruned_keys) = self.pool.write(move |conn| {
+            let remaining: i64 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM api_keys WHERE data_key_id IS NOT ?",
+                    params![data_key_id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| format!("Failed to count API keys: {}", e))?;
+
+            let pruned = conn
+                .execute(
+                    "DELETE FROM data_keys WHERE NOT active
+                     AND id NOT IN (SELECT data_key_id FROM api_keys WHERE data_key_id IS NOT NULL)",
+                    [],
+                )
+                .map_err(|e| format!("Failed to prune data keys: {}", e))?;
+
+            Ok((remaining, pruned))
+        }).await?;
+
+        Ok(KeyRotationReport {
+            data_key_id,
+            reencrypted,
+            remaining,
+            pruned_keys,
+        })
     }
 
     pub async fn get_encryption_service(&self) -> Arc<EncryptionService> {
@@ -229,282 +492,878 @@ impl Database {
 
     pub async fn save_api_key(&self, api_key: &str) -> Result<String, String> {
         let hash = EncryptionService::hash_api_key(api_key);
-        let (encrypted, nonce) = self.encryption.encrypt_api_key(api_key).await?;
 
-        let conn = self.conn.lock().await;
-
-        conn.execute(
-            "INSERT OR REPLACE INTO api_keys (api_key_hash, encrypted_api_key, nonce, last_used_at)
-             VALUES (?, ?, ?, CURRENT_TIMESTAMP)",
-            params![hash, encrypted.as_slice(), nonce.as_slice()],
-        )
-        .map_err(|e| format!("Failed to save API key: {}", e))?;
+        // A rotation in another process may switch the active data key between
+        // encrypting and storing; reload and try again once if it did.
+        for _ in 0..2 {
+            let encrypted = self.encryption.encrypt_api_key(api_key, &hash).await?;
+            let api_key_hash = hash.clone();
+            let stored = self.pool.write(move |conn| {
+                let tx = conn
+                    .transaction_with_behavior(TransactionBehavior::Immediate)
+                    .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+                let active: Option<i64> = tx
+                    .query_row("SELECT id FROM data_keys WHERE active", [], |row| row.get(0))
+                    .optional()
+                    .map_err(|e| format!("Failed to read data key: {}", e))?;
+                if active != Some(encrypted.data_key_id) {
+                    return Ok(false);
+                }
+
+                tx.execute(
+                    "INSERT INTO api_keys (api_key_hash, encrypted_api_key, nonce, data_key_id, cipher_version, last_used_at)
+                     VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
+                     ON CONFLICT(api_key_hash) DO UPDATE SET
+                         encrypted_api_key = excluded.encrypted_api_key,
+                         nonce = excluded.nonce,
+                         data_key_id = excluded.data_key_id,
+                         cipher_version = excluded.cipher_version,
+                         last_used_at = excluded.last_used_at",
+                    params![api_key_hash, encrypted.ciphertext, encrypted.nonce, encrypted.data_key_id, CIPHER_BOUND],
+                )
+                .map_err(|e| format!("Failed to save API key: {}", e))?;
+
+                tx.commit().map_err(|e| format!("Failed to save API key: {}", e))?;
+                Ok(true)
+            }).await?;
+
+            if stored {
+                return Ok(hash);
+            }
+            self.reload_data_keys().await?;
+        }
 
-        Ok(hash)
+        Err("Failed to save API key: the data key changed during rotation".to_string())
     }
 
+    /// Runs on every scheduled run and poll, so it only reads; `last_used_at` is
+    /// bumped at most once an hour, and skipped while another write is going on.
     pub async fn get_api_key(&self, api_key_hash: &str) -> Result<String, String> {
-        let conn = self.conn.lock().await;
+        let hash = api_key_hash.to_string();
+        let (encrypted, nonce, data_key_id, cipher_version, stale): (Vec<u8>, Vec<u8>, Option<i64>, i64, bool) = self.pool.read(move |conn| {
+            conn.query_row(
+                "SELECT encrypted_api_key, nonce, data_key_id, cipher_version,
+                        last_used_at IS NULL OR last_used_at < datetime('now', '-1 hour')
+                 FROM api_keys WHERE api_key_hash = ?",
+                params![hash],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+            )
+            .map_err(|e| format!("Failed to get API key: {}", e))
+        }).await?;
+
+        if stale {
+            let hash = api_key_hash.to_string();
+            let touched = self.pool.try_write(move |conn| {
+                conn.execute(
+                    "UPDATE api_keys SET last_used_at = CURRENT_TIMESTAMP WHERE api_key_hash = ?",
+                    params![hash],
+                )
+                .map_err(|e| format!("Failed to update last_used_at: {}", e))
+            }).await;
+            if let Some(Err(e)) = touched {
+                log!("{}", e);
+            }
+        }
 
-        let (encrypted, nonce): (Vec<u8>, Vec<u8>) = conn
-            .query_row(
-                "SELECT encrypted_api_key, nonce FROM api_keys WHERE api_key_hash = ?",
+        let data_key_id = data_key_id.ok_or("Failed to get API key: no data key recorded")?;
+        if !self.encryption.has_key(data_key_id).await {
+            // Written under a key rotated in by another process.
+            self.reload_data_keys().await?;
+        }
+
+        self.encryption
+            .decrypt_api_key(data_key_id, cipher_version, api_key_hash, &encrypted, &nonce)
+            .await
+    }
+
+    pub async fn get_all_api_key_hashes(&self) -> Result<Vec<String>, String> {
+        self.pool.read(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT api_key_hash FROM api_keys")
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+            let hashes = stmt
+                .query_map([], |row| row.get::<_, String>(0))
+                .map_err(|e| format!("Failed to query API keys: {}", e))?
+                .collect::<Result<Vec<String>, _>>()
+                .map_err(|e| format!("Failed to collect API keys: {}", e))?;
+
+            Ok(hashes)
+        }).await
+    }
+
+    /// Counts a rejection of the key by TorBox. Returns true if this failure
+    /// suspended the key's rules.
+    pub async fn record_auth_failure(&self, api_key_hash: &str, error: &str) -> Result<bool, String> {
+        let (api_key_hash, error) = (api_key_hash.to_string(), error.to_string());
+        self.pool.write(move |conn| {
+            conn.execute(
+                "UPDATE api_keys SET auth_failures = auth_failures + 1, last_auth_error = ? WHERE api_key_hash = ?",
+                params![error, api_key_hash],
+            )
+            .map_err(|e| format!("Failed to record authentication failure: {}", e))?;
+
+            let suspended = conn
+                .execute(
+                    "UPDATE api_keys SET suspended_at = CURRENT_TIMESTAMP
+                     WHERE api_key_hash = ? AND suspended_at IS NULL AND auth_failures >= ?",
+                    params![api_key_hash, AUTH_FAILURE_THRESHOLD],
+                )
+                .map_err(|e| format!("Failed to suspend API key: {}", e))?;
+
+            Ok(suspended > 0)
+        }).await
+    }
+
+    /// Resets the key's failure count and lifts its suspension. Returns true if
+    /// the key was suspended.
+    pub async fn record_auth_success(&self, api_key_hash: &str) -> Result<bool, String> {
+        let api_key_hash = api_key_hash.to_string();
+        self.pool.write(move |conn| {
+            let was_suspended: Option<bool> = conn
+                .query_row(
+                    "SELECT suspended_at IS NOT NULL FROM api_keys WHERE api_key_hash = ? AND (auth_failures > 0 OR suspended_at IS NOT NULL)",
+                    params![api_key_hash],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(|e| format!("Failed to read API key status: {}", e))?;
+
+            let Some(was_suspended) = was_suspended else {
+                return Ok(false);
+            };
+
+            conn.execute(
+                "UPDATE api_keys SET auth_failures = 0, last_auth_error = NULL, suspended_at = NULL WHERE api_key_hash = ?",
                 params![api_key_hash],
-                |row| Ok((row.get(0)?, row.get(1)?)),
             )
-            .map_err(|e| format!("Failed to get API key: {}", e))?;
+            .map_err(|e| format!("Failed to reset API key status: {}", e))?;
 
-        conn.execute(
-            "UPDATE api_keys SET last_used_at = CURRENT_TIMESTAMP WHERE api_key_hash = ?",
-            params![api_key_hash],
-        )
-        .map_err(|e| format!("Failed to update last_used_at: {}", e))?;
+            Ok(was_suspended)
+        }).await
+    }
 
-        self.encryption.decrypt_api_key(&encrypted, &nonce).await
+    pub async fn is_key_suspended(&self, api_key_hash: &str) -> Result<bool, String> {
+        let api_key_hash = api_key_hash.to_string();
+        self.pool.read(move |conn| {
+            conn.query_row(
+                "SELECT COUNT(*) FROM api_keys WHERE api_key_hash = ? AND suspended_at IS NOT NULL",
+                params![api_key_hash],
+                |row| Ok(row.get::<_, i64>(0)? > 0),
+            )
+            .map_err(|e| format!("Failed to read API key status: {}", e))
+        }).await
     }
 
-    pub async fn get_all_api_key_hashes(&self) -> Result<Vec<String>, String> {
-        let conn = self.conn.lock().await;
+    pub async fn set_torbox_user_id(&self, api_key_hash: &str, torbox_user_id: i64) -> Result<(), String> {
+        let api_key_hash = api_key_hash.to_string();
+        self.pool.write(move |conn| {
+            conn.execute(
+                "UPDATE api_keys SET torbox_user_id = ? WHERE api_key_hash = ?",
+                params![torbox_user_id, api_key_hash],
+            )
+            .map_err(|e| format!("Failed to save TorBox user: {}", e))?;
+            Ok(())
+        }).await
+    }
 
-        let mut stmt = conn
-            .prepare("SELECT api_key_hash FROM api_keys")
-            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+    /// The key's failure state and the other keys of its TorBox account that
+    /// own rules. `torbox_user_id` overrides the account recorded for the key,
+    /// for keys that aren't stored yet.
+    pub async fn get_key_status(&self, api_key_hash: &str, torbox_user_id: Option<i64>) -> Result<ApiKeyStatus, String> {
+        let api_key_hash = api_key_hash.to_string();
+        self.pool.read(move |conn| {
+            let stored = conn
+                .query_row(
+                    "SELECT auth_failures, last_auth_error, suspended_at, torbox_user_id FROM api_keys WHERE api_key_hash = ?",
+                    params![api_key_hash],
+                    |row| Ok((row.get::<_, i64>(0)?, row.get::<_, Option<String>>(1)?, row.get::<_, Option<String>>(2)?, row.get::<_, Option<i64>>(3)?)),
+                )
+                .optional()
+                .map_err(|e| format!("Failed to read API key status: {}", e))?;
+
+            let mut status = ApiKeyStatus::default();
+            let mut account = torbox_user_id;
+            if let Some((auth_failures, last_auth_error, suspended_at, stored_user_id)) = stored {
+                status.stored = true;
+                status.auth_failures = auth_failures;
+                status.last_auth_error = last_auth_error;
+                status.suspended_at = suspended_at;
+                account = account.or(stored_user_id);
+            }
 
-        let hashes = stmt
-            .query_map([], |row| Ok(row.get::<_, String>(0)?))
-            .map_err(|e| format!("Failed to query API keys: {}", e))?
-            .collect::<Result<Vec<String>, _>>()
-            .map_err(|e| format!("Failed to collect API keys: {}", e))?;
+            let Some(account) = account else {
+                return Ok(status);
+            };
+
+            let mut stmt = conn
+                .prepare(
+                    "SELECT k.api_key_hash, COUNT(r.id), k.suspended_at
+                     FROM api_keys k JOIN automation_rules r ON r.api_key_hash = k.api_key_hash
+                     WHERE k.torbox_user_id = ? AND k.api_key_hash != ?
+                     GROUP BY k.api_key_hash
+                     ORDER BY k.last_used_at DESC",
+                )
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+            status.other_keys = stmt
+                .query_map(params![account, api_key_hash], |row| {
+                    Ok(OtherApiKey {
+                        api_key_hash: row.get(0)?,
+                        rule_count: row.get(1)?,
+                        suspended_at: row.get(2)?,
+                    })
+                })
+                .map_err(|e| format!("Failed to query API keys: {}", e))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to collect API keys: {}", e))?;
 
-        Ok(hashes)
+            Ok(status)
+        }).await
     }
 
-    pub async fn save_rule(&self, rule: &AutomationRule) -> Result<i64, String> {
-        let conn = self.conn.lock().await;
+    /// Moves every rule, log entry and pending transfer of `old_hash` over to
+    /// `new_hash`, which must already be stored, then forgets the old key.
+    /// Returns the number of rules moved.
+    pub async fn migrate_rules(&self, old_hash: &str, new_hash: &str) -> Result<usize, String> {
+        if old_hash == new_hash {
+            return Err("Cannot migrate rules to the same API key".to_string());
+        }
+
+        let (old_hash, new_hash) = (old_hash.to_string(), new_hash.to_string());
+        self.pool.write(move |conn| {
+            let tx = conn
+                .transaction()
+                .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+            let new_key_stored = tx
+                .query_row(
+                    "SELECT COUNT(*) FROM api_keys WHERE api_key_hash = ?",
+                    params![new_hash],
+                    |row| Ok(row.get::<_, i64>(0)? > 0),
+                )
+                .map_err(|e| format!("Failed to look up API key: {}", e))?;
+            if !new_key_stored {
+                return Err("The new API key is not stored; sign in with it first".to_string());
+            }
 
+            let moved = tx
+                .execute(
+                    "UPDATE automation_rules SET api_key_hash = ?, updated_at = CURRENT_TIMESTAMP WHERE api_key_hash = ?",
+                    params![new_hash, old_hash],
+                )
+                .map_err(|e| format!("Failed to migrate rules: {}", e))?;
+            tx.execute(
+                "UPDATE rule_execution_log SET api_key_hash = ? WHERE api_key_hash = ?",
+                params![new_hash, old_hash],
+            )
+            .map_err(|e| format!("Failed to migrate execution logs: {}", e))?;
+            tx.execute(
+                "UPDATE transfer_jobs SET api_key_hash = ? WHERE api_key_hash = ?",
+                params![new_hash, old_hash],
+            )
+            .map_err(|e| format!("Failed to migrate transfer jobs: {}", e))?;
+            tx.execute(
+                "UPDATE item_actions SET api_key_hash = ? WHERE api_key_hash = ?",
+                params![new_hash, old_hash],
+            )
+            .map_err(|e| format!("Failed to migrate action history: {}", e))?;
+            tx.execute(
+                "UPDATE deleted_torrents SET api_key_hash = ? WHERE api_key_hash = ?",
+                params![new_hash, old_hash],
+            )
+            .map_err(|e| format!("Failed to migrate deleted torrents: {}", e))?;
+            tx.execute("DELETE FROM api_keys WHERE api_key_hash = ?", params![old_hash])
+                .map_err(|e| format!("Failed to remove old API key: {}", e))?;
+
+            tx.commit().map_err(|e| format!("Failed to migrate rules: {}", e))?;
+            Ok(moved)
+        }).await
+    }
+
+    /// Inserts or updates a rule and records the result as its next revision.
+    /// Returns the rule id and the revision number.
+    pub async fn save_rule(&self, rule: &AutomationRule, source: &RevisionSource) -> Result<(i64, i64), String> {
         let trigger_json = serde_json::to_string(&rule.trigger_config)
             .map_err(|e| format!("Failed to serialize trigger_config: {}", e))?;
         let conditions_json = serde_json::to_string(&rule.conditions)
             .map_err(|e| format!("Failed to serialize conditions: {}", e))?;
         let action_json = serde_json::to_string(&rule.action_config)
             .map_err(|e| format!("Failed to serialize action_config: {}", e))?;
+        let download_types_json = serde_json::to_string(&rule.download_types)
+            .map_err(|e| format!("Failed to serialize download_types: {}", e))?;
+        let notification_json = rule.notification.as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| format!("Failed to serialize notification: {}", e))?;
+        let schedule_window_json = rule.schedule_window.as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| format!("Failed to serialize schedule_window: {}", e))?;
+        let quota_json = rule.quota.as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| format!("Failed to serialize quota: {}", e))?;
+        let cooldown_json = rule.cooldown.as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| format!("Failed to serialize cooldown: {}", e))?;
+        let definition_json = serde_json::to_string(&BundledRule::from(rule))
+            .map_err(|e| format!("Failed to serialize revision: {}", e))?;
+
+        let (id, api_key_hash, name, enabled) = (rule.id, rule.api_key_hash.clone(), rule.name.clone(), rule.enabled);
+        let source = source.clone();
+        self.pool.write(move |conn| {
+            let tx = conn
+                .transaction()
+                .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+            let id = if let Some(id) = id {
+                let updated = tx.execute(
+                    "UPDATE automation_rules 
+                     SET name = ?, enabled = ?, trigger_config = ?, conditions = ?, action_config = ?, download_types = ?, notification_config = ?, schedule_window = ?, quota = ?, cooldown = ?, updated_at = CURRENT_TIMESTAMP
+                     WHERE id = ? AND api_key_hash = ?",
+                    params![name, enabled, trigger_json, conditions_json, action_json, download_types_json, notification_json, schedule_window_json, quota_json, cooldown_json, id, api_key_hash],
+                )
+                .map_err(|e| format!("Failed to update rule: {}", e))?;
+                if updated == 0 {
+                    return Err(format!("Rule {} not found", id));
+                }
+                id
+            } else {
+                tx.execute(
+                    "INSERT INTO automation_rules (api_key_hash, name, enabled, trigger_config, conditions, action_config, download_types, notification_config, schedule_window, quota, cooldown)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                    params![api_key_hash, name, enabled, trigger_json, conditions_json, action_json, download_types_json, notification_json, schedule_window_json, quota_json, cooldown_json],
+                )
+                .map_err(|e| format!("Failed to insert rule: {}", e))?;
+                tx.last_insert_rowid()
+            };
+
+            let revision = insert_revision(&tx, id, &definition_json, &source)?;
+            tx.commit().map_err(|e| format!("Failed to save rule: {}", e))?;
+            Ok((id, revision))
+        }).await
+    }
 
-        if let Some(id) = rule.id {
-            conn.execute(
-                "UPDATE automation_rules 
-                 SET name = ?, enabled = ?, trigger_config = ?, conditions = ?, action_config = ?, updated_at = CURRENT_TIMESTAMP
-                 WHERE id = ? AND api_key_hash = ?",
-                params![rule.name, rule.enabled, trigger_json, conditions_json, action_json, id, rule.api_key_hash],
-            )
-            .map_err(|e| format!("Failed to update rule: {}", e))?;
-            Ok(id)
-        } else {
-            conn.execute(
-                "INSERT INTO automation_rules (api_key_hash, name, enabled, trigger_config, conditions, action_config)
-                 VALUES (?, ?, ?, ?, ?, ?)",
-                params![rule.api_key_hash, rule.name, rule.enabled, trigger_json, conditions_json, action_json],
+    /// Every revision of a rule, newest first.
+    pub async fn get_rule_revisions(&self, rule_id: i64, api_key_hash: &str) -> Result<Vec<RuleRevision>, String> {
+        let api_key_hash = api_key_hash.to_string();
+        self.pool.read(move |conn| {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT r.rule_id, r.revision, r.action, r.client_ip, r.created_at, r.definition
+                     FROM rule_revisions r
+                     JOIN automation_rules a ON a.id = r.rule_id
+                     WHERE r.rule_id = ? AND a.api_key_hash = ?
+                     ORDER BY r.revision DESC",
+                )
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+            let revisions = stmt
+                .query_map(params![rule_id, api_key_hash], revision_from_row)
+                .map_err(|e| format!("Failed to query rule revisions: {}", e))?;
+
+            revisions
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to collect rule revisions: {}", e))
+        }).await
+    }
+
+    pub async fn get_rule_revision(&self, rule_id: i64, api_key_hash: &str, revision: i64) -> Result<Option<RuleRevision>, String> {
+        let api_key_hash = api_key_hash.to_string();
+        self.pool.read(move |conn| {
+            conn.query_row(
+                "SELECT r.rule_id, r.revision, r.action, r.client_ip, r.created_at, r.definition
+                 FROM rule_revisions r
+                 JOIN automation_rules a ON a.id = r.rule_id
+                 WHERE r.rule_id = ? AND a.api_key_hash = ? AND r.revision = ?",
+                params![rule_id, api_key_hash, revision],
+                revision_from_row,
             )
-            .map_err(|e| format!("Failed to insert rule: {}", e))?;
-            Ok(conn.last_insert_rowid())
+            .optional()
+            .map_err(|e| format!("Failed to get rule revision: {}", e))
+        }).await
+    }
+
+    /// Gives rules saved before revisions were kept their current definition as
+    /// revision 1, so there is something to diff and roll back to.
+    async fn record_baseline_revisions(&self) -> Result<usize, String> {
+        let rules = self.pool.read(|conn| {
+            let mut stmt = conn
+                .prepare(format!("SELECT {} FROM automation_rules WHERE revision IS NULL", RULE_COLUMNS).as_str())
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+            let rules = stmt
+                .query_map([], rule_from_row)
+                .map_err(|e| format!("Failed to query rules: {}", e))?;
+
+            rules
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to collect rules: {}", e))
+        }).await?;
+
+        if rules.is_empty() {
+            return Ok(0);
+        }
+
+        let mut definitions = Vec::with_capacity(rules.len());
+        for rule in &rules {
+            let definition_json = serde_json::to_string(&BundledRule::from(rule))
+                .map_err(|e| format!("Failed to serialize revision: {}", e))?;
+            definitions.push((rule.id.unwrap_or(0), definition_json));
         }
+
+        self.pool.write(move |conn| {
+            let tx = conn
+                .transaction()
+                .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+            let source = RevisionSource::new("baseline", None);
+            for (rule_id, definition_json) in &definitions {
+                insert_revision(&tx, *rule_id, definition_json, &source)?;
+            }
+
+            tx.commit().map_err(|e| format!("Failed to record baseline revisions: {}", e))?;
+            Ok(definitions.len())
+        }).await
     }
 
     pub async fn count_rules_by_api_key(&self, api_key_hash: &str) -> Result<i64, String> {
-        let conn = self.conn.lock().await;
-
-        let count: i64 = conn
-            .query_row(
+        let api_key_hash = api_key_hash.to_string();
+        self.pool.read(move |conn| {
+            conn.query_row(
                 "SELECT COUNT(*) FROM automation_rules WHERE api_key_hash = ?",
                 params![api_key_hash],
                 |row| row.get(0),
             )
-            .map_err(|e| format!("Failed to count rules: {}", e))?;
-
-        Ok(count)
+            .map_err(|e| format!("Failed to count rules: {}", e))
+        }).await
     }
 
     pub async fn get_rules_by_api_key(&self, api_key_hash: &str) -> Result<Vec<AutomationRule>, String> {
-        let conn = self.conn.lock().await;
+        let api_key_hash = api_key_hash.to_string();
+        self.pool.read(move |conn| {
+            let mut stmt = conn
+                .prepare(
+                    format!("SELECT {} FROM automation_rules WHERE api_key_hash = ? ORDER BY created_at DESC", RULE_COLUMNS).as_str(),
+                )
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+            let rules = stmt
+                .query_map(params![api_key_hash], rule_from_row)
+                .map_err(|e| format!("Failed to query rules: {}", e))?
+                .collect::<Result<Vec<AutomationRule>, _>>()
+                .map_err(|e| format!("Failed to collect rules: {}", e))?;
+
+            Ok(rules)
+        }).await
+    }
 
-        let mut stmt = conn
-            .prepare(
-                "SELECT id, api_key_hash, name, enabled, trigger_config, conditions, action_config, created_at, updated_at
-                 FROM automation_rules WHERE api_key_hash = ? ORDER BY created_at DESC",
-            )
-            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
-
-        let rules = stmt
-            .query_map(params![api_key_hash], |row| {
-                let trigger_json: String = row.get(4)?;
-                let conditions_json: String = row.get(5)?;
-                let action_json: String = row.get(6)?;
-
-                Ok(AutomationRule {
-                    id: Some(row.get(0)?),
-                    api_key_hash: row.get(1)?,
-                    name: row.get(2)?,
-                    enabled: row.get(3)?,
-                    trigger_config: serde_json::from_str(&trigger_json)
-                        .map_err(|_| rusqlite::Error::InvalidColumnType(4, "trigger_config".to_string(), rusqlite::types::Type::Text))?,
-                    conditions: serde_json::from_str(&conditions_json)
-                        .map_err(|_| rusqlite::Error::InvalidColumnType(5, "conditions".to_string(), rusqlite::types::Type::Text))?,
-                    action_config: serde_json::from_str(&action_json)
-                        .map_err(|_| rusqlite::Error::InvalidColumnType(6, "action_config".to_string(), rusqlite::types::Type::Text))?,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                })
-            })
-            .map_err(|e| format!("Failed to query rules: {}", e))?
-            .collect::<Result<Vec<AutomationRule>, _>>()
-            .map_err(|e| format!("Failed to collect rules: {}", e))?;
+    /// Enabled rules whose API key isn't suspended.
+    pub async fn get_all_enabled_rules(&self) -> Result<Vec<AutomationRule>, String> {
+        self.pool.read(|conn| {
+            let mut stmt = conn
+                .prepare(
+                    format!(
+                        "SELECT {} FROM automation_rules WHERE enabled = true
+                         AND api_key_hash NOT IN (SELECT api_key_hash FROM api_keys WHERE suspended_at IS NOT NULL)",
+                        RULE_COLUMNS
+                    ).as_str(),
+                )
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+            let rules = stmt
+                .query_map([], rule_from_row)
+                .map_err(|e| format!("Failed to query rules: {}", e))?
+                .collect::<Result<Vec<AutomationRule>, _>>()
+                .map_err(|e| format!("Failed to collect rules: {}", e))?;
+
+            Ok(rules)
+        }).await
+    }
 
-        Ok(rules)
+    pub async fn get_rule_by_id(&self, rule_id: i64, api_key_hash: &str) -> Result<Option<AutomationRule>, String> {
+        let api_key_hash = api_key_hash.to_string();
+        self.pool.read(move |conn| {
+            let result = conn
+                .query_row(
+                    format!("SELECT {} FROM automation_rules WHERE id = ? AND api_key_hash = ?", RULE_COLUMNS).as_str(),
+                    params![rule_id, api_key_hash],
+                    rule_from_row,
+                );
+
+            match result {
+                Ok(rule) => Ok(Some(rule)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(format!("Failed to get rule: {}", e)),
+            }
+        }).await
     }
 
-    pub async fn get_all_enabled_rules(&self) -> Result<Vec<AutomationRule>, String> {
-        let conn = self.conn.lock().await;
+    pub async fn delete_rule(&self, rule_id: i64, api_key_hash: &str) -> Result<bool, String> {
+        let api_key_hash = api_key_hash.to_string();
+        self.pool.write(move |conn| {
+            let tx = conn
+                .transaction()
+                .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+            let rule_exists = tx
+                .query_row(
+                    "SELECT COUNT(*) FROM automation_rules WHERE id = ? AND api_key_hash = ?",
+                    params![rule_id, api_key_hash],
+                    |row| row.get::<_, i64>(0),
+                )
+                .map_err(|e| format!("Failed to check rule existence: {}", e))?;
+
+            if rule_exists == 0 {
+                return Ok(false);
+            }
 
-        let mut stmt = conn
-            .prepare(
-                "SELECT id, api_key_hash, name, enabled, trigger_config, conditions, action_config, created_at, updated_at
-                 FROM automation_rules WHERE enabled = true",
+            tx.execute(
+                "DELETE FROM rule_execution_log WHERE rule_id = ?",
+                params![rule_id],
             )
-            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
-
-        let rules = stmt
-            .query_map([], |row| {
-                let trigger_json: String = row.get(4)?;
-                let conditions_json: String = row.get(5)?;
-                let action_json: String = row.get(6)?;
-
-                Ok(AutomationRule {
-                    id: Some(row.get(0)?),
-                    api_key_hash: row.get(1)?,
-                    name: row.get(2)?,
-                    enabled: row.get(3)?,
-                    trigger_config: serde_json::from_str(&trigger_json)
-                        .map_err(|_| rusqlite::Error::InvalidColumnType(4, "trigger_config".to_string(), rusqlite::types::Type::Text))?,
-                    conditions: serde_json::from_str(&conditions_json)
-                        .map_err(|_| rusqlite::Error::InvalidColumnType(5, "conditions".to_string(), rusqlite::types::Type::Text))?,
-                    action_config: serde_json::from_str(&action_json)
-                        .map_err(|_| rusqlite::Error::InvalidColumnType(6, "action_config".to_string(), rusqlite::types::Type::Text))?,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                })
-            })
-            .map_err(|e| format!("Failed to query rules: {}", e))?
-            .collect::<Result<Vec<AutomationRule>, _>>()
-            .map_err(|e| format!("Failed to collect rules: {}", e))?;
+            .map_err(|e| format!("Failed to delete execution logs: {}", e))?;
+
+            tx.execute(
+                "DELETE FROM transfer_jobs WHERE rule_id = ?",
+                params![rule_id],
+            )
+            .map_err(|e| format!("Failed to delete transfer jobs: {}", e))?;
+
+            tx.execute(
+                "DELETE FROM rule_revisions WHERE rule_id = ?",
+                params![rule_id],
+            )
+            .map_err(|e| format!("Failed to delete rule revisions: {}", e))?;
 
-        Ok(rules)
+            let rows_affected = tx
+                .execute(
+                    "DELETE FROM automation_rules WHERE id = ? AND api_key_hash = ?",
+                    params![rule_id, api_key_hash],
+                )
+                .map_err(|e| format!("Failed to delete rule: {}", e))?;
+
+            tx.commit().map_err(|e| format!("Failed to delete rule: {}", e))?;
+
+            Ok(rows_affected > 0)
+        }).await
     }
 
-    pub async fn get_rule_by_id(&self, rule_id: i64, api_key_hash: &str) -> Result<Option<AutomationRule>, String> {
-        let conn = self.conn.lock().await;
-
-        let result = conn
-            .query_row(
-                "SELECT id, api_key_hash, name, enabled, trigger_config, conditions, action_config, created_at, updated_at
-                 FROM automation_rules WHERE id = ? AND api_key_hash = ?",
-                params![rule_id, api_key_hash],
-                |row| {
-                    let trigger_json: String = row.get(4)?;
-                    let conditions_json: String = row.get(5)?;
-                    let action_json: String = row.get(6)?;
-
-                    Ok(AutomationRule {
-                        id: Some(row.get(0)?),
-                        api_key_hash: row.get(1)?,
-                        name: row.get(2)?,
-                        enabled: row.get(3)?,
-                        trigger_config: serde_json::from_str(&trigger_json)
-                            .map_err(|_| rusqlite::Error::InvalidColumnType(4, "trigger_config".to_string(), rusqlite::types::Type::Text))?,
-                        conditions: serde_json::from_str(&conditions_json)
-                            .map_err(|_| rusqlite::Error::InvalidColumnType(5, "conditions".to_string(), rusqlite::types::Type::Text))?,
-                        action_config: serde_json::from_str(&action_json)
-                            .map_err(|_| rusqlite::Error::InvalidColumnType(6, "action_config".to_string(), rusqlite::types::Type::Text))?,
-                        created_at: row.get(7)?,
-                        updated_at: row.get(8)?,
-                    })
-                },
-            );
+    /// Stores a run's log entry and adds every successful action step it took to
+    /// the per-download action history.
+    pub async fn log_execution(&self, log: &ExecutionLog) -> Result<(), String> {
+        let processed_items_json = log.processed_items.as_ref()
+            .and_then(|items| serde_json::to_string(items).ok());
 
-        match result {
-            Ok(rule) => Ok(Some(rule)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(format!("Failed to get rule: {}", e)),
+        let mut actions = Vec::new();
+        for item in log.processed_items.iter().flatten() {
+            let download_type_json = item.download_type.as_ref()
+                .map(serde_json::to_string)
+                .transpose()
+                .map_err(|e| format!("Failed to serialize download type: {}", e))?;
+            for step in item.steps.iter().filter(|step| step.success) {
+                if let Some(action_type) = &step.action_type {
+                    let action_type_json = serde_json::to_string(action_type)
+                        .map_err(|e| format!("Failed to serialize action type: {}", e))?;
+                    actions.push((item.id, item.hash.clone(), download_type_json.clone(), action_type_json));
+                }
+            }
         }
+
+        let log = log.clone();
+        self.pool.write(move |conn| {
+            let tx = conn
+                .transaction()
+                .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+            tx.execute(
+                "INSERT INTO rule_execution_log (rule_id, rule_name, api_key_hash, execution_type, items_processed, total_items, success, error_message, processed_items, partial, rule_revision)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    log.rule_id,
+                    log.rule_name,
+                    log.api_key_hash,
+                    log.execution_type,
+                    log.items_processed,
+                    log.total_items,
+                    log.success,
+                    log.error_message,
+                    processed_items_json,
+                    log.partial,
+                    log.rule_revision
+                ],
+            )
+            .map_err(|e| format!("Failed to log execution: {}", e))?;
+
+            let execution_log_id = tx.last_insert_rowid();
+            for (download_id, download_hash, download_type, action_type) in &actions {
+                tx.execute(
+                    "INSERT INTO item_actions (api_key_hash, download_id, download_hash, download_type, action_type, rule_id, execution_log_id)
+                     VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    params![log.api_key_hash, download_id, download_hash, download_type, action_type, log.rule_id, execution_log_id],
+                )
+                .map_err(|e| format!("Failed to record action history: {}", e))?;
+            }
+
+            tx.commit().map_err(|e| format!("Failed to log execution: {}", e))
+        }).await
     }
 
-    pub async fn delete_rule(&self, rule_id: i64, api_key_hash: &str) -> Result<bool, String> {
-        let conn = self.conn.lock().await;
+    /// Totals of the actions taken on each download of the key, plus how often
+    /// and when `rule_id` last acted on each.
+    pub async fn get_item_history(&self, api_key_hash: &str, rule_id: i64) -> Result<ItemHistory, String> {
+        let api_key_hash = api_key_hash.to_string();
+        self.pool.read(move |conn| {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT download_id, download_hash, action_type, COUNT(*), CAST(strftime('%s', MAX(acted_at)) AS INTEGER)
+                     FROM item_actions WHERE api_key_hash = ?
+                     GROUP BY download_id, download_hash, action_type",
+                )
+                .map_err(|e| format!("Failed to prepare query: {}", e))?;
+            let actions = stmt
+                .query_map(params![api_key_hash], |row| {
+                    let action_type_json: String = row.get(2)?;
+                    Ok((row.get(0)?, ActionTally {
+                        download_hash: row.get(1)?,
+                        action_type: serde_json::from_str(&action_type_json)
+                            .map_err(|_| rusqlite::Error::InvalidColumnType(2, "action_type".to_string(), rusqlite::types::Type::Text))?,
+                        count: row.get(3)?,
+                        last_at: row.get(4)?,
+                    }))
+                })
+                .map_err(|e| format!("Failed to query action history: {}", e))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to read action history: {}", e))?;
+
+            let mut stmt = conn
+                .prepare(
+                    "SELECT download_id, download_hash, COUNT(DISTINCT execution_log_id), CAST(strftime('%s', MAX(acted_at)) AS INTEGER)
+                     FROM item_actions WHERE api_key_hash = ? AND rule_id = ?
+                     GROUP BY download_id, download_hash",
+                )
+                .map_err(|e| format!("Failed to prepare query: {}", e))?;
+            let rule = stmt
+                .query_map(params![api_key_hash, rule_id], |row| {
+                    Ok((row.get(0)?, RuleTally {
+                        download_hash: row.get(1)?,
+                        runs: row.get(2)?,
+                        last_at: row.get(3)?,
+                    }))
+                })
+                .map_err(|e| format!("Failed to query action history: {}", e))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to read action history: {}", e))?;
 
-        let rule_exists = conn
-            .query_row(
-                "SELECT COUNT(*) FROM automation_rules WHERE id = ? AND api_key_hash = ?",
-                params![rule_id, api_key_hash],
-                |row| row.get::<_, i64>(0),
-            )
-            .map_err(|e| format!("Failed to check rule existence: {}", e))?;
+            Ok(ItemHistory::new(actions, rule))
+        }).await
+    }
 
-        if rule_exists == 0 {
-            return Ok(false);
+    pub async fn save_transfers(&self, transfers: &[TrackedTransfer]) -> Result<(), String> {
+        if transfers.is_empty() {
+            return Ok(());
         }
 
-        conn.execute(
-            "DELETE FROM rule_execution_log WHERE rule_id = ?",
-            params![rule_id],
-        )
-        .map_err(|e| format!("Failed to delete execution logs: {}", e))?;
-
-        let rows_affected = conn
-            .execute(
-                "DELETE FROM automation_rules WHERE id = ? AND api_key_hash = ?",
-                params![rule_id, api_key_hash],
-            )
-            .map_err(|e| format!("Failed to delete rule: {}", e))?;
+        let transfers = transfers.to_vec();
+        self.pool.write(move |conn| {
+            let tx = conn
+                .transaction()
+                .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+            for transfer in &transfers {
+                let download_type_json = serde_json::to_string(&transfer.download_type)
+                    .map_err(|e| format!("Failed to serialize download type: {}", e))?;
+                let provider_json = serde_json::to_string(&transfer.provider)
+                    .map_err(|e| format!("Failed to serialize provider: {}", e))?;
+                let on_success_json = serde_json::to_string(&transfer.on_success)
+                    .map_err(|e| format!("Failed to serialize follow-up actions: {}", e))?;
+
+                tx.execute(
+                    "INSERT INTO transfer_jobs (rule_id, rule_name, api_key_hash, batch_id, download_id, download_type, item_name, file_name, provider, job_id, status, on_success, rule_revision)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        transfer.rule_id,
+                        transfer.rule_name,
+                        transfer.api_key_hash,
+                        transfer.batch_id,
+                        transfer.download_id,
+                        download_type_json,
+                        transfer.item_name,
+                        transfer.file_name,
+                        provider_json,
+                        transfer.job_id,
+                        transfer.status,
+                        on_success_json,
+                        transfer.rule_revision
+                    ],
+                )
+                .map_err(|e| format!("Failed to save transfer job: {}", e))?;
+            }
 
-        Ok(rows_affected > 0)
+            tx.commit().map_err(|e| format!("Failed to save transfer jobs: {}", e))
+        }).await
     }
 
-    pub async fn log_execution(&self, log: &ExecutionLog) -> Result<(), String> {
-        let conn = self.conn.lock().await;
+    pub async fn get_pending_transfers(&self) -> Result<Vec<TrackedTransfer>, String> {
+        self.pool.read(|conn| {
+            let query = format!("SELECT {} FROM transfer_jobs WHERE status = ? ORDER BY id", TRANSFER_COLUMNS);
+            let mut stmt = conn
+                .prepare(&query)
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+            let transfers = stmt
+                .query_map(params![TRANSFER_PENDING], transfer_from_row)
+                .map_err(|e| format!("Failed to query transfer jobs: {}", e))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to collect transfer jobs: {}", e))?;
+
+            Ok(transfers)
+        }).await
+    }
 
-        let processed_items_json = log.processed_items.as_ref()
-            .and_then(|items| serde_json::to_string(items).ok());
+    pub async fn get_transfer_batch(&self, batch_id: &str) -> Result<Vec<TrackedTransfer>, String> {
+        let batch_id = batch_id.to_string();
+        self.pool.read(move |conn| {
+            let query = format!("SELECT {} FROM transfer_jobs WHERE batch_id = ? ORDER BY id", TRANSFER_COLUMNS);
+            let mut stmt = conn
+                .prepare(&query)
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+            let transfers = stmt
+                .query_map(params![batch_id], transfer_from_row)
+                .map_err(|e| format!("Failed to query transfer jobs: {}", e))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to collect transfer jobs: {}", e))?;
+
+            Ok(transfers)
+        }).await
+    }
 
-        conn.execute(
-            "INSERT INTO rule_execution_log (rule_id, rule_name, api_key_hash, execution_type, items_processed, total_items, success, error_message, processed_items, partial)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-            params![
-                log.rule_id,
-                log.rule_name,
-                log.api_key_hash,
-                log.execution_type,
-                log.items_processed,
-                log.total_items,
-                log.success,
-                log.error_message,
-                processed_items_json,
-                log.partial
-            ],
-        )
-        .map_err(|e| format!("Failed to log execution: {}", e))?;
+    pub async fn finish_transfer(&self, id: i64, status: &str) -> Result<(), String> {
+        let status = status.to_string();
+        self.pool.write(move |conn| {
+            conn.execute(
+                "UPDATE transfer_jobs SET status = ?, completed_at = CURRENT_TIMESTAMP WHERE id = ?",
+                params![status, id],
+            )
+            .map_err(|e| format!("Failed to update transfer job: {}", e))?;
 
-        Ok(())
+            Ok(())
+        }).await
     }
 
+    /// Deletes execution logs, and the action history, older than `days_to_keep`.
     pub async fn cleanup_old_logs(&self, days_to_keep: i64) -> Result<usize, String> {
-        let conn = self.conn.lock().await;
-
-        let rows_affected = conn
-            .execute(
+        self.pool.write(move |conn| {
+            conn.execute(
+                "DELETE FROM item_actions WHERE acted_at < datetime('now', '-' || ? || ' days')",
+                params![days_to_keep],
+            )
+            .map_err(|e| format!("Failed to cleanup old action history: {}", e))?;
+            conn.execute(
                 "DELETE FROM rule_execution_log WHERE executed_at < datetime('now', '-' || ? || ' days')",
                 params![days_to_keep],
             )
-            .map_err(|e| format!("Failed to cleanup old logs: {}", e))?;
+            .map_err(|e| format!("Failed to cleanup old logs: {}", e))
+        }).await
+    }
+
+    /// Stores an archived torrent and returns its id.
+    pub async fn save_deleted_torrent(&self, deleted: &DeletedTorrent) -> Result<i64, String> {
+        let files_json = serde_json::to_string(&deleted.files)
+            .map_err(|e| format!("Failed to serialize files: {}", e))?;
+        let torrent = deleted.clone();
+
+        self.pool.write(move |conn| {
+            conn.execute(
+                "INSERT INTO deleted_torrents (api_key_hash, torrent_id, hash, name, size, magnet, files, rule_id, rule_name)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    torrent.api_key_hash,
+                    torrent.torrent_id,
+                    torrent.hash,
+                    torrent.name,
+                    torrent.size,
+                    torrent.magnet,
+                    files_json,
+                    torrent.rule_id,
+                    torrent.rule_name
+                ],
+            )
+            .map_err(|e| format!("Failed to archive deleted torrent: {}", e))?;
+            Ok(conn.last_insert_rowid())
+        }).await
+    }
+
+    /// Archived torrents of an API key, most recently deleted first.
+    pub async fn get_deleted_torrents(&self, api_key_hash: &str) -> Result<Vec<DeletedTorrent>, String> {
+        let api_key_hash = api_key_hash.to_string();
+        self.pool.read(move |conn| {
+            let mut stmt = conn
+                .prepare(
+                    format!("SELECT {} FROM deleted_torrents WHERE api_key_hash = ? ORDER BY id DESC", DELETED_TORRENT_COLUMNS).as_str(),
+                )
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+            let deleted = stmt
+                .query_map(params![api_key_hash], deleted_torrent_from_row)
+                .map_err(|e| format!("Failed to query deleted torrents: {}", e))?;
+
+            deleted
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to collect deleted torrents: {}", e))
+        }).await
+    }
+
+    pub async fn get_deleted_torrent(&self, id: i64, api_key_hash: &str) -> Result<Option<DeletedTorrent>, String> {
+        let api_key_hash = api_key_hash.to_string();
+        self.pool.read(move |conn| {
+            conn.query_row(
+                format!("SELECT {} FROM deleted_torrents WHERE id = ? AND api_key_hash = ?", DELETED_TORRENT_COLUMNS).as_str(),
+                params![id, api_key_hash],
+                deleted_torrent_from_row,
+            )
+            .optional()
+            .map_err(|e| format!("Failed to get deleted torrent: {}", e))
+        }).await
+    }
+
+    pub async fn mark_torrent_readded(&self, id: i64, readded_torrent_id: Option<i32>) -> Result<(), String> {
+        self.pool.write(move |conn| {
+            conn.execute(
+                "UPDATE deleted_torrents SET readded_at = CURRENT_TIMESTAMP, readded_torrent_id = ? WHERE id = ?",
+                params![readded_torrent_id, id],
+            )
+            .map_err(|e| format!("Failed to update deleted torrent: {}", e))?;
+
+            Ok(())
+        }).await
+    }
+
+    pub async fn forget_deleted_torrent(&self, id: i64, api_key_hash: &str) -> Result<bool, String> {
+        let api_key_hash = api_key_hash.to_string();
+        self.pool.write(move |conn| {
+            conn.execute(
+                "DELETE FROM deleted_torrents WHERE id = ? AND api_key_hash = ?",
+                params![id, api_key_hash],
+            )
+            .map(|rows| rows > 0)
+            .map_err(|e| format!("Failed to forget deleted torrent: {}", e))
+        }).await
+    }
 
-        Ok(rows_affected)
+    /// Drops archived torrents deleted more than `days_to_keep` days ago.
+    pub async fn cleanup_deleted_torrents(&self, days_to_keep: i64) -> Result<usize, String> {
+        self.pool.write(move |conn| {
+            conn.execute(
+                "DELETE FROM deleted_torrents WHERE deleted_at < datetime('now', '-' || ? || ' days')",
+                params![days_to_keep],
+            )
+            .map_err(|e| format!("Failed to cleanup deleted torrents: {}", e))
+        }).await
     }
 
     pub async fn get_execution_logs(
@@ -513,71 +1372,118 @@ impl Database {
         api_key_hash: &str,
         limit: i32,
     ) -> Result<Vec<ExecutionLog>, String> {
-        let conn = self.conn.lock().await;
-
-        let max_limit = limit.min(1000);
-        let query = if let Some(_id) = rule_id {
-            "SELECT id, rule_id, rule_name, api_key_hash, execution_type, items_processed, total_items, success, error_message, processed_items, executed_at, partial
-             FROM rule_execution_log WHERE rule_id = ? AND api_key_hash = ? ORDER BY executed_at DESC LIMIT ?"
-        } else {
-            "SELECT id, rule_id, rule_name, api_key_hash, execution_type, items_processed, total_items, success, error_message, processed_items, executed_at, partial
-             FROM rule_execution_log WHERE api_key_hash = ? ORDER BY executed_at DESC LIMIT ?"
-        };
+        let api_key_hash = api_key_hash.to_string();
+        self.pool.read(move |conn| {
+            let max_limit = limit.min(1000);
+            let query = format!(
+                "SELECT {} FROM rule_execution_log
+                 WHERE api_key_hash = ?1 AND (?2 IS NULL OR rule_id = ?2)
+                 ORDER BY executed_at DESC LIMIT ?3",
+                LOG_COLUMNS
+            );
 
-        let mut stmt = conn
-            .prepare(query)
-            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
-
-        let logs: Result<Vec<ExecutionLog>, _> = if let Some(id) = rule_id {
-            stmt.query_map(params![id, api_key_hash, max_limit], |row| {
-                let processed_items_json: Option<String> = row.get(9)?;
-                let processed_items = processed_items_json
-                    .and_then(|json| serde_json::from_str(&json).ok());
-                
-                Ok(ExecutionLog {
-                    id: Some(row.get(0)?),
-                    rule_id: row.get(1)?,
-                    rule_name: row.get(2)?,
-                    api_key_hash: row.get(3)?,
-                    execution_type: row.get(4)?,
-                    items_processed: row.get(5)?,
-                    total_items: row.get(6)?,
-                    success: row.get(7)?,
-                    error_message: row.get(8)?,
-                    processed_items,
-                    executed_at: row.get(10)?,
-                    partial: row.get(11)?,
-                })
-            })
-            .map_err(|e| format!("Failed to query logs: {}", e))?
-            .collect()
-        } else {
-            stmt.query_map(params![api_key_hash, max_limit], |row| {
-                let processed_items_json: Option<String> = row.get(9)?;
-                let processed_items = processed_items_json
-                    .and_then(|json| serde_json::from_str(&json).ok());
-                
-                Ok(ExecutionLog {
-                    id: Some(row.get(0)?),
-                    rule_id: row.get(1)?,
-                    rule_name: row.get(2)?,
-                    api_key_hash: row.get(3)?,
-                    execution_type: row.get(4)?,
-                    items_processed: row.get(5)?,
-                    total_items: row.get(6)?,
-                    success: row.get(7)?,
-                    error_message: row.get(8)?,
-                    processed_items,
-                    executed_at: row.get(10)?,
-                    partial: row.get(11)?,
-                })
-            })
-            .map_err(|e| format!("Failed to query logs: {}", e))?
-            .collect()
-        };
+            let mut stmt = conn
+                .prepare(&query)
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+            let logs = stmt
+                .query_map(params![api_key_hash, rule_id, max_limit], log_from_row)
+                .map_err(|e| format!("Failed to query logs: {}", e))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to collect logs: {}", e))?;
+
+            Ok(logs)
+        }).await
+    }
+
+    /// Log entries matching `filter`, newest first, starting below the log id
+    /// `before` when paging.
+    pub async fn search_execution_logs(
+        &self,
+        api_key_hash: &str,
+        filter: &LogFilter,
+        before: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<ExecutionLog>, String> {
+        let api_key_hash = api_key_hash.to_string();
+        let filter = filter.clone();
+        self.pool.read(move |conn| {
+            let query = format!(
+                "SELECT {} FROM rule_execution_log
+                 WHERE api_key_hash = ?1
+                   AND (?2 IS NULL OR rule_id = ?2)
+                   AND (?3 IS NULL OR executed_at >= ?3)
+                   AND (?4 IS NULL OR executed_at < ?4)
+                   AND (?5 IS NULL
+                        OR (?5 = 'success' AND success AND NOT COALESCE(partial, 0))
+                        OR (?5 = 'partial' AND COALESCE(partial, 0))
+                        OR (?5 = 'skipped' AND NOT success AND NOT COALESCE(partial, 0) AND error_message IN (?6, ?7))
+                        OR (?5 = 'failed' AND NOT success AND NOT COALESCE(partial, 0) AND COALESCE(error_message, '') NOT IN (?6, ?7)))
+                   AND (?8 IS NULL OR EXISTS (
+                        SELECT 1 FROM json_each(rule_execution_log.processed_items)
+                        WHERE json_extract(json_each.value, '$.name') LIKE ?8 ESCAPE '\\'))
+                   AND (?9 IS NULL OR id < ?9)
+                 ORDER BY id DESC LIMIT ?10",
+                LOG_COLUMNS
+            );
+
+            let mut stmt = conn
+                .prepare(&query)
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+            let [skipped_already_running, skipped_outside_window] = SKIPPED_MESSAGES;
+            let logs = stmt
+                .query_map(
+                    params![
+                        api_key_hash,
+                        filter.rule_id,
+                        filter.from,
+                        filter.to,
+                        filter.status.map(|status| status.name()),
+                        skipped_already_running,
+                        skipped_outside_window,
+                        filter.item.as_deref().map(like_pattern),
+                        before,
+                        limit as i64,
+                    ],
+                    log_from_row,
+                )
+                .map_err(|e| format!("Failed to query logs: {}", e))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to collect logs: {}", e))?;
+
+            Ok(logs)
+        }).await
+    }
+
+    /// Every log entry of the last `days` days, oldest first, for analytics.
+    pub async fn get_logs_since(
+        &self,
+        api_key_hash: &str,
+        rule_id: Option<i64>,
+        days: u32,
+    ) -> Result<Vec<ExecutionLog>, String> {
+        let api_key_hash = api_key_hash.to_string();
+        self.pool.read(move |conn| {
+            let query = format!(
+                "SELECT {} FROM rule_execution_log
+                 WHERE api_key_hash = ?1 AND (?2 IS NULL OR rule_id = ?2)
+                   AND executed_at >= datetime('now', '-' || ?3 || ' days')
+                 ORDER BY executed_at ASC",
+                LOG_COLUMNS
+            );
+
+            let mut stmt = conn
+                .prepare(&query)
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
 
-        let logs = logs.map_err(|e| format!("Failed to collect logs: {}", e))?;
+            let logs = stmt
+                .query_map(params![api_key_hash, rule_id, days], log_from_row)
+                .map_err(|e| format!("Failed to query logs: {}", e))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to collect logs: {}", e))?;
 
-        Ok(logs)
+            Ok(logs)
+        }).await
     }
 }
@@ -1,25 +1,161 @@
 use aes_gcm::{
-    aead::{Aead, AeadCore, KeyInit, OsRng},
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
     Aes256Gcm, Key, Nonce,
 };
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// `api_keys.cipher_version` of rows encrypted before ciphertexts were bound to
+/// their `api_key_hash`.
+pub const CIPHER_LEGACY: i64 = 0;
+/// `api_keys.cipher_version` of rows encrypted with the `api_key_hash` as associated data.
+pub const CIPHER_BOUND: i64 = 1;
+
+/// Associated data of a wrapped data key, so a wrapped blob can't be passed off as
+/// anything else encrypted under the master key.
+const DATA_KEY_AAD: &[u8] = b"torbox-companion:data-key";
+
+/// Lets a server without a master key create a data key that is stored in the clear.
+pub const ALLOW_UNWRAPPED_DATA_KEY_VAR: &str = "TORBOX_ALLOW_UNWRAPPED_DATA_KEY";
+
+/// Whether a new data key may be kept unwrapped in the database, next to the
+/// ciphertexts it protects. Off unless explicitly allowed.
+pub fn unwrapped_data_keys_allowed() -> bool {
+    std::env::var(ALLOW_UNWRAPPED_DATA_KEY_VAR)
+        .map(|value| matches!(value.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// The key-encryption key. Data keys are stored wrapped under it, so the database
+/// file alone is not enough to decrypt stored TorBox API keys.
+#[derive(Clone)]
+pub struct MasterKey {
+    key: [u8; 32],
+}
+
+impl MasterKey {
+    /// Reads `TORBOX_MASTER_KEY` (64 hex characters) or, failing that, the file named
+    /// by `TORBOX_MASTER_KEY_FILE`. Returns `None` when neither is set.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        if let Ok(hex) = std::env::var("TORBOX_MASTER_KEY") {
+            if !hex.trim().is_empty() {
+                return Self::from_hex(&hex).map(Some).map_err(|e| format!("TORBOX_MASTER_KEY: {}", e));
+            }
+        }
+
+        match std::env::var("TORBOX_MASTER_KEY_FILE") {
+            Ok(path) if !path.trim().is_empty() => Self::from_file(Path::new(path.trim())).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// A key file holds either 64 hex characters or the 32 raw key bytes.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read(path)
+            .map_err(|e| format!("Failed to read master key file {}: {}", path.display(), e))?;
+
+        if let Ok(text) = std::str::from_utf8(&contents) {
+            if text.trim().len() == 64 {
+                return Self::from_hex(text)
+                    .map_err(|e| format!("Master key file {}: {}", path.display(), e));
+            }
+        }
+
+        let key: [u8; 32] = contents
+            .as_slice()
+            .try_into()
+            .map_err(|_| format!("Master key file {} must contain 32 bytes or 64 hex characters", path.display()))?;
+        Ok(Self { key })
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self, String> {
+        let hex = hex.trim();
+        if hex.len() != 64 {
+            return Err("expected 64 hex characters".to_string());
+        }
+
+        let mut key = [0u8; 32];
+        for (index, byte) in key.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16)
+                .map_err(|_| "expected 64 hex characters".to_string())?;
+        }
+        Ok(Self { key })
+    }
+
+    /// Short fingerprint stored next to each wrapped data key, used to tell a wrong
+    /// master key apart from corrupted data.
+    pub fn id(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(b"torbox-companion:master-key-id");
+        hasher.update(self.key);
+        format!("{:x}", hasher.finalize()).chars().take(16).collect()
+    }
+
+    pub fn wrap(&self, data_key: &[u8; 32]) -> Result<(Vec<u8>, Vec<u8>), String> {
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.key));
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+        let wrapped = cipher
+            .encrypt(&nonce, Payload { msg: data_key, aad: DATA_KEY_AAD })
+            .map_err(|e| format!("Failed to wrap data key: {}", e))?;
+
+        Ok((wrapped, nonce.to_vec()))
+    }
+
+    pub fn unwrap(&self, wrapped: &[u8], nonce: &[u8]) -> Result<[u8; 32], String> {
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.key));
+
+        let data_key = cipher
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: wrapped, aad: DATA_KEY_AAD })
+            .map_err(|_| "Failed to unwrap data key: wrong master key or corrupted data".to_string())?;
+
+        data_key
+            .as_slice()
+            .try_into()
+            .map_err(|_| "Invalid data key length".to_string())
+    }
+}
+
+/// An API key encrypted under one of the data keys.
+pub struct EncryptedApiKey {
+    pub data_key_id: i64,
+    pub ciphertext: Vec<u8>,
+    pub nonce: Vec<u8>,
+}
+
+#[derive(Default)]
+struct DataKeys {
+    active: Option<i64>,
+    keys: HashMap<i64, [u8; 32]>,
+}
+
+/// Encrypts stored API keys with the active data key. Older data keys stay loaded
+/// until every row encrypted under them has been rotated.
 pub struct EncryptionService {
-    encryption_key: Arc<RwLock<Option<[u8; 32]>>>,
+    data_keys: Arc<RwLock<DataKeys>>,
 }
 
 impl EncryptionService {
     pub fn new() -> Self {
         Self {
-            encryption_key: Arc::new(RwLock::new(None)),
+            data_keys: Arc::new(RwLock::new(DataKeys::default())),
         }
     }
 
-    pub async fn initialize(&self, key: [u8; 32]) {
-        let mut enc_key = self.encryption_key.write().await;
-        *enc_key = Some(key);
+    pub async fn initialize(&self, active: i64, keys: HashMap<i64, [u8; 32]>) {
+        let mut data_keys = self.data_keys.write().await;
+        *data_keys = DataKeys { active: Some(active), keys };
+    }
+
+    pub async fn active_key_id(&self) -> Option<i64> {
+        self.data_keys.read().await.active
+    }
+
+    pub async fn has_key(&self, data_key_id: i64) -> bool {
+        self.data_keys.read().await.keys.contains_key(&data_key_id)
     }
 
     pub async fn generate_key() -> [u8; 32] {
@@ -29,33 +165,47 @@ impl EncryptionService {
         key
     }
 
-    pub async fn encrypt_api_key(&self, api_key: &str) -> Result<(Vec<u8>, Vec<u8>), String> {
-        let enc_key = self.encryption_key.read().await;
-        let key = enc_key.ok_or("Encryption key not initialized")?;
+    /// Encrypts `api_key` under the active data key, bound to `api_key_hash`.
+    pub async fn encrypt_api_key(&self, api_key: &str, api_key_hash: &str) -> Result<EncryptedApiKey, String> {
+        let data_keys = self.data_keys.read().await;
+        let data_key_id = data_keys.active.ok_or("Encryption key not initialized")?;
+        let key = data_keys.keys.get(&data_key_id).ok_or("Encryption key not initialized")?;
 
-        let cipher = Aes256Gcm::new(&Key::<Aes256Gcm>::from_slice(&key));
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
         let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
 
         let ciphertext = cipher
-            .encrypt(&nonce, api_key.as_bytes())
+            .encrypt(&nonce, Payload { msg: api_key.as_bytes(), aad: api_key_hash.as_bytes() })
             .map_err(|e| format!("Encryption failed: {}", e))?;
 
-        Ok((ciphertext, nonce.to_vec()))
+        Ok(EncryptedApiKey {
+            data_key_id,
+            ciphertext,
+            nonce: nonce.to_vec(),
+        })
     }
 
+    /// Decrypts a stored API key. Rows written before `CIPHER_BOUND` carry no
+    /// associated data.
     pub async fn decrypt_api_key(
         &self,
+        data_key_id: i64,
+        cipher_version: i64,
+        api_key_hash: &str,
         encrypted: &[u8],
         nonce: &[u8],
     ) -> Result<String, String> {
-        let enc_key = self.encryption_key.read().await;
-        let key = enc_key.ok_or("Encryption key not initialized")?;
+        let data_keys = self.data_keys.read().await;
+        let key = data_keys.keys
+            .get(&data_key_id)
+            .ok_or_else(|| format!("Data key {} is not loaded", data_key_id))?;
 
-        let cipher = Aes256Gcm::new(&Key::<Aes256Gcm>::from_slice(&key));
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
         let nonce = Nonce::from_slice(nonce);
+        let aad: &[u8] = if cipher_version >= CIPHER_BOUND { api_key_hash.as_bytes() } else { b"" };
 
         let plaintext = cipher
-            .decrypt(nonce, encrypted)
+            .decrypt(nonce, Payload { msg: encrypted, aad })
             .map_err(|e| format!("Decryption failed: {}", e))?;
 
         String::from_utf8(plaintext).map_err(|e| format!("Invalid UTF-8: {}", e))
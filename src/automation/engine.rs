@@ -1,8 +1,16 @@
-use crate::api::{TorboxClient, ApiError};
-use crate::api::types::Torrent;
+use crate::api::types::CloudUpload;
+use crate::api::{ApiError, TorboxClient};
+use crate::automation::archive;
+use crate::automation::database::Database;
+use crate::automation::history::ItemHistory;
+use crate::automation::items::DownloadItem;
+use crate::automation::notifier::{self, NotificationEvent};
+use crate::automation::quota::{self, QuotaStatus};
 use crate::automation::types::*;
 use chrono::{DateTime, Utc};
 use leptos::logging::log;
+use regex::Regex;
+use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 pub struct AutomationEngine;
@@ -14,16 +22,56 @@ impl AutomationEngine {
 
     pub async fn execute_rule(
         &self,
+        database: &Database,
         rule: &AutomationRule,
         api_key: &str,
-    ) -> Result<ExecutionResult, String> {
+        history: &ItemHistory,
+    ) -> Result<ExecutionResult, RunError> {
+        let result = self.run_rule(database, rule, api_key, history).await;
+        notifier::notify_run(rule, &result).await;
+        result
+    }
+
+    /// Runs a rule against a given set of downloads instead of the full list,
+    /// e.g. the downloads that just produced one of its trigger events.
+    pub async fn execute_rule_for_items(
+        &self,
+        database: &Database,
+        rule: &AutomationRule,
+        api_key: &str,
+        items: &[DownloadItem],
+        history: &ItemHistory,
+    ) -> Result<ExecutionResult, RunError> {
         let client = TorboxClient::new(api_key.to_string());
+        let result = self.process_items(database, rule, &client, items, history).await;
+        if !matches!(&result, Ok(result) if result.total_items == 0) {
+            notifier::notify_run(rule, &result).await;
+        }
+        result
+    }
 
-        log!("Fetching torrent list for rule: {}", rule.name);
-        let torrents = self.fetch_torrents_with_retry(&client, rule.name.as_str(), 3).await?;
-        log!("Fetched {} torrents for rule: {}", torrents.len(), rule.name);
+    async fn run_rule(
+        &self,
+        database: &Database,
+        rule: &AutomationRule,
+        api_key: &str,
+        history: &ItemHistory,
+    ) -> Result<ExecutionResult, RunError> {
+        let client = TorboxClient::new(api_key.to_string());
+
+        let items = self.fetch_rule_items(rule, &client).await?;
+        self.process_items(database, rule, &client, &items, history).await
+    }
 
-        let matching_items = self.evaluate_conditions(rule, &torrents);
+    async fn process_items(
+        &self,
+        database: &Database,
+        rule: &AutomationRule,
+        client: &TorboxClient,
+        items: &[DownloadItem],
+        history: &ItemHistory,
+    ) -> Result<ExecutionResult, RunError> {
+        let (matching_items, _, _) = self.select_items(rule, items, history)?;
         log!("Rule '{}' matched {} items", rule.name, matching_items.len());
 
         let total_items = matching_items.len() as i32;
@@ -35,71 +83,96 @@ impl AutomationEngine {
                 error_message: None,
                 processed_items: Some(Vec::new()),
                 partial: false,
+                transfers: Vec::new(),
             });
         }
 
         let mut error_count = 0;
         let mut errors: Vec<String> = Vec::new();
         let mut processed_items: Vec<ProcessedItem> = Vec::new();
+        let mut transfers: Vec<TrackedTransfer> = Vec::new();
 
-        let action_name = match rule.action_config.action_type {
-            ActionType::StopSeeding => "Stop Seeding",
-            ActionType::Delete => "Delete",
-            ActionType::Stop => "Stop",
-            ActionType::Resume => "Resume",
-            ActionType::Restart => "Restart",
-            ActionType::Reannounce => "Reannounce",
-            ActionType::ForceStart => "Force Start",
-        }.to_string();
+        let action_name = rule.action_config.steps
+            .iter()
+            .map(|step| action_label(&step.action_type))
+            .collect::<Vec<_>>()
+            .join(" → ");
+        let mut aborted_at: Option<usize> = None;
 
         log!("Processing {} items for rule '{}' with action: {}", matching_items.len(), rule.name, action_name);
-        let per_item_timeout = tokio::time::Duration::from_secs(10);
         
         for (idx, item) in matching_items.iter().enumerate() {
             if idx > 0 && idx % 10 == 0 {
                 log!("Processed {}/{} items for rule '{}'", idx, matching_items.len(), rule.name);
             }
-            
-            let action_future = self.execute_action(&rule.action_config, &client, item);
-            let result = match tokio::time::timeout(per_item_timeout, action_future).await {
-                Ok(Ok(())) => Ok(()),
-                Ok(Err(e)) => {
-                    if e.contains("429") || e.contains("Rate limit") {
-                        log!("Rate limit hit for rule '{}', waiting 2 seconds before retry...", rule.name);
-                        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-                        let retry_result = self.execute_action(&rule.action_config, &client, item).await;
-                        if retry_result.is_err() {
-                            log!("Retry failed for rule '{}'", rule.name);
+
+            let mut steps: Vec<StepOutcome> = Vec::new();
+            let mut item_error: Option<String> = None;
+            let mut abort_run = false;
+
+            for (step_idx, step) in rule.action_config.steps.iter().enumerate() {
+                let label = action_label(&step.action_type);
+                match self.execute_step(database, rule, step, client, item).await {
+                    Ok(output) => {
+                        transfers.extend(output.transfers);
+                        steps.push(StepOutcome {
+                            step: step_idx + 1,
+                            action: label.to_string(),
+                            action_type: Some(step.action_type.clone()),
+                            success: true,
+                            error: None,
+                            detail: output.detail,
+                        });
+                    }
+                    Err(e) => {
+                        steps.push(StepOutcome {
+                            step: step_idx + 1,
+                            action: label.to_string(),
+                            action_type: Some(step.action_type.clone()),
+                            success: false,
+                            error: Some(e.clone()),
+                            detail: None,
+                        });
+                        if item_error.is_none() {
+                            item_error = Some(format!("Step {} ({}): {}", step_idx + 1, label, e));
+                        }
+                        match step.on_failure {
+                            FailurePolicy::Continue => continue,
+                            FailurePolicy::SkipRemaining => break,
+                            FailurePolicy::AbortRun => {
+                                abort_run = true;
+                                break;
+                            }
                         }
-                        retry_result
-                    } else {
-                        Err(e)
                     }
                 }
-                Err(_) => {
-                    log!("Action timed out after 10 seconds for rule '{}'", rule.name);
-                    Err(format!("Action timed out after 10 seconds"))
-                }
-            };
-            
-            let success = result.is_ok();
-            let error = result.as_ref().err().map(|e| e.to_string());
+            }
 
             processed_items.push(ProcessedItem {
-                id: item.id,
-                name: item.name.clone(),
+                id: item.id(),
+                download_type: Some(item.download_type()),
+                name: item.name().to_string(),
                 action: action_name.clone(),
-                success,
-                error: error.clone(),
+                success: item_error.is_none(),
+                error: item_error.clone(),
+                steps,
+                size: Some(item.size()),
+                hash: Some(item.hash().to_string()),
             });
 
-            if let Err(e) = result {
+            if let Some(e) = item_error {
                 error_count += 1;
                 errors.push(e);
                 if errors.len() >= 10 {
                     errors.push(format!("... and {} more errors", error_count - 10));
                 }
             }
+
+            if abort_run {
+                log!("Rule '{}' aborted after a failed step on item '{}'", rule.name, item.name());
+                aborted_at = Some(idx + 1);
+                break;
+            }
         }
 
         let items_processed = processed_items.len() as i32;
@@ -114,6 +187,9 @@ impl AutomationEngine {
             success: error_count == 0 && !partial,
             error_message: if error_count > 0 || partial {
                 let mut msg_parts = Vec::new();
+                if let Some(item_number) = aborted_at {
+                    msg_parts.push(format!("Run aborted at item {} by a step's failure policy", item_number));
+                }
                 if partial {
                     msg_parts.push(format!("Only processed {}/{} items", items_processed, total_items));
                 }
@@ -131,21 +207,197 @@ impl AutomationEngine {
             },
             processed_items: Some(processed_items),
             partial,
+            transfers,
         })
     }
 
-    fn evaluate_conditions<'a>(&self, rule: &AutomationRule, items: &'a [Torrent]) -> Vec<&'a Torrent> {
-        items
+    pub async fn preview_rule(
+        &self,
+        rule: &AutomationRule,
+        api_key: &str,
+        history: &ItemHistory,
+    ) -> Result<PreviewResult, RunError> {
+        let client = TorboxClient::new(api_key.to_string());
+
+        let items = self.fetch_rule_items(rule, &client).await?;
+        let (matching_items, on_cooldown, quota) = self.select_items(rule, &items, history)?;
+        log!("Preview of rule '{}' matched {} of {} items", rule.name, matching_items.len(), items.len());
+
+        let regexes = compile_text_regexes(&rule.conditions)?;
+        let leaves = rule.conditions.leaves();
+
+        let matched_items = matching_items
             .iter()
-            .filter(|item| {
-                rule.conditions.iter().all(|condition| {
-                    self.evaluate_condition(condition, item)
-                })
+            .take(MAX_PREVIEW_ITEMS)
+            .map(|item| PreviewItem {
+                id: item.id(),
+                download_type: item.download_type(),
+                name: item.name().to_string(),
+                size: item.size(),
+                download_state: item.download_state().to_string(),
+                conditions: leaves
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, leaf)| {
+                        let (matched, value) = match leaf {
+                            ConditionNode::Condition(condition) => self.measure_condition(condition, item, history),
+                            ConditionNode::Text(condition) => {
+                                let regex = match &condition.operator {
+                                    TextOperator::Matches(pattern) => regexes.get(&(pattern.clone(), condition.case_sensitive)),
+                                    _ => None,
+                                };
+                                self.measure_text_condition(condition, item, regex)
+                            }
+                            _ => (false, serde_json::Value::Null),
+                        };
+                        ConditionMeasurement {
+                            index: idx + 1,
+                            label: condition_label(leaf),
+                            value,
+                            matched,
+                        }
+                    })
+                    .collect(),
             })
-            .collect()
+            .collect();
+
+        Ok(PreviewResult {
+            total_items: items.len() as i32,
+            matched_count: matching_items.len() as i32,
+            truncated: matching_items.len() > MAX_PREVIEW_ITEMS,
+            matched_items,
+            on_cooldown: on_cooldown as i32,
+            quota,
+        })
+    }
+
+    async fn fetch_rule_items(&self, rule: &AutomationRule, client: &TorboxClient) -> Result<Vec<DownloadItem>, RunError> {
+        self.fetch_items(client, &rule.download_types, rule.name.as_str()).await
+    }
+
+    pub async fn fetch_items(
+        &self,
+        client: &TorboxClient,
+        download_types: &[DownloadType],
+        context: &str,
+    ) -> Result<Vec<DownloadItem>, RunError> {
+        let mut items: Vec<DownloadItem> = Vec::new();
+        for download_type in download_types {
+            log!("Fetching {} list for: {}", download_type.label(), context);
+            let fetched = self.fetch_items_with_retry(client, *download_type, context, 3).await?;
+            log!("Fetched {} {}s for: {}", fetched.len(), download_type.label(), context);
+            items.extend(fetched);
+        }
+        Ok(items)
+    }
+
+    /// The downloads a run acts on: those matching the conditions and not on the
+    /// rule's cooldown, narrowed down for quota rules to the fewest it takes to
+    /// get under the target. Also returns how many were left out for the cooldown.
+    fn select_items<'a>(
+        &self,
+        rule: &AutomationRule,
+        items: &'a [DownloadItem],
+        history: &ItemHistory,
+    ) -> Result<(Vec<&'a DownloadItem>, usize, Option<QuotaStatus>), String> {
+        let mut matching_items = self.evaluate_conditions(rule, items, history)?;
+
+        let mut on_cooldown = 0;
+        if let Some(cooldown) = &rule.cooldown {
+            let now = Utc::now().timestamp();
+            let before = matching_items.len();
+            matching_items.retain(|item| !history.on_cooldown(cooldown, item, now));
+            on_cooldown = before - matching_items.len();
+            if on_cooldown > 0 {
+                log!("Rule '{}' left out {} matching items on cooldown", rule.name, on_cooldown);
+            }
+        }
+
+        let Some(config) = &rule.quota else {
+            return Ok((matching_items, on_cooldown, None));
+        };
+
+        let selection = quota::select(config, items, matching_items);
+        let status = &selection.status;
+        log!(
+            "Rule '{}' ({}): {} downloads using {} bytes, picked {} of {} matching, leaving {} downloads using {} bytes{}",
+            rule.name,
+            quota::describe(config),
+            status.total_count,
+            status.total_size,
+            selection.items.len(),
+            status.matching,
+            status.projected_count,
+            status.projected_size,
+            if status.target_reached { "" } else if status.capped { " (capped by max items per run)" } else { " (not enough matching downloads to reach the target)" },
+        );
+        Ok((selection.items, on_cooldown, Some(selection.status)))
+    }
+
+    fn evaluate_conditions<'a>(&self, rule: &AutomationRule, items: &'a [DownloadItem], history: &ItemHistory) -> Result<Vec<&'a DownloadItem>, String> {
+        let regexes = compile_text_regexes(&rule.conditions)?;
+        Ok(items
+            .iter()
+            .filter(|item| self.evaluate_node(&rule.conditions, item, &regexes, history))
+            .collect())
+    }
+
+    fn evaluate_node(&self, node: &ConditionNode, item: &DownloadItem, regexes: &RegexCache, history: &ItemHistory) -> bool {
+        match node {
+            ConditionNode::Condition(condition) => self.evaluate_condition(condition, item, history),
+            ConditionNode::Text(condition) => {
+                let regex = match &condition.operator {
+                    TextOperator::Matches(pattern) => regexes.get(&(pattern.clone(), condition.case_sensitive)),
+                    _ => None,
+                };
+                self.evaluate_text_condition(condition, item, regex)
+            }
+            ConditionNode::All(children) => children.iter().all(|child| self.evaluate_node(child, item, regexes, history)),
+            ConditionNode::Any(children) => children.iter().any(|child| self.evaluate_node(child, item, regexes, history)),
+            ConditionNode::Not(inner) => !self.evaluate_node(inner, item, regexes, history),
+        }
+    }
+
+    fn evaluate_text_condition(&self, condition: &TextCondition, item: &DownloadItem, regex: Option<&Regex>) -> bool {
+        self.measure_text_condition(condition, item, regex).0
+    }
+
+    fn measure_text_condition(&self, condition: &TextCondition, item: &DownloadItem, regex: Option<&Regex>) -> (bool, serde_json::Value) {
+        if !condition.field.supports(item.download_type()) {
+            return (false, serde_json::Value::Null);
+        }
+
+        match condition.field {
+            TextField::Name => (condition.matches_value(item.name(), regex), serde_json::json!(item.name())),
+            TextField::Tracker => (
+                item.tracker().map(|t| condition.matches_value(t, regex)).unwrap_or(false),
+                serde_json::json!(item.tracker()),
+            ),
+            TextField::TrackerMessage => (
+                item.tracker_message().map(|m| condition.matches_value(m, regex)).unwrap_or(false),
+                serde_json::json!(item.tracker_message()),
+            ),
+            TextField::DownloadState => (
+                condition.matches_value(item.download_state(), regex),
+                serde_json::json!(item.download_state()),
+            ),
+            TextField::FileExtension => {
+                let extensions = item.file_extensions();
+                let matched = extensions.iter().any(|ext| condition.matches_value(ext, regex));
+                (matched, serde_json::json!(extensions))
+            }
+        }
     }
 
-    fn evaluate_condition(&self, condition: &Condition, item: &Torrent) -> bool {
+    fn evaluate_condition(&self, condition: &Condition, item: &DownloadItem, history: &ItemHistory) -> bool {
+        self.measure_condition(condition, item, history).0
+    }
+
+    fn measure_condition(&self, condition: &Condition, item: &DownloadItem, history: &ItemHistory) -> (bool, serde_json::Value) {
+        if !condition.r#type.supports(item.download_type()) {
+            return (false, serde_json::Value::Null);
+        }
+
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
@@ -153,37 +405,40 @@ impl AutomationEngine {
 
         let condition_value: Option<f64> = match condition.r#type {
             ConditionType::SeedingTime => {
-                if !item.active {
-                    return false;
+                if !item.active() {
+                    return (false, serde_json::Value::Null);
                 }
-                if !item.download_finished {
-                    return false;
+                if !item.download_finished() {
+                    return (false, serde_json::Value::Null);
                 }
-                if let Some(cached_at) = &item.cached_at {
+                if let Some(cached_at) = item.cached_at() {
                     if let Ok(cached_time) = DateTime::parse_from_rfc3339(cached_at) {
                         let elapsed = now - cached_time.timestamp();
                         Some(elapsed as f64 / 3600.0)
                     } else {
-                        return false;
+                        return (false, serde_json::Value::Null);
                     }
-                } else if let Ok(updated_time) = DateTime::parse_from_rfc3339(&item.updated_at) {
+                } else if let Ok(updated_time) = DateTime::parse_from_rfc3339(item.updated_at()) {
                     let elapsed = now - updated_time.timestamp();
                     Some(elapsed as f64 / 3600.0)
                 } else {
-                    return false;
+                    return (false, serde_json::Value::Null);
                 }
             }
             ConditionType::SeedingRatio => {
-                if !item.active {
-                    return false;
+                if !item.active() {
+                    return (false, serde_json::Value::Null);
+                }
+                match item.as_torrent() {
+                    Some(torrent) => Some(torrent.ratio as f64),
+                    None => return (false, serde_json::Value::Null),
                 }
-                Some(item.ratio as f64)
             }
             ConditionType::StalledTime => {
-                let status_lower = item.download_state.to_lowercase();
+                let status_lower = item.download_state().to_lowercase();
                 
                 if status_lower.contains("stalled") {
-                    if let Ok(created_time) = DateTime::parse_from_rfc3339(&item.created_at) {
+                    if let Ok(created_time) = DateTime::parse_from_rfc3339(item.created_at()) {
                         let elapsed = now - created_time.timestamp();
                         Some(elapsed as f64 / 3600.0)
                     } else {
@@ -196,66 +451,68 @@ impl AutomationEngine {
                     let is_checking = status_lower == "checking";
                     
                     if !is_downloading_status && !is_checking {
-                        return false;
+                        return (false, serde_json::Value::Null);
                     }
                     
-                    let has_no_speed = item.download_speed < 1024;
-                    let has_no_seeds = item.seeds == 0;
-                    let has_no_peers = item.peers == 0;
-                    let is_inactive = !item.active;
+                    let has_no_speed = item.download_speed() < 1024;
+                    let has_no_seeds = item.seeds() == 0;
+                    let has_no_peers = item.peers() == 0;
+                    let is_inactive = !item.active();
                     
                     let is_stalled = if is_checking {
-                        if let Ok(updated_time) = DateTime::parse_from_rfc3339(&item.updated_at) {
+                        if let Ok(updated_time) = DateTime::parse_from_rfc3339(item.updated_at()) {
                             let elapsed = now - updated_time.timestamp();
                             elapsed > 21600
                         } else {
                             false
                         }
                     } else if is_downloading_status {
-                        has_no_speed && item.upload_speed == 0 && ((has_no_seeds && has_no_peers) || is_inactive)
+                        has_no_speed && item.upload_speed() == 0 && ((has_no_seeds && has_no_peers) || is_inactive)
                     } else {
                         false
                     };
                     
                     if !is_stalled {
-                        return false;
+                        return (false, serde_json::Value::Null);
                     }
                     
-                    if let Ok(updated_time) = DateTime::parse_from_rfc3339(&item.updated_at) {
+                    if let Ok(updated_time) = DateTime::parse_from_rfc3339(item.updated_at()) {
                         let elapsed = now - updated_time.timestamp();
                         Some(elapsed as f64 / 3600.0)
                     } else {
-                        return false;
+                        return (false, serde_json::Value::Null);
                     }
                 }
             }
             ConditionType::Age => {
-                if let Ok(created_time) = DateTime::parse_from_rfc3339(&item.created_at) {
+                if let Ok(created_time) = DateTime::parse_from_rfc3339(item.created_at()) {
                     let elapsed = now - created_time.timestamp();
                     Some(elapsed as f64 / 3600.0)
                 } else {
-                    return false;
+                    return (false, serde_json::Value::Null);
                 }
             }
-            ConditionType::DownloadSpeed => Some(item.download_speed as f64),
-            ConditionType::UploadSpeed => Some(item.upload_speed as f64),
-            ConditionType::FileSize => Some(item.size as f64 / (1024.0 * 1024.0 * 1024.0)),
-            ConditionType::Progress => Some(item.progress as f64),
-            ConditionType::Seeds => Some(item.seeds as f64),
-            ConditionType::Peers => Some(item.peers as f64),
-            ConditionType::TotalUploaded => Some(item.total_uploaded as f64 / (1024.0 * 1024.0 * 1024.0)),
-            ConditionType::TotalDownloaded => Some(item.total_downloaded as f64 / (1024.0 * 1024.0 * 1024.0)),
+            ConditionType::DownloadSpeed => Some(item.download_speed() as f64),
+            ConditionType::UploadSpeed => Some(item.upload_speed() as f64),
+            ConditionType::FileSize => Some(item.size() as f64 / (1024.0 * 1024.0 * 1024.0)),
+            ConditionType::Progress => Some(item.progress() as f64),
+            ConditionType::Seeds => Some(item.seeds() as f64),
+            ConditionType::Peers => Some(item.peers() as f64),
+            ConditionType::TotalUploaded => item.as_torrent().map(|t| t.total_uploaded as f64 / (1024.0 * 1024.0 * 1024.0)),
+            ConditionType::TotalDownloaded => item.as_torrent().map(|t| t.total_downloaded as f64 / (1024.0 * 1024.0 * 1024.0)),
             ConditionType::DownloadState => {
-                return match condition.value as i32 {
-                    0 => item.download_state == "downloading",
-                    1 => item.download_state == "uploading" || item.download_state == "uploading (no peers)",
-                    2 => item.download_state == "stopped seeding" || item.download_state == "stopped",
-                    3 => item.download_state == "cached",
+                let download_state = item.download_state();
+                let matched = match condition.value as i32 {
+                    0 => download_state == "downloading",
+                    1 => download_state == "uploading" || download_state == "uploading (no peers)",
+                    2 => download_state == "stopped seeding" || download_state == "stopped",
+                    3 => download_state == "cached",
                     _ => false,
                 };
+                return (matched, serde_json::json!(download_state));
             }
             ConditionType::Inactive => {
-                let status_lower = item.download_state.to_lowercase();
+                let status_lower = item.download_state().to_lowercase();
                 
                 if status_lower == "reported missing"
                     || status_lower == "missingfiles"
@@ -263,7 +520,7 @@ impl AutomationEngine {
                     || status_lower.starts_with("failed")
                     || status_lower == "error" {
                     Some(1.0)
-                } else if item.download_finished {
+                } else if item.download_finished() {
                     Some(0.0)
                 } else {
                     let is_stalled = {
@@ -273,28 +530,28 @@ impl AutomationEngine {
                             || status_lower.contains("downloading");
                         
                         if is_checking {
-                            if let Ok(updated_time) = DateTime::parse_from_rfc3339(&item.updated_at) {
+                            if let Ok(updated_time) = DateTime::parse_from_rfc3339(item.updated_at()) {
                                 let elapsed = now - updated_time.timestamp();
                                 elapsed > 21600
                             } else {
                                 false
                             }
                         } else if is_downloading_status {
-                            let has_no_speed = item.download_speed < 1024;
-                            let has_no_seeds = item.seeds == 0;
-                            let has_no_peers = item.peers == 0;
-                            let is_inactive = !item.active;
+                            let has_no_speed = item.download_speed() < 1024;
+                            let has_no_seeds = item.seeds() == 0;
+                            let has_no_peers = item.peers() == 0;
+                            let is_inactive = !item.active();
                             
-                            has_no_speed && item.upload_speed == 0 && ((has_no_seeds && has_no_peers) || is_inactive)
+                            has_no_speed && item.upload_speed() == 0 && ((has_no_seeds && has_no_peers) || is_inactive)
                         } else {
                             status_lower.contains("stalled")
                         }
                     };
                     
-                    if is_stalled && !item.active {
+                    if is_stalled && !item.active() {
                         Some(1.0)
                     } else {
-                        let is_expired = if let Some(expires_at) = &item.expires_at {
+                        let is_expired = if let Some(expires_at) = item.expires_at() {
                             if let Ok(expires_time) = DateTime::parse_from_rfc3339(expires_at) {
                                 expires_time.timestamp() < now
                             } else {
@@ -306,7 +563,7 @@ impl AutomationEngine {
                         
                         if is_expired || status_lower == "expired" {
                             Some(1.0)
-                        } else if !item.active 
+                        } else if !item.active() 
                             && status_lower != "expired" 
                             && !status_lower.contains("cached") 
                             && !status_lower.contains("completed") 
@@ -326,28 +583,33 @@ impl AutomationEngine {
                 }
             }
             ConditionType::DownloadFinished => {
-                return if item.download_finished { 1.0 } else { 0.0 } == condition.value;
+                let flag = if item.download_finished() { 1.0 } else { 0.0 };
+                return (flag == condition.value, serde_json::json!(flag));
             }
             ConditionType::Cached => {
-                return if item.cached { 1.0 } else { 0.0 } == condition.value;
+                let flag = if item.cached() { 1.0 } else { 0.0 };
+                return (flag == condition.value, serde_json::json!(flag));
             }
             ConditionType::Private => {
-                return if item.private { 1.0 } else { 0.0 } == condition.value;
+                let flag = if item.as_torrent().map(|t| t.private).unwrap_or(false) { 1.0 } else { 0.0 };
+                return (flag == condition.value, serde_json::json!(flag));
             }
             ConditionType::LongTermSeeding => {
-                return if item.long_term_seeding { 1.0 } else { 0.0 } == condition.value;
+                let flag = if item.as_torrent().map(|t| t.long_term_seeding).unwrap_or(false) { 1.0 } else { 0.0 };
+                return (flag == condition.value, serde_json::json!(flag));
             }
             ConditionType::SeedTorrent => {
-                return if item.seed_torrent { 1.0 } else { 0.0 } == condition.value;
+                let flag = if item.as_torrent().map(|t| t.seed_torrent).unwrap_or(false) { 1.0 } else { 0.0 };
+                return (flag == condition.value, serde_json::json!(flag));
             }
             ConditionType::ETA => {
-                Some(item.eta as f64 / 3600.0)
+                Some(item.eta() as f64 / 3600.0)
             }
             ConditionType::Availability => {
-                Some(item.availability as f64)
+                Some(item.availability() as f64)
             }
             ConditionType::ExpiresAt => {
-                if let Some(expires_at) = &item.expires_at {
+                if let Some(expires_at) = item.expires_at() {
                     if let Ok(expires_time) = DateTime::parse_from_rfc3339(expires_at) {
                         let elapsed = expires_time.timestamp() - now;
                         if elapsed > 0 {
@@ -363,128 +625,319 @@ impl AutomationEngine {
                 }
             }
             ConditionType::DownloadPresent => {
-                return if item.download_present { 1.0 } else { 0.0 } == condition.value;
+                let flag = if item.download_present() { 1.0 } else { 0.0 };
+                return (flag == condition.value, serde_json::json!(flag));
             }
             ConditionType::TorrentFile => {
-                return if item.torrent_file { 1.0 } else { 0.0 } == condition.value;
+                let flag = if item.torrent_file() { 1.0 } else { 0.0 };
+                return (flag == condition.value, serde_json::json!(flag));
             }
             ConditionType::AllowZipped => {
-                return if item.allow_zipped { 1.0 } else { 0.0 } == condition.value;
+                let flag = if item.as_torrent().map(|t| t.allow_zipped).unwrap_or(false) { 1.0 } else { 0.0 };
+                return (flag == condition.value, serde_json::json!(flag));
             }
             ConditionType::HasMagnet => {
-                return if item.magnet.is_some() { 1.0 } else { 0.0 } == condition.value;
+                let flag = if item.as_torrent().map(|t| t.magnet.is_some()).unwrap_or(false) { 1.0 } else { 0.0 };
+                return (flag == condition.value, serde_json::json!(flag));
             }
+            ConditionType::TimesRestarted => Some(history.action_count(item, Some(&ActionType::Restart)) as f64),
+            ConditionType::TimesReannounced => Some(history.action_count(item, Some(&ActionType::Reannounce)) as f64),
+            ConditionType::TimesResumed => Some(history.action_count(item, Some(&ActionType::Resume)) as f64),
+            ConditionType::TimesActedOn => Some(history.action_count(item, None) as f64),
+            ConditionType::HoursSinceLastAction => match history.last_action_at(item) {
+                Some(last_at) => Some((now - last_at) as f64 / 3600.0),
+                None => return (false, serde_json::Value::Null),
+            },
         };
 
+        let measured = serde_json::json!(condition_value);
         let condition_value = condition_value.unwrap_or(0.0);
-        match condition.operator {
+        let matched = match condition.operator {
             Operator::GreaterThan => condition_value > condition.value,
             Operator::LessThan => condition_value < condition.value,
             Operator::GreaterThanOrEqual => condition_value >= condition.value,
             Operator::LessThanOrEqual => condition_value <= condition.value,
             Operator::Equal => (condition_value - condition.value).abs() < 0.001,
+        };
+        (matched, measured)
+    }
+
+    async fn execute_step(
+        &self,
+        database: &Database,
+        rule: &AutomationRule,
+        step: &ActionStep,
+        client: &TorboxClient,
+        item: &DownloadItem,
+    ) -> Result<ActionOutput, String> {
+        // Requests can queue in the shared rate limiter before they are sent, and
+        // starting cloud transfers takes one request per file, so it gets more time.
+        let timeout_secs = match step.action_type {
+            ActionType::CloudUpload => 60,
+            _ => 20,
+        };
+        let per_step_timeout = tokio::time::Duration::from_secs(timeout_secs);
+
+        match tokio::time::timeout(per_step_timeout, self.execute_action(database, rule, step, client, item)).await {
+            Ok(result) => result,
+            Err(_) => {
+                log!("Action timed out after {} seconds for rule '{}'", timeout_secs, rule.name);
+                Err(format!("Action timed out after {} seconds", timeout_secs))
+            }
         }
     }
 
     async fn execute_action(
         &self,
-        action: &ActionConfig,
+        database: &Database,
+        rule: &AutomationRule,
+        step: &ActionStep,
+        client: &TorboxClient,
+        item: &DownloadItem,
+    ) -> Result<ActionOutput, String> {
+        let download_type = item.download_type();
+        if !step.action_type.supports(download_type) {
+            return Err(format!("{} is not supported for {}s", action_label(&step.action_type), download_type.label()));
+        }
+
+        if let ActionType::ExportMagnet = step.action_type {
+            return client.export_torrent_data(item.id(), "magnet".to_string()).await
+                .map(|response| ActionOutput { detail: response.data, ..Default::default() })
+                .map_err(|e| format!("Failed to export magnet: {}", e));
+        }
+
+        if let ActionType::Notify = step.action_type {
+            let config: NotificationConfig = step.params.clone()
+                .ok_or_else(|| "Notify action is missing its notification settings".to_string())
+                .and_then(|params| serde_json::from_value(params)
+                    .map_err(|e| format!("Invalid notification settings: {}", e)))?;
+            return notifier::send(&config, &NotificationEvent::for_item(rule, item)).await
+                .map(|_| ActionOutput::default());
+        }
+
+        if let ActionType::CloudUpload = step.action_type {
+            return self.upload_to_cloud(rule, step, client, item).await;
+        }
+
+        if let (ActionType::Delete, Some(torrent)) = (&step.action_type, item.as_torrent()) {
+            let mut deleted = archive::archive(client, torrent, &rule.api_key_hash).await?;
+            deleted.rule_id = rule.id;
+            deleted.rule_name = Some(rule.name.clone());
+            return archive::delete_archived(database, client, deleted).await
+                .map(|_| ActionOutput::default());
+        }
+
+        self.control_download(client, &step.action_type, download_type, item.id()).await
+            .map(|_| ActionOutput::default())
+    }
+
+    /// Runs a control action (stop seeding, delete, ...) on a download by id.
+    pub async fn control_download(
+        &self,
         client: &TorboxClient,
-        item: &Torrent,
+        action_type: &ActionType,
+        download_type: DownloadType,
+        id: i32,
     ) -> Result<(), String> {
-        match action.action_type {
-            ActionType::StopSeeding => {
-                client
-                    .control_torrent("stop_seeding".to_string(), item.id, false)
-                    .await
-                    .map_err(|e| format!("Failed to stop seeding: {}", e))?;
-            }
-            ActionType::Delete => {
-                client
-                    .control_torrent("delete".to_string(), item.id, false)
-                    .await
-                    .map_err(|e| format!("Failed to delete: {}", e))?;
-            }
-            ActionType::Stop => {
-                client
-                    .control_torrent("stop".to_string(), item.id, false)
-                    .await
-                    .map_err(|e| format!("Failed to stop: {}", e))?;
-            }
-            ActionType::Resume => {
-                client
-                    .control_torrent("resume".to_string(), item.id, false)
-                    .await
-                    .map_err(|e| format!("Failed to resume: {}", e))?;
-            }
-            ActionType::Restart => {
-                client
-                    .control_torrent("restart".to_string(), item.id, false)
-                    .await
-                    .map_err(|e| format!("Failed to restart: {}", e))?;
-            }
-            ActionType::Reannounce => {
-                client
-                    .control_torrent("reannounce".to_string(), item.id, false)
-                    .await
-                    .map_err(|e| format!("Failed to reannounce: {}", e))?;
-            }
-            ActionType::ForceStart => {
-                client
-                    .control_torrent("start".to_string(), item.id, false)
-                    .await
-                    .map_err(|e| format!("Failed to force start: {}", e))?;
+        let operation = action_type.operation_for(download_type)
+            .ok_or_else(|| format!("{} is not supported for {}s", action_label(action_type), download_type.label()))?;
+
+        let result = match download_type {
+            DownloadType::Torrent => client.control_torrent(operation.to_string(), id, false).await,
+            DownloadType::WebDownload => client.control_web_download(operation.to_string(), id, false).await,
+            DownloadType::Usenet => client.control_usenet_download(operation.to_string(), id, false).await,
+        };
+
+        result
+            .map(|_| ())
+            .map_err(|e| format!("Failed to {}: {}", action_label(action_type).to_lowercase(), e))
+    }
+
+    /// Starts TorBox transfer jobs for a finished download. Any `on_success`
+    /// actions are left to the transfer tracker, which runs them once every
+    /// transfer started here has completed.
+    async fn upload_to_cloud(
+        &self,
+        rule: &AutomationRule,
+        step: &ActionStep,
+        client: &TorboxClient,
+        item: &DownloadItem,
+    ) -> Result<ActionOutput, String> {
+        let config: CloudUploadConfig = step.params.clone()
+            .ok_or_else(|| "Cloud upload action is missing its settings".to_string())
+            .and_then(|params| serde_json::from_value(params)
+                .map_err(|e| format!("Invalid cloud upload settings: {}", e)))?;
+
+        if !item.download_finished() {
+            return Err("Download has not finished yet".to_string());
+        }
+
+        // file_id 0 sends the whole download.
+        let targets: Vec<(i32, Option<String>)> = if config.file_patterns.is_empty() {
+            vec![(0, None)]
+        } else {
+            let patterns = config.file_matchers()?;
+            item.files()
+                .into_iter()
+                .filter(|(_, name)| patterns.iter().any(|pattern| pattern.is_match(name)))
+                .map(|(id, name)| (id, Some(name.to_string())))
+                .collect()
+        };
+
+        if targets.is_empty() {
+            return Err("No files match the upload patterns".to_string());
+        }
+
+        let batch_id = uuid::Uuid::new_v4().to_string();
+        let mut transfers: Vec<TrackedTransfer> = Vec::new();
+
+        for (file_id, file_name) in targets {
+            let request = CloudUpload {
+                id: item.id(),
+                file_id,
+                zip: config.zip && file_id == 0,
+                r#type: item.download_type().integration_type().to_string(),
+                token: String::new(),
+            };
+
+            let response = match config.provider {
+                CloudProvider::GoogleDrive => client.upload_to_google_drive(request).await,
+                CloudProvider::Dropbox => client.upload_to_dropbox(request).await,
+                CloudProvider::OneDrive => client.upload_to_onedrive(request).await,
+            };
+
+            let job_id = response
+                .map_err(|e| e.to_string())
+                .and_then(|response| transfer_job_id(response.data.as_ref())
+                    .ok_or_else(|| "TorBox returned no transfer job id to track".to_string()));
+
+            match job_id {
+                Ok(job_id) => transfers.push(TrackedTransfer {
+                    id: None,
+                    rule_id: rule.id.unwrap_or(0),
+                    rule_name: rule.name.clone(),
+                    rule_revision: rule.revision,
+                    api_key_hash: rule.api_key_hash.clone(),
+                    batch_id: batch_id.clone(),
+                    download_id: item.id(),
+                    download_type: item.download_type(),
+                    item_name: item.name().to_string(),
+                    file_name,
+                    provider: config.provider,
+                    job_id,
+                    status: TRANSFER_PENDING.to_string(),
+                    on_success: config.on_success.clone(),
+                    created_at: None,
+                    completed_at: None,
+                }),
+                Err(e) if transfers.is_empty() => {
+                    return Err(format!("Failed to start {} upload: {}", config.provider.label(), e));
+                }
+                Err(e) => {
+                    // Follow-up actions must not run on a partially uploaded download.
+                    let started: Vec<String> = transfers.iter().map(|t| t.job_id.to_string()).collect();
+                    return Err(format!(
+                        "Failed to start {} upload of '{}': {} (transfer jobs {} were started but will not be tracked)",
+                        config.provider.label(), file_name.unwrap_or_else(|| item.name().to_string()), e, started.join(", ")
+                    ));
+                }
             }
         }
-        Ok(())
+
+        let job_ids: Vec<String> = transfers.iter().map(|t| t.job_id.to_string()).collect();
+        Ok(ActionOutput {
+            detail: Some(format!("Started {} transfer job {}", config.provider.label(), job_ids.join(", "))),
+            transfers,
+        })
     }
 
-    async fn fetch_torrents_with_retry(
+    async fn fetch_items_with_retry(
         &self,
         client: &TorboxClient,
+        download_type: DownloadType,
         rule_name: &str,
         max_retries: u32,
-    ) -> Result<Vec<Torrent>, String> {
-        let mut last_error = None;
-        
+    ) -> Result<Vec<DownloadItem>, RunError> {
         for attempt in 1..=max_retries {
-            match client.get_torrent_list(None, Some(true), None, None).await {
-                Ok(response) => {
-                    if let Some(data) = response.data {
-                        if attempt > 1 {
-                            log!("Successfully fetched torrent list for rule '{}' on attempt {}", rule_name, attempt);
-                        }
-                        return Ok(data);
-                    } else {
-                        return Err("No torrent data returned".to_string());
+            let fetched = match download_type {
+                DownloadType::Torrent => client.get_torrent_list(None, Some(true), None, None).await
+                    .map(|r| r.data.map(|d| d.into_iter().map(DownloadItem::Torrent).collect::<Vec<_>>())),
+                DownloadType::WebDownload => client.get_web_download_list(None, Some(true), None, None).await
+                    .map(|r| r.data.map(|d| d.into_iter().map(DownloadItem::WebDownload).collect::<Vec<_>>())),
+                DownloadType::Usenet => client.get_usenet_download_list(None, Some(true), None, None).await
+                    .map(|r| r.data.map(|d| d.into_iter().map(DownloadItem::Usenet).collect::<Vec<_>>())),
+            };
+
+            match fetched {
+                Ok(Some(data)) => {
+                    if attempt > 1 {
+                        log!("Successfully fetched {} list for rule '{}' on attempt {}", download_type.label(), rule_name, attempt);
                     }
+                    return Ok(data);
+                }
+                Ok(None) => {
+                    return Err(RunError::Other(format!("No {} data returned", download_type.label())));
                 }
                 Err(e) => {
-                    let error_str = e.to_string();
-                    let is_transient = error_str.contains("530") 
-                        || error_str.contains("504") 
-                        || error_str.contains("502") 
-                        || error_str.contains("503")
-                        || error_str.contains("Network error");
-                    
-                    last_error = Some(error_str.clone());
-                    
-                    if is_transient && attempt < max_retries {
-                        let delay_secs = attempt as u64;
-                        log!("Transient error fetching torrents for rule '{}' (attempt {}/{}): {}. Retrying in {} seconds...", 
-                             rule_name, attempt, max_retries, error_str, delay_secs);
-                        tokio::time::sleep(tokio::time::Duration::from_secs(delay_secs)).await;
+                    let delay = e.retry_after()
+                        .unwrap_or_else(|| std::time::Duration::from_secs(attempt as u64));
+
+                    if e.is_retryable() && attempt < max_retries && delay <= MAX_FETCH_RETRY_DELAY {
+                        log!("Transient error fetching {}s for rule '{}' (attempt {}/{}): {}. Retrying in {} seconds...",
+                             download_type.label(), rule_name, attempt, max_retries, e, delay.as_secs());
+                        tokio::time::sleep(delay).await;
                         continue;
-                    } else {
-                        if !is_transient {
-                            return Err(format!("Failed to fetch torrents: {}", error_str));
-                        }
                     }
+
+                    let context = if attempt > 1 {
+                        format!("Failed to fetch {}s after {} attempts", download_type.label(), attempt)
+                    } else {
+                        format!("Failed to fetch {}s", download_type.label())
+                    };
+                    return Err(RunError::Api { context, error: e });
                 }
             }
         }
-        
-        Err(format!("Failed to fetch torrents after {} attempts: {}", max_retries, last_error.unwrap_or_else(|| "Unknown error".to_string())))
+
+        Err(RunError::Other(format!("Failed to fetch {}s", download_type.label())))
+    }
+}
+
+/// Why a rule run or preview failed before it could process anything. TorBox
+/// errors keep their `ApiError` so callers can tell transient upstream failures
+/// from ones that need attention.
+#[derive(Debug, Clone)]
+pub enum RunError {
+    Api { context: String, error: ApiError },
+    Other(String),
+}
+
+impl RunError {
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            RunError::Api { error, .. } => Some(error),
+            RunError::Other(_) => None,
+        }
+    }
+
+    /// An upstream failure that is likely to clear up by the next run.
+    pub fn is_transient(&self) -> bool {
+        self.api_error().map(|error| error.is_retryable()).unwrap_or(false)
+    }
+}
+
+impl std::fmt::Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunError::Api { context, error } => write!(f, "{}: {}", context, error),
+            RunError::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<String> for RunError {
+    fn from(message: String) -> Self {
+        RunError::Other(message)
     }
 }
 
@@ -496,6 +949,106 @@ pub struct ExecutionResult {
     pub error_message: Option<String>,
     pub processed_items: Option<Vec<ProcessedItem>>,
     pub partial: bool,
+    /// Cloud transfers started by this run, for the caller to hand to the transfer tracker.
+    #[serde(default)]
+    pub transfers: Vec<TrackedTransfer>,
+}
+
+#[derive(Debug, Default)]
+struct ActionOutput {
+    detail: Option<String>,
+    transfers: Vec<TrackedTransfer>,
+}
+
+const MAX_PREVIEW_ITEMS: usize = 500;
+
+/// Longest `Retry-After` worth waiting out inside a run; anything longer fails
+/// the fetch and leaves it to the next trigger.
+const MAX_FETCH_RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(30);
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PreviewResult {
+    pub total_items: i32,
+    pub matched_count: i32,
+    pub truncated: bool,
+    pub matched_items: Vec<PreviewItem>,
+    /// Matching downloads left out because the rule acted on them too recently or too often.
+    #[serde(default)]
+    pub on_cooldown: i32,
+    /// Totals before and after the run, for quota rules.
+    #[serde(default)]
+    pub quota: Option<QuotaStatus>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PreviewItem {
+    pub id: i32,
+    pub download_type: DownloadType,
+    pub name: String,
+    pub size: i64,
+    pub download_state: String,
+    pub conditions: Vec<ConditionMeasurement>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ConditionMeasurement {
+    pub index: usize,
+    pub label: String,
+    pub value: serde_json::Value,
+    pub matched: bool,
+}
+
+fn condition_label(leaf: &ConditionNode) -> String {
+    match leaf {
+        ConditionNode::Condition(condition) => {
+            format!("{:?} {:?} {}", condition.r#type, condition.operator, condition.value)
+        }
+        ConditionNode::Text(condition) => match &condition.operator {
+            TextOperator::Equals(value) => format!("{:?} equals \"{}\"", condition.field, value),
+            TextOperator::Contains(value) => format!("{:?} contains \"{}\"", condition.field, value),
+            TextOperator::Matches(pattern) => format!("{:?} matches /{}/", condition.field, pattern),
+            TextOperator::InList(values) => format!("{:?} in [{}]", condition.field, values.join(", ")),
+        },
+        _ => String::new(),
+    }
+}
+
+type RegexCache = HashMap<(String, bool), Regex>;
+
+fn compile_text_regexes(conditions: &ConditionNode) -> Result<RegexCache, String> {
+    let mut regexes = HashMap::new();
+    for condition in conditions.text_conditions() {
+        if let TextOperator::Matches(pattern) = &condition.operator {
+            if let Some(regex) = condition.compile_regex()? {
+                regexes.insert((pattern.clone(), condition.case_sensitive), regex);
+            }
+        }
+    }
+    Ok(regexes)
+}
+
+pub fn action_label(action_type: &ActionType) -> &'static str {
+    match action_type {
+        ActionType::StopSeeding => "Stop Seeding",
+        ActionType::Delete => "Delete",
+        ActionType::Stop => "Stop",
+        ActionType::Resume => "Resume",
+        ActionType::Restart => "Restart",
+        ActionType::Reannounce => "Reannounce",
+        ActionType::ForceStart => "Force Start",
+        ActionType::ExportMagnet => "Export Magnet",
+        ActionType::Notify => "Notify",
+        ActionType::CloudUpload => "Cloud Upload",
+    }
+}
+
+/// Pulls the job id out of an upload response, which may be the bare id or an object holding it.
+fn transfer_job_id(data: Option<&serde_json::Value>) -> Option<i32> {
+    let data = data?;
+    data.as_i64()
+        .or_else(|| data.get("job_id").and_then(|id| id.as_i64()))
+        .or_else(|| data.get("id").and_then(|id| id.as_i64()))
+        .map(|id| id as i32)
 }
 
 impl Default for AutomationEngine {
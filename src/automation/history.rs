@@ -0,0 +1,118 @@
+use crate::automation::database::Database;
+use crate::automation::items::DownloadItem;
+use crate::automation::types::*;
+use std::collections::HashMap;
+
+/// Successful actions of one type on one download, totalled over every rule.
+#[derive(Debug, Clone)]
+pub struct ActionTally {
+    pub download_hash: Option<String>,
+    pub action_type: ActionType,
+    pub count: i64,
+    pub last_at: i64,
+}
+
+/// Runs of the rule being evaluated that acted on one download.
+#[derive(Debug, Clone)]
+pub struct RuleTally {
+    pub download_hash: Option<String>,
+    pub runs: i64,
+    pub last_at: i64,
+}
+
+/// What automation has done to the downloads of one API key, as far back as
+/// execution logs are kept. Loaded before a run for rules with a cooldown or a
+/// history condition; empty otherwise.
+#[derive(Debug, Default)]
+pub struct ItemHistory {
+    actions: HashMap<i32, Vec<ActionTally>>,
+    rule: HashMap<i32, Vec<RuleTally>>,
+}
+
+impl ItemHistory {
+    pub fn new(actions: Vec<(i32, ActionTally)>, rule: Vec<(i32, RuleTally)>) -> Self {
+        let mut history = Self::default();
+        for (download_id, tally) in actions {
+            history.actions.entry(download_id).or_default().push(tally);
+        }
+        for (download_id, tally) in rule {
+            history.rule.entry(download_id).or_default().push(tally);
+        }
+        history
+    }
+
+    /// Successful actions on `item`, of `action_type` or of any type.
+    pub fn action_count(&self, item: &DownloadItem, action_type: Option<&ActionType>) -> i64 {
+        self.actions
+            .get(&item.id())
+            .into_iter()
+            .flatten()
+            .filter(|tally| same_download(tally.download_hash.as_deref(), item))
+            .filter(|tally| action_type.map(|action_type| &tally.action_type == action_type).unwrap_or(true))
+            .map(|tally| tally.count)
+            .sum()
+    }
+
+    /// Unix time of the last action any rule took on `item`.
+    pub fn last_action_at(&self, item: &DownloadItem) -> Option<i64> {
+        self.actions
+            .get(&item.id())
+            .into_iter()
+            .flatten()
+            .filter(|tally| same_download(tally.download_hash.as_deref(), item))
+            .map(|tally| tally.last_at)
+            .max()
+    }
+
+    /// Whether the rule's cooldown keeps it off `item` at unix time `now`.
+    pub fn on_cooldown(&self, cooldown: &CooldownConfig, item: &DownloadItem, now: i64) -> bool {
+        let tallies: Vec<&RuleTally> = self.rule
+            .get(&item.id())
+            .into_iter()
+            .flatten()
+            .filter(|tally| same_download(tally.download_hash.as_deref(), item))
+            .collect();
+        let Some(last_at) = tallies.iter().map(|tally| tally.last_at).max() else {
+            return false;
+        };
+
+        let runs: i64 = tallies.iter().map(|tally| tally.runs).sum();
+        let too_many = cooldown.max_actions.map(|max| runs >= max as i64).unwrap_or(false);
+        let too_recent = cooldown.hours.map(|hours| ((now - last_at) as f64 / 3600.0) < hours).unwrap_or(false);
+        too_many || too_recent
+    }
+}
+
+/// A recorded hash has to match too, in case the id now belongs to a different
+/// download. Actions recorded without one (cloud upload follow-ups) match on id alone.
+fn same_download(recorded_hash: Option<&str>, item: &DownloadItem) -> bool {
+    recorded_hash.map(|hash| hash == item.hash()).unwrap_or(true)
+}
+
+pub fn rule_uses_history(rule: &AutomationRule) -> bool {
+    rule.cooldown.is_some()
+        || rule.conditions.leaves().iter().any(|leaf| matches!(leaf, ConditionNode::Condition(condition) if condition.r#type.uses_history()))
+}
+
+/// The history `rule` needs to run, skipping the lookup for rules that do not use it.
+pub async fn load(database: &Database, rule: &AutomationRule) -> Result<ItemHistory, String> {
+    if !rule_uses_history(rule) {
+        return Ok(ItemHistory::default());
+    }
+    database.get_item_history(&rule.api_key_hash, rule.id.unwrap_or(0)).await
+}
+
+pub fn validate_cooldown(cooldown: &CooldownConfig) -> Result<(), String> {
+    if cooldown.hours.is_none() && cooldown.max_actions.is_none() {
+        return Err("Cooldown needs a number of hours, a maximum number of actions, or both".to_string());
+    }
+    if let Some(hours) = cooldown.hours {
+        if !hours.is_finite() || hours <= 0.0 || hours > 24.0 * 365.0 {
+            return Err("Cooldown hours must be between 0 and 8760".to_string());
+        }
+    }
+    if cooldown.max_actions == Some(0) {
+        return Err("Cooldown maximum actions must be at least 1".to_string());
+    }
+    Ok(())
+}
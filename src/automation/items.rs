@@ -0,0 +1,222 @@
+use crate::api::types::{Torrent, UsenetDownload, WebDownload};
+use crate::automation::types::DownloadType;
+
+#[derive(Debug, Clone)]
+pub enum DownloadItem {
+    Torrent(Torrent),
+    WebDownload(WebDownload),
+    Usenet(UsenetDownload),
+}
+
+impl DownloadItem {
+    pub fn download_type(&self) -> DownloadType {
+        match self {
+            DownloadItem::Torrent(_) => DownloadType::Torrent,
+            DownloadItem::WebDownload(_) => DownloadType::WebDownload,
+            DownloadItem::Usenet(_) => DownloadType::Usenet,
+        }
+    }
+
+    pub fn as_torrent(&self) -> Option<&Torrent> {
+        match self {
+            DownloadItem::Torrent(t) => Some(t),
+            _ => None,
+        }
+    }
+
+    pub fn id(&self) -> i32 {
+        match self {
+            DownloadItem::Torrent(t) => t.id,
+            DownloadItem::WebDownload(w) => w.id,
+            DownloadItem::Usenet(u) => u.id,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            DownloadItem::Torrent(t) => &t.name,
+            DownloadItem::WebDownload(w) => &w.name,
+            DownloadItem::Usenet(u) => &u.name,
+        }
+    }
+
+    pub fn hash(&self) -> &str {
+        match self {
+            DownloadItem::Torrent(t) => &t.hash,
+            DownloadItem::WebDownload(w) => &w.hash,
+            DownloadItem::Usenet(u) => &u.hash,
+        }
+    }
+
+    pub fn active(&self) -> bool {
+        match self {
+            DownloadItem::Torrent(t) => t.active,
+            DownloadItem::WebDownload(w) => w.active,
+            DownloadItem::Usenet(u) => u.active,
+        }
+    }
+
+    pub fn created_at(&self) -> &str {
+        match self {
+            DownloadItem::Torrent(t) => &t.created_at,
+            DownloadItem::WebDownload(w) => &w.created_at,
+            DownloadItem::Usenet(u) => &u.created_at,
+        }
+    }
+
+    pub fn updated_at(&self) -> &str {
+        match self {
+            DownloadItem::Torrent(t) => &t.updated_at,
+            DownloadItem::WebDownload(w) => &w.updated_at,
+            DownloadItem::Usenet(u) => &u.updated_at,
+        }
+    }
+
+    pub fn download_state(&self) -> &str {
+        match self {
+            DownloadItem::Torrent(t) => &t.download_state,
+            DownloadItem::WebDownload(w) => &w.download_state,
+            DownloadItem::Usenet(u) => &u.download_state,
+        }
+    }
+
+    pub fn download_finished(&self) -> bool {
+        match self {
+            DownloadItem::Torrent(t) => t.download_finished,
+            DownloadItem::WebDownload(w) => w.download_finished,
+            DownloadItem::Usenet(u) => u.download_finished,
+        }
+    }
+
+    pub fn download_present(&self) -> bool {
+        match self {
+            DownloadItem::Torrent(t) => t.download_present,
+            DownloadItem::WebDownload(w) => w.download_present,
+            DownloadItem::Usenet(u) => u.download_present,
+        }
+    }
+
+    pub fn size(&self) -> i64 {
+        match self {
+            DownloadItem::Torrent(t) => t.size,
+            DownloadItem::WebDownload(w) => w.size,
+            DownloadItem::Usenet(u) => u.size,
+        }
+    }
+
+    pub fn progress(&self) -> f32 {
+        match self {
+            DownloadItem::Torrent(t) => t.progress,
+            DownloadItem::WebDownload(w) => w.progress,
+            DownloadItem::Usenet(u) => u.progress,
+        }
+    }
+
+    pub fn download_speed(&self) -> i64 {
+        match self {
+            DownloadItem::Torrent(t) => t.download_speed,
+            DownloadItem::WebDownload(w) => w.download_speed,
+            DownloadItem::Usenet(u) => u.download_speed,
+        }
+    }
+
+    pub fn upload_speed(&self) -> i64 {
+        match self {
+            DownloadItem::Torrent(t) => t.upload_speed,
+            DownloadItem::WebDownload(w) => w.upload_speed,
+            DownloadItem::Usenet(_) => 0,
+        }
+    }
+
+    pub fn eta(&self) -> i32 {
+        match self {
+            DownloadItem::Torrent(t) => t.eta,
+            DownloadItem::WebDownload(w) => w.eta,
+            DownloadItem::Usenet(u) => u.eta,
+        }
+    }
+
+    pub fn expires_at(&self) -> Option<&str> {
+        match self {
+            DownloadItem::Torrent(t) => t.expires_at.as_deref(),
+            DownloadItem::WebDownload(w) => w.expires_at.as_deref(),
+            DownloadItem::Usenet(u) => u.expires_at.as_deref(),
+        }
+    }
+
+    pub fn cached(&self) -> bool {
+        match self {
+            DownloadItem::Torrent(t) => t.cached,
+            DownloadItem::WebDownload(_) => false,
+            DownloadItem::Usenet(u) => u.cached,
+        }
+    }
+
+    pub fn cached_at(&self) -> Option<&str> {
+        match self {
+            DownloadItem::Torrent(t) => t.cached_at.as_deref(),
+            DownloadItem::WebDownload(_) => None,
+            DownloadItem::Usenet(u) => u.cached_at.as_deref(),
+        }
+    }
+
+    pub fn availability(&self) -> f32 {
+        match self {
+            DownloadItem::Torrent(t) => t.availability,
+            DownloadItem::WebDownload(w) => w.availability,
+            DownloadItem::Usenet(_) => 0.0,
+        }
+    }
+
+    pub fn torrent_file(&self) -> bool {
+        match self {
+            DownloadItem::Torrent(t) => t.torrent_file,
+            DownloadItem::WebDownload(w) => w.torrent_file,
+            DownloadItem::Usenet(_) => false,
+        }
+    }
+
+    pub fn tracker(&self) -> Option<&str> {
+        self.as_torrent().and_then(|t| t.tracker.as_deref())
+    }
+
+    pub fn tracker_message(&self) -> Option<&str> {
+        self.as_torrent().and_then(|t| t.tracker_message.as_deref())
+    }
+
+    pub fn file_names(&self) -> Vec<&str> {
+        match self {
+            DownloadItem::Torrent(t) => t.files.iter().flatten().map(|f| f.name.as_str()).collect(),
+            DownloadItem::WebDownload(w) => w.files.iter().map(|f| f.name.as_str()).collect(),
+            DownloadItem::Usenet(u) => u.files.iter().map(|f| f.name.as_str()).collect(),
+        }
+    }
+
+    pub fn files(&self) -> Vec<(i32, &str)> {
+        match self {
+            DownloadItem::Torrent(t) => t.files.iter().flatten().map(|f| (f.id, f.name.as_str())).collect(),
+            DownloadItem::WebDownload(w) => w.files.iter().map(|f| (f.id, f.name.as_str())).collect(),
+            DownloadItem::Usenet(u) => u.files.iter().map(|f| (f.id, f.name.as_str())).collect(),
+        }
+    }
+
+    pub fn file_extensions(&self) -> Vec<String> {
+        let mut extensions: Vec<String> = self
+            .file_names()
+            .into_iter()
+            .filter_map(|name| name.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()))
+            .filter(|ext| !ext.is_empty() && !ext.contains('/'))
+            .collect();
+        extensions.sort();
+        extensions.dedup();
+        extensions
+    }
+
+    pub fn seeds(&self) -> i32 {
+        self.as_torrent().map(|t| t.seeds).unwrap_or(0)
+    }
+
+    pub fn peers(&self) -> i32 {
+        self.as_torrent().map(|t| t.peers).unwrap_or(0)
+    }
+}
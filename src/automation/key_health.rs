@@ -0,0 +1,52 @@
+use crate::api::ApiError;
+use crate::automation::database::Database;
+use crate::automation::engine::RunError;
+use crate::automation::types::*;
+use leptos::logging::log;
+
+/// Updates the key's authentication status after a run. A run that got as far as
+/// returning a result proves TorBox accepted the key; a rejected key counts
+/// towards suspension; every other failure says nothing about the key.
+///
+/// Returns true if the run lifted a suspension, in which case the caller should
+/// reload the scheduler.
+pub async fn record_outcome<T>(database: &Database, api_key_hash: &str, outcome: &Result<T, RunError>) -> bool {
+    let recorded = match outcome {
+        Ok(_) => database.record_auth_success(api_key_hash).await,
+        Err(e) => match e.api_error() {
+            Some(error) => record_api_error(database, api_key_hash, error).await.map(|_| false),
+            None => Ok(false),
+        },
+    };
+
+    recorded.unwrap_or_else(|e| {
+        log!("Failed to update API key status: {}", e);
+        false
+    })
+}
+
+/// Counts `error` against the key if it is an authentication failure, and marks
+/// each of the key's rules as suspended once it crosses the threshold.
+pub async fn record_api_error(database: &Database, api_key_hash: &str, error: &ApiError) -> Result<(), String> {
+    if !matches!(error, ApiError::AuthenticationError(_)) {
+        return Ok(());
+    }
+
+    if !database.record_auth_failure(api_key_hash, &error.to_string()).await? {
+        return Ok(());
+    }
+
+    log!("TorBox rejected an API key {} times in a row; suspending its rules", AUTH_FAILURE_THRESHOLD);
+
+    let message = format!(
+        "Key invalid: TorBox rejected the API key {} times in a row ({}). The rule is suspended until you sign in with a valid key.",
+        AUTH_FAILURE_THRESHOLD, error
+    );
+    for rule in database.get_rules_by_api_key(api_key_hash).await? {
+        if rule.enabled {
+            database.log_execution(&ExecutionLog::without_result(&rule, EXECUTION_TYPE_SUSPENDED, message.clone())).await?;
+        }
+    }
+
+    Ok(())
+}
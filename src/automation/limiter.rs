@@ -0,0 +1,89 @@
+use leptos::logging::log;
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+
+pub const SKIPPED_ALREADY_RUNNING: &str = "skipped: already running";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunRejection {
+    AlreadyRunning,
+    TimedOut(u64),
+}
+
+impl RunRejection {
+    pub fn message(&self) -> String {
+        match self {
+            RunRejection::AlreadyRunning => SKIPPED_ALREADY_RUNNING.to_string(),
+            RunRejection::TimedOut(secs) => format!("Rule execution timed out after {} seconds", secs),
+        }
+    }
+}
+
+/// Gate every rule execution goes through, whatever triggered it: at most one run
+/// per rule at a time, a global cap on concurrent runs, and the execution timeout.
+pub struct ExecutionLimiter {
+    in_flight: Arc<Mutex<HashSet<i64>>>,
+    slots: Semaphore,
+    timeout_secs: u64,
+}
+
+/// Releases the rule's in-flight slot when the run finishes or is cancelled.
+struct InFlightGuard {
+    in_flight: Arc<Mutex<HashSet<i64>>>,
+    rule_id: i64,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            in_flight.remove(&self.rule_id);
+        }
+    }
+}
+
+impl ExecutionLimiter {
+    pub fn new(max_concurrent_runs: usize, timeout_secs: u64) -> Self {
+        Self {
+            in_flight: Arc::new(Mutex::new(HashSet::new())),
+            slots: Semaphore::new(max_concurrent_runs.max(1)),
+            timeout_secs,
+        }
+    }
+
+    pub fn is_running(&self, rule_id: i64) -> bool {
+        self.in_flight.lock().map(|in_flight| in_flight.contains(&rule_id)).unwrap_or(false)
+    }
+
+    pub fn running_count(&self) -> usize {
+        self.in_flight.lock().map(|in_flight| in_flight.len()).unwrap_or(0)
+    }
+
+    /// Runs `run` for `rule_id` unless that rule is already in flight. Waits for a
+    /// free slot under the global cap, then applies the timeout to the run itself.
+    pub async fn run<F, T>(&self, rule_id: i64, run: F) -> Result<T, RunRejection>
+    where
+        F: Future<Output = T>,
+    {
+        let _guard = {
+            let mut in_flight = self.in_flight.lock().map_err(|_| RunRejection::AlreadyRunning)?;
+            if !in_flight.insert(rule_id) {
+                return Err(RunRejection::AlreadyRunning);
+            }
+            InFlightGuard { in_flight: self.in_flight.clone(), rule_id }
+        };
+
+        let _permit = match self.slots.try_acquire() {
+            Ok(permit) => permit,
+            Err(_) => {
+                log!("Rule {} is waiting for a free execution slot", rule_id);
+                self.slots.acquire().await.map_err(|_| RunRejection::AlreadyRunning)?
+            }
+        };
+
+        tokio::time::timeout(tokio::time::Duration::from_secs(self.timeout_secs), run)
+            .await
+            .map_err(|_| RunRejection::TimedOut(self.timeout_secs))
+    }
+}
@@ -0,0 +1,232 @@
+use crate::automation::limiter::SKIPPED_ALREADY_RUNNING;
+use crate::automation::types::*;
+use crate::automation::window::SKIPPED_OUTSIDE_WINDOW;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+pub const MAX_PAGE_SIZE: usize = 500;
+/// Runs an export may contain; narrower filters are needed beyond this.
+pub const MAX_EXPORT_LOGS: usize = 10_000;
+
+/// Messages of runs that were skipped rather than attempted.
+pub const SKIPPED_MESSAGES: [&str; 2] = [SKIPPED_ALREADY_RUNNING, SKIPPED_OUTSIDE_WINDOW];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStatus {
+    Success,
+    Partial,
+    Failed,
+    Skipped,
+}
+
+impl LogStatus {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_lowercase().as_str() {
+            "success" => Ok(LogStatus::Success),
+            "partial" => Ok(LogStatus::Partial),
+            "failed" => Ok(LogStatus::Failed),
+            "skipped" => Ok(LogStatus::Skipped),
+            other => Err(format!("Unknown log status '{}', expected success, partial, failed or skipped", other)),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogStatus::Success => "success",
+            LogStatus::Partial => "partial",
+            LogStatus::Failed => "failed",
+            LogStatus::Skipped => "skipped",
+        }
+    }
+
+    pub fn of(log: &ExecutionLog) -> Self {
+        if log.partial.unwrap_or(false) {
+            LogStatus::Partial
+        } else if log.success {
+            LogStatus::Success
+        } else if log.error_message.as_deref().map(|message| SKIPPED_MESSAGES.contains(&message)).unwrap_or(false) {
+            LogStatus::Skipped
+        } else {
+            LogStatus::Failed
+        }
+    }
+}
+
+/// Which execution logs of an API key to return. Bounds are UTC timestamps in
+/// the `YYYY-MM-DD HH:MM:SS` form `executed_at` is stored in.
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    pub rule_id: Option<i64>,
+    pub from: Option<String>,
+    /// Exclusive upper bound.
+    pub to: Option<String>,
+    pub status: Option<LogStatus>,
+    /// Matched case-insensitively against the names of the processed items.
+    pub item: Option<String>,
+}
+
+impl LogFilter {
+    /// Reads `rule_id`, `from`, `to`, `status` and `item` from query parameters.
+    pub fn from_query(query: &HashMap<String, String>) -> Result<Self, String> {
+        let param = |name: &str| query.get(name).map(|value| value.trim()).filter(|value| !value.is_empty());
+
+        let rule_id = param("rule_id")
+            .map(|id| id.parse::<i64>().map_err(|_| format!("Invalid rule_id: {}", id)))
+            .transpose()?;
+        let from = param("from").map(|value| parse_bound(value, false)).transpose()?;
+        let to = param("to").map(|value| parse_bound(value, true)).transpose()?;
+        let status = param("status").map(LogStatus::from_name).transpose()?;
+        let item = param("item").map(|item| item.to_string());
+
+        if let (Some(from), Some(to)) = (&from, &to) {
+            if from >= to {
+                return Err("'from' must be before 'to'".to_string());
+            }
+        }
+        if item.as_ref().map(|item| item.len() > 200).unwrap_or(false) {
+            return Err("Item search cannot exceed 200 characters".to_string());
+        }
+
+        Ok(Self { rule_id, from, to, status, item })
+    }
+}
+
+/// Accepts an RFC 3339 timestamp or a `YYYY-MM-DD` date in UTC. A date used as
+/// the upper bound covers that whole day.
+fn parse_bound(value: &str, upper: bool) -> Result<String, String> {
+    let time = if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let date = if upper { date + Duration::days(1) } else { date };
+        date.and_hms_opt(0, 0, 0).map(|time| time.and_utc())
+    } else {
+        DateTime::parse_from_rfc3339(value).ok().map(|time| time.with_timezone(&Utc))
+    };
+    time.map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+        .ok_or_else(|| format!("Invalid date '{}', expected YYYY-MM-DD or an RFC 3339 timestamp", value))
+}
+
+/// `LIKE` pattern matching `search` anywhere, with `\` as the escape character.
+pub fn like_pattern(search: &str) -> String {
+    let escaped = search.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+#[derive(Debug, Serialize)]
+pub struct LogPage {
+    pub logs: Vec<ExecutionLog>,
+    /// Pass as `cursor` to get the next, older page; `None` on the last page.
+    pub next_cursor: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogExportFormat {
+    Csv,
+    Json,
+}
+
+impl LogExportFormat {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "csv" => Ok(LogExportFormat::Csv),
+            "json" => Ok(LogExportFormat::Json),
+            other => Err(format!("Unsupported log export format: {}", other)),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            LogExportFormat::Csv => "csv",
+            LogExportFormat::Json => "json",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            LogExportFormat::Csv => "text/csv",
+            LogExportFormat::Json => "application/json",
+        }
+    }
+
+    pub fn serialize(&self, logs: &[ExecutionLog]) -> Result<String, String> {
+        match self {
+            LogExportFormat::Csv => Ok(to_csv(logs)),
+            LogExportFormat::Json => serde_json::to_string_pretty(logs).map_err(|e| format!("Failed to serialize logs: {}", e)),
+        }
+    }
+}
+
+const CSV_HEADER: [&str; 16] = [
+    "log_id",
+    "executed_at",
+    "rule_id",
+    "rule_name",
+    "execution_type",
+    "status",
+    "items_processed",
+    "total_items",
+    "error_message",
+    "item_id",
+    "item_type",
+    "item_name",
+    "item_size",
+    "item_action",
+    "item_success",
+    "item_error",
+];
+
+/// One row per processed item, so a spreadsheet can filter on what happened to
+/// each download. Runs without items get a single row with the item columns empty.
+fn to_csv(logs: &[ExecutionLog]) -> String {
+    let mut csv = CSV_HEADER.join(",");
+    csv.push_str("\r\n");
+
+    for log in logs {
+        let run = [
+            log.id.map(|id| id.to_string()).unwrap_or_default(),
+            log.executed_at.clone().unwrap_or_default(),
+            log.rule_id.to_string(),
+            log.rule_name.clone(),
+            log.execution_type.clone(),
+            LogStatus::of(log).name().to_string(),
+            log.items_processed.to_string(),
+            log.total_items.map(|total| total.to_string()).unwrap_or_default(),
+            log.error_message.clone().unwrap_or_default(),
+        ];
+
+        let items = log.processed_items.as_deref().unwrap_or_default();
+        let item_rows: Vec<[String; 7]> = if items.is_empty() {
+            vec![Default::default()]
+        } else {
+            items
+                .iter()
+                .map(|item| [
+                    item.id.to_string(),
+                    item.download_type.map(|download_type| download_type.label().to_string()).unwrap_or_default(),
+                    item.name.clone(),
+                    item.size.map(|size| size.to_string()).unwrap_or_default(),
+                    item.action.clone(),
+                    item.success.to_string(),
+                    item.error.clone().unwrap_or_default(),
+                ])
+                .collect()
+        };
+
+        for item in item_rows {
+            let row: Vec<String> = run.iter().chain(item.iter()).map(|field| csv_field(field)).collect();
+            csv.push_str(&row.join(","));
+            csv.push_str("\r\n");
+        }
+    }
+
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,345 @@
+use leptos::logging::log;
+use rusqlite::{params, Connection, Transaction};
+
+/// One step of the automation schema. Versions are applied in order, each in its
+/// own transaction, and recorded in `schema_version`.
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    apply: fn(&Transaction) -> rusqlite::Result<()>,
+}
+
+/// Every schema change, oldest first. Append new migrations to the end; never edit
+/// or renumber one that has shipped.
+///
+/// Databases created before versioning existed start at version 0 with any subset
+/// of the first five migrations already in place, so those only add what is missing.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "initial schema",
+        apply: initial_schema,
+    },
+    Migration {
+        version: 2,
+        description: "record run details in execution logs",
+        apply: execution_log_details,
+    },
+    Migration {
+        version: 3,
+        description: "add download types to rules",
+        apply: rule_download_types,
+    },
+    Migration {
+        version: 4,
+        description: "add notification settings to rules",
+        apply: rule_notification_config,
+    },
+    Migration {
+        version: 5,
+        description: "track cloud upload transfer jobs",
+        apply: transfer_jobs,
+    },
+    Migration {
+        version: 6,
+        description: "versioned data keys for API key encryption",
+        apply: data_keys,
+    },
+    Migration {
+        version: 7,
+        description: "track authentication failures per API key",
+        apply: api_key_health,
+    },
+    Migration {
+        version: 8,
+        description: "add schedule windows to rules",
+        apply: rule_schedule_window,
+    },
+    Migration {
+        version: 9,
+        description: "add storage quotas to rules",
+        apply: rule_quota,
+    },
+    Migration {
+        version: 10,
+        description: "per-download action history and rule cooldowns",
+        apply: item_action_history,
+    },
+    Migration {
+        version: 11,
+        description: "rule revision history",
+        apply: rule_revisions,
+    },
+    Migration {
+        version: 12,
+        description: "archive of deleted torrents",
+        apply: deleted_torrents,
+    },
+];
+
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map(|migration| migration.version).unwrap_or(0)
+}
+
+/// Brings the database up to `latest_version()` and returns the version it was at
+/// before. Refuses to touch a database written by a newer build.
+pub fn run(conn: &mut Connection) -> Result<u32, String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create schema_version table: {}", e))?;
+
+    let current = current_version(conn)?;
+    let latest = latest_version();
+    if current > latest {
+        return Err(format!(
+            "Database schema version {} is newer than this build supports ({}). Upgrade TorBox Companion or restore a backup.",
+            current, latest
+        ));
+    }
+
+    for migration in MIGRATIONS.iter().filter(|migration| migration.version > current) {
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to start migration {}: {}", migration.version, e))?;
+        (migration.apply)(&tx)
+            .and_then(|_| {
+                tx.execute(
+                    "INSERT INTO schema_version (version, description) VALUES (?, ?)",
+                    params![migration.version, migration.description],
+                )
+            })
+            .map_err(|e| format!("Migration {} ({}) failed: {}", migration.version, migration.description, e))?;
+        tx.commit()
+            .map_err(|e| format!("Failed to commit migration {}: {}", migration.version, e))?;
+        log!("Applied database migration {}: {}", migration.version, migration.description);
+    }
+
+    Ok(current)
+}
+
+pub fn current_version(conn: &Connection) -> Result<u32, String> {
+    conn.query_row("SELECT COALESCE(MAX(version), 0) FROM schema_version", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read schema version: {}", e))
+}
+
+fn column_exists(tx: &Transaction, table: &str, column: &str) -> rusqlite::Result<bool> {
+    tx.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?",
+        params![table, column],
+        |row| Ok(row.get::<_, i64>(0)? > 0),
+    )
+}
+
+fn add_column_if_missing(tx: &Transaction, table: &str, column: &str, definition: &str) -> rusqlite::Result<()> {
+    if !column_exists(tx, table, column)? {
+        tx.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    }
+    Ok(())
+}
+
+fn initial_schema(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS server_key (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            encryption_key BLOB NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS api_keys (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            api_key_hash TEXT UNIQUE NOT NULL,
+            encrypted_api_key BLOB NOT NULL,
+            nonce BLOB NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            last_used_at DATETIME
+        );
+
+        CREATE TABLE IF NOT EXISTS automation_rules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            api_key_hash TEXT NOT NULL,
+            name TEXT NOT NULL,
+            enabled BOOLEAN DEFAULT true,
+            trigger_config TEXT NOT NULL,
+            conditions TEXT NOT NULL,
+            action_config TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (api_key_hash) REFERENCES api_keys(api_key_hash)
+        );
+
+        CREATE TABLE IF NOT EXISTS rule_execution_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            rule_id INTEGER NOT NULL,
+            rule_name TEXT NOT NULL,
+            api_key_hash TEXT NOT NULL,
+            execution_type TEXT NOT NULL,
+            items_processed INTEGER DEFAULT 0,
+            success BOOLEAN DEFAULT true,
+            error_message TEXT,
+            executed_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (rule_id) REFERENCES automation_rules(id)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_rules_api_key_hash ON automation_rules(api_key_hash);
+        CREATE INDEX IF NOT EXISTS idx_rules_enabled ON automation_rules(enabled);
+        CREATE INDEX IF NOT EXISTS idx_logs_rule_id ON rule_execution_log(rule_id);
+        CREATE INDEX IF NOT EXISTS idx_logs_api_key_hash ON rule_execution_log(api_key_hash);
+        CREATE INDEX IF NOT EXISTS idx_logs_executed_at ON rule_execution_log(executed_at);",
+    )
+}
+
+fn execution_log_details(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "rule_execution_log", "processed_items", "TEXT")?;
+    add_column_if_missing(tx, "rule_execution_log", "total_items", "INTEGER")?;
+    add_column_if_missing(tx, "rule_execution_log", "partial", "BOOLEAN")
+}
+
+fn rule_download_types(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "automation_rules", "download_types", "TEXT")
+}
+
+fn rule_notification_config(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "automation_rules", "notification_config", "TEXT")
+}
+
+fn transfer_jobs(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS transfer_jobs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            rule_id INTEGER NOT NULL,
+            rule_name TEXT NOT NULL,
+            api_key_hash TEXT NOT NULL,
+            batch_id TEXT NOT NULL,
+            download_id INTEGER NOT NULL,
+            download_type TEXT NOT NULL,
+            item_name TEXT NOT NULL,
+            file_name TEXT,
+            provider TEXT NOT NULL,
+            job_id INTEGER NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            on_success TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            completed_at DATETIME,
+            FOREIGN KEY (rule_id) REFERENCES automation_rules(id)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_transfers_status ON transfer_jobs(status);
+        CREATE INDEX IF NOT EXISTS idx_transfers_batch_id ON transfer_jobs(batch_id);",
+    )
+}
+
+/// Moves the single server key into `data_keys`, where it can be wrapped by a
+/// master key and rotated. Existing API key rows keep their legacy ciphertext
+/// until they are re-encrypted on the next startup.
+fn data_keys(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE data_keys (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            key_material BLOB NOT NULL,
+            wrap_nonce BLOB,
+            master_key_id TEXT,
+            active BOOLEAN NOT NULL DEFAULT false,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            retired_at DATETIME
+        );
+
+        INSERT INTO data_keys (id, key_material, active, created_at)
+            SELECT id, encryption_key, true, created_at FROM server_key;
+        DROP TABLE server_key;
+
+        CREATE UNIQUE INDEX idx_data_keys_active ON data_keys(active) WHERE active;
+
+        ALTER TABLE api_keys ADD COLUMN data_key_id INTEGER REFERENCES data_keys(id);
+        ALTER TABLE api_keys ADD COLUMN cipher_version INTEGER NOT NULL DEFAULT 0;
+        UPDATE api_keys SET data_key_id = (SELECT id FROM data_keys WHERE active);",
+    )
+}
+
+fn api_key_health(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "ALTER TABLE api_keys ADD COLUMN torbox_user_id INTEGER;
+        ALTER TABLE api_keys ADD COLUMN auth_failures INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE api_keys ADD COLUMN last_auth_error TEXT;
+        ALTER TABLE api_keys ADD COLUMN suspended_at DATETIME;
+
+        CREATE INDEX idx_api_keys_torbox_user_id ON api_keys(torbox_user_id);",
+    )
+}
+
+fn rule_schedule_window(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute("ALTER TABLE automation_rules ADD COLUMN schedule_window TEXT", []).map(|_| ())
+}
+
+fn rule_quota(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute("ALTER TABLE automation_rules ADD COLUMN quota TEXT", []).map(|_| ())
+}
+
+fn item_action_history(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE item_actions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            api_key_hash TEXT NOT NULL,
+            download_id INTEGER NOT NULL,
+            download_hash TEXT,
+            download_type TEXT,
+            action_type TEXT NOT NULL,
+            rule_id INTEGER NOT NULL,
+            execution_log_id INTEGER NOT NULL,
+            acted_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE INDEX idx_item_actions_download ON item_actions(api_key_hash, download_id);
+        CREATE INDEX idx_item_actions_acted_at ON item_actions(acted_at);
+
+        ALTER TABLE automation_rules ADD COLUMN cooldown TEXT;",
+    )
+}
+
+/// Rules that exist before this migration get their first revision on the next
+/// startup, from `Database::record_baseline_revisions`.
+fn rule_revisions(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE rule_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            rule_id INTEGER NOT NULL,
+            revision INTEGER NOT NULL,
+            definition TEXT NOT NULL,
+            action TEXT NOT NULL,
+            client_ip TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE (rule_id, revision)
+        );
+
+        ALTER TABLE automation_rules ADD COLUMN revision INTEGER;
+        ALTER TABLE rule_execution_log ADD COLUMN rule_revision INTEGER;
+        ALTER TABLE transfer_jobs ADD COLUMN rule_revision INTEGER;",
+    )
+}
+
+fn deleted_torrents(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE deleted_torrents (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            api_key_hash TEXT NOT NULL,
+            torrent_id INTEGER NOT NULL,
+            hash TEXT NOT NULL,
+            name TEXT NOT NULL,
+            size INTEGER NOT NULL,
+            magnet TEXT NOT NULL,
+            files TEXT NOT NULL,
+            rule_id INTEGER,
+            rule_name TEXT,
+            deleted_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            readded_at DATETIME,
+            readded_torrent_id INTEGER
+        );
+
+        CREATE INDEX idx_deleted_torrents_api_key ON deleted_torrents(api_key_hash, deleted_at);",
+    )
+}
@@ -1,15 +1,51 @@
 #[cfg(feature = "ssr")]
+pub mod analytics;
+#[cfg(feature = "ssr")]
+pub mod archive;
+#[cfg(feature = "ssr")]
+pub mod bundle;
+#[cfg(feature = "ssr")]
 pub mod database;
 #[cfg(feature = "ssr")]
 pub mod encryption;
 #[cfg(feature = "ssr")]
 pub mod engine;
 #[cfg(feature = "ssr")]
+pub mod history;
+#[cfg(feature = "ssr")]
+pub mod items;
+#[cfg(feature = "ssr")]
+pub mod key_health;
+#[cfg(feature = "ssr")]
+pub mod limiter;
+#[cfg(feature = "ssr")]
+pub mod logs;
+#[cfg(feature = "ssr")]
+pub mod migrations;
+#[cfg(feature = "ssr")]
+pub mod notifier;
+#[cfg(feature = "ssr")]
+pub mod pool;
+#[cfg(feature = "ssr")]
+pub mod quota;
+#[cfg(feature = "ssr")]
+pub mod revisions;
+#[cfg(feature = "ssr")]
 pub mod routes;
 #[cfg(feature = "ssr")]
+pub mod schedule;
+#[cfg(feature = "ssr")]
 pub mod scheduler;
 #[cfg(feature = "ssr")]
+pub mod templates;
+#[cfg(feature = "ssr")]
+pub mod transfers;
+#[cfg(feature = "ssr")]
 pub mod types;
+#[cfg(feature = "ssr")]
+pub mod watcher;
+#[cfg(feature = "ssr")]
+pub mod window;
 
 #[cfg(feature = "ssr")]
 pub use database::Database;
@@ -20,4 +56,8 @@ pub use routes::{create_routes, AppState};
 #[cfg(feature = "ssr")]
 pub use scheduler::AutomationScheduler;
 #[cfg(feature = "ssr")]
+pub use transfers::TransferTracker;
+#[cfg(feature = "ssr")]
 pub use types::*;
+#[cfg(feature = "ssr")]
+pub use watcher::EventWatcher;
@@ -0,0 +1,395 @@
+use crate::automation::engine::{ExecutionResult, RunError};
+use crate::automation::items::DownloadItem;
+use crate::automation::types::*;
+use leptos::logging::log;
+use reqwest::{Client, Url};
+use serde::Serialize;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+const MAX_LISTED_ITEMS: usize = 10;
+const MAX_PAYLOAD_ITEMS: usize = 25;
+const DISCORD_CONTENT_LIMIT: usize = 2000;
+
+/// Set to `true` to let notifications reach loopback, private and link-local
+/// addresses, e.g. a Gotify server on the same network.
+pub const ALLOW_PRIVATE_TARGETS_VAR: &str = "TORBOX_ALLOW_PRIVATE_NOTIFICATION_TARGETS";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationEvent {
+    pub event: String,
+    pub rule_id: Option<i64>,
+    pub rule_name: String,
+    pub status: String,
+    pub items_processed: i32,
+    pub total_items: i32,
+    pub error_message: Option<String>,
+    pub items: Vec<NotifiedItem>,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NotifiedItem {
+    pub id: i32,
+    pub name: String,
+    pub download_type: Option<DownloadType>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+impl NotificationEvent {
+    pub fn from_run(rule: &AutomationRule, result: &Result<ExecutionResult, RunError>) -> Self {
+        match result {
+            Ok(result) => {
+                let status = if result.success {
+                    "success"
+                } else if result.partial {
+                    "partial"
+                } else {
+                    "failed"
+                };
+                let items = result.processed_items
+                    .as_deref()
+                    .unwrap_or_default()
+                    .iter()
+                    .take(MAX_PAYLOAD_ITEMS)
+                    .map(|item| NotifiedItem {
+                        id: item.id,
+                        name: item.name.clone(),
+                        download_type: item.download_type,
+                        success: item.success,
+                        error: item.error.clone(),
+                    })
+                    .collect();
+
+                Self {
+                    event: "rule_run".to_string(),
+                    rule_id: rule.id,
+                    rule_name: rule.name.clone(),
+                    status: status.to_string(),
+                    items_processed: result.items_processed,
+                    total_items: result.total_items,
+                    error_message: result.error_message.clone(),
+                    items,
+                    timestamp: chrono::Utc::now().to_rfc3339(),
+                }
+            }
+            Err(e) => Self {
+                event: "rule_run".to_string(),
+                rule_id: rule.id,
+                rule_name: rule.name.clone(),
+                status: "failed".to_string(),
+                items_processed: 0,
+                total_items: 0,
+                error_message: Some(e.to_string()),
+                items: Vec::new(),
+                timestamp: chrono::Utc::now().to_rfc3339(),
+            },
+        }
+    }
+
+    pub fn for_item(rule: &AutomationRule, item: &DownloadItem) -> Self {
+        Self {
+            event: "item_action".to_string(),
+            rule_id: rule.id,
+            rule_name: rule.name.clone(),
+            status: "success".to_string(),
+            items_processed: 1,
+            total_items: 1,
+            error_message: None,
+            items: vec![NotifiedItem {
+                id: item.id(),
+                name: item.name().to_string(),
+                download_type: Some(item.download_type()),
+                success: true,
+                error: None,
+            }],
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    pub fn test(rule_name: &str) -> Self {
+        Self {
+            event: "test".to_string(),
+            rule_id: None,
+            rule_name: rule_name.to_string(),
+            status: "success".to_string(),
+            items_processed: 1,
+            total_items: 1,
+            error_message: None,
+            items: vec![NotifiedItem {
+                id: 0,
+                name: "Example download".to_string(),
+                download_type: Some(DownloadType::Torrent),
+                success: true,
+                error: None,
+            }],
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    fn is_failure(&self) -> bool {
+        self.status != "success"
+    }
+
+    fn title(&self) -> String {
+        match (self.event.as_str(), self.status.as_str()) {
+            ("test", _) => format!("Test notification for '{}'", self.rule_name),
+            ("item_action", _) => format!("Rule '{}' processed a download", self.rule_name),
+            (_, "success") => format!("Rule '{}' completed", self.rule_name),
+            (_, "partial") => format!("Rule '{}' partially completed", self.rule_name),
+            _ => format!("Rule '{}' failed", self.rule_name),
+        }
+    }
+
+    fn message(&self) -> String {
+        let mut lines = Vec::new();
+        if self.event == "rule_run" {
+            lines.push(format!("Processed {} of {} matching downloads.", self.items_processed, self.total_items));
+        }
+        if let Some(error) = &self.error_message {
+            lines.push(error.clone());
+        }
+        for item in self.items.iter().take(MAX_LISTED_ITEMS) {
+            match &item.error {
+                Some(error) => lines.push(format!("✗ {}: {}", item.name, error)),
+                None => lines.push(format!("✓ {}", item.name)),
+            }
+        }
+        let unlisted = (self.items_processed as usize).saturating_sub(MAX_LISTED_ITEMS);
+        if unlisted > 0 {
+            lines.push(format!("... and {} more", unlisted));
+        }
+        lines.join("\n")
+    }
+}
+
+pub fn should_notify(config: &NotificationConfig, result: &Result<ExecutionResult, RunError>) -> bool {
+    match (config.notify_on, result) {
+        (NotifyOn::AfterRun, _) => true,
+        (NotifyOn::FailuresOnly, Ok(result)) => !result.success,
+        (NotifyOn::FailuresOnly, Err(_)) => true,
+        (NotifyOn::ItemsProcessed, Ok(result)) => result.items_processed > 0,
+        (NotifyOn::ItemsProcessed, Err(_)) => false,
+    }
+}
+
+pub fn validate_config(config: &NotificationConfig) -> Result<(), String> {
+    if config.url.len() > 2048 {
+        return Err("Notification URL cannot exceed 2048 characters".to_string());
+    }
+
+    let url = Url::parse(config.url.trim())
+        .map_err(|e| format!("Invalid notification URL: {}", e))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err("Notification URL must use http or https".to_string());
+    }
+
+    // Host names are checked when the notification is sent, once they resolve.
+    let literal_ip = url.host_str()
+        .and_then(|host| host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>().ok());
+    if literal_ip.map(is_private_address).unwrap_or(false) && !private_targets_allowed() {
+        return Err(private_target_error());
+    }
+
+    if config.format == NotificationFormat::Ntfy && ntfy_topic(&url).is_none() {
+        return Err("ntfy URL must include the topic, e.g. https://ntfy.sh/my-topic".to_string());
+    }
+
+    if config.headers.len() > 10 {
+        return Err("Maximum of 10 notification headers allowed".to_string());
+    }
+
+    for (name, value) in &config.headers {
+        reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| format!("Invalid notification header name: {}", name))?;
+        reqwest::header::HeaderValue::from_str(value)
+            .map_err(|_| format!("Invalid value for notification header: {}", name))?;
+    }
+
+    if config.payload_template.is_some() && config.format != NotificationFormat::Generic {
+        return Err("A payload template can only be used with generic webhooks".to_string());
+    }
+
+    Ok(())
+}
+
+pub fn private_targets_allowed() -> bool {
+    std::env::var(ALLOW_PRIVATE_TARGETS_VAR)
+        .map(|value| matches!(value.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// Loopback, private, link-local, carrier-grade NAT and unspecified addresses,
+/// which a notification could use to reach services on the server's network.
+pub fn is_private_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified()
+                || ip.is_broadcast() || (first == 100 && (second & 0xc0) == 64)
+        }
+        IpAddr::V6(ip) => {
+            let first = ip.segments()[0];
+            ip.is_loopback() || ip.is_unspecified() || (first & 0xfe00) == 0xfc00 || (first & 0xffc0) == 0xfe80
+                || ip.to_ipv4_mapped().map(|ip| is_private_address(IpAddr::V4(ip))).unwrap_or(false)
+        }
+    }
+}
+
+fn private_target_error() -> String {
+    format!("Notification URL points to a private or loopback address; set {}=true to allow it", ALLOW_PRIVATE_TARGETS_VAR)
+}
+
+/// Resolves the host of `url`, refusing it when any address is private unless
+/// `allow_private` is set.
+pub async fn resolve_target(url: &Url, allow_private: bool) -> Result<Vec<SocketAddr>, String> {
+    let host = url.host_str()
+        .ok_or_else(|| "Notification URL has no host".to_string())?
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    let port = url.port_or_known_default()
+        .ok_or_else(|| "Notification URL has no port".to_string())?;
+
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port)).await
+        .map_err(|e| format!("Failed to resolve notification host {}: {}", host, e))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(format!("Notification host {} did not resolve to any address", host));
+    }
+    if !allow_private && addrs.iter().any(|addr| is_private_address(addr.ip())) {
+        return Err(private_target_error());
+    }
+
+    Ok(addrs)
+}
+
+/// Posts the notification. The client connects only to the addresses checked by
+/// `resolve_target` and does not follow redirects, so neither a second DNS answer
+/// nor a redirect can send it somewhere private.
+pub async fn send(config: &NotificationConfig, event: &NotificationEvent) -> Result<(), String> {
+    let mut url = Url::parse(config.url.trim())
+        .map_err(|e| format!("Invalid notification URL: {}", e))?;
+
+    let addrs = resolve_target(&url, private_targets_allowed()).await?;
+    let mut builder = Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .redirect(reqwest::redirect::Policy::none());
+    if let Some(domain) = url.domain() {
+        builder = builder.resolve_to_addrs(domain, &addrs);
+    }
+    let client = builder
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let body = match config.format {
+        NotificationFormat::Generic => match &config.payload_template {
+            Some(template) => fill_template(template, event),
+            None => serde_json::to_value(event)
+                .map_err(|e| format!("Failed to serialize notification: {}", e))?,
+        },
+        NotificationFormat::Discord => {
+            let content = format!("**{}**\n{}", event.title(), event.message());
+            serde_json::json!({ "content": truncate(&content, DISCORD_CONTENT_LIMIT) })
+        }
+        NotificationFormat::Slack => {
+            serde_json::json!({ "text": format!("*{}*\n{}", event.title(), event.message()) })
+        }
+        NotificationFormat::Ntfy => {
+            let topic = ntfy_topic(&url)
+                .ok_or_else(|| "ntfy URL must include the topic".to_string())?;
+            // JSON messages go to the server root, which keeps any path the
+            // server is hosted under.
+            let base = url.path().rsplit_once('/').map(|(base, _)| base.to_string()).unwrap_or_default();
+            url.set_path(&format!("{}/", base));
+            serde_json::json!({
+                "topic": topic,
+                "title": event.title(),
+                "message": event.message(),
+                "priority": if event.is_failure() { 4 } else { 3 },
+                "tags": [if event.is_failure() { "warning" } else { "white_check_mark" }],
+            })
+        }
+        NotificationFormat::Gotify => serde_json::json!({
+            "title": event.title(),
+            "message": event.message(),
+            "priority": if event.is_failure() { 8 } else { 5 },
+        }),
+    };
+
+    let mut request = client.post(url).json(&body);
+    for (name, value) in &config.headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+
+    let response = request.send().await
+        .map_err(|e| format!("Failed to send notification: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Notification endpoint returned {}", response.status()));
+    }
+
+    Ok(())
+}
+
+/// Sends the rule's completion notification, if it has one and the run qualifies.
+/// Delivery failures are logged rather than failing the run.
+pub async fn notify_run(rule: &AutomationRule, result: &Result<ExecutionResult, RunError>) {
+    let Some(config) = &rule.notification else {
+        return;
+    };
+
+    if !should_notify(config, result) {
+        return;
+    }
+
+    let event = NotificationEvent::from_run(rule, result);
+    if let Err(e) = send(config, &event).await {
+        log!("Failed to send notification for rule '{}': {}", rule.name, e);
+    }
+}
+
+fn ntfy_topic(url: &Url) -> Option<String> {
+    url.path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|topic| !topic.is_empty())
+        .map(|topic| topic.to_string())
+}
+
+fn fill_template(template: &serde_json::Value, event: &NotificationEvent) -> serde_json::Value {
+    match template {
+        serde_json::Value::String(text) => {
+            let filled = text
+                .replace("{{event}}", &event.event)
+                .replace("{{rule_name}}", &event.rule_name)
+                .replace("{{rule_id}}", &event.rule_id.map(|id| id.to_string()).unwrap_or_default())
+                .replace("{{status}}", &event.status)
+                .replace("{{items_processed}}", &event.items_processed.to_string())
+                .replace("{{total_items}}", &event.total_items.to_string())
+                .replace("{{error_message}}", event.error_message.as_deref().unwrap_or(""))
+                .replace("{{title}}", &event.title())
+                .replace("{{message}}", &event.message())
+                .replace("{{timestamp}}", &event.timestamp);
+            serde_json::Value::String(filled)
+        }
+        serde_json::Value::Array(values) => {
+            serde_json::Value::Array(values.iter().map(|value| fill_template(value, event)).collect())
+        }
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .map(|(key, value)| (key.clone(), fill_template(value, event)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars - 1).collect();
+    truncated.push('…');
+    truncated
+}
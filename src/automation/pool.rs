@@ -0,0 +1,137 @@
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, Semaphore};
+
+pub const DEFAULT_READ_CONNECTIONS: usize = 4;
+
+/// How long a connection waits on a lock held by another connection before
+/// SQLite reports `SQLITE_BUSY`.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// SQLite connections shared by the automation database.
+///
+/// SQLite allows a single writer at a time, so all writes go through one
+/// connection behind an async mutex; in WAL mode readers never wait for it and
+/// are served from a fixed set of read-only connections. Every query runs on
+/// tokio's blocking thread pool so slow disk I/O never stalls the async workers.
+pub struct ConnectionPool {
+    path: PathBuf,
+    writer: Arc<Mutex<Connection>>,
+    readers: std::sync::Mutex<Vec<Connection>>,
+    available_readers: Semaphore,
+    read_connections: usize,
+}
+
+impl ConnectionPool {
+    /// Opens the writer and `read_connections` readers. `prepare_writer` runs on
+    /// the writer before any reader is opened, so schema migrations are in place
+    /// by the time the first read happens.
+    pub fn open(
+        path: &Path,
+        read_connections: usize,
+        prepare_writer: impl FnOnce(&mut Connection) -> Result<(), String>,
+    ) -> Result<Self, String> {
+        let read_connections = read_connections.max(1);
+
+        let mut writer = open_connection(path, false)?;
+        let _: String = writer
+            .query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))
+            .map_err(|e| format!("Failed to set WAL mode: {}", e))?;
+        writer.execute_batch("PRAGMA synchronous = NORMAL")
+            .map_err(|e| format!("Failed to set synchronous mode: {}", e))?;
+        prepare_writer(&mut writer)?;
+
+        let readers = (0..read_connections)
+            .map(|_| open_connection(path, true))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer: Arc::new(Mutex::new(writer)),
+            readers: std::sync::Mutex::new(readers),
+            available_readers: Semaphore::new(read_connections),
+            read_connections,
+        })
+    }
+
+    pub fn read_connections(&self) -> usize {
+        self.read_connections
+    }
+
+    /// Runs `f` on a read-only connection, waiting for one to become free.
+    pub async fn read<T, F>(&self, f: F) -> Result<T, String>
+    where
+        F: FnOnce(&Connection) -> Result<T, String> + Send + 'static,
+        T: Send + 'static,
+    {
+        let _permit = self.available_readers
+            .acquire()
+            .await
+            .map_err(|_| "Database connection pool is closed".to_string())?;
+
+        let pooled = self.readers.lock().ok().and_then(|mut readers| readers.pop());
+        let conn = match pooled {
+            Some(conn) => conn,
+            // A reader is only missing if a query panicked while holding it.
+            None => open_connection(&self.path, true)?,
+        };
+
+        let (conn, result) = tokio::task::spawn_blocking(move || {
+            let result = f(&conn);
+            (conn, result)
+        })
+        .await
+        .map_err(|e| format!("Database read task failed: {}", e))?;
+
+        if let Ok(mut readers) = self.readers.lock() {
+            readers.push(conn);
+        }
+        result
+    }
+
+    /// Runs `f` on the writer connection. Writes are serialized in the order they
+    /// are requested.
+    pub async fn write<T, F>(&self, f: F) -> Result<T, String>
+    where
+        F: FnOnce(&mut Connection) -> Result<T, String> + Send + 'static,
+        T: Send + 'static,
+    {
+        let mut conn = self.writer.clone().lock_owned().await;
+        tokio::task::spawn_blocking(move || f(&mut conn))
+            .await
+            .map_err(|e| format!("Database write task failed: {}", e))?
+    }
+
+    /// Runs `f` on the writer connection only if no other write holds it, and
+    /// returns `None` otherwise. For bookkeeping that can be skipped under load.
+    pub async fn try_write<T, F>(&self, f: F) -> Option<Result<T, String>>
+    where
+        F: FnOnce(&mut Connection) -> Result<T, String> + Send + 'static,
+        T: Send + 'static,
+    {
+        let mut conn = self.writer.clone().try_lock_owned().ok()?;
+        let result = tokio::task::spawn_blocking(move || f(&mut conn))
+            .await
+            .map_err(|e| format!("Database write task failed: {}", e))
+            .and_then(|result| result);
+        Some(result)
+    }
+}
+
+fn open_connection(path: &Path, read_only: bool) -> Result<Connection, String> {
+    let conn = Connection::open(path)
+        .map_err(|e| format!("Failed to open database: {}", e))?;
+
+    conn.busy_timeout(BUSY_TIMEOUT)
+        .map_err(|e| format!("Failed to set busy timeout: {}", e))?;
+    conn.execute("PRAGMA foreign_keys = ON", [])
+        .map_err(|e| format!("Failed to enable foreign keys: {}", e))?;
+    if read_only {
+        conn.execute("PRAGMA query_only = ON", [])
+            .map_err(|e| format!("Failed to make connection read-only: {}", e))?;
+    }
+
+    Ok(conn)
+}
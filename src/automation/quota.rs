@@ -0,0 +1,154 @@
+use crate::automation::items::DownloadItem;
+use crate::automation::types::*;
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+const BYTES_PER_GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+/// Where a quota rule stands before and after acting on the downloads it picked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaStatus {
+    pub total_size: i64,
+    pub total_count: usize,
+    /// What the totals will be once the picked downloads are gone.
+    pub projected_size: i64,
+    pub projected_count: usize,
+    /// Downloads that matched the conditions, before picking.
+    pub matching: usize,
+    /// False if the matching downloads, or `max_items_per_run`, are not enough to get under the target.
+    pub target_reached: bool,
+    pub capped: bool,
+}
+
+pub struct QuotaSelection<'a> {
+    pub items: Vec<&'a DownloadItem>,
+    pub status: QuotaStatus,
+}
+
+pub fn validate(quota: &QuotaConfig, trigger_config: &TriggerConfig, action_config: &ActionConfig) -> Result<(), String> {
+    match quota.target {
+        QuotaTarget::TotalSize { gb } if !gb.is_finite() || gb < 0.0 => {
+            return Err("Quota target size must be zero or more GB".to_string());
+        }
+        _ => {}
+    }
+
+    if quota.max_items_per_run == Some(0) {
+        return Err("Quota max items per run must be at least 1".to_string());
+    }
+
+    if let TriggerConfig::OnEvent { .. } = trigger_config {
+        return Err("Quota rules need a cron or interval trigger, since they weigh every download and not just the ones an event touched".to_string());
+    }
+
+    if !removes_downloads(action_config) {
+        return Err("Quota rules must delete the downloads they pick, either with a Delete step or a Cloud Upload that deletes after uploading".to_string());
+    }
+
+    Ok(())
+}
+
+fn removes_downloads(action_config: &ActionConfig) -> bool {
+    action_config.steps.iter().any(|step| match step.action_type {
+        ActionType::Delete => true,
+        ActionType::CloudUpload => step
+            .params
+            .clone()
+            .and_then(|params| serde_json::from_value::<CloudUploadConfig>(params).ok())
+            .map(|config| config.on_success.iter().any(|action| matches!(action, ActionType::Delete)))
+            .unwrap_or(false),
+        _ => false,
+    })
+}
+
+/// Picks the fewest `matching` downloads, in `sort_by` order, whose removal
+/// brings the totals of `all` under the target, stopping early at
+/// `max_items_per_run`. Nothing is picked when the totals are already under it.
+pub fn select<'a>(quota: &QuotaConfig, all: &[DownloadItem], mut matching: Vec<&'a DownloadItem>) -> QuotaSelection<'a> {
+    let total_size: i64 = all.iter().map(|item| item.size()).sum();
+    let total_count = all.len();
+    let matching_count = matching.len();
+
+    matching.sort_by(|a, b| compare(quota.sort_by, a, b).then_with(|| a.id().cmp(&b.id())));
+
+    let over = |size: i64, count: usize| match quota.target {
+        QuotaTarget::TotalSize { gb } => size as f64 > gb * BYTES_PER_GB,
+        QuotaTarget::ItemCount { count: target } => count > target as usize,
+    };
+    let limit = quota.max_items_per_run.map(|max| max as usize).unwrap_or(usize::MAX);
+
+    let mut items = Vec::new();
+    let (mut projected_size, mut projected_count) = (total_size, total_count);
+    let mut capped = false;
+    for item in matching {
+        if !over(projected_size, projected_count) {
+            break;
+        }
+        if items.len() >= limit {
+            capped = true;
+            break;
+        }
+        projected_size -= item.size();
+        projected_count -= 1;
+        items.push(item);
+    }
+
+    QuotaSelection {
+        items,
+        status: QuotaStatus {
+            total_size,
+            total_count,
+            projected_size,
+            projected_count,
+            matching: matching_count,
+            target_reached: !over(projected_size, projected_count),
+            capped,
+        },
+    }
+}
+
+fn compare(sort_by: QuotaSortKey, a: &DownloadItem, b: &DownloadItem) -> Ordering {
+    match sort_by {
+        QuotaSortKey::Age => earliest_first(timestamp(a.created_at()), timestamp(b.created_at())),
+        QuotaSortKey::LastActivity => earliest_first(timestamp(a.updated_at()), timestamp(b.updated_at())),
+        QuotaSortKey::Size => b.size().cmp(&a.size()),
+        QuotaSortKey::Ratio => {
+            let ratio = |item: &DownloadItem| item.as_torrent().map(|t| t.ratio);
+            match (ratio(a), ratio(b)) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+        }
+    }
+}
+
+/// Orders known times ascending, with unparseable ones last.
+fn earliest_first(a: Option<i64>, b: Option<i64>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn timestamp(value: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(value).ok().map(|time| time.timestamp())
+}
+
+pub fn describe(quota: &QuotaConfig) -> String {
+    let target = match quota.target {
+        QuotaTarget::TotalSize { gb } => format!("{} GB", gb),
+        QuotaTarget::ItemCount { count } => format!("{} downloads", count),
+    };
+    let order = match quota.sort_by {
+        QuotaSortKey::Age => "oldest",
+        QuotaSortKey::Ratio => "lowest ratio",
+        QuotaSortKey::Size => "largest",
+        QuotaSortKey::LastActivity => "least recently active",
+    };
+    format!("keep under {}, {} first", target, order)
+}
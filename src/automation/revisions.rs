@@ -0,0 +1,116 @@
+use crate::automation::bundle::BundledRule;
+use axum::http::HeaderMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::net::{IpAddr, SocketAddr};
+
+/// One saved state of a rule. Revisions are numbered per rule from 1 and never
+/// change once written.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleRevision {
+    pub rule_id: i64,
+    pub revision: i64,
+    /// What produced the revision, e.g. "updated" or "restored revision 3".
+    pub action: String,
+    pub client_ip: Option<String>,
+    pub created_at: Option<String>,
+    pub rule: BundledRule,
+}
+
+/// Who saved a rule and how, recorded with the revision the save creates.
+#[derive(Debug, Clone, Default)]
+pub struct RevisionSource {
+    pub action: String,
+    pub client_ip: Option<String>,
+}
+
+impl RevisionSource {
+    pub fn new(action: impl Into<String>, client_ip: Option<String>) -> Self {
+        Self { action: action.into(), client_ip }
+    }
+
+    /// A save made by the request with `headers` from `peer`.
+    pub fn from_request(action: impl Into<String>, headers: &HeaderMap, peer: SocketAddr) -> Self {
+        Self::new(action, Some(client_ip(headers, peer)))
+    }
+}
+
+/// The peer address, or the address a reverse proxy forwarded for. Forwarding
+/// headers are only believed from loopback and private addresses, so clients
+/// reaching the server directly cannot put someone else's address in the history.
+fn client_ip(headers: &HeaderMap, peer: SocketAddr) -> String {
+    let trusted_proxy = match peer.ip() {
+        IpAddr::V4(ip) => ip.is_loopback() || ip.is_private(),
+        IpAddr::V6(ip) => ip.is_loopback() || ip.to_ipv4_mapped().map(|ip| ip.is_loopback() || ip.is_private()).unwrap_or(false),
+    };
+
+    if trusted_proxy {
+        let forwarded = headers
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .or_else(|| headers.get("x-real-ip").and_then(|value| value.to_str().ok()))
+            .map(str::trim)
+            .filter(|ip| ip.parse::<IpAddr>().is_ok());
+        if let Some(ip) = forwarded {
+            return ip.to_string();
+        }
+    }
+
+    peer.ip().to_string()
+}
+
+/// A value that differs between two revisions, by its path in the rule, e.g.
+/// `action_config.steps[1].action_type`. `None` means absent on that side.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub path: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevisionDiff {
+    pub rule_id: i64,
+    pub from: i64,
+    pub to: i64,
+    pub changes: Vec<FieldChange>,
+}
+
+pub fn diff(before: &BundledRule, after: &BundledRule) -> Result<Vec<FieldChange>, String> {
+    let before = serde_json::to_value(before).map_err(|e| format!("Failed to serialize revision: {}", e))?;
+    let after = serde_json::to_value(after).map_err(|e| format!("Failed to serialize revision: {}", e))?;
+    let mut changes = Vec::new();
+    diff_values(String::new(), Some(&before), Some(&after), &mut changes);
+    Ok(changes)
+}
+
+/// Walks objects and arrays down to the values that differ. Array elements are
+/// compared by position, so inserting an action step shows every later step as changed.
+fn diff_values(path: String, before: Option<&Value>, after: Option<&Value>, changes: &mut Vec<FieldChange>) {
+    if before == after {
+        return;
+    }
+
+    match (before, after) {
+        (Some(Value::Object(before)), Some(Value::Object(after))) => {
+            let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                diff_values(child, before.get(key), after.get(key), changes);
+            }
+        }
+        (Some(Value::Array(before)), Some(Value::Array(after))) => {
+            for index in 0..before.len().max(after.len()) {
+                diff_values(format!("{}[{}]", path, index), before.get(index), after.get(index), changes);
+            }
+        }
+        _ => changes.push(FieldChange {
+            path,
+            before: before.cloned(),
+            after: after.cloned(),
+        }),
+    }
+}
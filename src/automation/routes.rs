@@ -1,10 +1,25 @@
+use crate::api::rate_limiter::{self, KeyBudget, RateLimiter};
+use crate::api::{format_api_error, ApiError, TorboxClient};
+use crate::automation::analytics::{self, AutomationAnalytics};
+use crate::automation::archive::{self, DeletedTorrent};
+use crate::automation::bundle::{BundleFormat, BundledRule, RuleBundle};
 use crate::automation::database::Database;
 use crate::automation::encryption::EncryptionService;
-use crate::automation::engine::ExecutionResult;
+use crate::automation::engine::{ExecutionResult, PreviewResult};
+use crate::automation::history;
+use crate::automation::key_health;
+use crate::automation::limiter::RunRejection;
+use crate::automation::logs::{self, LogExportFormat, LogFilter, LogPage};
+use crate::automation::notifier::{self, NotificationEvent};
+use crate::automation::quota;
+use crate::automation::revisions::{self, RevisionDiff, RevisionSource, RuleRevision};
+use crate::automation::schedule;
 use crate::automation::scheduler::AutomationScheduler;
+use crate::automation::templates::{self, RuleTemplate};
 use crate::automation::types::*;
+use crate::automation::window;
 use axum::{
-    extract::{Path, Query, State},
+    extract::{ConnectInfo, Path, Query, State},
     http::{HeaderMap, StatusCode},
     response::Json,
     routing::{get, post, put, delete},
@@ -13,6 +28,7 @@ use axum::{
 use leptos::logging::log;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio_cron_scheduler::Job;
 
@@ -31,6 +47,16 @@ pub struct ApiResponse<T> {
     pub data: Option<T>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct MigrateRulesRequest {
+    /// The old key itself, which proves it belongs to the caller.
+    #[serde(default)]
+    pub old_api_key: Option<String>,
+    /// Hash of another key of the caller's TorBox account, as listed in the key status.
+    #[serde(default)]
+    pub old_api_key_hash: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RuleLimitInfo {
     pub current_count: i64,
@@ -68,53 +94,189 @@ fn validate_rule(rule: &CreateRuleRequest) -> Result<(), String> {
         return Err("Rule name cannot exceed 200 characters".to_string());
     }
     
-    if rule.conditions.is_empty() {
+    if rule.download_types.is_empty() {
+        return Err("At least one download type is required".to_string());
+    }
+
+    let conditions = rule.conditions.leaves();
+
+    if conditions.is_empty() {
         return Err("At least one condition is required".to_string());
     }
     
-    if rule.conditions.len() > 20 {
+    if conditions.len() > 20 {
         return Err("Maximum of 20 conditions allowed".to_string());
     }
+
+    if rule.conditions.has_empty_group() {
+        return Err("Condition groups cannot be empty".to_string());
+    }
+
+    if rule.conditions.depth() > 6 {
+        return Err("Condition groups cannot be nested more than 5 levels deep".to_string());
+    }
     
-    for (idx, condition) in rule.conditions.iter().enumerate() {
-        if condition.value.is_nan() {
-            return Err(format!("Condition {}: value cannot be NaN", idx + 1));
+    for (idx, leaf) in conditions.iter().enumerate() {
+        match leaf {
+            ConditionNode::Condition(condition) => validate_condition(idx, condition, &rule.download_types)?,
+            ConditionNode::Text(condition) => validate_text_condition(idx, condition, &rule.download_types)?,
+            _ => {}
+        }
+    }
+
+    if rule.action_config.steps.is_empty() {
+        return Err("At least one action is required".to_string());
+    }
+
+    if rule.action_config.steps.len() > 10 {
+        return Err("Maximum of 10 action steps allowed".to_string());
+    }
+
+    for (idx, step) in rule.action_config.steps.iter().enumerate() {
+        for download_type in &rule.download_types {
+            if !step.action_type.supports(*download_type) {
+                return Err(format!("Action {}: {:?} is not supported for {}s", idx + 1, step.action_type, download_type.label()));
+            }
         }
-        
-        if condition.value.is_infinite() {
-            return Err(format!("Condition {}: value cannot be infinite", idx + 1));
+
+        if let ActionType::CloudUpload = step.action_type {
+            if idx + 1 < rule.action_config.steps.len() {
+                return Err(format!("Action {}: Cloud Upload must be the last step; add follow-up actions to its \"after upload\" list instead", idx + 1));
+            }
+            let config: CloudUploadConfig = step.params.clone()
+                .ok_or_else(|| format!("Action {}: cloud upload settings are required", idx + 1))
+                .and_then(|params| serde_json::from_value(params)
+                    .map_err(|e| format!("Action {}: invalid cloud upload settings: {}", idx + 1, e)))?;
+            validate_cloud_upload(idx, &config, &rule.download_types)?;
         }
-        
-        if condition.value < -1_000_000_000.0 || condition.value > 1_000_000_000.0 {
-            return Err(format!("Condition {}: value out of reasonable range", idx + 1));
+
+        if let ActionType::Notify = step.action_type {
+            let config: NotificationConfig = step.params.clone()
+                .ok_or_else(|| format!("Action {}: notification settings are required", idx + 1))
+                .and_then(|params| serde_json::from_value(params)
+                    .map_err(|e| format!("Action {}: invalid notification settings: {}", idx + 1, e)))?;
+            notifier::validate_config(&config)
+                .map_err(|e| format!("Action {}: {}", idx + 1, e))?;
         }
     }
+
+    if let Some(notification) = &rule.notification {
+        notifier::validate_config(notification)?;
+    }
+
+    if let Some(window) = &rule.schedule_window {
+        window::validate(window)?;
+    }
+
+    if let Some(quota) = &rule.quota {
+        quota::validate(quota, &rule.trigger_config, &rule.action_config)?;
+    }
+
+    if let Some(cooldown) = &rule.cooldown {
+        history::validate_cooldown(cooldown)?;
+    }
     
     match &rule.trigger_config {
-        TriggerConfig::Cron { expression } => {
-            let expr = expression.trim();
-            if expr.is_empty() {
-                return Err("Cron expression cannot be empty".to_string());
-            }
-            
-            let parts: Vec<&str> = expr.split_whitespace().collect();
-            if parts.len() != 6 {
-                return Err("Cron expression must have 6 fields (seconds minutes hours day month day-of-week)".to_string());
-            }
-            
-            if let Err(e) = tokio_cron_scheduler::Job::new_async(expr, move |_, _| {
-                Box::pin(async move {})
-            }) {
-                return Err(format!("Invalid cron expression: {}", e));
-            }
+        TriggerConfig::Cron { .. } => {
+            schedule::validate_trigger(&rule.trigger_config)?;
         }
         TriggerConfig::Interval { minutes } => {
             if *minutes > 525600 {
                 return Err("Interval cannot exceed 525600 minutes (1 year)".to_string());
             }
         }
+        TriggerConfig::OnEvent { events } => {
+            if events.is_empty() {
+                return Err("At least one trigger event is required".to_string());
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+fn validate_cloud_upload(idx: usize, config: &CloudUploadConfig, download_types: &[DownloadType]) -> Result<(), String> {
+    if config.file_patterns.len() > 20 {
+        return Err(format!("Action {}: maximum of 20 file patterns allowed", idx + 1));
+    }
+
+    if config.file_patterns.iter().any(|pattern| pattern.trim().is_empty()) {
+        return Err(format!("Action {}: file patterns cannot be empty", idx + 1));
+    }
+
+    if config.zip && !config.file_patterns.is_empty() {
+        return Err(format!("Action {}: zipping only applies when uploading the whole download", idx + 1));
+    }
+
+    config.file_matchers()
+        .map_err(|e| format!("Action {}: {}", idx + 1, e))?;
+
+    if config.on_success.len() > 5 {
+        return Err(format!("Action {}: maximum of 5 follow-up actions allowed", idx + 1));
+    }
+
+    for action_type in &config.on_success {
+        for download_type in download_types {
+            if action_type.operation_for(*download_type).is_none() {
+                return Err(format!("Action {}: {:?} cannot follow an upload of {}s", idx + 1, action_type, download_type.label()));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_condition(idx: usize, condition: &Condition, download_types: &[DownloadType]) -> Result<(), String> {
+    if condition.value.is_nan() {
+        return Err(format!("Condition {}: value cannot be NaN", idx + 1));
+    }
+    
+    if condition.value.is_infinite() {
+        return Err(format!("Condition {}: value cannot be infinite", idx + 1));
     }
     
+    if condition.value < -1_000_000_000.0 || condition.value > 1_000_000_000.0 {
+        return Err(format!("Condition {}: value out of reasonable range", idx + 1));
+    }
+
+    for download_type in download_types {
+        if !condition.r#type.supports(*download_type) {
+            return Err(format!("Condition {}: {:?} does not apply to {}s", idx + 1, condition.r#type, download_type.label()));
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_text_condition(idx: usize, condition: &TextCondition, download_types: &[DownloadType]) -> Result<(), String> {
+    match &condition.operator {
+        TextOperator::Equals(value) | TextOperator::Contains(value) | TextOperator::Matches(value) => {
+            if value.is_empty() {
+                return Err(format!("Condition {}: text value cannot be empty", idx + 1));
+            }
+            if value.len() > 1000 {
+                return Err(format!("Condition {}: text value cannot exceed 1000 characters", idx + 1));
+            }
+        }
+        TextOperator::InList(values) => {
+            if values.is_empty() {
+                return Err(format!("Condition {}: list cannot be empty", idx + 1));
+            }
+            if values.len() > 100 {
+                return Err(format!("Condition {}: list cannot have more than 100 entries", idx + 1));
+            }
+        }
+    }
+
+    condition.compile_regex()
+        .map_err(|e| format!("Condition {}: {}", idx + 1, e))?;
+
+    for download_type in download_types {
+        if !condition.field.supports(*download_type) {
+            return Err(format!("Condition {}: {:?} does not apply to {}s", idx + 1, condition.field, download_type.label()));
+        }
+    }
+
     Ok(())
 }
 
@@ -135,9 +297,10 @@ pub fn create_routes(
             }
         }).post({
             let state = state.clone();
-            move |headers: HeaderMap, query: Query<HashMap<String, String>>, payload: Json<CreateRuleRequest>| {
+            move |ConnectInfo(peer): ConnectInfo<SocketAddr>, headers: HeaderMap, query: Query<HashMap<String, String>>, payload: Json<CreateRuleRequest>| {
                 let state = state.clone();
-                async move { create_rule(headers, query, state, payload).await }
+                let source = RevisionSource::from_request("created", &headers, peer);
+                async move { create_rule(headers, query, state, payload, source).await }
             }
         }))
         .route("/api/automation/rules/{id}", get({
@@ -148,9 +311,10 @@ pub fn create_routes(
             }
         }).put({
             let state = state.clone();
-            move |id: Path<i64>, headers: HeaderMap, query: Query<HashMap<String, String>>, payload: Json<CreateRuleRequest>| {
+            move |id: Path<i64>, ConnectInfo(peer): ConnectInfo<SocketAddr>, headers: HeaderMap, query: Query<HashMap<String, String>>, payload: Json<CreateRuleRequest>| {
                 let state = state.clone();
-                async move { update_rule(id, headers, query, state, payload).await }
+                let source = RevisionSource::from_request("updated", &headers, peer);
+                async move { update_rule(id, headers, query, state, payload, source).await }
             }
         }).delete({
             let state = state.clone();
@@ -166,6 +330,42 @@ pub fn create_routes(
                 async move { get_rule_logs(id, headers, query, state).await }
             }
         }))
+        .route("/api/automation/rules/{id}/revisions", get({
+            let state = state.clone();
+            move |id: Path<i64>, headers: HeaderMap, query: Query<HashMap<String, String>>| {
+                let state = state.clone();
+                async move { get_rule_revisions(id, headers, query, state).await }
+            }
+        }))
+        .route("/api/automation/rules/{id}/revisions/diff", get({
+            let state = state.clone();
+            move |id: Path<i64>, headers: HeaderMap, query: Query<HashMap<String, String>>| {
+                let state = state.clone();
+                async move { diff_rule_revisions(id, headers, query, state).await }
+            }
+        }))
+        .route("/api/automation/rules/{id}/revisions/{revision}/restore", post({
+            let state = state.clone();
+            move |path: Path<(i64, i64)>, ConnectInfo(peer): ConnectInfo<SocketAddr>, headers: HeaderMap, query: Query<HashMap<String, String>>| {
+                let state = state.clone();
+                let source = RevisionSource::from_request(format!("restored revision {}", path.1), &headers, peer);
+                async move { restore_rule_revision(path, headers, query, state, source).await }
+            }
+        }))
+        .route("/api/automation/logs", get({
+            let state = state.clone();
+            move |headers: HeaderMap, query: Query<HashMap<String, String>>| {
+                let state = state.clone();
+                async move { search_logs(headers, query, state).await }
+            }
+        }))
+        .route("/api/automation/logs/export", get({
+            let state = state.clone();
+            move |headers: HeaderMap, query: Query<HashMap<String, String>>| {
+                let state = state.clone();
+                async move { export_logs(headers, query, state).await }
+            }
+        }))
         .route("/api/automation/rules/{id}/next-run", get({
             let state = state.clone();
             move |id: Path<i64>, headers: HeaderMap, query: Query<HashMap<String, String>>| {
@@ -173,6 +373,11 @@ pub fn create_routes(
                 async move { get_next_run_time(id, headers, query, state).await }
             }
         }))
+        .route("/api/automation/triggers/next-runs", post(
+            move |headers: HeaderMap, query: Query<HashMap<String, String>>, payload: Json<NextRunsRequest>| {
+                async move { get_trigger_next_runs(headers, query, payload).await }
+            }
+        ))
         .route("/api/automation/rules/{id}/run", post({
             let state = state.clone();
             move |id: Path<i64>, headers: HeaderMap, query: Query<HashMap<String, String>>| {
@@ -180,6 +385,79 @@ pub fn create_routes(
                 async move { force_run_rule(id, headers, query, state).await }
             }
         }))
+        .route("/api/automation/rules/{id}/preview", post({
+            let state = state.clone();
+            move |id: Path<i64>, headers: HeaderMap, query: Query<HashMap<String, String>>| {
+                let state = state.clone();
+                async move { preview_rule(id, headers, query, state).await }
+            }
+        }))
+        .route("/api/automation/rules/preview", post({
+            let state = state.clone();
+            move |headers: HeaderMap, query: Query<HashMap<String, String>>, payload: Json<CreateRuleRequest>| {
+                let state = state.clone();
+                async move { preview_unsaved_rule(headers, query, state, payload).await }
+            }
+        }))
+        .route("/api/automation/notifications/test", post(
+            move |headers: HeaderMap, query: Query<HashMap<String, String>>, payload: Json<TestNotificationRequest>| {
+                async move { test_notification(headers, query, payload).await }
+            }
+        ))
+        .route("/api/automation/rules/export", get({
+            let state = state.clone();
+            move |headers: HeaderMap, query: Query<HashMap<String, String>>| {
+                let state = state.clone();
+                async move { export_rules(headers, query, state).await }
+            }
+        }))
+        .route("/api/automation/rules/import", post({
+            let state = state.clone();
+            move |ConnectInfo(peer): ConnectInfo<SocketAddr>, headers: HeaderMap, query: Query<HashMap<String, String>>, payload: Json<ImportRulesRequest>| {
+                let state = state.clone();
+                let source = RevisionSource::from_request("imported", &headers, peer);
+                async move { import_rules(headers, query, state, payload, source).await }
+            }
+        }))
+        .route("/api/automation/templates", get(
+            move || async move { get_templates().await }
+        ))
+        .route("/api/automation/templates/{id}", post({
+            let state = state.clone();
+            move |id: Path<String>, ConnectInfo(peer): ConnectInfo<SocketAddr>, headers: HeaderMap, query: Query<HashMap<String, String>>, payload: Json<InstantiateTemplateRequest>| {
+                let state = state.clone();
+                let source = RevisionSource::from_request(format!("created from template {}", *id), &headers, peer);
+                async move { instantiate_template(id, headers, query, state, payload, source).await }
+            }
+        }))
+        .route("/api/automation/torrents/{id}/delete", post({
+            let state = state.clone();
+            move |id: Path<i32>, headers: HeaderMap, query: Query<HashMap<String, String>>| {
+                let state = state.clone();
+                async move { delete_torrent(id, headers, query, state).await }
+            }
+        }))
+        .route("/api/automation/deleted", get({
+            let state = state.clone();
+            move |headers: HeaderMap, query: Query<HashMap<String, String>>| {
+                let state = state.clone();
+                async move { get_deleted_torrents(headers, query, state).await }
+            }
+        }))
+        .route("/api/automation/deleted/{id}", delete({
+            let state = state.clone();
+            move |id: Path<i64>, headers: HeaderMap, query: Query<HashMap<String, String>>| {
+                let state = state.clone();
+                async move { forget_deleted_torrent(id, headers, query, state).await }
+            }
+        }))
+        .route("/api/automation/deleted/{id}/readd", post({
+            let state = state.clone();
+            move |id: Path<i64>, headers: HeaderMap, query: Query<HashMap<String, String>>| {
+                let state = state.clone();
+                async move { readd_deleted_torrent(id, headers, query, state).await }
+            }
+        }))
         .route("/api/automation/rules/bulk-delete", post({
             let state = state.clone();
             move |headers: HeaderMap, query: Query<HashMap<String, String>>, payload: Json<serde_json::Value>| {
@@ -187,6 +465,13 @@ pub fn create_routes(
                 async move { bulk_delete_rules(headers, query, state, payload).await }
             }
         }))
+        .route("/api/automation/analytics", get({
+            let state = state.clone();
+            move |headers: HeaderMap, query: Query<HashMap<String, String>>| {
+                let state = state.clone();
+                async move { get_analytics(headers, query, state).await }
+            }
+        }))
         .route("/api/automation/rules/limit", get({
             let state = state.clone();
             move |headers: HeaderMap, query: Query<HashMap<String, String>>| {
@@ -194,6 +479,32 @@ pub fn create_routes(
                 async move { get_rule_limit(headers, query, state).await }
             }
         }))
+        .route("/api/automation/keys/status", get({
+            let state = state.clone();
+            move |headers: HeaderMap, query: Query<HashMap<String, String>>| {
+                let state = state.clone();
+                async move { get_key_status(headers, query, state).await }
+            }
+        }))
+        .route("/api/automation/keys/sign-in", post({
+            let state = state.clone();
+            move |headers: HeaderMap, query: Query<HashMap<String, String>>| {
+                let state = state.clone();
+                async move { sign_in(headers, query, state).await }
+            }
+        }))
+        .route("/api/automation/keys/migrate", post({
+            let state = state.clone();
+            move |headers: HeaderMap, query: Query<HashMap<String, String>>, payload: Json<MigrateRulesRequest>| {
+                let state = state.clone();
+                async move { migrate_rules(headers, query, state, payload).await }
+            }
+        }))
+        .route("/api/automation/rate-limit", get(
+            move |headers: HeaderMap, query: Query<HashMap<String, String>>| {
+                async move { get_rate_limit(headers, query).await }
+            }
+        ))
         .route("/api/automation/health", get({
             let state = state.clone();
             move || {
@@ -255,9 +566,49 @@ async fn get_rule(
 struct CreateRuleRequest {
     name: String,
     enabled: Option<bool>,
+    #[serde(default = "default_download_types")]
+    download_types: Vec<DownloadType>,
     trigger_config: TriggerConfig,
-    conditions: Vec<Condition>,
+    #[serde(deserialize_with = "deserialize_conditions")]
+    conditions: ConditionNode,
     action_config: ActionConfig,
+    #[serde(default)]
+    notification: Option<NotificationConfig>,
+    #[serde(default)]
+    schedule_window: Option<ScheduleWindow>,
+    #[serde(default)]
+    quota: Option<QuotaConfig>,
+    #[serde(default)]
+    cooldown: Option<CooldownConfig>,
+}
+
+/// Builds the rule to store from a request, keeping the id, enabled flag and
+/// creation time of `existing` when updating.
+fn rule_from_request(payload: &CreateRuleRequest, api_key_hash: &str, existing: Option<&AutomationRule>) -> AutomationRule {
+    let mut trigger_config = payload.trigger_config.clone();
+    if let TriggerConfig::Interval { ref mut minutes } = trigger_config {
+        if *minutes < 30 {
+            *minutes = 30;
+        }
+    }
+
+    AutomationRule {
+        id: existing.and_then(|rule| rule.id),
+        api_key_hash: api_key_hash.to_string(),
+        name: payload.name.clone(),
+        enabled: payload.enabled.unwrap_or(existing.map(|rule| rule.enabled).unwrap_or(true)),
+        download_types: payload.download_types.clone(),
+        trigger_config,
+        conditions: payload.conditions.clone(),
+        action_config: payload.action_config.clone(),
+        notification: payload.notification.clone(),
+        schedule_window: payload.schedule_window.clone(),
+        quota: payload.quota.clone(),
+        cooldown: payload.cooldown.clone(),
+        revision: existing.and_then(|rule| rule.revision),
+        created_at: existing.and_then(|rule| rule.created_at.clone()),
+        updated_at: None,
+    }
 }
 
 async fn create_rule(
@@ -265,6 +616,7 @@ async fn create_rule(
     query: Query<HashMap<String, String>>,
     state: AppState,
     payload: Json<CreateRuleRequest>,
+    source: RevisionSource,
 ) -> Result<Json<ApiResponse<AutomationRule>>, StatusCode> {
     let api_key = extract_api_key(&headers, &query)
         .ok_or(StatusCode::UNAUTHORIZED)?;
@@ -299,29 +651,13 @@ async fn create_rule(
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
-    let mut trigger_config = payload.trigger_config.clone();
-    if let TriggerConfig::Interval { ref mut minutes } = trigger_config {
-        if *minutes < 30 {
-            *minutes = 30;
-        }
-    }
-
-    let rule = AutomationRule {
-        id: None,
-        api_key_hash: api_key_hash.clone(),
-        name: payload.name.clone(),
-        enabled: payload.enabled.unwrap_or(true),
-        trigger_config,
-        conditions: payload.conditions.clone(),
-        action_config: payload.action_config.clone(),
-        created_at: None,
-        updated_at: None,
-    };
+    let rule = rule_from_request(&payload, &api_key_hash, None);
 
-    match state.database.save_rule(&rule).await {
-        Ok(rule_id) => {
+    match state.database.save_rule(&rule, &source).await {
+        Ok((rule_id, revision)) => {
             let mut rule_with_id = rule;
             rule_with_id.id = Some(rule_id);
+            rule_with_id.revision = Some(revision);
 
             state.scheduler.reload_rules().await
                 .map_err(|e| {
@@ -348,6 +684,7 @@ async fn update_rule(
     query: Query<HashMap<String, String>>,
     state: AppState,
     payload: Json<CreateRuleRequest>,
+    source: RevisionSource,
 ) -> Result<Json<ApiResponse<AutomationRule>>, StatusCode> {
     let api_key = extract_api_key(&headers, &query)
         .ok_or(StatusCode::UNAUTHORIZED)?;
@@ -370,27 +707,12 @@ async fn update_rule(
 
     let rule = existing_rule.ok_or(StatusCode::NOT_FOUND)?;
 
-    let mut trigger_config = payload.trigger_config.clone();
-    if let TriggerConfig::Interval { ref mut minutes } = trigger_config {
-        if *minutes < 30 {
-            *minutes = 30;
-        }
-    }
+    let mut updated_rule = rule_from_request(&payload, &api_key_hash, Some(&rule));
 
-    let updated_rule = AutomationRule {
-        id: Some(*id),
-        api_key_hash: api_key_hash.clone(),
-        name: payload.name.clone(),
-        enabled: payload.enabled.unwrap_or(rule.enabled),
-        trigger_config,
-        conditions: payload.conditions.clone(),
-        action_config: payload.action_config.clone(),
-        created_at: rule.created_at,
-        updated_at: None,
-    };
+    match state.database.save_rule(&updated_rule, &source).await {
+        Ok((_, revision)) => {
+            updated_rule.revision = Some(revision);
 
-    match state.database.save_rule(&updated_rule).await {
-        Ok(_) => {
             state.scheduler.reload_rules().await
                 .map_err(|e| {
                     log!("Failed to reload rules: {}", e);
@@ -471,59 +793,45 @@ async fn get_rule_logs(
     }
 }
 
-async fn get_next_run_time(
+async fn get_rule_revisions(
     id: Path<i64>,
     headers: HeaderMap,
     query: Query<HashMap<String, String>>,
     state: AppState,
-) -> Result<Json<ApiResponse<Option<String>>>, StatusCode> {
+) -> Result<Json<ApiResponse<Vec<RuleRevision>>>, StatusCode> {
     let api_key = extract_api_key(&headers, &query)
         .ok_or(StatusCode::UNAUTHORIZED)?;
 
     let api_key_hash = get_api_key_hash(&api_key).await;
 
-    let rule = state.database.get_rule_by_id(*id, &api_key_hash).await
+    state.database.get_rule_by_id(*id, &api_key_hash).await
         .map_err(|e| {
             log!("Failed to get rule: {}", e);
             StatusCode::INTERNAL_SERVER_ERROR
         })?
         .ok_or(StatusCode::NOT_FOUND)?;
 
-    if !rule.enabled {
-        return Ok(Json(ApiResponse {
-            success: true,
-            error: None,
-            data: None,
-        }));
-    }
-
-    match state.scheduler.get_next_run_time(*id).await {
-        Ok(Some(next_tick)) => {
-            let formatted = next_tick.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
-            Ok(Json(ApiResponse {
-                success: true,
-                error: None,
-                data: Some(Some(formatted)),
-            }))
-        }
-        Ok(None) => Ok(Json(ApiResponse {
+    match state.database.get_rule_revisions(*id, &api_key_hash).await {
+        Ok(revisions) => Ok(Json(ApiResponse {
             success: true,
             error: None,
-            data: None,
+            data: Some(revisions),
         })),
         Err(e) => {
-            log!("Failed to get next run time: {}", e);
+            log!("Failed to get rule revisions: {}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
 }
 
-async fn force_run_rule(
+/// Compares revision `from` with revision `to`, or with the current revision
+/// when `to` is not given.
+async fn diff_rule_revisions(
     id: Path<i64>,
     headers: HeaderMap,
     query: Query<HashMap<String, String>>,
     state: AppState,
-) -> Result<Json<ApiResponse<ExecutionResult>>, StatusCode> {
+) -> Result<Json<ApiResponse<RevisionDiff>>, StatusCode> {
     let api_key = extract_api_key(&headers, &query)
         .ok_or(StatusCode::UNAUTHORIZED)?;
 
@@ -533,29 +841,417 @@ async fn force_run_rule(
         .map_err(|e| {
             log!("Failed to get rule: {}", e);
             StatusCode::INTERNAL_SERVER_ERROR
-        })?;
-
-    let rule = rule.ok_or(StatusCode::NOT_FOUND)?;
-
-    log!("Force running rule: {} (ID: {})", rule.name, id.0);
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
 
-    use crate::automation::engine::AutomationEngine;
-    let engine = AutomationEngine::new();
-    
-    let result = match engine.execute_rule(&rule, &api_key).await {
-        Ok(result) => result,
-        Err(e) => {
-            log!("Failed to execute rule: {}", e);
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    let revision_param = |name: &str| -> Result<Option<i64>, String> {
+        query.get(name)
+            .map(|value| value.trim().parse::<i64>().map_err(|_| format!("Invalid revision '{}' for '{}'", value, name)))
+            .transpose()
+    };
+    let (from, to) = match (revision_param("from"), revision_param("to")) {
+        (Ok(Some(from)), Ok(to)) => (from, to.or(rule.revision).unwrap_or(from)),
+        (Ok(None), _) => {
+            return Ok(Json(ApiResponse {
+                success: false,
+                error: Some("'from' revision is required".to_string()),
+                data: None,
+            }));
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            return Ok(Json(ApiResponse {
+                success: false,
+                error: Some(e),
+                data: None,
+            }));
         }
     };
 
-    let log_entry = ExecutionLog {
-        id: None,
-        rule_id: rule.id.unwrap_or(0),
-        rule_name: rule.name.clone(),
-        api_key_hash: rule.api_key_hash.clone(),
-        execution_type: "manual".to_string(),
+    let mut loaded = Vec::with_capacity(2);
+    for revision in [from, to] {
+        let found = state.database.get_rule_revision(*id, &api_key_hash, revision).await
+            .map_err(|e| {
+                log!("Failed to get rule revision: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        match found {
+            Some(found) => loaded.push(found),
+            None => {
+                return Ok(Json(ApiResponse {
+                    success: false,
+                    error: Some(format!("Rule {} has no revision {}", *id, revision)),
+                    data: None,
+                }));
+            }
+        }
+    }
+
+    match revisions::diff(&loaded[0].rule, &loaded[1].rule) {
+        Ok(changes) => Ok(Json(ApiResponse {
+            success: true,
+            error: None,
+            data: Some(RevisionDiff { rule_id: *id, from, to, changes }),
+        })),
+        Err(e) => {
+            log!("Failed to diff rule revisions: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Saves an earlier revision as the rule's newest one. History is kept: the
+/// restore is itself a revision, so it can be undone the same way.
+async fn restore_rule_revision(
+    path: Path<(i64, i64)>,
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    state: AppState,
+    source: RevisionSource,
+) -> Result<Json<ApiResponse<AutomationRule>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let api_key_hash = get_api_key_hash(&api_key).await;
+    let (rule_id, revision) = *path;
+
+    let existing_rule = state.database.get_rule_by_id(rule_id, &api_key_hash).await
+        .map_err(|e| {
+            log!("Failed to get rule: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let restored = state.database.get_rule_revision(rule_id, &api_key_hash, revision).await
+        .map_err(|e| {
+            log!("Failed to get rule revision: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let request = CreateRuleRequest::from(restored.rule);
+    if let Err(e) = validate_rule(&request) {
+        return Ok(Json(ApiResponse {
+            success: false,
+            error: Some(format!("Revision {} is no longer a valid rule: {}", revision, e)),
+            data: None,
+        }));
+    }
+
+    let mut rule = rule_from_request(&request, &api_key_hash, Some(&existing_rule));
+
+    match state.database.save_rule(&rule, &source).await {
+        Ok((_, new_revision)) => {
+            rule.revision = Some(new_revision);
+
+            state.scheduler.reload_rules().await
+                .map_err(|e| {
+                    log!("Failed to reload rules: {}", e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+
+            Ok(Json(ApiResponse {
+                success: true,
+                error: None,
+                data: Some(rule),
+            }))
+        }
+        Err(e) => {
+            log!("Failed to restore rule revision: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn search_logs(
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    state: AppState,
+) -> Result<Json<ApiResponse<LogPage>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let api_key_hash = get_api_key_hash(&api_key).await;
+
+    let filter = match LogFilter::from_query(&query) {
+        Ok(filter) => filter,
+        Err(e) => {
+            return Ok(Json(ApiResponse {
+                success: false,
+                error: Some(e),
+                data: None,
+            }));
+        }
+    };
+
+    let cursor = query.get("cursor").and_then(|s| s.parse::<i64>().ok());
+    let limit = query.get("limit")
+        .and_then(|s| s.parse::<usize>().ok())
+        .unwrap_or(logs::DEFAULT_PAGE_SIZE)
+        .clamp(1, logs::MAX_PAGE_SIZE);
+
+    // One extra row tells whether another page follows.
+    let mut page = state.database.search_execution_logs(&api_key_hash, &filter, cursor, limit + 1).await
+        .map_err(|e| {
+            log!("Failed to search logs: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let next_cursor = if page.len() > limit {
+        page.truncate(limit);
+        page.last().and_then(|log| log.id)
+    } else {
+        None
+    };
+
+    Ok(Json(ApiResponse {
+        success: true,
+        error: None,
+        data: Some(LogPage { logs: page, next_cursor }),
+    }))
+}
+
+#[derive(Debug, Serialize)]
+struct LogExport {
+    filename: String,
+    content_type: String,
+    log_count: usize,
+    content: String,
+}
+
+async fn export_logs(
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    state: AppState,
+) -> Result<Json<ApiResponse<LogExport>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let api_key_hash = get_api_key_hash(&api_key).await;
+
+    let parsed = LogExportFormat::from_name(query.get("format").map(|f| f.as_str()).unwrap_or("csv"))
+        .and_then(|format| LogFilter::from_query(&query).map(|filter| (format, filter)));
+    let (format, filter) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return Ok(Json(ApiResponse {
+                success: false,
+                error: Some(e),
+                data: None,
+            }));
+        }
+    };
+
+    let entries = state.database.search_execution_logs(&api_key_hash, &filter, None, logs::MAX_EXPORT_LOGS + 1).await
+        .map_err(|e| {
+            log!("Failed to get logs for export: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    if entries.len() > logs::MAX_EXPORT_LOGS {
+        return Ok(Json(ApiResponse {
+            success: false,
+            error: Some(format!("More than {} runs match; narrow the date range or filters to export them", logs::MAX_EXPORT_LOGS)),
+            data: None,
+        }));
+    }
+
+    let content = format.serialize(&entries)
+        .map_err(|e| {
+            log!("Failed to export logs: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        error: None,
+        data: Some(LogExport {
+            filename: format!("automation-logs-{}.{}", chrono::Utc::now().format("%Y%m%d"), format.extension()),
+            content_type: format.content_type().to_string(),
+            log_count: entries.len(),
+            content,
+        }),
+    }))
+}
+
+async fn get_next_run_time(
+    id: Path<i64>,
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    state: AppState,
+) -> Result<Json<ApiResponse<Option<String>>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let api_key_hash = get_api_key_hash(&api_key).await;
+
+    let rule = state.database.get_rule_by_id(*id, &api_key_hash).await
+        .map_err(|e| {
+            log!("Failed to get rule: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if !rule.enabled {
+        return Ok(Json(ApiResponse {
+            success: true,
+            error: None,
+            data: None,
+        }));
+    }
+
+    // A rule with a timezone is scheduled as a once-a-minute poll job, so the
+    // job's next tick says nothing about when the rule itself fires next.
+    let next_run = match &rule.trigger_config {
+        TriggerConfig::Cron { timezone: Some(timezone), .. } if !timezone.trim().is_empty() => {
+            schedule::next_runs(&rule.trigger_config, chrono::Utc::now(), 1)
+                .map(|(_, runs)| runs.first().map(|run| run.with_timezone(&chrono::Utc)))
+        }
+        _ => state.scheduler.get_next_run_time(*id).await,
+    };
+
+    match next_run {
+        Ok(Some(next_tick)) => {
+            let formatted = next_tick.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+            Ok(Json(ApiResponse {
+                success: true,
+                error: None,
+                data: Some(Some(formatted)),
+            }))
+        }
+        Ok(None) => Ok(Json(ApiResponse {
+            success: true,
+            error: None,
+            data: None,
+        })),
+        Err(e) => {
+            log!("Failed to get next run time: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NextRunsRequest {
+    trigger_config: TriggerConfig,
+    #[serde(default)]
+    count: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct NextRuns {
+    timezone: String,
+    runs: Vec<String>,
+}
+
+/// Fire times of a trigger that may not be saved yet, so the editor can show
+/// what a cron expression means before the rule exists.
+async fn get_trigger_next_runs(
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    payload: Json<NextRunsRequest>,
+) -> Result<Json<ApiResponse<NextRuns>>, StatusCode> {
+    extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let count = payload.count.unwrap_or(5).clamp(1, schedule::MAX_NEXT_RUNS);
+
+    match schedule::next_runs(&payload.trigger_config, chrono::Utc::now(), count) {
+        Ok((timezone, runs)) => Ok(Json(ApiResponse {
+            success: true,
+            error: None,
+            data: Some(NextRuns {
+                timezone: timezone.name().to_string(),
+                runs: runs.iter().map(|run| run.to_rfc3339()).collect(),
+            }),
+        })),
+        Err(e) => Ok(Json(ApiResponse {
+            success: false,
+            error: Some(e),
+            data: None,
+        })),
+    }
+}
+
+async fn force_run_rule(
+    id: Path<i64>,
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    state: AppState,
+) -> Result<Json<ApiResponse<ExecutionResult>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let api_key_hash = get_api_key_hash(&api_key).await;
+
+    let rule = state.database.get_rule_by_id(*id, &api_key_hash).await
+        .map_err(|e| {
+            log!("Failed to get rule: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let rule = rule.ok_or(StatusCode::NOT_FOUND)?;
+
+    log!("Force running rule: {} (ID: {})", rule.name, id.0);
+
+    use crate::automation::engine::AutomationEngine;
+    let engine = AutomationEngine::new();
+    
+    let history = history::load(&state.database, &rule).await
+        .map_err(|e| {
+            log!("Failed to load action history: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let limiter = state.scheduler.limiter();
+    let outcome = limiter.run(rule.id.unwrap_or(0), engine.execute_rule(&state.database, &rule, &api_key, &history)).await;
+    if let Ok(run) = &outcome {
+        if key_health::record_outcome(&state.database, &rule.api_key_hash, run).await {
+            if let Err(e) = state.scheduler.reload_rules().await {
+                log!("Failed to reload rules: {}", e);
+            }
+        }
+    }
+
+    let result = match outcome {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => {
+            log!("Failed to execute rule: {}", e);
+            // TorBox errors go back to the user; anything else is a server fault.
+            let Some(api_error) = e.api_error() else {
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            };
+            return Ok(Json(ApiResponse {
+                success: false,
+                error: Some(format_api_error(api_error)),
+                data: None,
+            }));
+        }
+        Err(rejection) => {
+            log!("Force run of rule {} rejected: {}", rule.name, rejection.message());
+            state.database.log_execution(&ExecutionLog::without_result(&rule, "manual", rejection.message())).await
+                .map_err(|e| {
+                    log!("Failed to log execution: {}", e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+
+            let error = match rejection {
+                RunRejection::AlreadyRunning => "Rule is already running".to_string(),
+                RunRejection::TimedOut(_) => rejection.message(),
+            };
+            return Ok(Json(ApiResponse {
+                success: false,
+                error: Some(error),
+                data: None,
+            }));
+        }
+    };
+
+    let log_entry = ExecutionLog {
+        id: None,
+        rule_id: rule.id.unwrap_or(0),
+        rule_name: rule.name.clone(),
+        api_key_hash: rule.api_key_hash.clone(),
+        execution_type: "manual".to_string(),
         items_processed: result.items_processed,
         total_items: Some(result.total_items),
         success: result.success,
@@ -563,32 +1259,675 @@ async fn force_run_rule(
         processed_items: result.processed_items.clone(),
         executed_at: None,
         partial: Some(result.partial),
+        rule_revision: rule.revision,
+    };
+
+    state.database.log_execution(&log_entry).await
+        .map_err(|e| {
+            log!("Failed to log execution: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    state.database.save_transfers(&result.transfers).await
+        .map_err(|e| {
+            log!("Failed to save transfer jobs: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    if result.success && !result.partial {
+        log!("Rule {} force run successful: {}/{} items processed", rule.name, result.items_processed, result.total_items);
+    } else if result.partial {
+        log!("Rule {} force run partially completed: {}/{} items processed", rule.name, result.items_processed, result.total_items);
+    } else {
+        log!("Rule {} force run had errors: {}", rule.name, result.error_message.as_ref().unwrap_or(&"Unknown error".to_string()));
+    }
+
+    Ok(Json(ApiResponse {
+        success: true,
+        error: None,
+        data: Some(result),
+    }))
+}
+
+async fn preview_rule(
+    id: Path<i64>,
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    state: AppState,
+) -> Result<Json<ApiResponse<PreviewResult>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let api_key_hash = get_api_key_hash(&api_key).await;
+
+    let rule = state.database.get_rule_by_id(*id, &api_key_hash).await
+        .map_err(|e| {
+            log!("Failed to get rule: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(run_preview(&state.database, &rule, &api_key).await))
+}
+
+/// Previews a rule as edited in the dashboard. `?rule_id=N` names the saved rule
+/// being edited so its cooldown is checked against its own action history.
+async fn preview_unsaved_rule(
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    state: AppState,
+    payload: Json<CreateRuleRequest>,
+) -> Result<Json<ApiResponse<PreviewResult>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    if let Err(e) = validate_rule(&payload) {
+        return Ok(Json(ApiResponse {
+            success: false,
+            error: Some(e),
+            data: None,
+        }));
+    }
+
+    let api_key_hash = get_api_key_hash(&api_key).await;
+    let existing = match query.get("rule_id").and_then(|id| id.parse::<i64>().ok()) {
+        Some(rule_id) => state.database.get_rule_by_id(rule_id, &api_key_hash).await
+            .map_err(|e| {
+                log!("Failed to get rule: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?,
+        None => None,
+    };
+    let rule = rule_from_request(&payload, &api_key_hash, existing.as_ref());
+
+    Ok(Json(run_preview(&state.database, &rule, &api_key).await))
+}
+
+async fn run_preview(database: &Database, rule: &AutomationRule, api_key: &str) -> ApiResponse<PreviewResult> {
+    use crate::automation::engine::AutomationEngine;
+    let engine = AutomationEngine::new();
+
+    let history = match history::load(database, rule).await {
+        Ok(history) => history,
+        Err(e) => {
+            log!("Failed to load action history: {}", e);
+            return ApiResponse {
+                success: false,
+                error: Some("Failed to load action history".to_string()),
+                data: None,
+            };
+        }
+    };
+
+    match engine.preview_rule(rule, api_key, &history).await {
+        Ok(preview) => ApiResponse {
+            success: true,
+            error: None,
+            data: Some(preview),
+        },
+        Err(e) => {
+            log!("Failed to preview rule '{}': {}", rule.name, e);
+            ApiResponse {
+                success: false,
+                error: Some(e.to_string()),
+                data: None,
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TestNotificationRequest {
+    rule_name: Option<String>,
+    notification: NotificationConfig,
+}
+
+async fn test_notification(
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    payload: Json<TestNotificationRequest>,
+) -> Result<Json<ApiResponse<()>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    // Only TorBox users may make the server send requests.
+    match TorboxClient::new(api_key).get_user(None).await {
+        Ok(_) => {}
+        Err(ApiError::AuthenticationError(_)) => return Err(StatusCode::UNAUTHORIZED),
+        Err(e) => {
+            return Ok(Json(ApiResponse {
+                success: false,
+                error: Some(format!("Could not verify the API key: {}", format_api_error(&e))),
+                data: None,
+            }));
+        }
+    }
+
+    if let Err(e) = notifier::validate_config(&payload.notification) {
+        return Ok(Json(ApiResponse {
+            success: false,
+            error: Some(e),
+            data: None,
+        }));
+    }
+
+    let rule_name = payload.rule_name.as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or("Untitled rule");
+
+    match notifier::send(&payload.notification, &NotificationEvent::test(rule_name)).await {
+        Ok(()) => Ok(Json(ApiResponse {
+            success: true,
+            error: None,
+            data: None,
+        })),
+        Err(e) => {
+            log!("Test notification failed: {}", e);
+            Ok(Json(ApiResponse {
+                success: false,
+                error: Some(e),
+                data: None,
+            }))
+        }
+    }
+}
+
+impl From<BundledRule> for CreateRuleRequest {
+    fn from(rule: BundledRule) -> Self {
+        Self {
+            name: rule.name,
+            enabled: Some(rule.enabled),
+            download_types: rule.download_types,
+            trigger_config: rule.trigger_config,
+            conditions: rule.conditions,
+            action_config: rule.action_config,
+            notification: rule.notification,
+            schedule_window: rule.schedule_window,
+            quota: rule.quota,
+            cooldown: rule.cooldown,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RuleExport {
+    filename: String,
+    content_type: String,
+    rule_count: usize,
+    content: String,
+}
+
+async fn export_rules(
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    state: AppState,
+) -> Result<Json<ApiResponse<RuleExport>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let api_key_hash = get_api_key_hash(&api_key).await;
+
+    let format = match BundleFormat::from_name(query.get("format").map(|f| f.as_str()).unwrap_or("json")) {
+        Ok(format) => format,
+        Err(e) => {
+            return Ok(Json(ApiResponse {
+                success: false,
+                error: Some(e),
+                data: None,
+            }));
+        }
+    };
+
+    let selected_ids: Option<Vec<i64>> = query.get("ids").map(|ids| {
+        ids.split(',')
+            .filter_map(|id| id.trim().parse::<i64>().ok())
+            .collect()
+    });
+
+    let mut rules = state.database.get_rules_by_api_key(&api_key_hash).await
+        .map_err(|e| {
+            log!("Failed to get rules for export: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    if let Some(ids) = &selected_ids {
+        rules.retain(|rule| rule.id.map(|id| ids.contains(&id)).unwrap_or(false));
+    }
+
+    if rules.is_empty() {
+        return Ok(Json(ApiResponse {
+            success: false,
+            error: Some("No rules to export".to_string()),
+            data: None,
+        }));
+    }
+
+    // Oldest first, so importing recreates rules in the order they were made.
+    rules.reverse();
+
+    let content = RuleBundle::from_rules(&rules).serialize(format)
+        .map_err(|e| {
+            log!("Failed to export rules: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        error: None,
+        data: Some(RuleExport {
+            filename: format!("automation-rules-{}.{}", chrono::Utc::now().format("%Y%m%d"), format.extension()),
+            content_type: format.content_type().to_string(),
+            rule_count: rules.len(),
+            content,
+        }),
+    }))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ConflictPolicy {
+    #[default]
+    Skip,
+    Rename,
+    Replace,
+}
+
+/// Either a parsed bundle or its raw JSON/YAML text.
+#[derive(Deserialize)]
+struct ImportRulesRequest {
+    #[serde(default)]
+    bundle: Option<RuleBundle>,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    on_conflict: ConflictPolicy,
+}
+
+#[derive(Debug, Serialize)]
+struct ImportedRuleResult {
+    index: usize,
+    name: String,
+    status: String,
+    rule_id: Option<i64>,
+    message: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ImportReport {
+    imported: usize,
+    replaced: usize,
+    skipped: usize,
+    results: Vec<ImportedRuleResult>,
+}
+
+async fn import_rules(
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    state: AppState,
+    payload: Json<ImportRulesRequest>,
+    source: RevisionSource,
+) -> Result<Json<ApiResponse<ImportReport>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let Json(import) = payload;
+    let on_conflict = import.on_conflict;
+    let bundle = match (import.bundle, import.content) {
+        (Some(bundle), _) => bundle.check_header().map(|_| bundle),
+        (None, Some(content)) => RuleBundle::parse(&content),
+        (None, None) => Err("Provide either a bundle or its content".to_string()),
+    };
+    let bundle = match bundle {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            return Ok(Json(ApiResponse {
+                success: false,
+                error: Some(e),
+                data: None,
+            }));
+        }
+    };
+
+    let api_key_hash = get_api_key_hash(&api_key).await;
+
+    let existing_rules = state.database.get_rules_by_api_key(&api_key_hash).await
+        .map_err(|e| {
+            log!("Failed to get rules for import: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    state.database.save_api_key(&api_key).await
+        .map_err(|e| {
+            log!("Failed to save API key: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let name_key = |name: &str| name.trim().to_lowercase();
+    // Names already taken, with the existing rule they belong to (None for rules added by this import).
+    let mut taken: HashMap<String, Option<AutomationRule>> = existing_rules
+        .iter()
+        .map(|rule| (name_key(&rule.name), Some(rule.clone())))
+        .collect();
+    let mut rule_count = existing_rules.len() as i64;
+
+    let mut report = ImportReport { imported: 0, replaced: 0, skipped: 0, results: Vec::new() };
+
+    for (index, bundled) in bundle.rules.into_iter().enumerate() {
+        let blank_secrets = bundled.has_blank_secrets();
+        let mut request = CreateRuleRequest::from(bundled);
+        let original_name = request.name.clone();
+        let mut result = ImportedRuleResult {
+            index,
+            name: original_name.clone(),
+            status: "skipped".to_string(),
+            rule_id: None,
+            message: None,
+        };
+
+        if let Err(e) = validate_rule(&request) {
+            result.status = "invalid".to_string();
+            result.message = Some(e);
+            report.skipped += 1;
+            report.results.push(result);
+            continue;
+        }
+
+        let mut replacing: Option<AutomationRule> = None;
+        if let Some(conflict) = taken.get(&name_key(&request.name)) {
+            match (on_conflict, conflict) {
+                (ConflictPolicy::Replace, Some(existing)) => {
+                    replacing = Some(existing.clone());
+                }
+                (ConflictPolicy::Rename, _) => {
+                    let mut suffix = 2;
+                    while taken.contains_key(&name_key(&format!("{} ({})", original_name, suffix))) {
+                        suffix += 1;
+                    }
+                    request.name = format!("{} ({})", original_name, suffix);
+                    result.message = Some(format!("Renamed to '{}' to avoid a name conflict", request.name));
+                }
+                (_, Some(_)) => {
+                    result.status = "conflict".to_string();
+                    result.message = Some(format!("A rule named '{}' already exists", original_name));
+                    report.skipped += 1;
+                    report.results.push(result);
+                    continue;
+                }
+                (_, None) => {
+                    result.status = "conflict".to_string();
+                    result.message = Some(format!("The bundle contains more than one rule named '{}'", original_name));
+                    report.skipped += 1;
+                    report.results.push(result);
+                    continue;
+                }
+            }
+        }
+
+        if replacing.is_none() && rule_count >= state.max_rules_per_user {
+            result.status = "limit_reached".to_string();
+            result.message = Some(format!("Maximum rule limit ({}) reached for this API key", state.max_rules_per_user));
+            report.skipped += 1;
+            report.results.push(result);
+            continue;
+        }
+
+        let rule = rule_from_request(&request, &api_key_hash, replacing.as_ref());
+        let rule_source = if replacing.is_some() {
+            RevisionSource::new("replaced by import", source.client_ip.clone())
+        } else {
+            source.clone()
+        };
+        match state.database.save_rule(&rule, &rule_source).await {
+            Ok((rule_id, _)) => {
+                result.rule_id = Some(rule_id);
+                if replacing.is_some() {
+                    result.status = "replaced".to_string();
+                    report.replaced += 1;
+                } else {
+                    result.status = "imported".to_string();
+                    report.imported += 1;
+                    rule_count += 1;
+                }
+                taken.insert(name_key(&request.name), None);
+                if blank_secrets {
+                    let note = "Notification header values are not exported; enter them again in the rule";
+                    result.message = Some(match result.message.take() {
+                        Some(message) => format!("{}. {}", message, note),
+                        None => note.to_string(),
+                    });
+                }
+            }
+            Err(e) => {
+                log!("Failed to import rule '{}': {}", request.name, e);
+                result.status = "error".to_string();
+                result.message = Some("Failed to save rule".to_string());
+                report.skipped += 1;
+            }
+        }
+        report.results.push(result);
+    }
+
+    if report.imported > 0 || report.replaced > 0 {
+        state.scheduler.reload_rules().await
+            .map_err(|e| {
+                log!("Failed to reload rules: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+    }
+
+    Ok(Json(ApiResponse {
+        success: true,
+        error: None,
+        data: Some(report),
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+struct BulkDeleteRequest {
+    rule_ids: Vec<i64>,
+}
+
+async fn get_templates() -> Json<ApiResponse<Vec<RuleTemplate>>> {
+    Json(ApiResponse {
+        success: true,
+        error: None,
+        data: Some(templates::catalog()),
+    })
+}
+
+#[derive(Deserialize)]
+struct InstantiateTemplateRequest {
+    name: Option<String>,
+    enabled: Option<bool>,
+    #[serde(default)]
+    params: HashMap<String, f64>,
+}
+
+/// Creates a rule from a template. The result goes through the same validation
+/// and rule limit as a hand-written rule.
+async fn instantiate_template(
+    id: Path<String>,
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    state: AppState,
+    payload: Json<InstantiateTemplateRequest>,
+    source: RevisionSource,
+) -> Result<Json<ApiResponse<AutomationRule>>, StatusCode> {
+    let Some(template) = templates::find(&id) else {
+        return Ok(Json(ApiResponse {
+            success: false,
+            error: Some(format!("Unknown template: {}", *id)),
+            data: None,
+        }));
+    };
+
+    let rule = match template.instantiate(&payload.params) {
+        Ok(rule) => rule,
+        Err(e) => {
+            return Ok(Json(ApiResponse {
+                success: false,
+                error: Some(e),
+                data: None,
+            }));
+        }
+    };
+
+    let request = CreateRuleRequest {
+        name: payload.name.clone()
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| template.name.to_string()),
+        enabled: payload.enabled,
+        download_types: rule.download_types,
+        trigger_config: rule.trigger_config,
+        conditions: rule.conditions,
+        action_config: rule.action_config,
+        notification: None,
+        schedule_window: None,
+        quota: rule.quota,
+        cooldown: None,
+    };
+
+    create_rule(headers, query, state, Json(request), source).await
+}
+
+/// Deletes a torrent from the downloads table, archiving it first so it shows up
+/// under "Recently Deleted".
+async fn delete_torrent(
+    id: Path<i32>,
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    state: AppState,
+) -> Result<Json<ApiResponse<DeletedTorrent>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let api_key_hash = get_api_key_hash(&api_key).await;
+    let client = TorboxClient::new(api_key);
+
+    let deleted = match archive::find_torrent(&client, *id, "manual delete").await {
+        Ok(Some(torrent)) => archive::archive(&client, &torrent, &api_key_hash).await,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => Err(e),
+    };
+    let deleted = match deleted {
+        Ok(deleted) => deleted,
+        Err(e) => {
+            return Ok(Json(ApiResponse {
+                success: false,
+                error: Some(format!("Torrent was not deleted: {}", e)),
+                data: None,
+            }));
+        }
+    };
+
+    match archive::delete_archived(&state.database, &client, deleted).await {
+        Ok(deleted) => Ok(Json(ApiResponse {
+            success: true,
+            error: None,
+            data: Some(deleted),
+        })),
+        Err(e) => Ok(Json(ApiResponse {
+            success: false,
+            error: Some(e),
+            data: None,
+        })),
+    }
+}
+
+async fn get_deleted_torrents(
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    state: AppState,
+) -> Result<Json<ApiResponse<Vec<DeletedTorrent>>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let api_key_hash = get_api_key_hash(&api_key).await;
+
+    match state.database.get_deleted_torrents(&api_key_hash).await {
+        Ok(deleted) => Ok(Json(ApiResponse {
+            success: true,
+            error: None,
+            data: Some(deleted),
+        })),
+        Err(e) => {
+            log!("Failed to get deleted torrents: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Adds an archived torrent again from its magnet. The archive entry stays, marked
+/// as re-added, until it expires or is forgotten.
+async fn readd_deleted_torrent(
+    id: Path<i64>,
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    state: AppState,
+) -> Result<Json<ApiResponse<DeletedTorrent>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let api_key_hash = get_api_key_hash(&api_key).await;
+
+    let mut deleted = state.database.get_deleted_torrent(*id, &api_key_hash).await
+        .map_err(|e| {
+            log!("Failed to get deleted torrent: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let readded_torrent_id = match archive::readd(&TorboxClient::new(api_key), &deleted).await {
+        Ok(torrent_id) => torrent_id,
+        Err(e) => {
+            return Ok(Json(ApiResponse {
+                success: false,
+                error: Some(e),
+                data: None,
+            }));
+        }
     };
 
-    state.database.log_execution(&log_entry).await
+    state.database.mark_torrent_readded(*id, readded_torrent_id).await
         .map_err(|e| {
-            log!("Failed to log execution: {}", e);
+            log!("Failed to update deleted torrent: {}", e);
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
-    if result.success && !result.partial {
-        log!("Rule {} force run successful: {}/{} items processed", rule.name, result.items_processed, result.total_items);
-    } else if result.partial {
-        log!("Rule {} force run partially completed: {}/{} items processed", rule.name, result.items_processed, result.total_items);
-    } else {
-        log!("Rule {} force run had errors: {}", rule.name, result.error_message.as_ref().unwrap_or(&"Unknown error".to_string()));
-    }
+    deleted.readded_at = Some(chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string());
+    deleted.readded_torrent_id = readded_torrent_id;
 
     Ok(Json(ApiResponse {
         success: true,
         error: None,
-        data: Some(result),
+        data: Some(deleted),
     }))
 }
 
-#[derive(Debug, Deserialize)]
-struct BulkDeleteRequest {
-    rule_ids: Vec<i64>,
+async fn forget_deleted_torrent(
+    id: Path<i64>,
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    state: AppState,
+) -> Result<Json<ApiResponse<()>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let api_key_hash = get_api_key_hash(&api_key).await;
+
+    match state.database.forget_deleted_torrent(*id, &api_key_hash).await {
+        Ok(true) => Ok(Json(ApiResponse {
+            success: true,
+            error: None,
+            data: None,
+        })),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            log!("Failed to forget deleted torrent: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
 }
 
 async fn bulk_delete_rules(
@@ -695,6 +2034,293 @@ async fn get_rule_limit(
     }))
 }
 
+async fn get_key_status(
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    state: AppState,
+) -> Result<Json<ApiResponse<ApiKeyStatus>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let api_key_hash = get_api_key_hash(&api_key).await;
+
+    match state.database.get_key_status(&api_key_hash, None).await {
+        Ok(status) => Ok(Json(ApiResponse {
+            success: true,
+            error: None,
+            data: Some(status),
+        })),
+        Err(e) => {
+            log!("Failed to get API key status: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Called by the landing page after a successful sign-in. Checks the key with
+/// TorBox, lifts its suspension and takes over the rules of suspended keys of
+/// the same TorBox account, which is what a regenerated key leaves behind.
+async fn sign_in(
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    state: AppState,
+) -> Result<Json<ApiResponse<ApiKeyStatus>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let api_key_hash = get_api_key_hash(&api_key).await;
+
+    let user = match TorboxClient::new(api_key.clone()).get_user(None).await {
+        Ok(response) => response.data,
+        Err(e) => {
+            return Ok(Json(ApiResponse {
+                success: false,
+                error: Some(format_api_error(&e)),
+                data: None,
+            }));
+        }
+    };
+    let Some(user) = user else {
+        return Ok(Json(ApiResponse {
+            success: false,
+            error: Some("TorBox returned no account details for this API key".to_string()),
+            data: None,
+        }));
+    };
+    let torbox_user_id = user.id as i64;
+
+    let status = state.database.get_key_status(&api_key_hash, Some(torbox_user_id)).await
+        .map_err(|e| {
+            log!("Failed to get API key status: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let suspended_keys: Vec<&OtherApiKey> = status.other_keys
+        .iter()
+        .filter(|key| key.suspended_at.is_some())
+        .collect();
+
+    // Keys are only stored once they have rules to run.
+    if !status.stored && suspended_keys.is_empty() {
+        return Ok(Json(ApiResponse {
+            success: true,
+            error: None,
+            data: Some(status),
+        }));
+    }
+
+    state.database.save_api_key(&api_key).await
+        .map_err(|e| {
+            log!("Failed to save API key: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    state.database.set_torbox_user_id(&api_key_hash, torbox_user_id).await
+        .map_err(|e| {
+            log!("Failed to save TorBox user: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let mut reload = state.database.record_auth_success(&api_key_hash).await
+        .map_err(|e| {
+            log!("Failed to reset API key status: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let mut rule_count = state.database.count_rules_by_api_key(&api_key_hash).await
+        .map_err(|e| {
+            log!("Failed to count rules: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let mut migrated = 0;
+    for key in suspended_keys {
+        if rule_count + key.rule_count > state.max_rules_per_user {
+            log!("Not moving {} rules from a suspended API key: the rule limit ({}) would be exceeded", key.rule_count, state.max_rules_per_user);
+            continue;
+        }
+        match state.database.migrate_rules(&key.api_key_hash, &api_key_hash).await {
+            Ok(moved) => {
+                migrated += moved;
+                rule_count += moved as i64;
+            }
+            Err(e) => log!("Failed to move rules from a suspended API key: {}", e),
+        }
+    }
+
+    if migrated > 0 {
+        log!("Moved {} rules from suspended API keys to a new key on sign-in", migrated);
+        reload = true;
+    }
+
+    if reload {
+        state.scheduler.reload_rules().await
+            .map_err(|e| {
+                log!("Failed to reload rules: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+    }
+
+    let mut status = state.database.get_key_status(&api_key_hash, None).await
+        .map_err(|e| {
+            log!("Failed to get API key status: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    status.migrated_rules = migrated;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        error: None,
+        data: Some(status),
+    }))
+}
+
+async fn migrate_rules(
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    state: AppState,
+    payload: Json<MigrateRulesRequest>,
+) -> Result<Json<ApiResponse<ApiKeyStatus>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let api_key_hash = get_api_key_hash(&api_key).await;
+
+    let status = state.database.get_key_status(&api_key_hash, None).await
+        .map_err(|e| {
+            log!("Failed to get API key status: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    // A bare hash is only accepted for keys of the same TorBox account, which
+    // sign-in records.
+    let old_hash = match (&payload.old_api_key, &payload.old_api_key_hash) {
+        (Some(old_api_key), _) => get_api_key_hash(old_api_key.trim()).await,
+        (None, Some(old_hash)) if status.other_keys.iter().any(|key| &key.api_key_hash == old_hash) => old_hash.clone(),
+        (None, Some(_)) => {
+            return Ok(Json(ApiResponse {
+                success: false,
+                error: Some("That key doesn't belong to the TorBox account of this API key".to_string()),
+                data: None,
+            }));
+        }
+        (None, None) => {
+            return Ok(Json(ApiResponse {
+                success: false,
+                error: Some("Either old_api_key or old_api_key_hash is required".to_string()),
+                data: None,
+            }));
+        }
+    };
+
+    if old_hash == api_key_hash {
+        return Ok(Json(ApiResponse {
+            success: false,
+            error: Some("The old key is the key you are signed in with".to_string()),
+            data: None,
+        }));
+    }
+
+    let (old_count, current_count) = match (
+        state.database.count_rules_by_api_key(&old_hash).await,
+        state.database.count_rules_by_api_key(&api_key_hash).await,
+    ) {
+        (Ok(old_count), Ok(current_count)) => (old_count, current_count),
+        (Err(e), _) | (_, Err(e)) => {
+            log!("Failed to count rules: {}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    if old_count == 0 {
+        return Ok(Json(ApiResponse {
+            success: false,
+            error: Some("No rules belong to that key".to_string()),
+            data: None,
+        }));
+    }
+
+    if current_count + old_count > state.max_rules_per_user {
+        return Ok(Json(ApiResponse {
+            success: false,
+            error: Some(format!("Moving {} rules would exceed the maximum rule limit ({}) for this API key", old_count, state.max_rules_per_user)),
+            data: None,
+        }));
+    }
+
+    state.database.save_api_key(&api_key).await
+        .map_err(|e| {
+            log!("Failed to save API key: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let moved = state.database.migrate_rules(&old_hash, &api_key_hash).await
+        .map_err(|e| {
+            log!("Failed to migrate rules: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    state.scheduler.reload_rules().await
+        .map_err(|e| {
+            log!("Failed to reload rules: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let mut status = state.database.get_key_status(&api_key_hash, None).await
+        .map_err(|e| {
+            log!("Failed to get API key status: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    status.migrated_rules = moved;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        error: None,
+        data: Some(status),
+    }))
+}
+
+async fn get_analytics(
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    state: AppState,
+) -> Result<Json<ApiResponse<AutomationAnalytics>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let api_key_hash = get_api_key_hash(&api_key).await;
+
+    let days = query.get("days")
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(analytics::DEFAULT_ANALYTICS_DAYS)
+        .clamp(1, analytics::MAX_ANALYTICS_DAYS);
+    let rule_id = query.get("rule_id")
+        .and_then(|s| s.parse::<i64>().ok());
+
+    match state.database.get_logs_since(&api_key_hash, rule_id, days).await {
+        Ok(logs) => Ok(Json(ApiResponse {
+            success: true,
+            error: None,
+            data: Some(analytics::summarize(&logs, days, chrono::Utc::now().date_naive())),
+        })),
+        Err(e) => {
+            log!("Failed to load analytics: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// The rate limit budget of the caller's own API key.
+async fn get_rate_limit(
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+) -> Result<Json<ApiResponse<KeyBudget>>, StatusCode> {
+    let api_key = extract_api_key(&headers, &query)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        error: None,
+        data: Some(rate_limiter::global().budget(&RateLimiter::key_for(&api_key))),
+    }))
+}
+
 async fn health_check(state: AppState) -> Json<ApiResponse<serde_json::Value>> {
     let mut status = serde_json::json!({
         "database": "unknown",
@@ -712,6 +2338,8 @@ async fn health_check(state: AppState) -> Json<ApiResponse<serde_json::Value>> {
     }
 
     status["scheduler"] = serde_json::json!("running");
+    status["running_rules"] = serde_json::json!(state.scheduler.limiter().running_count());
+    status["rate_limits"] = serde_json::json!(rate_limiter::global().status());
 
     Json(ApiResponse {
         success: status["database"] == "ok",
@@ -0,0 +1,156 @@
+use crate::automation::types::*;
+use crate::automation::window;
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use cron::Schedule;
+use std::str::FromStr;
+
+pub const MAX_NEXT_RUNS: usize = 50;
+
+const MINIMUM_INTERVAL_MINUTES: u32 = 30;
+
+/// Field names and accepted values, in the order the scheduler reads them.
+const CRON_FIELDS: [(&str, &str); 6] = [
+    ("seconds", "0-59"),
+    ("minutes", "0-59"),
+    ("hours", "0-23"),
+    ("day of month", "1-31"),
+    ("month", "1-12 or JAN-DEC"),
+    ("day of week", "SUN-SAT, or 1-7 where 1 is Sunday"),
+];
+
+/// The cron expression and timezone the scheduler runs a rule on. Intervals
+/// become UTC cron expressions; event triggers have no schedule.
+pub fn trigger_cron(trigger: &TriggerConfig) -> Result<(String, Tz), String> {
+    match trigger {
+        TriggerConfig::Cron { expression, timezone } => {
+            let expr = expression.trim();
+            if expr.is_empty() {
+                return Err("Cron expression cannot be empty".to_string());
+            }
+            Ok((expr.to_string(), resolve_timezone(timezone.as_deref())?))
+        }
+        TriggerConfig::Interval { minutes } => Ok((interval_cron(*minutes), Tz::UTC)),
+        TriggerConfig::OnEvent { .. } => {
+            Err("Event-triggered rules are run by the event watcher, not on a schedule".to_string())
+        }
+    }
+}
+
+/// The UTC cron expression the job scheduler fires on, which only knows UTC.
+/// A rule in another timezone is polled every minute at its seconds field and
+/// runs when `LocalSchedule::is_due` says the local time matches.
+pub fn job_cron(expression: &str, timezone: Tz) -> Result<(String, Option<LocalSchedule>), String> {
+    if timezone == Tz::UTC {
+        return Ok((expression.to_string(), None));
+    }
+
+    let schedule = parse_cron(expression)?;
+    let seconds = expression.split_whitespace().next().unwrap_or("0");
+    Ok((format!("{} * * * * *", seconds), Some(LocalSchedule { schedule, timezone })))
+}
+
+/// A cron schedule evaluated in a rule's own timezone. Across a DST change a
+/// time that occurs twice fires twice and a skipped time does not fire.
+#[derive(Clone)]
+pub struct LocalSchedule {
+    schedule: Schedule,
+    timezone: Tz,
+}
+
+impl LocalSchedule {
+    /// The job may tick a moment late, so the second before `now` also counts.
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        let local = now.with_timezone(&self.timezone);
+        self.schedule.includes(local) || self.schedule.includes(local - Duration::seconds(1))
+    }
+}
+
+fn interval_cron(minutes: u32) -> String {
+    let minutes = minutes.max(MINIMUM_INTERVAL_MINUTES);
+
+    if minutes < 60 {
+        return format!("0 */{} * * * *", minutes);
+    }
+
+    let hours = minutes / 60;
+    let remaining_minutes = minutes % 60;
+    if remaining_minutes != 0 {
+        format!("0 {} */{} * * *", remaining_minutes, hours)
+    } else if hours == 1 {
+        "0 0 * * * *".to_string()
+    } else {
+        format!("0 0 */{} * * *", hours)
+    }
+}
+
+/// Rules saved before timezones existed have none and keep running in UTC.
+fn resolve_timezone(timezone: Option<&str>) -> Result<Tz, String> {
+    match timezone.map(str::trim).filter(|timezone| !timezone.is_empty()) {
+        Some(timezone) => window::parse_timezone(timezone),
+        None => Ok(Tz::UTC),
+    }
+}
+
+/// Parses a six-field cron expression, naming the field at fault when it does
+/// not parse and rejecting expressions that can never fire.
+pub fn parse_cron(expression: &str) -> Result<Schedule, String> {
+    let expr = expression.trim();
+    if expr.is_empty() {
+        return Err("Cron expression cannot be empty".to_string());
+    }
+
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+    if fields.len() != CRON_FIELDS.len() {
+        let hint = if fields.len() == 5 {
+            " Five-field expressions need a leading seconds field, e.g. '0 0 * * * *' for every hour."
+        } else {
+            ""
+        };
+        return Err(format!(
+            "Cron expression must have 6 fields (seconds minutes hours day-of-month month day-of-week), found {}.{}",
+            fields.len(),
+            hint
+        ));
+    }
+
+    // The cron crate's errors do not say which field is wrong; parsing each
+    // field against wildcards for the rest pins it down.
+    for (idx, field) in fields.iter().enumerate() {
+        let mut probe = vec!["*"; CRON_FIELDS.len()];
+        probe[idx] = field;
+        if Schedule::from_str(&probe.join(" ")).is_err() {
+            let (name, accepted) = CRON_FIELDS[idx];
+            return Err(format!("Invalid {} field '{}': expected {}, a list, range or step of them, or *", name, field, accepted));
+        }
+    }
+
+    let schedule = Schedule::from_str(&fields.join(" "))
+        .map_err(|e| format!("Invalid cron expression: {}", e))?;
+
+    if schedule.upcoming(Utc).next().is_none() {
+        return Err(format!("Cron expression '{}' never fires; check the day of month against the month", expr));
+    }
+
+    Ok(schedule)
+}
+
+pub fn validate_trigger(trigger: &TriggerConfig) -> Result<(), String> {
+    if let TriggerConfig::Cron { expression, timezone } = trigger {
+        parse_cron(expression)?;
+        resolve_timezone(timezone.as_deref())?;
+    }
+    Ok(())
+}
+
+/// The next `count` times a cron or interval trigger fires after `after`, in
+/// the timezone it runs in.
+pub fn next_runs(trigger: &TriggerConfig, after: DateTime<Utc>, count: usize) -> Result<(Tz, Vec<DateTime<Tz>>), String> {
+    let (expression, timezone) = trigger_cron(trigger)?;
+    let schedule = parse_cron(&expression)?;
+    let runs = schedule
+        .after(&after.with_timezone(&timezone))
+        .take(count.min(MAX_NEXT_RUNS))
+        .collect();
+    Ok((timezone, runs))
+}
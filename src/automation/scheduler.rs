@@ -1,6 +1,11 @@
 use crate::automation::database::Database;
-use crate::automation::engine::AutomationEngine;
+use crate::automation::engine::{AutomationEngine, RunError};
+use crate::automation::history;
+use crate::automation::key_health;
+use crate::automation::limiter::{ExecutionLimiter, RunRejection};
+use crate::automation::schedule;
 use crate::automation::types::*;
+use crate::automation::window;
 use leptos::logging::log;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -12,11 +17,15 @@ pub struct AutomationScheduler {
     database: Arc<Database>,
     engine: AutomationEngine,
     running_jobs: Arc<Mutex<std::collections::HashMap<i64, Uuid>>>,
-    execution_timeout_secs: u64,
+    limiter: Arc<ExecutionLimiter>,
 }
 
 impl AutomationScheduler {
-    pub async fn new(database: Arc<Database>, execution_timeout_secs: u64) -> Result<Self, String> {
+    pub async fn new(
+        database: Arc<Database>,
+        execution_timeout_secs: u64,
+        max_concurrent_runs: usize,
+    ) -> Result<Self, String> {
         let scheduler = JobScheduler::new()
             .await
             .map_err(|e| format!("Failed to create scheduler: {}", e))?;
@@ -26,10 +35,14 @@ impl AutomationScheduler {
             database,
             engine: AutomationEngine::new(),
             running_jobs: Arc::new(Mutex::new(std::collections::HashMap::new())),
-            execution_timeout_secs,
+            limiter: Arc::new(ExecutionLimiter::new(max_concurrent_runs, execution_timeout_secs)),
         })
     }
 
+    pub fn limiter(&self) -> Arc<ExecutionLimiter> {
+        self.limiter.clone()
+    }
+
     pub async fn start(&self) -> Result<(), String> {
         log!("Starting automation scheduler...");
         self.load_and_schedule_rules().await?;
@@ -73,11 +86,15 @@ impl AutomationScheduler {
         let mut jobs = self.running_jobs.lock().await;
 
         for rule in rules {
+            if let TriggerConfig::OnEvent { .. } = rule.trigger_config {
+                continue;
+            }
+
             if let Some(rule_id) = rule.id {
-                let cron_expr = match self.trigger_to_cron(&rule.trigger_config) {
-                    Ok(expr) => {
-                        log!("Rule {}: Generated cron expression: {}", rule_id, expr);
-                        expr
+                let (cron_expr, timezone) = match schedule::trigger_cron(&rule.trigger_config) {
+                    Ok((expr, timezone)) => {
+                        log!("Rule {}: Generated cron expression: {} ({})", rule_id, expr, timezone);
+                        (expr, timezone)
                     }
                     Err(e) => {
                         log!("Rule {}: Failed to generate cron expression: {}", rule_id, e);
@@ -85,30 +102,31 @@ impl AutomationScheduler {
                     }
                 };
 
+                let (job_expr, local_schedule) = schedule::job_cron(&cron_expr, timezone)
+                    .map_err(|e| format!("Failed to schedule rule {}: {}", rule_id, e))?;
+
                 let database = self.database.clone();
                 let engine = self.engine.clone();
                 let rule_name = rule.name.clone();
                 let rule_clone = rule.clone();
-                let timeout_secs = self.execution_timeout_secs;
+                let limiter = self.limiter.clone();
 
-                let job = Job::new_async(cron_expr.as_str(), move |_uuid, _l| {
+                let job = Job::new_async(job_expr.as_str(), move |_uuid, _l| {
+                    let due = local_schedule.as_ref().map(|local| local.is_due(chrono::Utc::now())).unwrap_or(true);
                     let database = database.clone();
                     let engine = engine.clone();
                     let rule = rule_clone.clone();
+                    let limiter = limiter.clone();
 
                     Box::pin(async move {
-                        if let Err(e) = Self::execute_rule_task(database, engine, rule.clone(), timeout_secs).await {
-                            let error_str = e.to_string();
-                            let is_transient = error_str.contains("530") 
-                                || error_str.contains("504") 
-                                || error_str.contains("502") 
-                                || error_str.contains("503")
-                                || error_str.contains("Network error");
-                            
-                            if is_transient {
-                                log!("Rule '{}' execution failed due to transient API error (will retry on next schedule)", rule.name);
+                        if !due {
+                            return;
+                        }
+                        if let Err(e) = Self::execute_rule_task(database, engine, rule.clone(), limiter).await {
+                            if e.is_transient() {
+                                log!("Rule '{}' execution failed due to transient API error (will retry on next schedule): {}", rule.name, e);
                             } else {
-                                log!("Rule execution error for '{}': {}", rule.name, error_str);
+                                log!("Rule execution error for '{}': {}", rule.name, e);
                             }
                         }
                     })
@@ -121,7 +139,7 @@ impl AutomationScheduler {
                     .map_err(|e| format!("Failed to add job for rule {}: {}", rule_id, e))?;
 
                 jobs.insert(rule_id, job_uuid);
-                log!("Scheduled rule: {} ({})", rule_name, cron_expr);
+                log!("Scheduled rule: {} ({} {})", rule_name, cron_expr, timezone);
             }
         }
 
@@ -132,14 +150,40 @@ impl AutomationScheduler {
         database: Arc<Database>,
         engine: AutomationEngine,
         rule: AutomationRule,
-        _timeout_secs: u64,
-    ) -> Result<(), String> {
+        limiter: Arc<ExecutionLimiter>,
+    ) -> Result<(), RunError> {
+        // Jobs scheduled before the key was suspended stay in place until the
+        // next reload.
+        if database.is_key_suspended(&rule.api_key_hash).await? {
+            return Ok(());
+        }
+
+        if let Some(reason) = window::closed_reason(&rule, chrono::Utc::now()) {
+            log!("Rule {} {}", rule.name, reason);
+            database.log_execution(&ExecutionLog::without_result(&rule, "execution", reason)).await?;
+            return Ok(());
+        }
+
         let api_key = database.get_api_key(&rule.api_key_hash).await?;
+        let history = history::load(&database, &rule).await?;
 
         log!("Executing rule: {}", rule.name);
 
-        let result = engine.execute_rule(&rule, &api_key).await
-            .map_err(|e| format!("Rule execution failed: {}", e))?;
+        let result = match limiter.run(rule.id.unwrap_or(0), engine.execute_rule(&database, &rule, &api_key, &history)).await {
+            Ok(result) => {
+                key_health::record_outcome(&database, &rule.api_key_hash, &result).await;
+                result?
+            }
+            Err(rejection) => {
+                if rejection == RunRejection::AlreadyRunning {
+                    log!("Rule {} skipped: previous run is still in progress", rule.name);
+                } else {
+                    log!("Rule {} {}", rule.name, rejection.message().to_lowercase());
+                }
+                database.log_execution(&ExecutionLog::without_result(&rule, "execution", rejection.message())).await?;
+                return Ok(());
+            }
+        };
 
         let log_entry = ExecutionLog {
             id: None,
@@ -154,9 +198,11 @@ impl AutomationScheduler {
             processed_items: result.processed_items.clone(),
             executed_at: None,
             partial: Some(result.partial),
+            rule_revision: rule.revision,
         };
 
         database.log_execution(&log_entry).await?;
+        database.save_transfers(&result.transfers).await?;
 
         if result.success && !result.partial {
             log!("Rule {} executed successfully: {}/{} items processed", rule.name, result.items_processed, result.total_items);
@@ -169,38 +215,6 @@ impl AutomationScheduler {
         Ok(())
     }
 
-    fn trigger_to_cron(&self, trigger: &TriggerConfig) -> Result<String, String> {
-        match trigger {
-            TriggerConfig::Cron { expression } => {
-                let expr = expression.trim();
-                if expr.is_empty() {
-                    return Err("Cron expression cannot be empty".to_string());
-                }
-                Ok(expr.to_string())
-            },
-            TriggerConfig::Interval { minutes } => {
-                let minutes = if *minutes < 30 { 30 } else { *minutes };
-
-                if minutes < 60 {
-                    Ok(format!("0 */{} * * * *", minutes))
-                } else {
-                    let hours = minutes / 60;
-                    let remaining_minutes = minutes % 60;
-
-                    if remaining_minutes == 0 {
-                        if hours == 1 {
-                            Ok("0 0 * * * *".to_string())
-                        } else {
-                            Ok(format!("0 0 */{} * * *", hours))
-                        }
-                    } else {
-                        Ok(format!("0 {} */{} * * *", remaining_minutes, hours))
-                    }
-                }
-            }
-        }
-    }
-
     pub async fn get_next_run_time(&self, rule_id: i64) -> Result<Option<chrono::DateTime<chrono::Utc>>, String> {
         let jobs = self.running_jobs.lock().await;
         let job_uuid = jobs.get(&rule_id).copied();
@@ -0,0 +1,218 @@
+use crate::automation::types::*;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// A parameterized rule the dashboard can instantiate with a few inputs. The
+/// template picks the condition types and operators so users only supply numbers.
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleTemplate {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub download_types: Vec<DownloadType>,
+    pub parameters: Vec<TemplateParameter>,
+    #[serde(skip)]
+    build: fn(&TemplateValues) -> TemplateRule,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateParameter {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub unit: Option<&'static str>,
+    pub default: f64,
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+}
+
+/// The parts of a rule a template fills in; the name and enabled flag come from the request.
+#[derive(Debug, Clone)]
+pub struct TemplateRule {
+    pub download_types: Vec<DownloadType>,
+    pub trigger_config: TriggerConfig,
+    pub conditions: ConditionNode,
+    pub action_config: ActionConfig,
+    pub quota: Option<QuotaConfig>,
+}
+
+pub struct TemplateValues(HashMap<&'static str, f64>);
+
+impl TemplateValues {
+    fn get(&self, key: &str) -> f64 {
+        self.0.get(key).copied().unwrap_or_default()
+    }
+
+    fn interval(&self) -> TriggerConfig {
+        TriggerConfig::Interval { minutes: self.get("interval_minutes").round() as u32 }
+    }
+}
+
+fn parameter(key: &'static str, label: &'static str, unit: Option<&'static str>, default: f64, min: f64, max: f64, step: f64) -> TemplateParameter {
+    TemplateParameter { key, label, unit, default, min, max, step }
+}
+
+fn interval_parameter(default: f64) -> TemplateParameter {
+    parameter("interval_minutes", "Check every", Some("minutes"), default, 30.0, 10080.0, 1.0)
+}
+
+fn condition(r#type: ConditionType, operator: Operator, value: f64) -> ConditionNode {
+    ConditionNode::Condition(Condition { r#type, operator, value })
+}
+
+fn state_contains(text: &str) -> ConditionNode {
+    ConditionNode::Text(TextCondition {
+        field: TextField::DownloadState,
+        operator: TextOperator::Contains(text.to_string()),
+        case_sensitive: false,
+    })
+}
+
+fn actions(action_types: Vec<ActionType>) -> ActionConfig {
+    ActionConfig {
+        steps: action_types
+            .into_iter()
+            .map(|action_type| ActionStep { action_type, params: None, on_failure: FailurePolicy::default() })
+            .collect(),
+    }
+}
+
+pub fn catalog() -> Vec<RuleTemplate> {
+    vec![
+        RuleTemplate {
+            id: "stop-seeding-ratio-or-time",
+            name: "Stop seeding at ratio or age",
+            description: "Stops seeding torrents once they reach a seeding ratio or have seeded for a number of days, whichever comes first.",
+            download_types: vec![DownloadType::Torrent],
+            parameters: vec![
+                parameter("ratio", "Seeding ratio", None, 2.0, 0.1, 100.0, 0.1),
+                parameter("days", "Seeding time", Some("days"), 7.0, 1.0, 365.0, 1.0),
+                interval_parameter(60.0),
+            ],
+            build: |values| TemplateRule {
+                download_types: vec![DownloadType::Torrent],
+                trigger_config: values.interval(),
+                conditions: ConditionNode::Any(vec![
+                    condition(ConditionType::SeedingRatio, Operator::GreaterThanOrEqual, values.get("ratio")),
+                    condition(ConditionType::SeedingTime, Operator::GreaterThanOrEqual, values.get("days") * 24.0),
+                ]),
+                action_config: actions(vec![ActionType::StopSeeding]),
+                quota: None,
+            },
+        },
+        RuleTemplate {
+            id: "delete-expiring-soon",
+            name: "Delete downloads about to expire",
+            description: "Deletes torrents, web downloads and usenet downloads that expire within the given number of hours.",
+            download_types: vec![DownloadType::Torrent, DownloadType::WebDownload, DownloadType::Usenet],
+            parameters: vec![
+                parameter("hours", "Expires within", Some("hours"), 12.0, 1.0, 168.0, 1.0),
+                interval_parameter(60.0),
+            ],
+            build: |values| TemplateRule {
+                download_types: vec![DownloadType::Torrent, DownloadType::WebDownload, DownloadType::Usenet],
+                trigger_config: values.interval(),
+                conditions: condition(ConditionType::ExpiresAt, Operator::LessThanOrEqual, values.get("hours")),
+                action_config: actions(vec![ActionType::Delete]),
+                quota: None,
+            },
+        },
+        RuleTemplate {
+            id: "reannounce-stalled",
+            name: "Reannounce stalled torrents",
+            description: "Reannounces torrents to their trackers after they have been stalled for the given number of hours.",
+            download_types: vec![DownloadType::Torrent],
+            parameters: vec![
+                parameter("hours", "Stalled for", Some("hours"), 1.0, 0.25, 168.0, 0.25),
+                interval_parameter(30.0),
+            ],
+            build: |values| TemplateRule {
+                download_types: vec![DownloadType::Torrent],
+                trigger_config: values.interval(),
+                conditions: condition(ConditionType::StalledTime, Operator::GreaterThanOrEqual, values.get("hours")),
+                action_config: actions(vec![ActionType::Reannounce]),
+                quota: None,
+            },
+        },
+        RuleTemplate {
+            id: "restart-failed",
+            name: "Restart failed torrents",
+            description: "Restarts torrents whose state reports a failure or error, once they are older than the given number of hours.",
+            download_types: vec![DownloadType::Torrent],
+            parameters: vec![
+                parameter("min_age_hours", "Older than", Some("hours"), 1.0, 0.0, 720.0, 1.0),
+                interval_parameter(60.0),
+            ],
+            build: |values| TemplateRule {
+                download_types: vec![DownloadType::Torrent],
+                trigger_config: values.interval(),
+                conditions: ConditionNode::All(vec![
+                    ConditionNode::Any(vec![state_contains("failed"), state_contains("error")]),
+                    condition(ConditionType::Age, Operator::GreaterThanOrEqual, values.get("min_age_hours")),
+                ]),
+                action_config: actions(vec![ActionType::Restart]),
+                quota: None,
+            },
+        },
+        RuleTemplate {
+            id: "keep-library-under-size",
+            name: "Keep library under a size",
+            description: "When finished torrents take up more than the given size, deletes the oldest ones until the library fits again.",
+            download_types: vec![DownloadType::Torrent],
+            parameters: vec![
+                parameter("gb", "Keep under", Some("GB"), 1500.0, 1.0, 100000.0, 1.0),
+                parameter("max_items", "Delete at most", Some("per run"), 20.0, 1.0, 500.0, 1.0),
+                interval_parameter(360.0),
+            ],
+            build: |values| TemplateRule {
+                download_types: vec![DownloadType::Torrent],
+                trigger_config: values.interval(),
+                conditions: condition(ConditionType::DownloadFinished, Operator::Equal, 1.0),
+                action_config: actions(vec![ActionType::Delete]),
+                quota: Some(QuotaConfig {
+                    sort_by: QuotaSortKey::Age,
+                    target: QuotaTarget::TotalSize { gb: values.get("gb") },
+                    max_items_per_run: Some(values.get("max_items").round() as u32),
+                }),
+            },
+        },
+    ]
+}
+
+pub fn find(id: &str) -> Option<RuleTemplate> {
+    catalog().into_iter().find(|template| template.id == id)
+}
+
+impl RuleTemplate {
+    /// Fills in defaults for missing parameters and rejects unknown or out-of-range ones.
+    pub fn resolve(&self, params: &HashMap<String, f64>) -> Result<TemplateValues, String> {
+        if let Some(unknown) = params.keys().find(|key| !self.parameters.iter().any(|p| p.key == key.as_str())) {
+            return Err(format!("Unknown parameter '{}' for template '{}'", unknown, self.id));
+        }
+
+        let mut values = HashMap::new();
+        for parameter in &self.parameters {
+            let value = params.get(parameter.key).copied().unwrap_or(parameter.default);
+            if !value.is_finite() {
+                return Err(format!("{} must be a number", parameter.label));
+            }
+            if value < parameter.min || value > parameter.max {
+                return Err(format!(
+                    "{} must be between {} and {}{}",
+                    parameter.label,
+                    parameter.min,
+                    parameter.max,
+                    parameter.unit.map(|unit| format!(" {}", unit)).unwrap_or_default()
+                ));
+            }
+            values.insert(parameter.key, value);
+        }
+
+        Ok(TemplateValues(values))
+    }
+
+    pub fn instantiate(&self, params: &HashMap<String, f64>) -> Result<TemplateRule, String> {
+        let values = self.resolve(params)?;
+        Ok((self.build)(&values))
+    }
+}
@@ -0,0 +1,231 @@
+use crate::api::TorboxClient;
+use crate::automation::archive;
+use crate::automation::database::Database;
+use crate::automation::engine::{action_label, AutomationEngine};
+use crate::automation::key_health;
+use crate::automation::types::*;
+use chrono::{NaiveDateTime, Utc};
+use leptos::logging::log;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Transfers still unfinished after this long are given up on.
+const MAX_TRACKING_HOURS: i64 = 24;
+
+/// Follows the TorBox transfer jobs started by `CloudUpload` steps. Once every
+/// transfer of a batch has finished it logs the outcome against the rule and, if
+/// they all completed, runs the step's `on_success` actions on the download.
+pub struct TransferTracker {
+    database: Arc<Database>,
+    engine: AutomationEngine,
+    poll_interval_secs: u64,
+}
+
+impl TransferTracker {
+    pub fn new(database: Arc<Database>, poll_interval_secs: u64) -> Self {
+        Self {
+            database,
+            engine: AutomationEngine::new(),
+            poll_interval_secs,
+        }
+    }
+
+    pub fn start(self: Arc<Self>) {
+        log!("Starting transfer tracker (polling every {}s)", self.poll_interval_secs);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(self.poll_interval_secs));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.poll().await {
+                    log!("Transfer tracker poll failed: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn poll(&self) -> Result<(), String> {
+        let mut transfers_by_key: HashMap<String, Vec<TrackedTransfer>> = HashMap::new();
+        for transfer in self.database.get_pending_transfers().await? {
+            transfers_by_key.entry(transfer.api_key_hash.clone()).or_default().push(transfer);
+        }
+
+        for (api_key_hash, transfers) in &transfers_by_key {
+            if let Err(e) = self.poll_key(api_key_hash, transfers).await {
+                log!("Failed to check transfer jobs: {}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn poll_key(&self, api_key_hash: &str, transfers: &[TrackedTransfer]) -> Result<(), String> {
+        let api_key = self.database.get_api_key(api_key_hash).await?;
+        let client = TorboxClient::new(api_key);
+
+        let response = match client.get_transfer_jobs().await {
+            Ok(response) => response,
+            Err(e) => {
+                key_health::record_api_error(&self.database, api_key_hash, &e).await?;
+                return Err(format!("Failed to fetch transfer jobs: {}", e));
+            }
+        };
+        let jobs = response.data.unwrap_or_default();
+        let statuses: HashMap<i32, String> = jobs
+            .into_iter()
+            .map(|job| (job.job_id, job.status))
+            .collect();
+
+        let mut settled_batches: HashSet<String> = HashSet::new();
+        for transfer in transfers {
+            let status = statuses
+                .get(&transfer.job_id)
+                .and_then(|status| final_status(status))
+                .or_else(|| is_stale(transfer).then_some(TRANSFER_TIMED_OUT));
+
+            let (Some(id), Some(status)) = (transfer.id, status) else {
+                continue;
+            };
+
+            self.database.finish_transfer(id, status).await?;
+            settled_batches.insert(transfer.batch_id.clone());
+        }
+
+        for batch_id in settled_batches {
+            if let Err(e) = self.settle_batch(&client, &batch_id).await {
+                log!("Failed to finish transfer batch {}: {}", batch_id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs one `on_success` action on the download. A torrent is archived
+    /// before it is deleted, like a rule's own Delete step.
+    async fn run_follow_up(&self, client: &TorboxClient, action_type: &ActionType, transfer: &TrackedTransfer) -> Result<(), String> {
+        if !matches!((action_type, transfer.download_type), (ActionType::Delete, DownloadType::Torrent)) {
+            return self.engine
+                .control_download(client, action_type, transfer.download_type, transfer.download_id)
+                .await;
+        }
+
+        let torrent = archive::find_torrent(client, transfer.download_id, &transfer.rule_name).await?
+            .ok_or_else(|| format!("Torrent {} no longer exists", transfer.download_id))?;
+        let mut deleted = archive::archive(client, &torrent, &transfer.api_key_hash).await?;
+        deleted.rule_id = Some(transfer.rule_id);
+        deleted.rule_name = Some(transfer.rule_name.clone());
+        archive::delete_archived(&self.database, client, deleted).await.map(|_| ())
+    }
+
+    async fn settle_batch(&self, client: &TorboxClient, batch_id: &str) -> Result<(), String> {
+        let batch = self.database.get_transfer_batch(batch_id).await?;
+        if batch.iter().any(|transfer| transfer.status == TRANSFER_PENDING) {
+            return Ok(());
+        }
+        let Some(first) = batch.first() else {
+            return Ok(());
+        };
+
+        let mut steps: Vec<StepOutcome> = batch
+            .iter()
+            .enumerate()
+            .map(|(idx, transfer)| {
+                let completed = transfer.status == TRANSFER_COMPLETED;
+                StepOutcome {
+                    step: idx + 1,
+                    action: "Cloud Upload".to_string(),
+                    // The upload itself was recorded when the rule ran.
+                    action_type: None,
+                    success: completed,
+                    error: (!completed).then(|| format!("Transfer {}", transfer.status.replace('_', " "))),
+                    detail: Some(format!(
+                        "{} job {} ({}): {}",
+                        transfer.provider.label(),
+                        transfer.job_id,
+                        transfer.file_name.as_deref().unwrap_or("whole download"),
+                        transfer.status
+                    )),
+                }
+            })
+            .collect();
+
+        let failed = batch.iter().filter(|transfer| transfer.status != TRANSFER_COMPLETED).count();
+        let mut error: Option<String> = None;
+
+        if failed > 0 {
+            error = Some(format!("{} of {} transfers did not complete; follow-up actions were skipped", failed, batch.len()));
+        } else {
+            for action_type in &first.on_success {
+                let outcome = self.run_follow_up(client, action_type, first).await;
+                steps.push(StepOutcome {
+                    step: steps.len() + 1,
+                    action: action_label(action_type).to_string(),
+                    action_type: Some(action_type.clone()),
+                    success: outcome.is_ok(),
+                    error: outcome.as_ref().err().cloned(),
+                    detail: None,
+                });
+                if let Err(e) = outcome {
+                    error = Some(e);
+                    break;
+                }
+            }
+        }
+
+        log!("Transfers for '{}' (rule '{}') finished: {}", first.item_name, first.rule_name,
+             error.as_deref().unwrap_or("all completed"));
+
+        let action = std::iter::once("Cloud Upload".to_string())
+            .chain(first.on_success.iter().map(|action_type| action_label(action_type).to_string()))
+            .collect::<Vec<_>>()
+            .join(" → ");
+
+        let log_entry = ExecutionLog {
+            id: None,
+            rule_id: first.rule_id,
+            rule_name: first.rule_name.clone(),
+            api_key_hash: first.api_key_hash.clone(),
+            execution_type: "transfer".to_string(),
+            items_processed: 1,
+            total_items: Some(1),
+            success: error.is_none(),
+            error_message: error.clone(),
+            processed_items: Some(vec![ProcessedItem {
+                id: first.download_id,
+                download_type: Some(first.download_type),
+                name: first.item_name.clone(),
+                action,
+                success: error.is_none(),
+                error,
+                steps,
+                size: None,
+                hash: None,
+            }]),
+            executed_at: None,
+            partial: Some(false),
+            rule_revision: first.rule_revision,
+        };
+
+        self.database.log_execution(&log_entry).await
+    }
+}
+
+/// Maps a TorBox job status onto a final tracker status, or `None` while it is still
+/// running. Only TorBox's two terminal statuses count; anything else, including
+/// statuses this code doesn't know, stays pending until the tracking timeout, so a
+/// follow-up such as Delete never runs on an unfinished transfer.
+fn final_status(status: &str) -> Option<&'static str> {
+    match status.trim().to_lowercase().as_str() {
+        "completed" => Some(TRANSFER_COMPLETED),
+        "failed" => Some(TRANSFER_FAILED),
+        _ => None,
+    }
+}
+
+fn is_stale(transfer: &TrackedTransfer) -> bool {
+    transfer.created_at
+        .as_deref()
+        .and_then(|created_at| NaiveDateTime::parse_from_str(created_at, "%Y-%m-%d %H:%M:%S").ok())
+        .map(|created_at| (Utc::now().naive_utc() - created_at).num_hours() >= MAX_TRACKING_HOURS)
+        .unwrap_or(false)
+}
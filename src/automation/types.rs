@@ -1,4 +1,5 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AutomationRule {
@@ -6,17 +7,192 @@ pub struct AutomationRule {
     pub api_key_hash: String,
     pub name: String,
     pub enabled: bool,
+    #[serde(default = "default_download_types")]
+    pub download_types: Vec<DownloadType>,
     pub trigger_config: TriggerConfig,
-    pub conditions: Vec<Condition>,
+    #[serde(deserialize_with = "deserialize_conditions")]
+    pub conditions: ConditionNode,
     pub action_config: ActionConfig,
+    #[serde(default)]
+    pub notification: Option<NotificationConfig>,
+    #[serde(default)]
+    pub schedule_window: Option<ScheduleWindow>,
+    #[serde(default)]
+    pub quota: Option<QuotaConfig>,
+    #[serde(default)]
+    pub cooldown: Option<CooldownConfig>,
+    /// Number of the latest revision, assigned when the rule is saved.
+    #[serde(default)]
+    pub revision: Option<i64>,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DownloadType {
+    Torrent,
+    WebDownload,
+    Usenet,
+}
+
+impl DownloadType {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DownloadType::Torrent => "torrent",
+            DownloadType::WebDownload => "web download",
+            DownloadType::Usenet => "usenet download",
+        }
+    }
+
+    /// The `type` TorBox's cloud integration endpoints expect.
+    pub fn integration_type(&self) -> &'static str {
+        match self {
+            DownloadType::Torrent => "torrent",
+            DownloadType::WebDownload => "webdl",
+            DownloadType::Usenet => "usenet",
+        }
+    }
+}
+
+pub fn default_download_types() -> Vec<DownloadType> {
+    vec![DownloadType::Torrent]
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TriggerConfig {
-    Cron { expression: String },
+    Cron {
+        expression: String,
+        /// IANA timezone the expression is read in; UTC when absent.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timezone: Option<String>,
+    },
     Interval { minutes: u32 },
+    OnEvent { events: Vec<DownloadEvent> },
+}
+
+/// Limits when a rule may act, whatever triggered it. Runs that fall outside
+/// the window are skipped and logged as such.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleWindow {
+    /// Days on which the window opens; empty means every day.
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+    /// Times of day the window is open on those days; empty means all day.
+    #[serde(default)]
+    pub ranges: Vec<TimeRange>,
+    /// IANA timezone the days and times are in, e.g. `Europe/Berlin`.
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+}
+
+/// `HH:MM` times, end exclusive. A range whose end is not after its start runs
+/// past midnight into the next day; `24:00` ends a range at midnight.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeRange {
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl From<chrono::Weekday> for Weekday {
+    fn from(day: chrono::Weekday) -> Self {
+        match day {
+            chrono::Weekday::Mon => Weekday::Monday,
+            chrono::Weekday::Tue => Weekday::Tuesday,
+            chrono::Weekday::Wed => Weekday::Wednesday,
+            chrono::Weekday::Thu => Weekday::Thursday,
+            chrono::Weekday::Fri => Weekday::Friday,
+            chrono::Weekday::Sat => Weekday::Saturday,
+            chrono::Weekday::Sun => Weekday::Sunday,
+        }
+    }
+}
+
+pub fn default_timezone() -> String {
+    "UTC".to_string()
+}
+
+/// Turns a rule into a "keep under N" rule: instead of acting on every matching
+/// download, it acts on as few of them as it takes to bring the total of all
+/// the rule's downloads under the target, in `sort_by` order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaConfig {
+    pub sort_by: QuotaSortKey,
+    pub target: QuotaTarget,
+    /// Most downloads a single run may act on; the rest waits for the next run.
+    #[serde(default)]
+    pub max_items_per_run: Option<u32>,
+}
+
+/// Which matching downloads go first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuotaSortKey {
+    /// Oldest first.
+    Age,
+    /// Lowest seeding ratio first; downloads without a ratio go last.
+    Ratio,
+    /// Largest first.
+    Size,
+    /// Longest without activity first.
+    LastActivity,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum QuotaTarget {
+    TotalSize { gb: f64 },
+    ItemCount { count: u32 },
+}
+
+/// Keeps a rule off downloads it has already acted on. Either limit may be left
+/// out; a download is skipped as soon as one of them applies.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CooldownConfig {
+    /// Skip downloads this rule acted on within the last N hours.
+    #[serde(default)]
+    pub hours: Option<f64>,
+    /// Skip downloads this rule has acted on this many times.
+    #[serde(default)]
+    pub max_actions: Option<u32>,
+}
+
+/// State transitions the event watcher detects between two polls of the download list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DownloadEvent {
+    Finished,
+    Stalled,
+    ExpiringSoon,
+    Cached,
+    Errored,
+}
+
+impl DownloadEvent {
+    pub const ALL: [DownloadEvent; 5] = [
+        DownloadEvent::Finished,
+        DownloadEvent::Stalled,
+        DownloadEvent::ExpiringSoon,
+        DownloadEvent::Cached,
+        DownloadEvent::Errored,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DownloadEvent::Finished => "finished downloading",
+            DownloadEvent::Stalled => "became stalled",
+            DownloadEvent::ExpiringSoon => "expiring soon",
+            DownloadEvent::Cached => "became cached",
+            DownloadEvent::Errored => "errored",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +202,92 @@ pub struct Condition {
     pub value: f64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ConditionNode {
+    Condition(Condition),
+    Text(TextCondition),
+    All(Vec<ConditionNode>),
+    Any(Vec<ConditionNode>),
+    Not(Box<ConditionNode>),
+}
+
+impl ConditionNode {
+    pub fn leaves(&self) -> Vec<&ConditionNode> {
+        let mut leaves = Vec::new();
+        self.collect_leaves(&mut leaves);
+        leaves
+    }
+
+    fn collect_leaves<'a>(&'a self, leaves: &mut Vec<&'a ConditionNode>) {
+        match self {
+            ConditionNode::Condition(_) | ConditionNode::Text(_) => leaves.push(self),
+            ConditionNode::All(children) | ConditionNode::Any(children) => {
+                for child in children {
+                    child.collect_leaves(leaves);
+                }
+            }
+            ConditionNode::Not(inner) => inner.collect_leaves(leaves),
+        }
+    }
+
+    pub fn text_conditions(&self) -> Vec<&TextCondition> {
+        self.leaves()
+            .into_iter()
+            .filter_map(|leaf| match leaf {
+                ConditionNode::Text(condition) => Some(condition),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn depth(&self) -> usize {
+        match self {
+            ConditionNode::Condition(_) | ConditionNode::Text(_) => 1,
+            ConditionNode::All(children) | ConditionNode::Any(children) => {
+                1 + children.iter().map(|c| c.depth()).max().unwrap_or(0)
+            }
+            ConditionNode::Not(inner) => 1 + inner.depth(),
+        }
+    }
+
+    pub fn has_empty_group(&self) -> bool {
+        match self {
+            ConditionNode::Condition(_) | ConditionNode::Text(_) => false,
+            ConditionNode::All(children) | ConditionNode::Any(children) => {
+                children.is_empty() || children.iter().any(|c| c.has_empty_group())
+            }
+            ConditionNode::Not(inner) => inner.has_empty_group(),
+        }
+    }
+}
+
+/// Rules saved before condition groups existed store a flat array that was always ANDed.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredConditions {
+    Flat(Vec<Condition>),
+    Tree(ConditionNode),
+}
+
+impl From<StoredConditions> for ConditionNode {
+    fn from(stored: StoredConditions) -> Self {
+        match stored {
+            StoredConditions::Flat(conditions) => {
+                ConditionNode::All(conditions.into_iter().map(ConditionNode::Condition).collect())
+            }
+            StoredConditions::Tree(node) => node,
+        }
+    }
+}
+
+pub fn deserialize_conditions<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ConditionNode, D::Error> {
+    StoredConditions::deserialize(deserializer).map(ConditionNode::from)
+}
+
+pub fn parse_conditions(json: &str) -> Result<ConditionNode, serde_json::Error> {
+    serde_json::from_str::<StoredConditions>(json).map(ConditionNode::from)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ConditionType {
     SeedingTime,
@@ -54,6 +316,127 @@ pub enum ConditionType {
     TorrentFile,
     AllowZipped,
     HasMagnet,
+    /// Successful Restart actions any rule took on the download.
+    TimesRestarted,
+    TimesReannounced,
+    TimesResumed,
+    /// Successful actions of any kind any rule took on the download.
+    TimesActedOn,
+    /// Hours since any rule last acted on the download; never-touched downloads do not match.
+    HoursSinceLastAction,
+}
+
+impl ConditionType {
+    /// Measured from the action history instead of the download itself.
+    pub fn uses_history(&self) -> bool {
+        matches!(
+            self,
+            ConditionType::TimesRestarted
+                | ConditionType::TimesReannounced
+                | ConditionType::TimesResumed
+                | ConditionType::TimesActedOn
+                | ConditionType::HoursSinceLastAction
+        )
+    }
+
+    pub fn supports(&self, download_type: DownloadType) -> bool {
+        match download_type {
+            DownloadType::Torrent => true,
+            DownloadType::WebDownload => !matches!(
+                self,
+                ConditionType::SeedingTime
+                    | ConditionType::SeedingRatio
+                    | ConditionType::Seeds
+                    | ConditionType::Peers
+                    | ConditionType::TotalUploaded
+                    | ConditionType::TotalDownloaded
+                    | ConditionType::Cached
+                    | ConditionType::Private
+                    | ConditionType::LongTermSeeding
+                    | ConditionType::SeedTorrent
+                    | ConditionType::AllowZipped
+                    | ConditionType::HasMagnet
+            ),
+            DownloadType::Usenet => !matches!(
+                self,
+                ConditionType::SeedingTime
+                    | ConditionType::SeedingRatio
+                    | ConditionType::Seeds
+                    | ConditionType::Peers
+                    | ConditionType::UploadSpeed
+                    | ConditionType::TotalUploaded
+                    | ConditionType::TotalDownloaded
+                    | ConditionType::Private
+                    | ConditionType::LongTermSeeding
+                    | ConditionType::SeedTorrent
+                    | ConditionType::Availability
+                    | ConditionType::TorrentFile
+                    | ConditionType::AllowZipped
+                    | ConditionType::HasMagnet
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextCondition {
+    pub field: TextField,
+    pub operator: TextOperator,
+    #[serde(default)]
+    pub case_sensitive: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextField {
+    Name,
+    Tracker,
+    TrackerMessage,
+    DownloadState,
+    FileExtension,
+}
+
+impl TextField {
+    pub fn supports(&self, download_type: DownloadType) -> bool {
+        match self {
+            TextField::Tracker | TextField::TrackerMessage => download_type == DownloadType::Torrent,
+            TextField::Name | TextField::DownloadState | TextField::FileExtension => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TextOperator {
+    Equals(String),
+    Contains(String),
+    Matches(String),
+    InList(Vec<String>),
+}
+
+impl TextCondition {
+    pub fn compile_regex(&self) -> Result<Option<regex::Regex>, String> {
+        match &self.operator {
+            TextOperator::Matches(pattern) => regex::RegexBuilder::new(pattern)
+                .case_insensitive(!self.case_sensitive)
+                .size_limit(1 << 20)
+                .build()
+                .map(Some)
+                .map_err(|e| format!("Invalid regex '{}': {}", pattern, e)),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn matches_value(&self, value: &str, regex: Option<&regex::Regex>) -> bool {
+        let normalize = |s: &str| if self.case_sensitive { s.to_string() } else { s.to_lowercase() };
+        match &self.operator {
+            TextOperator::Equals(expected) => normalize(value) == normalize(expected),
+            TextOperator::Contains(needle) => normalize(value).contains(&normalize(needle)),
+            TextOperator::Matches(_) => regex.map(|r| r.is_match(value)).unwrap_or(false),
+            TextOperator::InList(options) => {
+                let value = normalize(value);
+                options.iter().any(|option| normalize(option) == value)
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,12 +449,45 @@ pub enum Operator {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "StoredActionConfig")]
 pub struct ActionConfig {
+    pub steps: Vec<ActionStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionStep {
     pub action_type: ActionType,
     pub params: Option<serde_json::Value>,
+    #[serde(default)]
+    pub on_failure: FailurePolicy,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FailurePolicy {
+    Continue,
+    #[default]
+    SkipRemaining,
+    AbortRun,
+}
+
+/// Rules saved before pipelines existed store a single `{action_type, params}` object.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredActionConfig {
+    Pipeline { steps: Vec<ActionStep> },
+    Single(ActionStep),
+}
+
+impl From<StoredActionConfig> for ActionConfig {
+    fn from(stored: StoredActionConfig) -> Self {
+        match stored {
+            StoredActionConfig::Pipeline { steps } => ActionConfig { steps },
+            StoredActionConfig::Single(step) => ActionConfig { steps: vec![step] },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ActionType {
     StopSeeding,
     Delete,
@@ -80,15 +496,181 @@ pub enum ActionType {
     Restart,
     Reannounce,
     ForceStart,
+    ExportMagnet,
+    Notify,
+    CloudUpload,
+}
+
+impl ActionType {
+    pub fn supports(&self, download_type: DownloadType) -> bool {
+        match self {
+            ActionType::ExportMagnet => download_type == DownloadType::Torrent,
+            ActionType::Notify | ActionType::CloudUpload => true,
+            _ => self.operation_for(download_type).is_some(),
+        }
+    }
+
+    pub fn operation_for(&self, download_type: DownloadType) -> Option<&'static str> {
+        match (self, download_type) {
+            (ActionType::StopSeeding, DownloadType::Torrent) => Some("stop_seeding"),
+            (ActionType::Delete, _) => Some("delete"),
+            (ActionType::Stop, DownloadType::Torrent) => Some("stop"),
+            (ActionType::Stop, DownloadType::Usenet) => Some("pause"),
+            (ActionType::Resume, DownloadType::Torrent) => Some("resume"),
+            (ActionType::Resume, DownloadType::Usenet) => Some("resume"),
+            (ActionType::Restart, DownloadType::Torrent) => Some("restart"),
+            (ActionType::Reannounce, DownloadType::Torrent) => Some("reannounce"),
+            (ActionType::ForceStart, DownloadType::Torrent) => Some("start"),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CloudProvider {
+    GoogleDrive,
+    Dropbox,
+    OneDrive,
+}
+
+impl CloudProvider {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CloudProvider::GoogleDrive => "Google Drive",
+            CloudProvider::Dropbox => "Dropbox",
+            CloudProvider::OneDrive => "OneDrive",
+        }
+    }
+}
+
+/// Params of a `CloudUpload` action step. Without file patterns the whole download
+/// is sent as one transfer; with patterns each matching file gets its own transfer.
+/// Transfers go to the provider account linked in TorBox.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloudUploadConfig {
+    pub provider: CloudProvider,
+    #[serde(default)]
+    pub zip: bool,
+    /// Glob patterns (`*`, `?`) matched case-insensitively against file names.
+    #[serde(default)]
+    pub file_patterns: Vec<String>,
+    /// Actions to run on the download once every transfer for it has completed.
+    #[serde(default)]
+    pub on_success: Vec<ActionType>,
+}
+
+impl CloudUploadConfig {
+    /// Compiles the `*`/`?` file patterns into case-insensitive, anchored regexes.
+    pub fn file_matchers(&self) -> Result<Vec<regex::Regex>, String> {
+        self.file_patterns
+            .iter()
+            .map(|pattern| {
+                let escaped = regex::escape(pattern.trim())
+                    .replace("\\*", ".*")
+                    .replace("\\?", ".");
+                regex::RegexBuilder::new(&format!("^{}$", escaped))
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| format!("Invalid file pattern '{}': {}", pattern, e))
+            })
+            .collect()
+    }
+}
+
+pub const TRANSFER_PENDING: &str = "pending";
+pub const TRANSFER_COMPLETED: &str = "completed";
+pub const TRANSFER_FAILED: &str = "failed";
+pub const TRANSFER_TIMED_OUT: &str = "timed_out";
+
+/// A TorBox transfer job started by a `CloudUpload` step, followed until it finishes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedTransfer {
+    pub id: Option<i64>,
+    pub rule_id: i64,
+    pub rule_name: String,
+    /// Revision of the rule whose run started the transfer.
+    #[serde(default)]
+    pub rule_revision: Option<i64>,
+    pub api_key_hash: String,
+    /// Shared by the transfers one step started for one download.
+    pub batch_id: String,
+    pub download_id: i32,
+    pub download_type: DownloadType,
+    pub item_name: String,
+    /// The uploaded file when the step used file patterns; `None` for the whole download.
+    pub file_name: Option<String>,
+    pub provider: CloudProvider,
+    pub job_id: i32,
+    pub status: String,
+    #[serde(default)]
+    pub on_success: Vec<ActionType>,
+    pub created_at: Option<String>,
+    pub completed_at: Option<String>,
+}
+
+/// Where and when to send a notification. Used both as a rule's completion
+/// setting and as the params of a `Notify` action step, which ignores `notify_on`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationConfig {
+    pub url: String,
+    #[serde(default)]
+    pub format: NotificationFormat,
+    #[serde(default)]
+    pub notify_on: NotifyOn,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Custom body for `Generic` webhooks; `{{placeholders}}` in string values are filled in.
+    #[serde(default)]
+    pub payload_template: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NotificationFormat {
+    #[default]
+    Generic,
+    Discord,
+    Slack,
+    Ntfy,
+    Gotify,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NotifyOn {
+    #[default]
+    AfterRun,
+    FailuresOnly,
+    ItemsProcessed,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessedItem {
     pub id: i32,
+    #[serde(default)]
+    pub download_type: Option<DownloadType>,
     pub name: String,
     pub action: String,
     pub success: bool,
     pub error: Option<String>,
+    #[serde(default)]
+    pub steps: Vec<StepOutcome>,
+    /// Size in bytes when the run saw it, so deletions can be totalled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+    /// TorBox hash, recorded with the item's actions in the action history.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepOutcome {
+    pub step: usize,
+    pub action: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_type: Option<ActionType>,
+    pub success: bool,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub detail: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -107,6 +689,30 @@ pub struct ExecutionLog {
     pub executed_at: Option<String>,
     #[serde(default)]
     pub partial: Option<bool>,
+    /// Revision of the rule that ran.
+    #[serde(default)]
+    pub rule_revision: Option<i64>,
+}
+
+impl ExecutionLog {
+    /// Log entry for a run that never produced a result, e.g. skipped or timed out.
+    pub fn without_result(rule: &AutomationRule, execution_type: &str, message: String) -> Self {
+        Self {
+            id: None,
+            rule_id: rule.id.unwrap_or(0),
+            rule_name: rule.name.clone(),
+            api_key_hash: rule.api_key_hash.clone(),
+            execution_type: execution_type.to_string(),
+            items_processed: 0,
+            total_items: Some(0),
+            success: false,
+            error_message: Some(message),
+            processed_items: None,
+            executed_at: None,
+            partial: Some(false),
+            rule_revision: rule.revision,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -118,3 +724,33 @@ pub struct ApiKeyRecord {
     pub created_at: String,
     pub last_used_at: Option<String>,
 }
+
+/// Consecutive authentication failures after which a key's rules are suspended.
+pub const AUTH_FAILURE_THRESHOLD: i64 = 3;
+
+/// `execution_type` of the log entry written to each rule when its key is suspended.
+pub const EXECUTION_TYPE_SUSPENDED: &str = "suspended";
+
+/// Whether TorBox still accepts the API key a set of rules runs with.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApiKeyStatus {
+    /// False until the key has been used to create a rule or to sign in.
+    pub stored: bool,
+    pub auth_failures: i64,
+    pub last_auth_error: Option<String>,
+    /// Set once TorBox rejected the key `AUTH_FAILURE_THRESHOLD` times in a row.
+    /// Its rules don't run again until the key signs in successfully.
+    pub suspended_at: Option<String>,
+    /// Other keys of the same TorBox account that still own rules.
+    pub other_keys: Vec<OtherApiKey>,
+    /// Rules the sign-in that produced this status moved over from suspended keys.
+    #[serde(default)]
+    pub migrated_rules: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OtherApiKey {
+    pub api_key_hash: String,
+    pub rule_count: i64,
+    pub suspended_at: Option<String>,
+}
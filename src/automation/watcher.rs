@@ -0,0 +1,254 @@
+use crate::api::TorboxClient;
+use crate::automation::database::Database;
+use crate::automation::engine::{AutomationEngine, RunError};
+use crate::automation::history;
+use crate::automation::items::DownloadItem;
+use crate::automation::key_health;
+use crate::automation::limiter::{ExecutionLimiter, RunRejection};
+use crate::automation::types::*;
+use crate::automation::window;
+use chrono::{DateTime, Utc};
+use leptos::logging::log;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+const EXPIRING_SOON_SECS: i64 = 24 * 3600;
+
+type ItemKey = (DownloadType, i32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ItemState {
+    finished: bool,
+    stalled: bool,
+    expiring_soon: bool,
+    cached: bool,
+    errored: bool,
+}
+
+impl ItemState {
+    fn of(item: &DownloadItem, now: i64) -> Self {
+        let state = item.download_state().to_lowercase();
+        let expiring_soon = item.expires_at()
+            .and_then(|expires_at| DateTime::parse_from_rfc3339(expires_at).ok())
+            .map(|expires| {
+                let remaining = expires.timestamp() - now;
+                remaining > 0 && remaining <= EXPIRING_SOON_SECS
+            })
+            .unwrap_or(false);
+
+        Self {
+            finished: item.download_finished(),
+            stalled: state.contains("stalled"),
+            expiring_soon,
+            cached: item.cached(),
+            errored: state.contains("error") || state.contains("failed"),
+        }
+    }
+
+    fn has(&self, event: DownloadEvent) -> bool {
+        match event {
+            DownloadEvent::Finished => self.finished,
+            DownloadEvent::Stalled => self.stalled,
+            DownloadEvent::ExpiringSoon => self.expiring_soon,
+            DownloadEvent::Cached => self.cached,
+            DownloadEvent::Errored => self.errored,
+        }
+    }
+}
+
+/// The last poll of one API key's downloads.
+struct Snapshot {
+    download_types: HashSet<DownloadType>,
+    items: HashMap<ItemKey, ItemState>,
+}
+
+/// Polls the download lists of every API key that has event-triggered rules and
+/// diffs each poll against the previous one. A download whose state flips on
+/// (or that appears already in that state) emits an event to the matching rules.
+pub struct EventWatcher {
+    database: Arc<Database>,
+    engine: AutomationEngine,
+    limiter: Arc<ExecutionLimiter>,
+    poll_interval_secs: u64,
+    snapshots: Mutex<HashMap<String, Snapshot>>,
+}
+
+impl EventWatcher {
+    pub fn new(database: Arc<Database>, limiter: Arc<ExecutionLimiter>, poll_interval_secs: u64) -> Self {
+        Self {
+            database,
+            engine: AutomationEngine::new(),
+            limiter,
+            poll_interval_secs,
+            snapshots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn start(self: Arc<Self>) {
+        log!("Starting event watcher (polling every {}s)", self.poll_interval_secs);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(self.poll_interval_secs));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.poll().await {
+                    log!("Event watcher poll failed: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn poll(&self) -> Result<(), String> {
+        let mut rules_by_key: HashMap<String, Vec<AutomationRule>> = HashMap::new();
+        for rule in self.database.get_all_enabled_rules().await? {
+            if let TriggerConfig::OnEvent { .. } = rule.trigger_config {
+                rules_by_key.entry(rule.api_key_hash.clone()).or_default().push(rule);
+            }
+        }
+
+        self.snapshots.lock().await.retain(|api_key_hash, _| rules_by_key.contains_key(api_key_hash));
+
+        for (api_key_hash, rules) in &rules_by_key {
+            if let Err(e) = self.poll_key(api_key_hash, rules).await {
+                if e.is_transient() {
+                    log!("Event watcher skipped a poll due to a transient API error (will retry next poll)");
+                } else {
+                    log!("Event watcher failed to poll downloads: {}", e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn poll_key(&self, api_key_hash: &str, rules: &[AutomationRule]) -> Result<(), RunError> {
+        let api_key = self.database.get_api_key(api_key_hash).await?;
+        let client = TorboxClient::new(api_key.clone());
+
+        let mut download_types: Vec<DownloadType> = Vec::new();
+        for rule in rules {
+            for download_type in &rule.download_types {
+                if !download_types.contains(download_type) {
+                    download_types.push(*download_type);
+                }
+            }
+        }
+
+        let fetched = self.engine.fetch_items(&client, &download_types, "event watcher").await;
+        key_health::record_outcome(&self.database, api_key_hash, &fetched).await;
+        let items = fetched?;
+
+        let now = Utc::now().timestamp();
+        let current = Snapshot {
+            download_types: download_types.iter().copied().collect(),
+            items: items
+                .iter()
+                .map(|item| ((item.download_type(), item.id()), ItemState::of(item, now)))
+                .collect(),
+        };
+
+        let previous = self.snapshots.lock().await.insert(api_key_hash.to_string(), current);
+        let Some(previous) = previous else {
+            // The first poll only establishes a baseline.
+            return Ok(());
+        };
+
+        let mut events: HashMap<ItemKey, Vec<DownloadEvent>> = HashMap::new();
+        for item in &items {
+            // Types that weren't being watched last poll have no baseline yet.
+            if !previous.download_types.contains(&item.download_type()) {
+                continue;
+            }
+
+            let key = (item.download_type(), item.id());
+            let state = ItemState::of(item, now);
+            let before = previous.items.get(&key);
+            let emitted: Vec<DownloadEvent> = DownloadEvent::ALL
+                .iter()
+                .copied()
+                .filter(|event| state.has(*event) && !before.map(|b| b.has(*event)).unwrap_or(false))
+                .collect();
+
+            if !emitted.is_empty() {
+                events.insert(key, emitted);
+            }
+        }
+
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        for rule in rules {
+            let TriggerConfig::OnEvent { events: wanted } = &rule.trigger_config else {
+                continue;
+            };
+
+            let triggered: Vec<DownloadItem> = items
+                .iter()
+                .filter(|item| rule.download_types.contains(&item.download_type()))
+                .filter(|item| {
+                    events
+                        .get(&(item.download_type(), item.id()))
+                        .map(|emitted| emitted.iter().any(|event| wanted.contains(event)))
+                        .unwrap_or(false)
+                })
+                .cloned()
+                .collect();
+
+            if triggered.is_empty() {
+                continue;
+            }
+
+            log!("Rule '{}' triggered by events on {} downloads", rule.name, triggered.len());
+            if let Err(e) = self.run_rule(rule, &api_key, &triggered).await {
+                log!("Event-triggered execution failed for rule '{}': {}", rule.name, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn run_rule(&self, rule: &AutomationRule, api_key: &str, items: &[DownloadItem]) -> Result<(), RunError> {
+        // Events that fire while the window is closed are not replayed later.
+        if let Some(reason) = window::closed_reason(rule, Utc::now()) {
+            log!("Rule '{}' {}", rule.name, reason);
+            return Ok(self.database.log_execution(&ExecutionLog::without_result(rule, "event", reason)).await?);
+        }
+
+        let history = history::load(&self.database, rule).await?;
+        let run = self.engine.execute_rule_for_items(&self.database, rule, api_key, items, &history);
+        let result = match self.limiter.run(rule.id.unwrap_or(0), run).await {
+            Ok(result) => result?,
+            Err(rejection) => {
+                if rejection == RunRejection::AlreadyRunning {
+                    log!("Rule '{}' skipped: previous run is still in progress", rule.name);
+                }
+                return Ok(self.database.log_execution(&ExecutionLog::without_result(rule, "event", rejection.message())).await?);
+            }
+        };
+
+        if result.total_items == 0 {
+            return Ok(());
+        }
+
+        let log_entry = ExecutionLog {
+            id: None,
+            rule_id: rule.id.unwrap_or(0),
+            rule_name: rule.name.clone(),
+            api_key_hash: rule.api_key_hash.clone(),
+            execution_type: "event".to_string(),
+            items_processed: result.items_processed,
+            total_items: Some(result.total_items),
+            success: result.success,
+            error_message: result.error_message.clone(),
+            processed_items: result.processed_items.clone(),
+            executed_at: None,
+            partial: Some(result.partial),
+            rule_revision: rule.revision,
+        };
+
+        self.database.log_execution(&log_entry).await?;
+        Ok(self.database.save_transfers(&result.transfers).await?)
+    }
+}
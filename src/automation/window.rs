@@ -0,0 +1,90 @@
+use crate::automation::types::*;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use chrono_tz::Tz;
+
+pub const SKIPPED_OUTSIDE_WINDOW: &str = "skipped: outside schedule window";
+
+const MAX_TIME_RANGES: usize = 10;
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+pub fn validate(window: &ScheduleWindow) -> Result<(), String> {
+    parse_timezone(&window.timezone)?;
+
+    if window.days.is_empty() && window.ranges.is_empty() {
+        return Err("Schedule window needs at least one day or time range".to_string());
+    }
+
+    if window.ranges.len() > MAX_TIME_RANGES {
+        return Err(format!("Schedule window allows at most {} time ranges", MAX_TIME_RANGES));
+    }
+
+    for (idx, range) in window.ranges.iter().enumerate() {
+        let start = parse_time(&range.start).map_err(|e| format!("Time range {}: {}", idx + 1, e))?;
+        let end = parse_time(&range.end).map_err(|e| format!("Time range {}: {}", idx + 1, e))?;
+        if start == MINUTES_PER_DAY {
+            return Err(format!("Time range {}: start must be before 24:00", idx + 1));
+        }
+        if start == end {
+            return Err(format!("Time range {}: start and end are the same", idx + 1));
+        }
+    }
+
+    Ok(())
+}
+
+/// Why the rule may not run at `now`, or `None` if it has no window or the
+/// window is open.
+pub fn closed_reason(rule: &AutomationRule, now: DateTime<Utc>) -> Option<String> {
+    let window = rule.schedule_window.as_ref()?;
+    match is_open(window, now) {
+        Ok(true) => None,
+        Ok(false) => Some(SKIPPED_OUTSIDE_WINDOW.to_string()),
+        Err(e) => Some(format!("Invalid schedule window: {}", e)),
+    }
+}
+
+pub fn is_open(window: &ScheduleWindow, now: DateTime<Utc>) -> Result<bool, String> {
+    let local = now.with_timezone(&parse_timezone(&window.timezone)?);
+    let minute = local.hour() * 60 + local.minute();
+    let open_on = |day: chrono::Weekday| window.days.is_empty() || window.days.contains(&Weekday::from(day));
+
+    if window.ranges.is_empty() {
+        return Ok(open_on(local.weekday()));
+    }
+
+    for range in &window.ranges {
+        let start = parse_time(&range.start)?;
+        let end = parse_time(&range.end)?;
+        let open = if start < end {
+            open_on(local.weekday()) && minute >= start && minute < end
+        } else {
+            // The part after midnight belongs to the day the range started on.
+            (open_on(local.weekday()) && minute >= start) || (open_on(local.weekday().pred()) && minute < end)
+        };
+        if open {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+pub fn parse_timezone(timezone: &str) -> Result<Tz, String> {
+    timezone
+        .trim()
+        .parse()
+        .map_err(|_| format!("Unknown timezone: {}", timezone))
+}
+
+/// Minutes since midnight of an `HH:MM` time, allowing `24:00`.
+fn parse_time(time: &str) -> Result<u32, String> {
+    let invalid = || format!("Invalid time '{}', expected HH:MM", time);
+    let (hours, minutes) = time.trim().split_once(':').ok_or_else(invalid)?;
+    let hours: u32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: u32 = minutes.parse().map_err(|_| invalid())?;
+
+    if minutes >= 60 || hours > 24 || (hours == 24 && minutes > 0) {
+        return Err(invalid());
+    }
+    Ok(hours * 60 + minutes)
+}
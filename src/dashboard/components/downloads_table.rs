@@ -606,6 +606,35 @@ fn extract_season_number(name: &str) -> Option<i32> {
     None
 }
 
+/// Deletes a torrent through the companion server, which archives its magnet
+/// first so it can be re-added from "Recently Deleted".
+#[cfg(target_arch = "wasm32")]
+async fn archive_and_delete_torrent(api_key: &str, id: i32) -> Result<(), String> {
+    let window = web_sys::window().ok_or_else(|| "No window".to_string())?;
+    let headers = web_sys::Headers::new().map_err(|_| "Failed to build request".to_string())?;
+    headers.set("Authorization", &format!("Bearer {}", api_key)).map_err(|_| "Failed to build request".to_string())?;
+
+    let init = web_sys::RequestInit::new();
+    init.set_method("POST");
+    init.set_headers(&headers);
+
+    let url = format!("/api/automation/torrents/{}/delete", id);
+    let response = wasm_bindgen_futures::JsFuture::from(window.fetch_with_str_and_init(&url, &init)).await
+        .map_err(|_| "Network request failed".to_string())?;
+    let response: web_sys::Response = response.dyn_into().map_err(|_| "Invalid response".to_string())?;
+    let text = match response.text() {
+        Ok(promise) => wasm_bindgen_futures::JsFuture::from(promise).await.ok().and_then(|value| value.as_string()).unwrap_or_default(),
+        Err(_) => String::new(),
+    };
+
+    match serde_json::from_str::<serde_json::Value>(&text) {
+        Ok(body) if body["success"].as_bool() == Some(true) => Ok(()),
+        Ok(body) => Err(body["error"].as_str().unwrap_or("Delete failed").to_string()),
+        Err(_) if response.status() == 404 => Err("Torrent not found".to_string()),
+        Err(_) => Err(format!("Delete failed: {}", response.status())),
+    }
+}
+
 fn format_size(bytes: i64) -> String {
     if bytes < 0 {
         return "Unknown".to_string();
@@ -2175,7 +2204,11 @@ pub fn DownloadsTable(
             };
             
             let title = format!("Delete {}", download_type_name);
-            let message = format!("Are you sure you want to delete \"{}\"? This action cannot be undone.", download_name);
+            let message = if download_type == DownloadType::Torrent {
+                format!("Are you sure you want to delete \"{}\"? It can be re-added from Recently Deleted on the Automations tab.", download_name)
+            } else {
+                format!("Are you sure you want to delete \"{}\"? This action cannot be undone.", download_name)
+            };
             
             let action_loading_local = action_loading_clone.clone();
             let action_errors_local = action_errors_clone.clone();
@@ -2209,13 +2242,17 @@ pub fn DownloadsTable(
                                             
                                             let result = match download_type_clone {
                                                 DownloadType::Torrent => {
-                                                    client.control_torrent("delete".to_string(), id_clone, false).await
+                                                    archive_and_delete_torrent(&api_key, id_clone).await
                                                 }
                                                 DownloadType::WebDownload => {
                                                     client.control_web_download("delete".to_string(), id_clone, false).await
+                                                        .map(|_| ())
+                                                        .map_err(|e| format_api_error(&e))
                                                 }
                                                 DownloadType::Usenet => {
                                                     client.control_usenet_download("delete".to_string(), id_clone, false).await
+                                                        .map(|_| ())
+                                                        .map_err(|e| format_api_error(&e))
                                                 }
                                             };
                                             
@@ -2307,8 +2344,7 @@ pub fn DownloadsTable(
                                                         let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
                                                     }
                                                 }
-                                                Err(e) => {
-                                                    let error_msg = format_api_error(&e);
+                                                Err(error_msg) => {
                                                     let download_type_name = match download_type_clone {
                                                         DownloadType::Torrent => "torrent",
                                                         DownloadType::WebDownload => "web download",
@@ -2319,7 +2355,7 @@ pub fn DownloadsTable(
                                                     action_errors_local.update(|error_map| {
                                                         error_map.insert(id_clone, format!("Failed to delete: {}", error_msg));
                                                     });
-                                                    log!("Failed to delete download: {:?}", e);
+                                                    log!("Failed to delete download: {}", error_msg);
                                                     
                                                     action_loading_local.update(|loading_map| {
                                                         loading_map.remove(&id_clone);
@@ -2707,13 +2743,17 @@ pub fn DownloadsTable(
                                         async move {
                                             let result = match download_type {
                                                 DownloadType::Torrent => {
-                                                    client.control_torrent("delete".to_string(), *item_id, false).await
+                                                    archive_and_delete_torrent(&api_key, *item_id).await
                                                 }
                                                 DownloadType::WebDownload => {
                                                     client.control_web_download("delete".to_string(), *item_id, false).await
+                                                        .map(|_| ())
+                                                        .map_err(|e| format_api_error(&e))
                                                 }
                                                 DownloadType::Usenet => {
                                                     client.control_usenet_download("delete".to_string(), *item_id, false).await
+                                                        .map(|_| ())
+                                                        .map_err(|e| format_api_error(&e))
                                                 }
                                             };
                                             
@@ -2729,8 +2769,7 @@ pub fn DownloadsTable(
                                                     );
                                                     Ok((*item_id, download_type_clone))
                                                 }
-                                                Err(e) => {
-                                                    let error_msg = format_api_error(&e);
+                                                Err(error_msg) => {
                                                     log!("Failed to delete {} {}: {}", 
                                                         match download_type {
                                                             DownloadType::Torrent => "torrent",
@@ -4366,7 +4405,11 @@ pub fn DownloadsTable(
                                                                                         DownloadType::Usenet => "usenet download",
                                                                                     };
                                                                                     let title = format!("Delete {}", download_type_name);
-                                                                                    let message = format!("Are you sure you want to delete \"{}\"? This action cannot be undone.", download_name_val);
+                                                                                    let message = if delete_type_val == DownloadType::Torrent {
+                                                                                        format!("Are you sure you want to delete \"{}\"? It can be re-added from Recently Deleted on the Automations tab.", download_name_val)
+                                                                                    } else {
+                                                                                        format!("Are you sure you want to delete \"{}\"? This action cannot be undone.", download_name_val)
+                                                                                    };
                                                                                     let notifications_for_callback = notifications_clone_for_delete.clone();
                                                                                     show_confirmation(
                                                                                         confirmation_state_for_delete,
@@ -4394,13 +4437,17 @@ pub fn DownloadsTable(
                                                                                                                     
                                                                                                                     let result = match download_type {
                                                                                                                         DownloadType::Torrent => {
-                                                                                                                            client.control_torrent("delete".to_string(), id, false).await
+                                                                                                                            archive_and_delete_torrent(&api_key, id).await
                                                                                                                         }
                                                                                                                         DownloadType::WebDownload => {
                                                                                                                             client.control_web_download("delete".to_string(), id, false).await
+                                                                                                                                .map(|_| ())
+                                                                                                                                .map_err(|e| format_api_error(&e))
                                                                                                                         }
                                                                                                                         DownloadType::Usenet => {
                                                                                                                             client.control_usenet_download("delete".to_string(), id, false).await
+                                                                                                                                .map(|_| ())
+                                                                                                                                .map_err(|e| format_api_error(&e))
                                                                                                                         }
                                                                                                                     };
                                                                                                                     
@@ -4479,8 +4526,7 @@ pub fn DownloadsTable(
                                                                                                                                 let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
                                                                                                                             }
                                                                                                                         }
-                                                                                                                        Err(e) => {
-                                                                                                                            let error_msg = format_api_error(&e);
+                                                                                                                        Err(error_msg) => {
                                                                                                                             let download_type_name = match download_type {
                                                                                                                                 DownloadType::Torrent => "torrent",
                                                                                                                                 DownloadType::WebDownload => "web download",
@@ -4491,7 +4537,7 @@ pub fn DownloadsTable(
                                                                                                                             action_errors_local.update(|error_map| {
                                                                                                                                 error_map.insert(id, format!("Failed to delete: {}", error_msg));
                                                                                                                             });
-                                                                                                                            log!("Failed to delete download: {:?}", e);
+                                                                                                                            log!("Failed to delete download: {}", error_msg);
                                                                                                                             
                                                                                                                             action_loading_local.update(|loading_map| {
                                                                                                                                 loading_map.remove(&id);
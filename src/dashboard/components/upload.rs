@@ -238,15 +238,9 @@ pub fn UploadComponent() -> impl IntoView {
                                                 Err(e) => {
                                                     log!("Failed to upload torrent link: {:?}", e);
                                                     let error_msg = format!("{}", e);
-                                                    let status_code = match &e {
-                                                        crate::api::ApiError::HttpError { status_code, .. } => format!("Status: {}", status_code),
-                                                        crate::api::ApiError::RateLimitError => "Status: 429".to_string(),
-                                                        crate::api::ApiError::AuthenticationError => "Status: 401".to_string(),
-                                                        crate::api::ApiError::ValidationError => "Status: 400".to_string(),
-                                                        crate::api::ApiError::NotFoundError => "Status: 404".to_string(),
-                                                        crate::api::ApiError::ServerError => "Status: 500".to_string(),
-                                                        _ => "Unknown error".to_string(),
-                                                    };
+                                                    let status_code = e.status_code()
+                                                        .map(|status_code| format!("Status: {}", status_code))
+                                                        .unwrap_or_else(|| "Unknown error".to_string());
                                                     errors_signal.update(|errors| {
                                                         errors.push((link.clone(), format!("{} ({})", error_msg, status_code)));
                                                     });
@@ -274,15 +268,9 @@ pub fn UploadComponent() -> impl IntoView {
                                                 Err(e) => {
                                                     log!("Failed to upload NZB link: {:?}", e);
                                                     let error_msg = format!("{}", e);
-                                                    let status_code = match &e {
-                                                        crate::api::ApiError::HttpError { status_code, .. } => format!("Status: {}", status_code),
-                                                        crate::api::ApiError::RateLimitError => "Status: 429".to_string(),
-                                                        crate::api::ApiError::AuthenticationError => "Status: 401".to_string(),
-                                                        crate::api::ApiError::ValidationError => "Status: 400".to_string(),
-                                                        crate::api::ApiError::NotFoundError => "Status: 404".to_string(),
-                                                        crate::api::ApiError::ServerError => "Status: 500".to_string(),
-                                                        _ => "Unknown error".to_string(),
-                                                    };
+                                                    let status_code = e.status_code()
+                                                        .map(|status_code| format!("Status: {}", status_code))
+                                                        .unwrap_or_else(|| "Unknown error".to_string());
                                                     errors_signal.update(|errors| {
                                                         errors.push((link.clone(), format!("{} ({})", error_msg, status_code)));
                                                     });
@@ -308,15 +296,9 @@ pub fn UploadComponent() -> impl IntoView {
                                                 Err(e) => {
                                                     log!("Failed to upload web link: {:?}", e);
                                                     let error_msg = format!("{}", e);
-                                                    let status_code = match &e {
-                                                        crate::api::ApiError::HttpError { status_code, .. } => format!("Status: {}", status_code),
-                                                        crate::api::ApiError::RateLimitError => "Status: 429".to_string(),
-                                                        crate::api::ApiError::AuthenticationError => "Status: 401".to_string(),
-                                                        crate::api::ApiError::ValidationError => "Status: 400".to_string(),
-                                                        crate::api::ApiError::NotFoundError => "Status: 404".to_string(),
-                                                        crate::api::ApiError::ServerError => "Status: 500".to_string(),
-                                                        _ => "Unknown error".to_string(),
-                                                    };
+                                                    let status_code = e.status_code()
+                                                        .map(|status_code| format!("Status: {}", status_code))
+                                                        .unwrap_or_else(|| "Unknown error".to_string());
                                                     errors_signal.update(|errors| {
                                                         errors.push((link.clone(), format!("{} ({})", error_msg, status_code)));
                                                     });
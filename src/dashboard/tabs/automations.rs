@@ -18,9 +18,21 @@ pub struct AutomationRule {
     pub id: Option<i64>,
     pub name: String,
     pub enabled: bool,
+    #[serde(default)]
+    pub download_types: Vec<String>,
     pub trigger_config: serde_json::Value,
-    pub conditions: Vec<serde_json::Value>,
+    pub conditions: serde_json::Value,
     pub action_config: serde_json::Value,
+    #[serde(default)]
+    pub notification: Option<serde_json::Value>,
+    #[serde(default)]
+    pub schedule_window: Option<serde_json::Value>,
+    #[serde(default)]
+    pub quota: Option<serde_json::Value>,
+    #[serde(default)]
+    pub cooldown: Option<serde_json::Value>,
+    #[serde(default)]
+    pub revision: Option<i64>,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
 }
@@ -28,10 +40,24 @@ pub struct AutomationRule {
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ProcessedItem {
     pub id: i32,
+    #[serde(default)]
+    pub download_type: Option<String>,
     pub name: String,
     pub action: String,
     pub success: bool,
     pub error: Option<String>,
+    #[serde(default)]
+    pub steps: Vec<StepOutcome>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StepOutcome {
+    pub step: usize,
+    pub action: String,
+    pub success: bool,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub detail: Option<String>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -50,6 +76,115 @@ pub struct ExecutionLog {
     pub executed_at: Option<String>,
     #[serde(default)]
     pub partial: Option<bool>,
+    #[serde(default)]
+    pub rule_revision: Option<i64>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RuleRevision {
+    pub rule_id: i64,
+    pub revision: i64,
+    pub action: String,
+    pub client_ip: Option<String>,
+    pub created_at: Option<String>,
+    pub rule: serde_json::Value,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FieldChange {
+    pub path: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RevisionDiff {
+    pub rule_id: i64,
+    pub from: i64,
+    pub to: i64,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ArchivedFile {
+    pub name: String,
+    pub size: i64,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DeletedTorrent {
+    pub id: Option<i64>,
+    pub torrent_id: i32,
+    pub hash: String,
+    pub name: String,
+    pub size: i64,
+    pub files: Vec<ArchivedFile>,
+    pub rule_id: Option<i64>,
+    pub rule_name: Option<String>,
+    pub deleted_at: Option<String>,
+    pub readded_at: Option<String>,
+    pub readded_torrent_id: Option<i32>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LogPage {
+    pub logs: Vec<ExecutionLog>,
+    pub next_cursor: Option<i64>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LogExport {
+    pub filename: String,
+    pub content_type: String,
+    pub log_count: usize,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PreviewResult {
+    pub total_items: i32,
+    pub matched_count: i32,
+    pub truncated: bool,
+    pub matched_items: Vec<PreviewItem>,
+    #[serde(default)]
+    pub on_cooldown: i32,
+    #[serde(default)]
+    pub quota: Option<QuotaStatus>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct QuotaStatus {
+    pub total_size: i64,
+    pub total_count: usize,
+    pub projected_size: i64,
+    pub projected_count: usize,
+    pub matching: usize,
+    pub target_reached: bool,
+    pub capped: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NextRuns {
+    pub timezone: String,
+    pub runs: Vec<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PreviewItem {
+    pub id: i32,
+    pub download_type: String,
+    pub name: String,
+    pub size: i64,
+    pub download_state: String,
+    pub conditions: Vec<ConditionMeasurement>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ConditionMeasurement {
+    pub index: usize,
+    pub label: String,
+    pub value: serde_json::Value,
+    pub matched: bool,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -63,9 +198,14 @@ pub struct ApiResponse<T> {
 struct CreateRuleRequest {
     name: String,
     enabled: Option<bool>,
+    download_types: Vec<String>,
     trigger_config: serde_json::Value,
-    conditions: Vec<serde_json::Value>,
+    conditions: serde_json::Value,
     action_config: serde_json::Value,
+    notification: Option<serde_json::Value>,
+    schedule_window: Option<serde_json::Value>,
+    quota: Option<serde_json::Value>,
+    cooldown: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -74,584 +214,2585 @@ pub struct RuleLimitInfo {
     pub max_rules: i64,
 }
 
-#[component]
-pub fn AutomationsTab() -> impl IntoView {
-    let rules = RwSignal::new(Vec::<AutomationRule>::new());
-    let loading = RwSignal::new(false);
-    let error = RwSignal::new(None::<String>);
-    let show_create_modal = RwSignal::new(false);
-    let editing_rule_id = RwSignal::new(None::<i64>);
-    let preset_data = RwSignal::new(None::<(String, u32, String, String, f64, String)>);
-    let rule_logs = RwSignal::new(std::collections::HashMap::<i64, Vec<ExecutionLog>>::new());
-    let next_run_times = RwSignal::new(std::collections::HashMap::<i64, Option<String>>::new());
-    let confirmation_state = use_confirmation();
-    let rule_limit = RwSignal::new(None::<RuleLimitInfo>);
-    let selected_rules = RwSignal::new(std::collections::HashSet::<i64>::new());
-    let running_rules = RwSignal::new(std::collections::HashSet::<i64>::new());
-    let expanded_items = RwSignal::new(std::collections::HashSet::<i64>::new());
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RuleExport {
+    pub filename: String,
+    pub content_type: String,
+    pub rule_count: usize,
+    pub content: String,
+}
 
-    let fetch_rules = move || {
-        #[cfg(feature = "hydrate")]
-        {
-            loading.set(true);
-            error.set(None);
-            spawn_local(async move {
-                if let Some(window) = web_sys::window() {
-                    if let Ok(Some(storage)) = window.local_storage() {
-                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
-                            if !api_key.is_empty() {
-                                let url = "/api/automation/rules";
-                                let headers = web_sys::Headers::new().unwrap();
-                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
-                                
-                                let init = {
-                                    let mut i = web_sys::RequestInit::new();
-                                    i.set_method("GET");
-                                    i.set_headers(&headers);
-                                    i
-                                };
-                                
-                                let promise = window.fetch_with_str_and_init(url, &init);
-                                let future = wasm_bindgen_futures::JsFuture::from(promise);
-                                
-                                if let Ok(response) = future.await {
-                                    let resp: web_sys::Response = response.dyn_into().unwrap();
-                                    if resp.status() == 200 {
-                                        let text_promise = resp.text().unwrap();
-                                        let text_future = wasm_bindgen_futures::JsFuture::from(text_promise);
-                                        if let Ok(text_value) = text_future.await {
-                                            if let Some(text) = text_value.as_string() {
-                                                if let Ok(api_response) = serde_json::from_str::<ApiResponse<Vec<AutomationRule>>>(&text) {
-                                                    if let Some(data) = api_response.data {
-                                                        rules.set(data);
-                                                    } else if let Some(err) = api_response.error {
-                                                        error.set(Some(err));
-                                                    }
-                                                } else {
-                                                    error.set(Some("Failed to parse response".to_string()));
-                                                }
-                                            } else {
-                                                error.set(Some("Response is not a string".to_string()));
-                                            }
-                                        } else {
-                                            error.set(Some("Failed to read response text".to_string()));
-                                        }
-                                    } else {
-                                        error.set(Some(format!("Failed to fetch rules: {}", resp.status())));
-                                    }
-                                } else {
-                                    error.set(Some("Network request failed".to_string()));
-                                }
-                            } else {
-                                error.set(Some("No API key found".to_string()));
-                            }
-                        } else {
-                            error.set(Some("No API key found".to_string()));
-                        }
-                    }
-                }
-                loading.set(false);
-            });
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub replaced: usize,
+    pub skipped: usize,
+    pub results: Vec<ImportedRuleResult>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ImportedRuleResult {
+    pub index: usize,
+    pub name: String,
+    pub status: String,
+    pub rule_id: Option<i64>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RuleTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub download_types: Vec<String>,
+    pub parameters: Vec<TemplateParameter>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TemplateParameter {
+    pub key: String,
+    pub label: String,
+    pub unit: Option<String>,
+    pub default: f64,
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AutomationAnalytics {
+    pub days: u32,
+    pub total_runs: i64,
+    pub successful_runs: i64,
+    pub partial_runs: i64,
+    pub failed_runs: i64,
+    pub skipped_runs: i64,
+    pub success_rate: f64,
+    pub partial_rate: f64,
+    pub items_processed: i64,
+    pub reclaimed_bytes: i64,
+    pub runs_per_day: Vec<DailyRuns>,
+    pub actions: Vec<ActionStats>,
+    pub failing_items: Vec<FailingItem>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DailyRuns {
+    pub date: String,
+    pub runs: i64,
+    pub successful: i64,
+    pub partial: i64,
+    pub failed: i64,
+    pub items_processed: i64,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ActionStats {
+    pub action: String,
+    pub succeeded: i64,
+    pub failed: i64,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FailingItem {
+    pub id: i32,
+    pub download_type: Option<String>,
+    pub name: String,
+    pub failures: i64,
+    pub last_error: Option<String>,
+    pub last_failed_at: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ApiKeyStatus {
+    pub stored: bool,
+    pub auth_failures: i64,
+    pub last_auth_error: Option<String>,
+    pub suspended_at: Option<String>,
+    #[serde(default)]
+    pub other_keys: Vec<OtherApiKey>,
+    #[serde(default)]
+    pub migrated_rules: usize,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct OtherApiKey {
+    pub api_key_hash: String,
+    pub rule_count: i64,
+    pub suspended_at: Option<String>,
+}
+
+const ANALYTICS_RANGE_OPTIONS: [(u32, &str); 4] = [
+    (7, "Last 7 days"),
+    (30, "Last 30 days"),
+    (90, "Last 90 days"),
+    (365, "Last year"),
+];
+
+const LOG_STATUS_OPTIONS: [(&str, &str); 5] = [
+    ("", "Any status"),
+    ("success", "Successful"),
+    ("partial", "Partial"),
+    ("failed", "Failed"),
+    ("skipped", "Skipped"),
+];
+
+/// Filters of the execution log browser, as entered.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct LogFilterDraft {
+    rule_id: Option<i64>,
+    from: String,
+    to: String,
+    status: String,
+    item: String,
+}
+
+impl LogFilterDraft {
+    /// Query string parameters for `/api/automation/logs`, without the leading `?`.
+    #[cfg(feature = "hydrate")]
+    fn to_query(&self) -> String {
+        let mut params = Vec::new();
+        if let Some(rule_id) = self.rule_id {
+            params.push(format!("rule_id={}", rule_id));
         }
-    };
+        for (name, value) in [("from", &self.from), ("to", &self.to), ("status", &self.status), ("item", &self.item)] {
+            if !value.trim().is_empty() {
+                params.push(format!("{}={}", name, js_sys::encode_uri_component(value.trim())));
+            }
+        }
+        params.join("&")
+    }
+}
 
-    let fetch_rule_limit = move || {
-        #[cfg(feature = "hydrate")]
-        {
-            spawn_local(async move {
-                if let Some(window) = web_sys::window() {
-                    if let Ok(Some(storage)) = window.local_storage() {
-                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
-                            if !api_key.is_empty() {
-                                let url = "/api/automation/rules/limit";
-                                let headers = web_sys::Headers::new().unwrap();
-                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
-                                
-                                let init = {
-                                    let mut i = web_sys::RequestInit::new();
-                                    i.set_method("GET");
-                                    i.set_headers(&headers);
-                                    i
-                                };
-                                
-                                let promise = window.fetch_with_str_and_init(url, &init);
-                                let future = wasm_bindgen_futures::JsFuture::from(promise);
-                                
-                                if let Ok(response) = future.await {
-                                    let resp: web_sys::Response = response.dyn_into().unwrap();
-                                    if resp.status() == 200 {
-                                        let text_promise = resp.text().unwrap();
-                                        let text_future = wasm_bindgen_futures::JsFuture::from(text_promise);
-                                        if let Ok(text_value) = text_future.await {
-                                            if let Some(text) = text_value.as_string() {
-                                                if let Ok(api_response) = serde_json::from_str::<ApiResponse<RuleLimitInfo>>(&text) {
-                                                    if let Some(data) = api_response.data {
-                                                        rule_limit.set(Some(data));
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            });
+const IMPORT_CONFLICT_OPTIONS: [(&str, &str); 3] = [
+    ("skip", "Skip rules whose name already exists"),
+    ("rename", "Import them under a new name"),
+    ("replace", "Replace the existing rule"),
+];
+
+const DOWNLOAD_TYPE_OPTIONS: [(&str, &str); 3] = [
+    ("Torrent", "Torrents"),
+    ("WebDownload", "Web Downloads"),
+    ("Usenet", "Usenet"),
+];
+
+const DOWNLOAD_EVENT_OPTIONS: [(&str, &str); 5] = [
+    ("Finished", "Finished downloading"),
+    ("Stalled", "Became stalled"),
+    ("ExpiringSoon", "Expiring within 24 hours"),
+    ("Cached", "Became cached"),
+    ("Errored", "Errored"),
+];
+
+fn trigger_events(trigger_config: &serde_json::Value) -> Option<Vec<String>> {
+    trigger_config
+        .get("OnEvent")
+        .and_then(|v| v.get("events"))
+        .and_then(|v| v.as_array())
+        .map(|events| events.iter().filter_map(|e| e.as_str().map(|e| e.to_string())).collect())
+}
+
+fn format_trigger_events(events: &[String]) -> String {
+    let labels: Vec<&str> = DOWNLOAD_EVENT_OPTIONS
+        .iter()
+        .filter(|(value, _)| events.iter().any(|e| e == value))
+        .map(|(_, label)| *label)
+        .collect();
+    labels.join(" or ").to_lowercase()
+}
+
+const WEEKDAY_OPTIONS: [(&str, &str); 7] = [
+    ("Monday", "Mon"),
+    ("Tuesday", "Tue"),
+    ("Wednesday", "Wed"),
+    ("Thursday", "Thu"),
+    ("Friday", "Fri"),
+    ("Saturday", "Sat"),
+    ("Sunday", "Sun"),
+];
+
+/// Editable view of a rule's schedule window.
+#[derive(Debug, Clone, PartialEq)]
+struct ScheduleWindowDraft {
+    days: Vec<String>,
+    ranges: Vec<(String, String)>,
+    timezone: String,
+}
+
+impl ScheduleWindowDraft {
+    fn new() -> Self {
+        Self {
+            days: WEEKDAY_OPTIONS[..5].iter().map(|(value, _)| value.to_string()).collect(),
+            ranges: vec![("02:00".to_string(), "05:00".to_string())],
+            timezone: browser_timezone(),
         }
-    };
+    }
 
-    fetch_rules();
-    fetch_rule_limit();
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        let map = value.as_object()?;
+        Some(Self {
+            days: map.get("days")
+                .and_then(|v| v.as_array())
+                .map(|days| days.iter().filter_map(|d| d.as_str().map(|d| d.to_string())).collect())
+                .unwrap_or_default(),
+            ranges: map.get("ranges")
+                .and_then(|v| v.as_array())
+                .map(|ranges| ranges.iter().map(|range| {
+                    let time = |key: &str| range.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+                    (time("start"), time("end"))
+                }).collect())
+                .unwrap_or_default(),
+            timezone: map.get("timezone").and_then(|v| v.as_str()).unwrap_or("UTC").to_string(),
+        })
+    }
 
-    Effect::new(move |_| {
-        let rules_clone = rules.get();
-        for rule in rules_clone {
-            if let Some(id) = rule.id {
-                #[cfg(feature = "hydrate")]
-                {
-                    let rule_logs_clone = rule_logs.clone();
-                    spawn_local(async move {
-                        if let Some(window) = web_sys::window() {
-                            if let Ok(Some(storage)) = window.local_storage() {
-                                if let Ok(Some(api_key)) = storage.get_item("api_key") {
-                                    if !api_key.is_empty() {
-                                        let url = format!("/api/automation/rules/{}/logs?limit=10", id);
-                                        let headers = web_sys::Headers::new().unwrap();
-                                        headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
-                                        
-                                        let init = {
-                                            let mut i = web_sys::RequestInit::new();
-                                            i.set_method("GET");
-                                            i.set_headers(&headers);
-                                            i
-                                        };
-                                        
-                                        let promise = window.fetch_with_str_and_init(&url, &init);
-                                        let future = wasm_bindgen_futures::JsFuture::from(promise);
-                                        
-                                        if let Ok(response) = future.await {
-                                            let resp: web_sys::Response = response.dyn_into().unwrap();
-                                            if resp.status() == 200 {
-                                                let text_promise = resp.text().unwrap();
-                                                let text_future = wasm_bindgen_futures::JsFuture::from(text_promise);
-                                                if let Ok(text_value) = text_future.await {
-                                                    if let Some(text) = text_value.as_string() {
-                                                        if let Ok(api_response) = serde_json::from_str::<ApiResponse<Vec<ExecutionLog>>>(&text) {
-                                                            if let Some(logs) = api_response.data {
-                                                                rule_logs_clone.update(|map| {
-                                                                    map.insert(id, logs);
-                                                                });
-                                                            }
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    });
+    fn to_json(&self) -> serde_json::Value {
+        let days: Vec<&str> = WEEKDAY_OPTIONS
+            .iter()
+            .map(|(value, _)| *value)
+            .filter(|value| self.days.iter().any(|d| d == value))
+            .collect();
+        serde_json::json!({
+            "days": days,
+            "ranges": self.ranges.iter().map(|(start, end)| serde_json::json!({ "start": start, "end": end })).collect::<Vec<_>>(),
+            "timezone": self.timezone.trim(),
+        })
+    }
+
+    fn summary(&self) -> String {
+        let days: Vec<&str> = WEEKDAY_OPTIONS
+            .iter()
+            .filter(|(value, _)| self.days.iter().any(|d| d == value))
+            .map(|(_, label)| *label)
+            .collect();
+        let days = if days.is_empty() || days.len() == WEEKDAY_OPTIONS.len() { "every day".to_string() } else { days.join(", ") };
+        let times = if self.ranges.is_empty() {
+            "all day".to_string()
+        } else {
+            self.ranges.iter().map(|(start, end)| format!("{}–{}", start, end)).collect::<Vec<_>>().join(", ")
+        };
+        format!("Acts only {} {} ({})", days, times, self.timezone)
+    }
+}
+
+const CRON_MODE_OPTIONS: [(&str, &str); 5] = [
+    ("hourly", "Every hour"),
+    ("daily", "Every day"),
+    ("weekly", "On chosen weekdays"),
+    ("monthly", "Once a month"),
+    ("custom", "Custom expression"),
+];
+
+/// Editable view of a cron trigger. Expressions the builder can express are
+/// shown in its fields; anything else is kept as a custom expression.
+#[derive(Debug, Clone, PartialEq)]
+struct CronDraft {
+    mode: String,
+    minute: u32,
+    hour: u32,
+    days: Vec<String>,
+    day_of_month: u32,
+    expression: String,
+    timezone: String,
+}
+
+impl CronDraft {
+    fn new() -> Self {
+        Self {
+            mode: "daily".to_string(),
+            minute: 0,
+            hour: 3,
+            days: WEEKDAY_OPTIONS[..5].iter().map(|(value, _)| value.to_string()).collect(),
+            day_of_month: 1,
+            expression: "0 0 3 * * *".to_string(),
+            timezone: browser_timezone(),
+        }
+    }
+
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        let expression = value.get("expression")?.as_str()?.trim().to_string();
+        // Rules saved before timezones existed run in UTC.
+        let timezone = value.get("timezone").and_then(|v| v.as_str()).unwrap_or("UTC").to_string();
+        let mut draft = Self { mode: "custom".to_string(), expression: expression.clone(), timezone, ..Self::new() };
+
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let number = |field: &str, max: u32| field.parse::<u32>().ok().filter(|n| *n <= max);
+        let &[second, minute, hour, day_of_month, month, day_of_week] = fields.as_slice() else {
+            return Some(draft);
+        };
+        if second != "0" || month != "*" {
+            return Some(draft);
+        }
+        let Some(minute) = number(minute, 59) else {
+            return Some(draft);
+        };
+        draft.minute = minute;
+
+        if hour == "*" && day_of_month == "*" && day_of_week == "*" {
+            draft.mode = "hourly".to_string();
+            return Some(draft);
+        }
+        let Some(hour) = number(hour, 23) else {
+            return Some(draft);
+        };
+        draft.hour = hour;
+
+        match (day_of_month, day_of_week) {
+            ("*", "*") => draft.mode = "daily".to_string(),
+            ("*", days) => {
+                let days: Option<Vec<String>> = days
+                    .split(',')
+                    .map(|day| WEEKDAY_OPTIONS.iter().find(|(_, label)| label.eq_ignore_ascii_case(day)).map(|(value, _)| value.to_string()))
+                    .collect();
+                if let Some(days) = days {
+                    draft.days = days;
+                    draft.mode = "weekly".to_string();
+                }
+            }
+            (day, "*") => {
+                if let Some(day) = number(day, 28).filter(|day| *day >= 1) {
+                    draft.day_of_month = day;
+                    draft.mode = "monthly".to_string();
                 }
             }
+            _ => {}
         }
-    });
+        Some(draft)
+    }
 
-    let toggle_rule = move |rule_id: i64, current_enabled: bool, rule_clone: AutomationRule| {
-        #[cfg(feature = "hydrate")]
-        {
-            spawn_local(async move {
-                if let Some(window) = web_sys::window() {
-                    if let Ok(Some(storage)) = window.local_storage() {
-                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
-                            if !api_key.is_empty() {
-                                let url = format!("/api/automation/rules/{}", rule_id);
-                                let headers = web_sys::Headers::new().unwrap();
-                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
-                                headers.set("Content-Type", "application/json").unwrap();
+    /// The six-field expression the scheduler runs, in the rule's timezone.
+    fn to_expression(&self) -> String {
+        match self.mode.as_str() {
+            "hourly" => format!("0 {} * * * *", self.minute),
+            "daily" => format!("0 {} {} * * *", self.minute, self.hour),
+            "weekly" => {
+                let days: Vec<String> = WEEKDAY_OPTIONS
+                    .iter()
+                    .filter(|(value, _)| self.days.iter().any(|d| d == value))
+                    .map(|(_, label)| label.to_uppercase())
+                    .collect();
+                let days = if days.is_empty() { "*".to_string() } else { days.join(",") };
+                format!("0 {} {} * * {}", self.minute, self.hour, days)
+            }
+            "monthly" => format!("0 {} {} {} * *", self.minute, self.hour, self.day_of_month),
+            _ => self.expression.trim().to_string(),
+        }
+    }
 
-                                let request_body = serde_json::json!({
-                                    "name": rule_clone.name,
-                                    "enabled": !current_enabled,
-                                    "trigger_config": rule_clone.trigger_config,
-                                    "conditions": rule_clone.conditions,
-                                    "action_config": rule_clone.action_config
-                                });
-                                let body_str = serde_json::to_string(&request_body).unwrap();
-                                let body_js = wasm_bindgen::JsValue::from_str(&body_str);
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "expression": self.to_expression(),
+            "timezone": self.timezone.trim(),
+        })
+    }
 
-                                let init = {
-                                    let mut i = web_sys::RequestInit::new();
-                                    i.set_method("PUT");
-                                    i.set_headers(&headers);
-                                    i.set_body(&body_js);
-                                    i
-                                };
+    fn summary(&self) -> String {
+        let time = format!("{:02}:{:02}", self.hour, self.minute);
+        let when = match self.mode.as_str() {
+            "hourly" => format!("Runs every hour at :{:02}", self.minute),
+            "daily" => format!("Runs every day at {}", time),
+            "weekly" => {
+                let days: Vec<&str> = WEEKDAY_OPTIONS
+                    .iter()
+                    .filter(|(value, _)| self.days.iter().any(|d| d == value))
+                    .map(|(_, label)| *label)
+                    .collect();
+                let days = if days.is_empty() || days.len() == WEEKDAY_OPTIONS.len() { "every day".to_string() } else { days.join(", ") };
+                format!("Runs {} at {}", days, time)
+            }
+            "monthly" => format!("Runs on day {} of every month at {}", self.day_of_month, time),
+            _ => format!("Runs on cron '{}'", self.expression.trim()),
+        };
+        format!("{} ({})", when, self.timezone.trim())
+    }
+}
 
-                                let promise = window.fetch_with_str_and_init(&url, &init);
-                                let future = wasm_bindgen_futures::JsFuture::from(promise);
+const QUOTA_SORT_OPTIONS: [(&str, &str); 4] = [
+    ("Age", "Oldest first"),
+    ("Ratio", "Lowest ratio first"),
+    ("Size", "Largest first"),
+    ("LastActivity", "Least recently active first"),
+];
 
-                                if let Ok(response) = future.await {
-                                    let resp: web_sys::Response = response.dyn_into().unwrap();
-                                if resp.status() == 200 {
-                                    fetch_rules();
-                                    fetch_rule_limit();
-                                } else {
-                                        let text_promise = resp.text().unwrap();
-                                        let text_future = wasm_bindgen_futures::JsFuture::from(text_promise);
-                                        if let Ok(text_value) = text_future.await {
-                                            if let Some(text) = text_value.as_string() {
-                                                error.set(Some(format!("Failed to toggle rule: {}", text)));
-                                            } else {
-                                                error.set(Some(format!("Failed to toggle rule: {}", resp.status())));
-                                            }
-                                        } else {
-                                            error.set(Some(format!("Failed to toggle rule: {}", resp.status())));
-                                        }
-                                    }
-                                } else {
-                                    error.set(Some("Network request failed".to_string()));
-                                }
-                            }
-                        }
-                    }
-                }
-            });
-        }
-    };
+/// Editable view of a rule's storage quota.
+#[derive(Debug, Clone, PartialEq)]
+struct QuotaDraft {
+    sort_by: String,
+    /// `TotalSize` (GB) or `ItemCount`.
+    target_kind: String,
+    target: f64,
+    max_items_per_run: Option<u32>,
+}
 
-    let delete_rule = move |rule_id: i64| {
-        #[cfg(feature = "hydrate")]
-        {
-            spawn_local(async move {
-                if let Some(window) = web_sys::window() {
-                    if let Ok(Some(storage)) = window.local_storage() {
-                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
-                            if !api_key.is_empty() {
-                                let url = format!("/api/automation/rules/{}", rule_id);
-                                let headers = web_sys::Headers::new().unwrap();
-                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
-                                
-                                let init = {
-                                    let mut i = web_sys::RequestInit::new();
-                                    i.set_method("DELETE");
-                                    i.set_headers(&headers);
-                                    i
-                                };
-                                
-                                let promise = window.fetch_with_str_and_init(&url, &init);
-                                let future = wasm_bindgen_futures::JsFuture::from(promise);
-                                
-                                if let Ok(response) = future.await {
-                                    let resp: web_sys::Response = response.dyn_into().unwrap();
-                                    if resp.status() == 200 || resp.status() == 204 {
-                                        selected_rules.update(|set| {
-                                            set.remove(&rule_id);
-                                        });
-                                        fetch_rules();
-                                        fetch_rule_limit();
-                                    } else {
-                                        error.set(Some(format!("Failed to delete rule: {}", resp.status())));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            });
+impl QuotaDraft {
+    fn new() -> Self {
+        Self {
+            sort_by: "Age".to_string(),
+            target_kind: "TotalSize".to_string(),
+            target: 1500.0,
+            max_items_per_run: Some(20),
         }
-    };
+    }
 
-    let create_preset_rule = move |preset_name: String, trigger_minutes: u32, condition_type_val: String, condition_op: String, condition_val: f64, action_type_val: String| {
-        #[cfg(feature = "hydrate")]
-        {
-            loading.set(true);
-            error.set(None);
-            spawn_local(async move {
-                if let Some(window) = web_sys::window() {
-                    if let Ok(Some(storage)) = window.local_storage() {
-                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
-                            if !api_key.is_empty() {
-                                let trigger_config = serde_json::json!({
-                                    "Interval": {
-                                        "minutes": trigger_minutes
-                                    }
-                                });
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        let map = value.as_object()?;
+        let target = map.get("target")?.as_object()?;
+        let (target_kind, target) = if let Some(size) = target.get("TotalSize") {
+            ("TotalSize", size.get("gb").and_then(|v| v.as_f64()).unwrap_or_default())
+        } else {
+            ("ItemCount", target.get("ItemCount").and_then(|count| count.get("count")).and_then(|v| v.as_f64()).unwrap_or_default())
+        };
+        Some(Self {
+            sort_by: map.get("sort_by").and_then(|v| v.as_str()).unwrap_or("Age").to_string(),
+            target_kind: target_kind.to_string(),
+            target,
+            max_items_per_run: map.get("max_items_per_run").and_then(|v| v.as_u64()).map(|max| max as u32),
+        })
+    }
 
-                                let conditions = vec![serde_json::json!({
-                                    "type": condition_type_val,
-                                    "operator": condition_op,
-                                    "value": condition_val
-                                })];
+    fn to_json(&self) -> serde_json::Value {
+        let target = if self.target_kind == "ItemCount" {
+            serde_json::json!({ "ItemCount": { "count": self.target.max(0.0).round() as u64 } })
+        } else {
+            serde_json::json!({ "TotalSize": { "gb": self.target } })
+        };
+        serde_json::json!({
+            "sort_by": self.sort_by,
+            "target": target,
+            "max_items_per_run": self.max_items_per_run,
+        })
+    }
 
-                                let action_config = serde_json::json!({
-                                    "action_type": action_type_val,
-                                    "params": serde_json::Value::Null
-                                });
+    fn summary(&self) -> String {
+        let target = if self.target_kind == "ItemCount" {
+            format!("{} downloads", self.target.round())
+        } else {
+            format!("{} GB", self.target)
+        };
+        let order = QUOTA_SORT_OPTIONS
+            .iter()
+            .find(|(value, _)| *value == self.sort_by)
+            .map(|(_, label)| label.to_lowercase())
+            .unwrap_or_default();
+        let cap = self.max_items_per_run.map(|max| format!(", at most {} per run", max)).unwrap_or_default();
+        format!("Keeps the library under {}, {}{}", target, order, cap)
+    }
+}
 
-                                let request = CreateRuleRequest {
-                                    name: preset_name.clone(),
-                                    enabled: Some(true),
-                                    trigger_config,
-                                    conditions,
-                                    action_config,
-                                };
+/// Editable view of a rule's cooldown; empty fields leave that limit off.
+#[derive(Debug, Clone, PartialEq)]
+struct CooldownDraft {
+    hours: Option<f64>,
+    max_actions: Option<u32>,
+}
 
-                                let url = "/api/automation/rules";
-                                let headers = web_sys::Headers::new().unwrap();
-                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
-                                headers.set("Content-Type", "application/json").unwrap();
+impl CooldownDraft {
+    fn new() -> Self {
+        Self { hours: Some(6.0), max_actions: Some(3) }
+    }
 
-                                let body = serde_json::to_string(&request).unwrap();
-                                let body_js = wasm_bindgen::JsValue::from_str(&body);
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        let map = value.as_object()?;
+        Some(Self {
+            hours: map.get("hours").and_then(|v| v.as_f64()),
+            max_actions: map.get("max_actions").and_then(|v| v.as_u64()).map(|max| max as u32),
+        })
+    }
 
-                                let init = {
-                                    let mut i = web_sys::RequestInit::new();
-                                    i.set_method("POST");
-                                    i.set_headers(&headers);
-                                    i.set_body(&body_js);
-                                    i
-                                };
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "hours": self.hours,
+            "max_actions": self.max_actions,
+        })
+    }
 
-                                let promise = window.fetch_with_str_and_init(&url, &init);
-                                let future = wasm_bindgen_futures::JsFuture::from(promise);
+    fn summary(&self) -> String {
+        let limits: Vec<String> = [
+            self.hours.map(|hours| format!("again within {} hours", hours)),
+            self.max_actions.map(|max| format!("more than {} times", max)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if limits.is_empty() {
+            "No cooldown limits set".to_string()
+        } else {
+            format!("Never acts on the same download {}", limits.join(" or "))
+        }
+    }
+}
 
-                                if let Ok(response) = future.await {
-                                    let resp: web_sys::Response = response.dyn_into().unwrap();
-                                    if resp.status() == 200 || resp.status() == 201 {
-                                        fetch_rules();
-                                        fetch_rule_limit();
-                                    } else {
-                                        let text_promise = resp.text().unwrap();
-                                        let text_future = wasm_bindgen_futures::JsFuture::from(text_promise);
-                                        if let Ok(text_value) = text_future.await {
-                                            if let Some(text) = text_value.as_string() {
-                                                if let Ok(api_response) = serde_json::from_str::<ApiResponse<serde_json::Value>>(&text) {
-                                                    if let Some(err_msg) = api_response.error {
-                                                        error.set(Some(err_msg));
-                                                    } else {
-                                                        error.set(Some(format!("Failed to create rule: {}", text)));
-                                                    }
-                                                } else {
-                                                    error.set(Some(format!("Failed to create rule: {}", text)));
-                                                }
-                                            } else {
-                                                error.set(Some(format!("Failed to create rule: {}", resp.status())));
-                                            }
-                                        } else {
-                                            error.set(Some(format!("Failed to create rule: {}", resp.status())));
-                                        }
-                                        fetch_rule_limit();
-                                    }
-                                } else {
-                                    error.set(Some("Network request failed".to_string()));
-                                }
-                            }
-                        }
-                    }
-                }
-                loading.set(false);
-            });
+/// The IANA timezone the browser is set to, used as the default for new windows.
+fn browser_timezone() -> String {
+    #[cfg(feature = "hydrate")]
+    {
+        let options = js_sys::Intl::DateTimeFormat::new(&js_sys::Array::new(), &js_sys::Object::new()).resolved_options();
+        if let Some(timezone) = js_sys::Reflect::get(&options, &"timeZone".into()).ok().and_then(|v| v.as_string()) {
+            return timezone;
         }
-    };
+    }
+    "UTC".to_string()
+}
 
-    view! {
-            <div class="flex flex-col w-full mt-10 sm:mt-12">
-            <div class="mb-6">
-                <div class="flex flex-col sm:flex-row items-start sm:items-center gap-4 mb-6">
-                    <div class="flex flex-col sm:flex-row items-start sm:items-center gap-3 sm:ml-auto">
-                        <Show when=move || rule_limit.get().is_some()>
-                            <div class="text-sm" style="color: var(--text-secondary);">
-                                {move || {
-                                    if let Some(limit_info) = rule_limit.get() {
-                                        format!("Rules: {}/{}", limit_info.current_count, limit_info.max_rules)
-                                    } else {
-                                        "".to_string()
-                                    }
-                                }}
-                            </div>
-                        </Show>
-                        <button
-                            class="px-4 py-2 rounded-lg font-medium transition-colors whitespace-nowrap shrink-0 self-start sm:self-center disabled:opacity-50 disabled:cursor-not-allowed"
-                            style:background-color=move || {
-                                if let Some(limit_info) = rule_limit.get() {
-                                    if limit_info.current_count >= limit_info.max_rules {
-                                        "var(--bg-disabled, #6b7280)"
-                                    } else {
-                                        "var(--accent-primary)"
-                                    }
-                                } else {
-                                    "var(--accent-primary)"
+fn format_download_types(download_types: &[String]) -> String {
+    let labels: Vec<&str> = DOWNLOAD_TYPE_OPTIONS
+        .iter()
+        .filter(|(value, _)| download_types.iter().any(|t| t == value))
+        .map(|(_, label)| *label)
+        .collect();
+    if labels.is_empty() {
+        "Torrents".to_string()
+    } else {
+        labels.join(", ")
+    }
+}
+
+const CONDITION_TYPE_GROUPS: [(&str, &[(&str, &str)]); 7] = [
+    ("Time-Based", &[
+        ("SeedingTime", "Seeding Time (hours)"),
+        ("StalledTime", "Stalled Time (hours)"),
+        ("Age", "Age (hours)"),
+        ("ExpiresAt", "Expires At (hours remaining)"),
+        ("ETA", "ETA (hours)"),
+    ]),
+    ("Performance", &[
+        ("SeedingRatio", "Seeding Ratio"),
+        ("DownloadSpeed", "Download Speed (bytes/sec)"),
+        ("UploadSpeed", "Upload Speed (bytes/sec)"),
+        ("Progress", "Progress (%)"),
+        ("Availability", "Availability (0-1)"),
+    ]),
+    ("Network", &[
+        ("Seeds", "Seeds (count)"),
+        ("Peers", "Peers (count)"),
+    ]),
+    ("Size & Data", &[
+        ("FileSize", "File Size (GB)"),
+        ("TotalUploaded", "Total Uploaded (GB)"),
+        ("TotalDownloaded", "Total Downloaded (GB)"),
+    ]),
+    ("Status", &[
+        ("DownloadState", "Download State"),
+        ("Inactive", "Inactive"),
+        ("DownloadFinished", "Download Finished"),
+        ("Cached", "Cached"),
+        ("DownloadPresent", "Download Present"),
+    ]),
+    ("Properties", &[
+        ("Private", "Private"),
+        ("LongTermSeeding", "Long Term Seeding"),
+        ("SeedTorrent", "Seed Torrent"),
+        ("TorrentFile", "Has Torrent File"),
+        ("AllowZipped", "Allow Zipped"),
+        ("HasMagnet", "Has Magnet Link"),
+    ]),
+    ("History", &[
+        ("TimesRestarted", "Times Restarted"),
+        ("TimesReannounced", "Times Reannounced"),
+        ("TimesResumed", "Times Resumed"),
+        ("TimesActedOn", "Times Acted On"),
+        ("HoursSinceLastAction", "Hours Since Last Action"),
+    ]),
+];
+
+const TEXT_FIELD_OPTIONS: [(&str, &str); 5] = [
+    ("Name", "Name"),
+    ("Tracker", "Tracker"),
+    ("TrackerMessage", "Tracker Message"),
+    ("DownloadState", "Download State (text)"),
+    ("FileExtension", "File Extension"),
+];
+
+const TEXT_OPERATOR_OPTIONS: [(&str, &str); 4] = [
+    ("Equals", "Equals"),
+    ("Contains", "Contains"),
+    ("Matches", "Matches Regex"),
+    ("InList", "In List (comma separated)"),
+];
+
+const OPERATOR_OPTIONS: [(&str, &str, &str); 5] = [
+    ("GreaterThan", "Greater Than (>)", ">"),
+    ("LessThan", "Less Than (<)", "<"),
+    ("GreaterThanOrEqual", "Greater Than Or Equal (≥)", "≥"),
+    ("LessThanOrEqual", "Less Than Or Equal (≤)", "≤"),
+    ("Equal", "Equal (=)", "="),
+];
+
+fn condition_hint(condition_type: &str) -> Option<&'static str> {
+    match condition_type {
+        "SeedingTime" => Some("Hours since the torrent started seeding (from cached_at timestamp)"),
+        "SeedingRatio" => Some("Upload/download ratio (e.g., 1.0 = uploaded as much as downloaded)"),
+        "StalledTime" => Some("Hours since last update (indicates stalled torrents)"),
+        "Age" => Some("Hours since torrent was created"),
+        "Inactive" => Some("Torrent is not active or in error/failed state (use value 1.0 for Equal)"),
+        "DownloadFinished" => Some("Download is complete (use value 1.0 for Equal)"),
+        "Cached" => Some("Torrent is cached on server (use value 1.0 for Equal)"),
+        "DownloadState" => Some("State: 0=downloading, 1=uploading, 2=stopped, 3=cached (use Equal operator)"),
+        "Progress" => Some("Download progress percentage (0-100)"),
+        "FileSize" => Some("Torrent size in gigabytes"),
+        "ETA" => Some("Estimated time to completion in hours"),
+        "Availability" => Some("Torrent availability (0.0 to 1.0, where 1.0 = fully available)"),
+        "ExpiresAt" => Some("Hours until torrent expires (only for torrents with expiration)"),
+        "TimesRestarted" | "TimesReannounced" | "TimesResumed" => Some("How often any rule has done this to the download, counted as far back as execution logs are kept"),
+        "TimesActedOn" => Some("Successful actions of any kind any rule has taken on the download"),
+        "HoursSinceLastAction" => Some("Hours since any rule last acted on the download; downloads no rule has touched do not match"),
+        "Text:Name" => Some("Matches the download name. Text matching ignores case unless the pattern says otherwise."),
+        "Text:Tracker" => Some("Matches the tracker URL (torrents only)"),
+        "Text:TrackerMessage" => Some("Matches the tracker's status message, e.g. 'unregistered' (torrents only)"),
+        "Text:DownloadState" => Some("Matches the raw download state, e.g. 'stalled (no seeds)'"),
+        "Text:FileExtension" => Some("Matches if any file has this extension, without the dot (e.g. mkv)"),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ConditionDraftKind {
+    Leaf { condition_type: String, operator: String, value: f64 },
+    Text { field: String, operator: String, value: String },
+    Group { any: bool, children: Vec<ConditionDraft> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ConditionDraft {
+    negated: bool,
+    kind: ConditionDraftKind,
+}
+
+impl ConditionDraft {
+    fn leaf(condition_type: &str, operator: &str, value: f64) -> Self {
+        Self {
+            negated: false,
+            kind: ConditionDraftKind::Leaf {
+                condition_type: condition_type.to_string(),
+                operator: operator.to_string(),
+                value,
+            },
+        }
+    }
+
+    fn text(field: &str, operator: &str, value: &str) -> Self {
+        Self {
+            negated: false,
+            kind: ConditionDraftKind::Text {
+                field: field.to_string(),
+                operator: operator.to_string(),
+                value: value.to_string(),
+            },
+        }
+    }
+
+    fn group(any: bool, children: Vec<ConditionDraft>) -> Self {
+        Self { negated: false, kind: ConditionDraftKind::Group { any, children } }
+    }
+
+    fn root_with(leaf: ConditionDraft) -> Self {
+        Self::group(false, vec![leaf])
+    }
+
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        if let Some(items) = value.as_array() {
+            let children = items.iter().filter_map(Self::from_json).collect();
+            return Some(Self::group(false, children));
+        }
+
+        let obj = value.as_object()?;
+        if let Some(condition_type) = obj.get("type").and_then(|v| v.as_str()) {
+            let operator = obj.get("operator").and_then(|v| v.as_str()).unwrap_or("GreaterThan");
+            let value = obj.get("value").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            return Some(Self::leaf(condition_type, operator, value));
+        }
+        if let Some(condition) = obj.get("Condition") {
+            return Self::from_json(condition);
+        }
+        if let Some(text) = obj.get("Text").and_then(|v| v.as_object()) {
+            let field = text.get("field").and_then(|v| v.as_str()).unwrap_or("Name");
+            let operator = text.get("operator").and_then(|v| v.as_object())?;
+            let (op_name, op_value) = operator.iter().next()?;
+            let value = match op_value {
+                serde_json::Value::Array(values) => values
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                other => other.as_str().unwrap_or_default().to_string(),
+            };
+            return Some(Self::text(field, op_name, &value));
+        }
+        if let Some(children) = obj.get("All").and_then(|v| v.as_array()) {
+            return Some(Self::group(false, children.iter().filter_map(Self::from_json).collect()));
+        }
+        if let Some(children) = obj.get("Any").and_then(|v| v.as_array()) {
+            return Some(Self::group(true, children.iter().filter_map(Self::from_json).collect()));
+        }
+        if let Some(inner) = obj.get("Not") {
+            let mut draft = Self::from_json(inner)?;
+            draft.negated = !draft.negated;
+            return Some(draft);
+        }
+        None
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let node = match &self.kind {
+            ConditionDraftKind::Leaf { condition_type, operator, value } => serde_json::json!({
+                "Condition": {
+                    "type": condition_type,
+                    "operator": operator,
+                    "value": value
+                }
+            }),
+            ConditionDraftKind::Text { field, operator, value } => {
+                let operator_value = if operator == "InList" {
+                    serde_json::json!(value
+                        .split(',')
+                        .map(|v| v.trim())
+                        .filter(|v| !v.is_empty())
+                        .collect::<Vec<_>>())
+                } else {
+                    serde_json::json!(value)
+                };
+                serde_json::json!({
+                    "Text": {
+                        "field": field,
+                        "operator": { operator.as_str(): operator_value }
+                    }
+                })
+            }
+            ConditionDraftKind::Group { any, children } => {
+                let children: Vec<serde_json::Value> = children.iter().map(|c| c.to_json()).collect();
+                if *any {
+                    serde_json::json!({ "Any": children })
+                } else {
+                    serde_json::json!({ "All": children })
+                }
+            }
+        };
+        if self.negated {
+            serde_json::json!({ "Not": node })
+        } else {
+            node
+        }
+    }
+
+    fn summary(&self, nested: bool) -> String {
+        let body = match &self.kind {
+            ConditionDraftKind::Leaf { condition_type, operator, value } => {
+                let symbol = OPERATOR_OPTIONS
+                    .iter()
+                    .find(|(v, _, _)| v == operator)
+                    .map(|(_, _, symbol)| *symbol)
+                    .unwrap_or("?");
+                format!("{} {} {}", condition_type, symbol, value)
+            }
+            ConditionDraftKind::Text { field, operator, value } => {
+                let verb = match operator.as_str() {
+                    "Equals" => "is",
+                    "Contains" => "contains",
+                    "Matches" => "matches",
+                    "InList" => "in",
+                    _ => "?",
+                };
+                format!("{} {} \"{}\"", field, verb, value)
+            }
+            ConditionDraftKind::Group { any, children } => {
+                let joined = children
+                    .iter()
+                    .map(|c| c.summary(true))
+                    .collect::<Vec<_>>()
+                    .join(if *any { " OR " } else { " AND " });
+                if nested && children.len() > 1 {
+                    format!("({})", joined)
+                } else {
+                    joined
+                }
+            }
+        };
+        if self.negated {
+            format!("NOT {}", body)
+        } else {
+            body
+        }
+    }
+
+    fn node_at_mut(&mut self, path: &[usize]) -> Option<&mut ConditionDraft> {
+        match path.split_first() {
+            None => Some(self),
+            Some((idx, rest)) => match &mut self.kind {
+                ConditionDraftKind::Group { children, .. } => children.get_mut(*idx)?.node_at_mut(rest),
+                _ => None,
+            },
+        }
+    }
+
+    fn remove_at(&mut self, path: &[usize]) {
+        if let Some((idx, parent_path)) = path.split_last() {
+            if let Some(parent) = self.node_at_mut(parent_path) {
+                if let ConditionDraftKind::Group { children, .. } = &mut parent.kind {
+                    if *idx < children.len() {
+                        children.remove(*idx);
+                    }
+                }
+            }
+        }
+    }
+}
+
+const ACTION_TYPE_GROUPS: [(&str, &[(&str, &str)]); 5] = [
+    ("Control", &[
+        ("StopSeeding", "Stop Seeding"),
+        ("Stop", "Stop"),
+        ("Resume", "Resume"),
+        ("Restart", "Restart"),
+        ("ForceStart", "Force Start"),
+        ("Reannounce", "Reannounce"),
+    ]),
+    ("Export", &[
+        ("ExportMagnet", "Export Magnet"),
+    ]),
+    ("Notify", &[
+        ("Notify", "Send Notification"),
+    ]),
+    ("Transfer", &[
+        ("CloudUpload", "Upload to Cloud"),
+    ]),
+    ("Remove", &[
+        ("Delete", "Delete"),
+    ]),
+];
+
+const FAILURE_POLICY_OPTIONS: [(&str, &str); 3] = [
+    ("SkipRemaining", "On failure: skip remaining steps"),
+    ("Continue", "On failure: continue"),
+    ("AbortRun", "On failure: abort run"),
+];
+
+fn action_hint(action_type: &str) -> Option<&'static str> {
+    match action_type {
+        "StopSeeding" => Some("Stops seeding for completed/seeding torrents. Use for torrents that have finished downloading."),
+        "Stop" => Some("Stops active torrents (downloads or uploads). Use for torrents currently downloading or uploading."),
+        "Resume" => Some("Resumes a stopped torrent. Use to restart paused or stopped downloads."),
+        "Restart" => Some("Restarts a torrent from the beginning. Use to reset and restart downloads."),
+        "ForceStart" => Some("Forces a torrent to start immediately. Use to start queued or stopped torrents."),
+        "Reannounce" => Some("Reannounces to tracker. Use for stalled torrents to refresh tracker connection."),
+        "ExportMagnet" => Some("Exports the torrent's magnet link and records it in the execution log (torrents only)."),
+        "Notify" => Some("Sends a notification naming the download to a webhook, Discord, Slack, ntfy or Gotify endpoint."),
+        "CloudUpload" => Some("Sends finished downloads (or matching files) to a cloud provider linked in TorBox. Follow-up actions run only after every transfer completes; must be the last step."),
+        "Delete" => Some("Permanently deletes the torrent and its files. This action cannot be undone."),
+        _ => None,
+    }
+}
+
+const NOTIFICATION_FORMAT_OPTIONS: [(&str, &str); 5] = [
+    ("Generic", "Generic webhook (JSON)"),
+    ("Discord", "Discord"),
+    ("Slack", "Slack"),
+    ("Ntfy", "ntfy"),
+    ("Gotify", "Gotify"),
+];
+
+const NOTIFY_ON_OPTIONS: [(&str, &str); 3] = [
+    ("AfterRun", "After every run"),
+    ("FailuresOnly", "Only when a run fails"),
+    ("ItemsProcessed", "Only when downloads were processed"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NotificationField {
+    Url,
+    Format,
+    NotifyOn,
+}
+
+/// Editable view of a notification config. Keys the form doesn't expose
+/// (headers, payload templates) are kept in `extra` so saving doesn't drop them.
+#[derive(Debug, Clone, PartialEq)]
+struct NotificationDraft {
+    url: String,
+    format: String,
+    notify_on: String,
+    extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl NotificationDraft {
+    fn new() -> Self {
+        Self {
+            url: String::new(),
+            format: "Generic".to_string(),
+            notify_on: "AfterRun".to_string(),
+            extra: serde_json::Map::new(),
+        }
+    }
+
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        let map = value.as_object()?;
+        let text = |key: &str, default: &str| map.get(key).and_then(|v| v.as_str()).unwrap_or(default).to_string();
+        Some(Self {
+            url: text("url", ""),
+            format: text("format", "Generic"),
+            notify_on: text("notify_on", "AfterRun"),
+            extra: map.clone(),
+        })
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let mut map = self.extra.clone();
+        map.insert("url".to_string(), serde_json::json!(self.url.trim()));
+        map.insert("format".to_string(), serde_json::json!(self.format));
+        map.insert("notify_on".to_string(), serde_json::json!(self.notify_on));
+        serde_json::Value::Object(map)
+    }
+
+    fn set(&mut self, field: NotificationField, value: String) {
+        match field {
+            NotificationField::Url => self.url = value,
+            NotificationField::Format => self.format = value,
+            NotificationField::NotifyOn => self.notify_on = value,
+        }
+    }
+}
+
+const CLOUD_PROVIDER_OPTIONS: [(&str, &str); 3] = [
+    ("GoogleDrive", "Google Drive"),
+    ("Dropbox", "Dropbox"),
+    ("OneDrive", "OneDrive"),
+];
+
+const CLOUD_FOLLOW_UP_OPTIONS: [(&str, &str); 3] = [
+    ("StopSeeding", "Stop Seeding"),
+    ("Stop", "Stop"),
+    ("Delete", "Delete"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CloudUploadField {
+    Provider,
+    FilePatterns,
+    Zip,
+    FollowUp(&'static str),
+}
+
+/// Editable view of a cloud upload step's params. Keys the form doesn't expose
+/// are kept in `extra` so saving doesn't drop them.
+#[derive(Debug, Clone, PartialEq)]
+struct CloudUploadDraft {
+    provider: String,
+    file_patterns: String,
+    zip: bool,
+    on_success: Vec<String>,
+    extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl CloudUploadDraft {
+    fn new() -> Self {
+        Self {
+            provider: "GoogleDrive".to_string(),
+            file_patterns: String::new(),
+            zip: false,
+            on_success: Vec::new(),
+            extra: serde_json::Map::new(),
+        }
+    }
+
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        let map = value.as_object()?;
+        let strings = |key: &str| -> Vec<String> {
+            map.get(key)
+                .and_then(|v| v.as_array())
+                .map(|values| values.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+                .unwrap_or_default()
+        };
+        Some(Self {
+            provider: map.get("provider").and_then(|v| v.as_str()).unwrap_or("GoogleDrive").to_string(),
+            file_patterns: strings("file_patterns").join(", "),
+            zip: map.get("zip").and_then(|v| v.as_bool()).unwrap_or(false),
+            on_success: strings("on_success"),
+            extra: map.clone(),
+        })
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let patterns: Vec<&str> = self.file_patterns
+            .split(',')
+            .map(|pattern| pattern.trim())
+            .filter(|pattern| !pattern.is_empty())
+            .collect();
+        let mut map = self.extra.clone();
+        map.insert("provider".to_string(), serde_json::json!(self.provider));
+        map.insert("file_patterns".to_string(), serde_json::json!(patterns));
+        map.insert("zip".to_string(), serde_json::json!(self.zip && patterns.is_empty()));
+        map.insert("on_success".to_string(), serde_json::json!(self.on_success));
+        serde_json::Value::Object(map)
+    }
+
+    fn set(&mut self, field: CloudUploadField, value: String) {
+        match field {
+            CloudUploadField::Provider => self.provider = value,
+            CloudUploadField::FilePatterns => self.file_patterns = value,
+            CloudUploadField::Zip => self.zip = value == "true",
+            CloudUploadField::FollowUp(action) => {
+                self.on_success.retain(|existing| existing != action);
+                if value == "true" {
+                    self.on_success.push(action.to_string());
+                }
+            }
+        }
+    }
+}
+
+fn cloud_upload_inputs(
+    draft: CloudUploadDraft,
+    update: impl Fn(CloudUploadField, String) + Copy + Send + Sync + 'static,
+    saving: RwSignal<bool>,
+) -> impl IntoView {
+    let selected_provider = draft.provider.clone();
+    let on_success = draft.on_success.clone();
+    view! {
+        <div class="space-y-2">
+            <div class="flex flex-wrap items-center gap-2">
+                <select
+                    class="px-3 py-2 rounded-lg border text-sm"
+                    style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                    on:change=move |ev| update(CloudUploadField::Provider, event_target_value(&ev))
+                    disabled=move || saving.get()
+                >
+                    {CLOUD_PROVIDER_OPTIONS.iter().map(|(option_value, option_label)| {
+                        view! {
+                            <option value=*option_value selected=selected_provider == *option_value>{*option_label}</option>
+                        }
+                    }).collect::<Vec<_>>()}
+                </select>
+                <input
+                    type="text"
+                    class="flex-1 min-w-[14rem] px-3 py-2 rounded-lg border text-sm"
+                    style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                    placeholder="File patterns, e.g. *.mkv, *.srt (empty = whole download)"
+                    prop:value=draft.file_patterns.clone()
+                    on:change=move |ev| update(CloudUploadField::FilePatterns, event_target_value(&ev))
+                    disabled=move || saving.get()
+                />
+                <label class="flex items-center gap-1.5 text-xs" style="color: var(--text-secondary);">
+                    <input
+                        type="checkbox"
+                        style="accent-color: var(--accent-primary);"
+                        prop:checked=draft.zip
+                        on:change=move |ev| update(CloudUploadField::Zip, event_target_checked(&ev).to_string())
+                        disabled=move || saving.get()
+                    />
+                    "Zip"
+                </label>
+            </div>
+            <div class="flex flex-wrap items-center gap-3 text-xs" style="color: var(--text-secondary);">
+                <span>"After upload:"</span>
+                {CLOUD_FOLLOW_UP_OPTIONS.iter().map(|(option_value, option_label)| {
+                    let action = *option_value;
+                    let checked = on_success.iter().any(|existing| existing == action);
+                    view! {
+                        <label class="flex items-center gap-1.5">
+                            <input
+                                type="checkbox"
+                                style="accent-color: var(--accent-primary);"
+                                prop:checked=checked
+                                on:change=move |ev| update(CloudUploadField::FollowUp(action), event_target_checked(&ev).to_string())
+                                disabled=move || saving.get()
+                            />
+                            {*option_label}
+                        </label>
+                    }
+                }).collect::<Vec<_>>()}
+            </div>
+        </div>
+    }
+}
+
+fn notification_inputs(
+    draft: NotificationDraft,
+    show_notify_on: bool,
+    update: impl Fn(NotificationField, String) + Copy + Send + Sync + 'static,
+    saving: RwSignal<bool>,
+) -> impl IntoView {
+    let selected_format = draft.format.clone();
+    let selected_notify_on = draft.notify_on.clone();
+    view! {
+        <div class="flex flex-wrap items-center gap-2">
+            <select
+                class="px-3 py-2 rounded-lg border text-sm"
+                style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                on:change=move |ev| update(NotificationField::Format, event_target_value(&ev))
+                disabled=move || saving.get()
+            >
+                {NOTIFICATION_FORMAT_OPTIONS.iter().map(|(option_value, option_label)| {
+                    view! {
+                        <option value=*option_value selected=selected_format == *option_value>{*option_label}</option>
+                    }
+                }).collect::<Vec<_>>()}
+            </select>
+            <input
+                type="url"
+                class="flex-1 min-w-[14rem] px-3 py-2 rounded-lg border text-sm"
+                style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                placeholder="https://example.com/webhook"
+                prop:value=draft.url.clone()
+                on:change=move |ev| update(NotificationField::Url, event_target_value(&ev))
+                disabled=move || saving.get()
+            />
+            {show_notify_on.then(|| view! {
+                <select
+                    class="px-3 py-2 rounded-lg border text-sm"
+                    style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                    on:change=move |ev| update(NotificationField::NotifyOn, event_target_value(&ev))
+                    disabled=move || saving.get()
+                >
+                    {NOTIFY_ON_OPTIONS.iter().map(|(option_value, option_label)| {
+                        view! {
+                            <option value=*option_value selected=selected_notify_on == *option_value>{*option_label}</option>
+                        }
+                    }).collect::<Vec<_>>()}
+                </select>
+            })}
+        </div>
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ActionStepDraft {
+    action_type: String,
+    on_failure: String,
+    notification: NotificationDraft,
+    cloud_upload: CloudUploadDraft,
+}
+
+impl ActionStepDraft {
+    fn new(action_type: &str) -> Self {
+        Self {
+            action_type: action_type.to_string(),
+            on_failure: "SkipRemaining".to_string(),
+            notification: NotificationDraft::new(),
+            cloud_upload: CloudUploadDraft::new(),
+        }
+    }
+
+    fn list_from_json(value: &serde_json::Value) -> Vec<Self> {
+        let step_from = |step: &serde_json::Value| {
+            step.get("action_type").and_then(|v| v.as_str()).map(|action_type| Self {
+                action_type: action_type.to_string(),
+                on_failure: step
+                    .get("on_failure")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("SkipRemaining")
+                    .to_string(),
+                notification: step
+                    .get("params")
+                    .and_then(NotificationDraft::from_json)
+                    .unwrap_or_else(NotificationDraft::new),
+                cloud_upload: step
+                    .get("params")
+                    .and_then(CloudUploadDraft::from_json)
+                    .unwrap_or_else(CloudUploadDraft::new),
+            })
+        };
+        match value.get("steps").and_then(|v| v.as_array()) {
+            Some(steps) => steps.iter().filter_map(step_from).collect(),
+            None => step_from(value).into_iter().collect(),
+        }
+    }
+
+    fn list_to_json(steps: &[Self]) -> serde_json::Value {
+        serde_json::json!({
+            "steps": steps.iter().map(|step| serde_json::json!({
+                "action_type": step.action_type,
+                "params": match step.action_type.as_str() {
+                    "Notify" => step.notification.to_json(),
+                    "CloudUpload" => step.cloud_upload.to_json(),
+                    _ => serde_json::Value::Null,
+                },
+                "on_failure": step.on_failure
+            })).collect::<Vec<_>>()
+        })
+    }
+}
+
+fn render_action_steps(steps: RwSignal<Vec<ActionStepDraft>>, saving: RwSignal<bool>) -> impl IntoView {
+    let step_list = steps.get();
+    let step_count = step_list.len();
+    let step_views = step_list.into_iter().enumerate().map(|(idx, step)| {
+        let hint = action_hint(&step.action_type);
+        let selected_action = step.action_type.clone();
+        let selected_policy = step.on_failure.clone();
+        let notification = (step.action_type == "Notify").then(|| step.notification.clone());
+        let cloud_upload = (step.action_type == "CloudUpload").then(|| step.cloud_upload.clone());
+        view! {
+            <div class="p-3 rounded-lg border space-y-2" style="background-color: var(--bg-tertiary); border-color: var(--border-secondary);">
+                <div class="flex flex-wrap items-center gap-2">
+                    <span class="text-xs font-semibold" style="color: var(--text-secondary);">{format!("Step {}", idx + 1)}</span>
+                    <select
+                        class="flex-1 min-w-[10rem] px-3 py-2 rounded-lg border text-sm"
+                        style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                        on:change=move |ev| {
+                            let value = event_target_value(&ev);
+                            steps.update(|list| {
+                                if let Some(step) = list.get_mut(idx) {
+                                    step.action_type = value;
                                 }
+                            });
+                        }
+                        disabled=move || saving.get()
+                    >
+                        {ACTION_TYPE_GROUPS.iter().map(|(group_label, options)| {
+                            let selected_action = selected_action.clone();
+                            view! {
+                                <optgroup label=*group_label>
+                                    {options.iter().map(|(option_value, option_label)| {
+                                        view! {
+                                            <option value=*option_value selected=selected_action == *option_value>{*option_label}</option>
+                                        }
+                                    }).collect::<Vec<_>>()}
+                                </optgroup>
                             }
-                            style:color="var(--text-on-accent)"
-                            style:width="fit-content"
-                            style:max-width="none"
-                            style:flex="0 0 auto"
-                            disabled=move || {
-                                if let Some(limit_info) = rule_limit.get() {
-                                    limit_info.current_count >= limit_info.max_rules
-                                } else {
-                                    false
+                        }).collect::<Vec<_>>()}
+                    </select>
+                    <select
+                        class="px-3 py-2 rounded-lg border text-sm"
+                        style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                        on:change=move |ev| {
+                            let value = event_target_value(&ev);
+                            steps.update(|list| {
+                                if let Some(step) = list.get_mut(idx) {
+                                    step.on_failure = value;
                                 }
+                            });
+                        }
+                        disabled=move || saving.get()
+                    >
+                        {FAILURE_POLICY_OPTIONS.iter().map(|(option_value, option_label)| {
+                            view! {
+                                <option value=*option_value selected=selected_policy == *option_value>{*option_label}</option>
                             }
-                            on:click=move |_| {
-                                if let Some(limit_info) = rule_limit.get() {
-                                    if limit_info.current_count < limit_info.max_rules {
-                                        editing_rule_id.set(None);
-                                        show_create_modal.set(true);
+                        }).collect::<Vec<_>>()}
+                    </select>
+                    <button
+                        class="px-2 py-1 rounded text-xs transition-all"
+                        style="background-color: var(--bg-secondary); color: var(--text-primary); border: 1px solid var(--border-secondary);"
+                        on:click=move |_| steps.update(|list| if idx > 0 { list.swap(idx, idx - 1) })
+                        disabled=move || saving.get() || idx == 0
+                    >
+                        "↑"
+                    </button>
+                    <button
+                        class="px-2 py-1 rounded text-xs transition-all"
+                        style="background-color: var(--bg-secondary); color: var(--text-primary); border: 1px solid var(--border-secondary);"
+                        on:click=move |_| steps.update(|list| if idx + 1 < list.len() { list.swap(idx, idx + 1) })
+                        disabled=move || saving.get() || idx + 1 == step_count
+                    >
+                        "↓"
+                    </button>
+                    <button
+                        class="px-2 py-1 rounded text-xs transition-all"
+                        style="background-color: var(--bg-secondary); color: var(--text-error); border: 1px solid var(--border-secondary);"
+                        on:click=move |_| steps.update(|list| {
+                            if list.len() > 1 && idx < list.len() {
+                                list.remove(idx);
+                            }
+                        })
+                        disabled=move || saving.get() || step_count <= 1
+                    >
+                        "Remove"
+                    </button>
+                </div>
+                {notification.map(|draft| notification_inputs(
+                    draft,
+                    false,
+                    move |field, value| steps.update(|list| {
+                        if let Some(step) = list.get_mut(idx) {
+                            step.notification.set(field, value);
+                        }
+                    }),
+                    saving,
+                ))}
+                {cloud_upload.map(|draft| cloud_upload_inputs(
+                    draft,
+                    move |field, value| steps.update(|list| {
+                        if let Some(step) = list.get_mut(idx) {
+                            step.cloud_upload.set(field, value);
+                        }
+                    }),
+                    saving,
+                ))}
+                {hint.map(|hint| view! {
+                    <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">{hint}</p>
+                })}
+            </div>
+        }
+    }).collect::<Vec<_>>();
+
+    view! {
+        <div class="space-y-3">
+            {step_views}
+            <button
+                class="px-3 py-1.5 rounded-lg text-xs font-semibold transition-all"
+                style="background-color: var(--bg-tertiary); color: var(--text-primary); border: 1px solid var(--border-secondary);"
+                on:click=move |_| steps.update(|list| list.push(ActionStepDraft::new("Delete")))
+                disabled=move || { saving.get() || step_count >= 10 }
+            >
+                "+ Add Step"
+            </button>
+        </div>
+    }
+}
+
+/// Hands `content` to the browser as a file download.
+#[cfg(feature = "hydrate")]
+fn download_file(window: &web_sys::Window, filename: &str, content_type: &str, content: &str) {
+    let href = format!("data:{};charset=utf-8,{}", content_type, js_sys::encode_uri_component(content));
+    if let Some(document) = window.document() {
+        if let Ok(anchor) = document.create_element("a") {
+            if let Ok(anchor) = anchor.dyn_into::<web_sys::HtmlAnchorElement>() {
+                anchor.set_href(&href);
+                anchor.set_attribute("download", filename).ok();
+                anchor.set_attribute("style", "display: none;").ok();
+                if let Some(body) = document.body() {
+                    body.append_child(&anchor).ok();
+                    anchor.click();
+                    body.remove_child(&anchor).ok();
+                }
+            }
+        }
+    }
+}
+
+fn format_bytes(bytes: i64) -> String {
+    if bytes >= 1_099_511_627_776 {
+        format!("{:.2} TB", bytes as f64 / 1_099_511_627_776.0)
+    } else if bytes >= 1_073_741_824 {
+        format!("{:.2} GB", bytes as f64 / 1_073_741_824.0)
+    } else if bytes >= 1_048_576 {
+        format!("{:.2} MB", bytes as f64 / 1_048_576.0)
+    } else {
+        format!("{:.2} KB", bytes.max(0) as f64 / 1024.0)
+    }
+}
+
+fn format_conditions(conditions: &serde_json::Value) -> String {
+    match ConditionDraft::from_json(conditions) {
+        Some(draft) => draft.summary(false),
+        None => "No conditions".to_string(),
+    }
+}
+
+fn update_condition_node(tree: RwSignal<ConditionDraft>, path: &[usize], f: impl FnOnce(&mut ConditionDraft)) {
+    tree.update(|root| {
+        if let Some(node) = root.node_at_mut(path) {
+            f(node);
+        }
+    });
+}
+
+fn condition_type_select(
+    tree: RwSignal<ConditionDraft>,
+    path: Vec<usize>,
+    selected: String,
+    saving: RwSignal<bool>,
+) -> impl IntoView {
+    view! {
+        <select
+            class="flex-1 min-w-[10rem] px-3 py-2 rounded-lg border text-sm"
+            style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+            on:change=move |ev| {
+                let new_type = event_target_value(&ev);
+                update_condition_node(tree, &path, |n| {
+                    n.kind = match (new_type.strip_prefix("Text:"), &n.kind) {
+                        (Some(new_field), ConditionDraftKind::Text { operator, value, .. }) => ConditionDraftKind::Text {
+                            field: new_field.to_string(),
+                            operator: operator.clone(),
+                            value: value.clone(),
+                        },
+                        (Some(new_field), _) => ConditionDraft::text(new_field, "Contains", "").kind,
+                        (None, ConditionDraftKind::Leaf { operator, value, .. }) => ConditionDraftKind::Leaf {
+                            condition_type: new_type.clone(),
+                            operator: operator.clone(),
+                            value: *value,
+                        },
+                        (None, _) => ConditionDraft::leaf(&new_type, "GreaterThan", 0.0).kind,
+                    };
+                });
+            }
+            disabled=move || saving.get()
+        >
+            {CONDITION_TYPE_GROUPS.iter().map(|(group_label, options)| {
+                let selected_type = selected.clone();
+                view! {
+                    <optgroup label=*group_label>
+                        {options.iter().map(|(option_value, option_label)| {
+                            view! {
+                                <option value=*option_value selected=selected_type == *option_value>{*option_label}</option>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </optgroup>
+                }
+            }).collect::<Vec<_>>()}
+            <optgroup label="Text">
+                {TEXT_FIELD_OPTIONS.iter().map(|(field, label)| {
+                    let option_value = format!("Text:{}", field);
+                    let is_selected = selected == option_value;
+                    view! {
+                        <option value=option_value selected=is_selected>{*label}</option>
+                    }
+                }).collect::<Vec<_>>()}
+            </optgroup>
+        </select>
+    }
+}
+
+fn render_condition_node(
+    tree: RwSignal<ConditionDraft>,
+    node: ConditionDraft,
+    path: Vec<usize>,
+    saving: RwSignal<bool>,
+) -> AnyView {
+    let is_root = path.is_empty();
+    let negated = node.negated;
+    let path_for_not = path.clone();
+    let path_for_remove = path.clone();
+    let remove_button = (!is_root).then(|| view! {
+        <button
+            class="px-2 py-1 rounded text-xs transition-all"
+            style="background-color: var(--bg-secondary); color: var(--text-error); border: 1px solid var(--border-secondary);"
+            on:click=move |_| tree.update(|root| root.remove_at(&path_for_remove))
+            disabled=move || saving.get()
+        >
+            "Remove"
+        </button>
+    });
+    let not_toggle = view! {
+        <label class="flex items-center gap-1.5 text-xs font-semibold cursor-pointer" style="color: var(--text-secondary);">
+            <input
+                type="checkbox"
+                class="w-4 h-4 rounded cursor-pointer"
+                style="accent-color: var(--accent-primary);"
+                checked=negated
+                on:change=move |ev| {
+                    let checked = event_target_checked(&ev);
+                    update_condition_node(tree, &path_for_not, |n| n.negated = checked);
+                }
+                disabled=move || saving.get()
+            />
+            "NOT"
+        </label>
+    };
+
+    match node.kind {
+        ConditionDraftKind::Leaf { condition_type, operator, value } => {
+            let hint = condition_hint(&condition_type);
+            let path_for_type = path.clone();
+            let path_for_operator = path.clone();
+            let path_for_value = path.clone();
+            view! {
+                <div class="p-3 rounded-lg border space-y-2" style="background-color: var(--bg-tertiary); border-color: var(--border-secondary);">
+                    <div class="flex flex-wrap items-center gap-2">
+                        {not_toggle}
+                        {condition_type_select(tree, path_for_type, condition_type.clone(), saving)}
+                        <select
+                            class="px-3 py-2 rounded-lg border text-sm"
+                            style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                            on:change=move |ev| {
+                                let new_operator = event_target_value(&ev);
+                                update_condition_node(tree, &path_for_operator, |n| {
+                                    if let ConditionDraftKind::Leaf { operator, .. } = &mut n.kind {
+                                        *operator = new_operator;
                                     }
-                                } else {
-                                    editing_rule_id.set(None);
-                                    show_create_modal.set(true);
+                                });
+                            }
+                            disabled=move || saving.get()
+                        >
+                            {OPERATOR_OPTIONS.iter().map(|(option_value, option_label, _)| {
+                                view! {
+                                    <option value=*option_value selected=operator == *option_value>{*option_label}</option>
                                 }
+                            }).collect::<Vec<_>>()}
+                        </select>
+                        <input
+                            type="number"
+                            step="0.1"
+                            class="w-28 px-3 py-2 rounded-lg border text-sm"
+                            style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                            value=value.to_string()
+                            on:change=move |ev| {
+                                if let Ok(new_value) = event_target_value(&ev).parse::<f64>() {
+                                    update_condition_node(tree, &path_for_value, |n| {
+                                        if let ConditionDraftKind::Leaf { value, .. } = &mut n.kind {
+                                            *value = new_value;
+                                        }
+                                    });
+                                }
+                            }
+                            disabled=move || saving.get()
+                        />
+                        {remove_button}
+                    </div>
+                    {hint.map(|hint| view! {
+                        <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">{hint}</p>
+                    })}
+                </div>
+            }.into_any()
+        }
+        ConditionDraftKind::Text { field, operator, value } => {
+            let selected_type = format!("Text:{}", field);
+            let hint = condition_hint(&selected_type);
+            let path_for_type = path.clone();
+            let path_for_operator = path.clone();
+            let path_for_value = path.clone();
+            view! {
+                <div class="p-3 rounded-lg border space-y-2" style="background-color: var(--bg-tertiary); border-color: var(--border-secondary);">
+                    <div class="flex flex-wrap items-center gap-2">
+                        {not_toggle}
+                        {condition_type_select(tree, path_for_type, selected_type, saving)}
+                        <select
+                            class="px-3 py-2 rounded-lg border text-sm"
+                            style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                            on:change=move |ev| {
+                                let new_operator = event_target_value(&ev);
+                                update_condition_node(tree, &path_for_operator, |n| {
+                                    if let ConditionDraftKind::Text { operator, .. } = &mut n.kind {
+                                        *operator = new_operator;
+                                    }
+                                });
                             }
+                            disabled=move || saving.get()
                         >
-                            "+ Create Rule"
-                        </button>
+                            {TEXT_OPERATOR_OPTIONS.iter().map(|(option_value, option_label)| {
+                                view! {
+                                    <option value=*option_value selected=operator == *option_value>{*option_label}</option>
+                                }
+                            }).collect::<Vec<_>>()}
+                        </select>
+                        <input
+                            type="text"
+                            class="flex-1 min-w-[8rem] px-3 py-2 rounded-lg border text-sm"
+                            style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                            placeholder="Value"
+                            value=value
+                            on:change=move |ev| {
+                                let new_value = event_target_value(&ev);
+                                update_condition_node(tree, &path_for_value, |n| {
+                                    if let ConditionDraftKind::Text { value, .. } = &mut n.kind {
+                                        *value = new_value;
+                                    }
+                                });
+                            }
+                            disabled=move || saving.get()
+                        />
+                        {remove_button}
                     </div>
+                    {hint.map(|hint| view! {
+                        <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">{hint}</p>
+                    })}
                 </div>
-
-                <Show when=move || !loading.get()>
-                    <div class="mb-6 p-6 rounded-lg border" style="background-color: var(--bg-card); border-color: var(--border-secondary);">
-                        <h3 class="text-lg font-semibold mb-3" style="color: var(--text-primary); line-height: 1.5;">
-                            "Quick Start Presets"
-                        </h3>
-                        <p class="text-sm mb-4" style="color: var(--text-secondary); line-height: 1.5;">
-                            "Click a preset to quickly create a common automation rule:"
-                        </p>
-                        <div class="grid grid-cols-1 sm:grid-cols-2 lg:grid-cols-3 gap-3">
-                            <button
-                                class="p-4 rounded-lg border text-left transition-colors hover:opacity-90 w-full"
-                                style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary); display: flex; flex-direction: column; align-items: flex-start;"
-                                on:click=move |_| create_preset_rule("Delete Inactive Torrents".to_string(), 30, "Inactive".to_string(), "GreaterThan".to_string(), 0.0, "Delete".to_string())
-                            >
-                                <div class="font-semibold mb-1.5" style="line-height: 1.5;">"Delete Inactive"</div>
-                                <div class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
-                                    "Removes inactive torrents every 30 min"
-                                </div>
-                            </button>
-                            <button
-                                class="p-4 rounded-lg border text-left transition-colors hover:opacity-90 w-full"
-                                style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary); display: flex; flex-direction: column; align-items: flex-start;"
-                                on:click=move |_| create_preset_rule("Delete Stalled Torrents".to_string(), 30, "StalledTime".to_string(), "GreaterThan".to_string(), 1.0, "Delete".to_string())
-                            >
-                                <div class="font-semibold mb-1.5" style="line-height: 1.5;">"Delete Stalled"</div>
-                                <div class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
-                                    "Removes stalled torrents (>1h) every 30 min"
-                                </div>
-                            </button>
-                            <button
-                                class="p-4 rounded-lg border text-left transition-colors hover:opacity-90 w-full"
-                                style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary); display: flex; flex-direction: column; align-items: flex-start;"
-                                on:click=move |_| create_preset_rule("Stop Seeding Low Ratio".to_string(), 30, "SeedingRatio".to_string(), "GreaterThan".to_string(), 1.0, "StopSeeding".to_string())
-                            >
-                                <div class="font-semibold mb-1.5" style="line-height: 1.5;">"Stop Low Ratio"</div>
-                                <div class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
-                                    "Stops seeding when ratio > 1.0"
-                                </div>
-                            </button>
-                            <button
-                                class="p-4 rounded-lg border text-left transition-colors hover:opacity-90 w-full"
-                                style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary); display: flex; flex-direction: column; align-items: flex-start;"
-                                on:click=move |_| create_preset_rule("Stop After Seeding Time".to_string(), 60, "SeedingTime".to_string(), "GreaterThan".to_string(), 48.0, "StopSeeding".to_string())
-                            >
-                                <div class="font-semibold mb-1.5" style="line-height: 1.5;">"Stop After 48h"</div>
-                                <div class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
-                                    "Stops seeding after 48 hours"
-                                </div>
-                            </button>
-                            <button
-                                class="p-4 rounded-lg border text-left transition-colors hover:opacity-90 w-full"
-                                style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary); display: flex; flex-direction: column; align-items: flex-start;"
-                                on:click=move |_| create_preset_rule("Delete Old Torrents".to_string(), 60, "Age".to_string(), "GreaterThan".to_string(), 720.0, "Delete".to_string())
-                            >
-                                <div class="font-semibold mb-1.5" style="line-height: 1.5;">"Delete Old (30 days)"</div>
-                                <div class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
-                                    "Deletes torrents older than 30 days"
-                                </div>
-                            </button>
-                            <button
-                                class="p-4 rounded-lg border text-left transition-colors hover:opacity-90 w-full"
-                                style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary); display: flex; flex-direction: column; align-items: flex-start;"
-                                on:click=move |_| create_preset_rule("Delete Completed".to_string(), 60, "DownloadFinished".to_string(), "Equal".to_string(), 1.0, "Delete".to_string())
-                            >
-                                <div class="font-semibold mb-1.5" style="line-height: 1.5;">"Delete Completed"</div>
-                                <div class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
-                                    "Deletes finished downloads"
-                                </div>
-                            </button>
-                        </div>
-                    </div>
-                </Show>
-                
-                <Show when=move || error.get().is_some()>
-                    <div class="mb-4 p-4 rounded-lg" style="background-color: var(--bg-error); color: var(--text-error); line-height: 1.5;">
-                        {move || error.get().unwrap_or_default()}
+            }.into_any()
+        }
+        ConditionDraftKind::Group { any, children } => {
+            let path_for_logic = path.clone();
+            let path_for_add_condition = path.clone();
+            let path_for_add_group = path.clone();
+            let child_views = children
+                .into_iter()
+                .enumerate()
+                .map(|(idx, child)| {
+                    let mut child_path = path.clone();
+                    child_path.push(idx);
+                    render_condition_node(tree, child, child_path, saving)
+                })
+                .collect::<Vec<_>>();
+            view! {
+                <div
+                    class="p-3 rounded-lg border space-y-3"
+                    style={if is_root { "border-color: var(--border-secondary);" } else { "border-color: var(--accent-primary); border-style: dashed;" }}
+                >
+                    <div class="flex flex-wrap items-center gap-2">
+                        {not_toggle}
+                        <select
+                            class="px-3 py-2 rounded-lg border text-sm font-semibold"
+                            style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary);"
+                            on:change=move |ev| {
+                                let match_any = event_target_value(&ev) == "Any";
+                                update_condition_node(tree, &path_for_logic, |n| {
+                                    if let ConditionDraftKind::Group { any, .. } = &mut n.kind {
+                                        *any = match_any;
+                                    }
+                                });
+                            }
+                            disabled=move || saving.get()
+                        >
+                            <option value="All" selected=!any>"All of (AND)"</option>
+                            <option value="Any" selected=any>"Any of (OR)"</option>
+                        </select>
+                        <div class="flex-1"></div>
+                        {remove_button}
                     </div>
-                </Show>
-
-                <Show when=move || loading.get()>
-                    <div class="text-center py-8" style="color: var(--text-secondary); line-height: 1.5;">
-                        "Loading automations..."
+                    {child_views}
+                    <div class="flex flex-wrap gap-2">
+                        <button
+                            class="px-3 py-1.5 rounded-lg text-xs font-semibold transition-all"
+                            style="background-color: var(--bg-tertiary); color: var(--text-primary); border: 1px solid var(--border-secondary);"
+                            on:click=move |_| update_condition_node(tree, &path_for_add_condition, |n| {
+                                if let ConditionDraftKind::Group { children, .. } = &mut n.kind {
+                                    children.push(ConditionDraft::leaf("SeedingTime", "GreaterThan", 24.0));
+                                }
+                            })
+                            disabled=move || saving.get()
+                        >
+                            "+ Condition"
+                        </button>
+                        <button
+                            class="px-3 py-1.5 rounded-lg text-xs font-semibold transition-all"
+                            style="background-color: var(--bg-tertiary); color: var(--text-primary); border: 1px solid var(--border-secondary);"
+                            on:click=move |_| update_condition_node(tree, &path_for_add_group, |n| {
+                                if let ConditionDraftKind::Group { children, .. } = &mut n.kind {
+                                    children.push(ConditionDraft::group(true, vec![ConditionDraft::leaf("SeedingTime", "GreaterThan", 24.0)]));
+                                }
+                            })
+                            disabled=move || saving.get()
+                        >
+                            "+ Group"
+                        </button>
                     </div>
-                </Show>
+                </div>
+            }.into_any()
+        }
+    }
+}
 
+#[component]
+pub fn AutomationsTab() -> impl IntoView {
+    let rules = RwSignal::new(Vec::<AutomationRule>::new());
+    let loading = RwSignal::new(false);
+    let error = RwSignal::new(None::<String>);
+    let show_create_modal = RwSignal::new(false);
+    let editing_rule_id = RwSignal::new(None::<i64>);
+    let preset_data = RwSignal::new(None::<(String, u32, String, String, f64, String)>);
+    let rule_logs = RwSignal::new(std::collections::HashMap::<i64, Vec<ExecutionLog>>::new());
+    let next_run_times = RwSignal::new(std::collections::HashMap::<i64, Option<String>>::new());
+    let confirmation_state = use_confirmation();
+    let rule_limit = RwSignal::new(None::<RuleLimitInfo>);
+    let key_status = RwSignal::new(None::<ApiKeyStatus>);
+    let key_invalid = move || key_status.get().map(|status| status.suspended_at.is_some()).unwrap_or(false);
+    let selected_rules = RwSignal::new(std::collections::HashSet::<i64>::new());
+    let running_rules = RwSignal::new(std::collections::HashSet::<i64>::new());
+    let expanded_items = RwSignal::new(std::collections::HashSet::<i64>::new());
 
-                <Show when=move || !loading.get() && !rules.get().is_empty()>
-                    <div class="space-y-4">
-                        <div class="flex items-center justify-between p-4 rounded-lg border mb-4" style="background-color: var(--bg-card); border-color: var(--border-secondary);">
-                                <div class="flex items-center gap-4">
-                                    <div class="flex items-center gap-3">
-                                        <input
-                                            type="checkbox"
-                                            class="w-5 h-5 rounded cursor-pointer"
-                                            style="accent-color: var(--accent-primary);"
-                                            checked=move || {
-                                                let rules_list = rules.get();
-                                                !rules_list.is_empty() && rules_list.iter().all(|rule| {
-                                                    rule.id.map(|id| selected_rules.get().contains(&id)).unwrap_or(false)
-                                                })
+    let fetch_rules = move || {
+        #[cfg(feature = "hydrate")]
+        {
+            loading.set(true);
+            error.set(None);
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let url = "/api/automation/rules";
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+                                
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("GET");
+                                    i.set_headers(&headers);
+                                    i
+                                };
+                                
+                                let promise = window.fetch_with_str_and_init(url, &init);
+                                let future = wasm_bindgen_futures::JsFuture::from(promise);
+                                
+                                if let Ok(response) = future.await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                    if resp.status() == 200 {
+                                        let text_promise = resp.text().unwrap();
+                                        let text_future = wasm_bindgen_futures::JsFuture::from(text_promise);
+                                        if let Ok(text_value) = text_future.await {
+                                            if let Some(text) = text_value.as_string() {
+                                                if let Ok(api_response) = serde_json::from_str::<ApiResponse<Vec<AutomationRule>>>(&text) {
+                                                    if let Some(data) = api_response.data {
+                                                        rules.set(data);
+                                                    } else if let Some(err) = api_response.error {
+                                                        error.set(Some(err));
+                                                    }
+                                                } else {
+                                                    error.set(Some("Failed to parse response".to_string()));
+                                                }
+                                            } else {
+                                                error.set(Some("Response is not a string".to_string()));
                                             }
-                                            on:change=move |ev| {
-                                                let checked = event_target_checked(&ev);
-                                                let rules_list = rules.get();
-                                                selected_rules.update(|set| {
-                                                    if checked {
-                                                        for rule in &rules_list {
-                                                            if let Some(id) = rule.id {
-                                                                set.insert(id);
-                                                            }
-                                                        }
-                                                    } else {
-                                                        for rule in &rules_list {
-                                                            if let Some(id) = rule.id {
-                                                                set.remove(&id);
-                                                            }
-                                                        }
+                                        } else {
+                                            error.set(Some("Failed to read response text".to_string()));
+                                        }
+                                    } else {
+                                        error.set(Some(format!("Failed to fetch rules: {}", resp.status())));
+                                    }
+                                } else {
+                                    error.set(Some("Network request failed".to_string()));
+                                }
+                            } else {
+                                error.set(Some("No API key found".to_string()));
+                            }
+                        } else {
+                            error.set(Some("No API key found".to_string()));
+                        }
+                    }
+                }
+                loading.set(false);
+            });
+        }
+    };
+
+    let fetch_rule_limit = move || {
+        #[cfg(feature = "hydrate")]
+        {
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let url = "/api/automation/rules/limit";
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+                                
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("GET");
+                                    i.set_headers(&headers);
+                                    i
+                                };
+                                
+                                let promise = window.fetch_with_str_and_init(url, &init);
+                                let future = wasm_bindgen_futures::JsFuture::from(promise);
+                                
+                                if let Ok(response) = future.await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                    if resp.status() == 200 {
+                                        let text_promise = resp.text().unwrap();
+                                        let text_future = wasm_bindgen_futures::JsFuture::from(text_promise);
+                                        if let Ok(text_value) = text_future.await {
+                                            if let Some(text) = text_value.as_string() {
+                                                if let Ok(api_response) = serde_json::from_str::<ApiResponse<RuleLimitInfo>>(&text) {
+                                                    if let Some(data) = api_response.data {
+                                                        rule_limit.set(Some(data));
                                                     }
-                                                });
+                                                }
                                             }
-                                        />
-                                        <span class="text-sm font-medium" style="color: var(--text-primary);">
-                                            "Select All"
-                                        </span>
-                                    </div>
-                                    <Show when=move || !selected_rules.get().is_empty()>
-                                        <div class="text-sm" style="color: var(--text-secondary);">
-                                            {move || format!("{} rule(s) selected", selected_rules.get().len())}
-                                        </div>
-                                    </Show>
-                                </div>
-                                <Show when=move || !selected_rules.get().is_empty()>
-                                    <button
-                                    class="px-4 py-2 text-sm font-medium rounded-lg transition-all border"
-                                    style="background-color: var(--bg-error, #ef4444); color: var(--text-on-accent); border-color: var(--bg-error, #ef4444); line-height: 1.5;"
-                                    on:click=move |_| {
-                                        let selected_ids: Vec<i64> = selected_rules.get().into_iter().collect();
-                                        if !selected_ids.is_empty() {
-                                            let selected_ids_clone = selected_ids.clone();
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            });
+        }
+    };
+
+    let templates = RwSignal::new(Vec::<RuleTemplate>::new());
+    let selected_template = RwSignal::new(None::<String>);
+    let template_name = RwSignal::new(String::new());
+    let template_params = RwSignal::new(std::collections::HashMap::<String, f64>::new());
+    let creating_from_template = RwSignal::new(false);
+
+    let fetch_templates = move || {
+        #[cfg(feature = "hydrate")]
+        {
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    let init = {
+                        let mut i = web_sys::RequestInit::new();
+                        i.set_method("GET");
+                        i
+                    };
+
+                    let promise = window.fetch_with_str_and_init("/api/automation/templates", &init);
+                    if let Ok(response) = wasm_bindgen_futures::JsFuture::from(promise).await {
+                        let resp: web_sys::Response = response.dyn_into().unwrap();
+                        if let Ok(text_promise) = resp.text() {
+                            if let Ok(text_value) = wasm_bindgen_futures::JsFuture::from(text_promise).await {
+                                let text = text_value.as_string().unwrap_or_default();
+                                if let Ok(api_response) = serde_json::from_str::<ApiResponse<Vec<RuleTemplate>>>(&text) {
+                                    if let Some(data) = api_response.data {
+                                        templates.set(data);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            });
+        }
+    };
+
+    let select_template = move |template: RuleTemplate| {
+        if selected_template.get().as_deref() == Some(template.id.as_str()) {
+            selected_template.set(None);
+            return;
+        }
+        template_name.set(template.name.clone());
+        template_params.set(
+            template.parameters
+                .iter()
+                .map(|parameter| (parameter.key.clone(), parameter.default))
+                .collect(),
+        );
+        selected_template.set(Some(template.id));
+    };
+
+    let create_from_template = move |_| {
+        #[cfg(feature = "hydrate")]
+        {
+            let Some(template_id) = selected_template.get() else {
+                return;
+            };
+            creating_from_template.set(true);
+            error.set(None);
+            let body = serde_json::json!({
+                "name": template_name.get(),
+                "params": template_params.get(),
+            });
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let url = format!("/api/automation/templates/{}", template_id);
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+                                headers.set("Content-Type", "application/json").unwrap();
+
+                                let body_js = wasm_bindgen::JsValue::from_str(&body.to_string());
+
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("POST");
+                                    i.set_headers(&headers);
+                                    i.set_body(&body_js);
+                                    i
+                                };
+
+                                let promise = window.fetch_with_str_and_init(&url, &init);
+                                let future = wasm_bindgen_futures::JsFuture::from(promise);
+
+                                if let Ok(response) = future.await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                    if let Ok(text_promise) = resp.text() {
+                                        if let Ok(text_value) = wasm_bindgen_futures::JsFuture::from(text_promise).await {
+                                            let text = text_value.as_string().unwrap_or_default();
+                                            match serde_json::from_str::<ApiResponse<AutomationRule>>(&text) {
+                                                Ok(api_response) if api_response.success => {
+                                                    selected_template.set(None);
+                                                    fetch_rules();
+                                                    fetch_rule_limit();
+                                                }
+                                                Ok(api_response) => error.set(Some(api_response.error.unwrap_or_else(|| "Failed to create rule from template".to_string()))),
+                                                Err(_) => error.set(Some(format!("Failed to create rule from template: {}", resp.status()))),
+                                            }
+                                        }
+                                    }
+                                } else {
+                                    error.set(Some("Network request failed".to_string()));
+                                }
+                            }
+                        }
+                    }
+                }
+                creating_from_template.set(false);
+            });
+        }
+    };
+
+    fetch_rules();
+    fetch_rule_limit();
+    fetch_templates();
+
+    Effect::new(move |_| {
+        let rules_clone = rules.get();
+        for rule in rules_clone {
+            if let Some(id) = rule.id {
+                #[cfg(feature = "hydrate")]
+                {
+                    let rule_logs_clone = rule_logs.clone();
+                    spawn_local(async move {
+                        if let Some(window) = web_sys::window() {
+                            if let Ok(Some(storage)) = window.local_storage() {
+                                if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                                    if !api_key.is_empty() {
+                                        let url = format!("/api/automation/rules/{}/logs?limit=10", id);
+                                        let headers = web_sys::Headers::new().unwrap();
+                                        headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+                                        
+                                        let init = {
+                                            let mut i = web_sys::RequestInit::new();
+                                            i.set_method("GET");
+                                            i.set_headers(&headers);
+                                            i
+                                        };
+                                        
+                                        let promise = window.fetch_with_str_and_init(&url, &init);
+                                        let future = wasm_bindgen_futures::JsFuture::from(promise);
+                                        
+                                        if let Ok(response) = future.await {
+                                            let resp: web_sys::Response = response.dyn_into().unwrap();
+                                            if resp.status() == 200 {
+                                                let text_promise = resp.text().unwrap();
+                                                let text_future = wasm_bindgen_futures::JsFuture::from(text_promise);
+                                                if let Ok(text_value) = text_future.await {
+                                                    if let Some(text) = text_value.as_string() {
+                                                        if let Ok(api_response) = serde_json::from_str::<ApiResponse<Vec<ExecutionLog>>>(&text) {
+                                                            if let Some(logs) = api_response.data {
+                                                                rule_logs_clone.update(|map| {
+                                                                    map.insert(id, logs);
+                                                                });
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    });
+                }
+            }
+        }
+    });
+
+    let toggle_rule = move |rule_id: i64, current_enabled: bool, rule_clone: AutomationRule| {
+        #[cfg(feature = "hydrate")]
+        {
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let url = format!("/api/automation/rules/{}", rule_id);
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+                                headers.set("Content-Type", "application/json").unwrap();
+
+                                let request_body = serde_json::json!({
+                                    "name": rule_clone.name,
+                                    "enabled": !current_enabled,
+                                    "download_types": rule_clone.download_types,
+                                    "trigger_config": rule_clone.trigger_config,
+                                    "conditions": rule_clone.conditions,
+                                    "action_config": rule_clone.action_config,
+                                    "notification": rule_clone.notification,
+                                    "schedule_window": rule_clone.schedule_window,
+                                    "quota": rule_clone.quota,
+                                    "cooldown": rule_clone.cooldown
+                                });
+                                let body_str = serde_json::to_string(&request_body).unwrap();
+                                let body_js = wasm_bindgen::JsValue::from_str(&body_str);
+
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("PUT");
+                                    i.set_headers(&headers);
+                                    i.set_body(&body_js);
+                                    i
+                                };
+
+                                let promise = window.fetch_with_str_and_init(&url, &init);
+                                let future = wasm_bindgen_futures::JsFuture::from(promise);
+
+                                if let Ok(response) = future.await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                if resp.status() == 200 {
+                                    fetch_rules();
+                                    fetch_rule_limit();
+                                } else {
+                                        let text_promise = resp.text().unwrap();
+                                        let text_future = wasm_bindgen_futures::JsFuture::from(text_promise);
+                                        if let Ok(text_value) = text_future.await {
+                                            if let Some(text) = text_value.as_string() {
+                                                error.set(Some(format!("Failed to toggle rule: {}", text)));
+                                            } else {
+                                                error.set(Some(format!("Failed to toggle rule: {}", resp.status())));
+                                            }
+                                        } else {
+                                            error.set(Some(format!("Failed to toggle rule: {}", resp.status())));
+                                        }
+                                    }
+                                } else {
+                                    error.set(Some("Network request failed".to_string()));
+                                }
+                            }
+                        }
+                    }
+                }
+            });
+        }
+    };
+
+    let delete_rule = move |rule_id: i64| {
+        #[cfg(feature = "hydrate")]
+        {
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let url = format!("/api/automation/rules/{}", rule_id);
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+                                
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("DELETE");
+                                    i.set_headers(&headers);
+                                    i
+                                };
+                                
+                                let promise = window.fetch_with_str_and_init(&url, &init);
+                                let future = wasm_bindgen_futures::JsFuture::from(promise);
+                                
+                                if let Ok(response) = future.await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                    if resp.status() == 200 || resp.status() == 204 {
+                                        selected_rules.update(|set| {
+                                            set.remove(&rule_id);
+                                        });
+                                        fetch_rules();
+                                        fetch_rule_limit();
+                                    } else {
+                                        error.set(Some(format!("Failed to delete rule: {}", resp.status())));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            });
+        }
+    };
+
+    let create_preset_rule = move |preset_name: String, trigger_minutes: u32, condition_type_val: String, condition_op: String, condition_val: f64, action_type_val: String| {
+        #[cfg(feature = "hydrate")]
+        {
+            loading.set(true);
+            error.set(None);
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let trigger_config = serde_json::json!({
+                                    "Interval": {
+                                        "minutes": trigger_minutes
+                                    }
+                                });
+
+                                let conditions = ConditionDraft::root_with(
+                                    ConditionDraft::leaf(&condition_type_val, &condition_op, condition_val),
+                                ).to_json();
+
+                                let action_config = ActionStepDraft::list_to_json(&[ActionStepDraft::new(&action_type_val)]);
+
+                                let request = CreateRuleRequest {
+                                    name: preset_name.clone(),
+                                    enabled: Some(true),
+                                    download_types: vec!["Torrent".to_string()],
+                                    trigger_config,
+                                    conditions,
+                                    action_config,
+                                    notification: None,
+                                    schedule_window: None,
+                                    quota: None,
+                                    cooldown: None,
+                                };
+
+                                let url = "/api/automation/rules";
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+                                headers.set("Content-Type", "application/json").unwrap();
+
+                                let body = serde_json::to_string(&request).unwrap();
+                                let body_js = wasm_bindgen::JsValue::from_str(&body);
+
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("POST");
+                                    i.set_headers(&headers);
+                                    i.set_body(&body_js);
+                                    i
+                                };
+
+                                let promise = window.fetch_with_str_and_init(&url, &init);
+                                let future = wasm_bindgen_futures::JsFuture::from(promise);
+
+                                if let Ok(response) = future.await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                    if resp.status() == 200 || resp.status() == 201 {
+                                        fetch_rules();
+                                        fetch_rule_limit();
+                                    } else {
+                                        let text_promise = resp.text().unwrap();
+                                        let text_future = wasm_bindgen_futures::JsFuture::from(text_promise);
+                                        if let Ok(text_value) = text_future.await {
+                                            if let Some(text) = text_value.as_string() {
+                                                if let Ok(api_response) = serde_json::from_str::<ApiResponse<serde_json::Value>>(&text) {
+                                                    if let Some(err_msg) = api_response.error {
+                                                        error.set(Some(err_msg));
+                                                    } else {
+                                                        error.set(Some(format!("Failed to create rule: {}", text)));
+                                                    }
+                                                } else {
+                                                    error.set(Some(format!("Failed to create rule: {}", text)));
+                                                }
+                                            } else {
+                                                error.set(Some(format!("Failed to create rule: {}", resp.status())));
+                                            }
+                                        } else {
+                                            error.set(Some(format!("Failed to create rule: {}", resp.status())));
+                                        }
+                                        fetch_rule_limit();
+                                    }
+                                } else {
+                                    error.set(Some("Network request failed".to_string()));
+                                }
+                            }
+                        }
+                    }
+                }
+                loading.set(false);
+            });
+        }
+    };
+
+    let show_import_modal = RwSignal::new(false);
+    let history_rule_id = RwSignal::new(None::<i64>);
+    let show_analytics = RwSignal::new(false);
+    let show_logs = RwSignal::new(false);
+    let show_deleted = RwSignal::new(false);
+    let exporting = RwSignal::new(false);
+
+    let export_rules = move |format: &'static str| {
+        #[cfg(feature = "hydrate")]
+        {
+            exporting.set(true);
+            error.set(None);
+            let mut ids: Vec<i64> = selected_rules.get().into_iter().collect();
+            ids.sort();
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let mut url = format!("/api/automation/rules/export?format={}", format);
+                                if !ids.is_empty() {
+                                    let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+                                    url.push_str(&format!("&ids={}", ids.join(",")));
+                                }
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("GET");
+                                    i.set_headers(&headers);
+                                    i
+                                };
+
+                                let promise = window.fetch_with_str_and_init(&url, &init);
+                                let future = wasm_bindgen_futures::JsFuture::from(promise);
+
+                                if let Ok(response) = future.await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                    let text_promise = resp.text().unwrap();
+                                    let text_future = wasm_bindgen_futures::JsFuture::from(text_promise);
+                                    if let Ok(text_value) = text_future.await {
+                                        if let Some(text) = text_value.as_string() {
+                                            match serde_json::from_str::<ApiResponse<RuleExport>>(&text) {
+                                                Ok(api_response) => {
+                                                    if let Some(export) = api_response.data {
+                                                        download_file(&window, &export.filename, &export.content_type, &export.content);
+                                                    } else {
+                                                        error.set(Some(api_response.error.unwrap_or_else(|| "Failed to export rules".to_string())));
+                                                    }
+                                                }
+                                                Err(_) => {
+                                                    error.set(Some(format!("Failed to export rules: {}", resp.status())));
+                                                }
+                                            }
+                                        }
+                                    }
+                                } else {
+                                    error.set(Some("Network request failed".to_string()));
+                                }
+                            }
+                        }
+                    }
+                }
+                exporting.set(false);
+            });
+        }
+        #[cfg(not(feature = "hydrate"))]
+        let _ = format;
+    };
+
+    view! {
+            <div class="flex flex-col w-full mt-10 sm:mt-12">
+            <div class="mb-6">
+                <div class="flex flex-col sm:flex-row items-start sm:items-center gap-4 mb-6">
+                    <div class="flex flex-col sm:flex-row items-start sm:items-center gap-3 sm:ml-auto">
+                        <Show when=move || rule_limit.get().is_some()>
+                            <div class="text-sm" style="color: var(--text-secondary);">
+                                {move || {
+                                    if let Some(limit_info) = rule_limit.get() {
+                                        format!("Rules: {}/{}", limit_info.current_count, limit_info.max_rules)
+                                    } else {
+                                        "".to_string()
+                                    }
+                                }}
+                            </div>
+                        </Show>
+                        <div class="flex items-center gap-2 self-start sm:self-center">
+                            <button
+                                class="px-3 py-2 rounded-lg text-sm font-medium transition-colors whitespace-nowrap border disabled:opacity-50 disabled:cursor-not-allowed"
+                                style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                title=move || if selected_rules.get().is_empty() { "Export all rules as JSON. Notification header values are left out; notification URLs are included." } else { "Export selected rules as JSON. Notification header values are left out; notification URLs are included." }
+                                disabled=move || exporting.get() || rules.get().is_empty()
+                                on:click=move |_| export_rules("json")
+                            >
+                                "Export JSON"
+                            </button>
+                            <button
+                                class="px-3 py-2 rounded-lg text-sm font-medium transition-colors whitespace-nowrap border disabled:opacity-50 disabled:cursor-not-allowed"
+                                style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                title=move || if selected_rules.get().is_empty() { "Export all rules as YAML. Notification header values are left out; notification URLs are included." } else { "Export selected rules as YAML. Notification header values are left out; notification URLs are included." }
+                                disabled=move || exporting.get() || rules.get().is_empty()
+                                on:click=move |_| export_rules("yaml")
+                            >
+                                "Export YAML"
+                            </button>
+                            <button
+                                class="px-3 py-2 rounded-lg text-sm font-medium transition-colors whitespace-nowrap border"
+                                style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                on:click=move |_| show_import_modal.set(true)
+                            >
+                                "Import"
+                            </button>
+                            <button
+                                class="px-3 py-2 rounded-lg text-sm font-medium transition-colors whitespace-nowrap border"
+                                style="background-color: var(--bg-secondary); color: var(--text-primary);"
+                                style:border-color=move || if show_analytics.get() { "var(--accent-primary)" } else { "var(--border-secondary)" }
+                                on:click=move |_| show_analytics.update(|shown| *shown = !*shown)
+                            >
+                                "Analytics"
+                            </button>
+                            <button
+                                class="px-3 py-2 rounded-lg text-sm font-medium transition-colors whitespace-nowrap border"
+                                style="background-color: var(--bg-secondary); color: var(--text-primary);"
+                                style:border-color=move || if show_logs.get() { "var(--accent-primary)" } else { "var(--border-secondary)" }
+                                on:click=move |_| show_logs.update(|shown| *shown = !*shown)
+                            >
+                                "Logs"
+                            </button>
+                            <button
+                                class="px-3 py-2 rounded-lg text-sm font-medium transition-colors whitespace-nowrap border"
+                                style="background-color: var(--bg-secondary); color: var(--text-primary);"
+                                style:border-color=move || if show_deleted.get() { "var(--accent-primary)" } else { "var(--border-secondary)" }
+                                on:click=move |_| show_deleted.update(|shown| *shown = !*shown)
+                            >
+                                "Recently Deleted"
+                            </button>
+                        </div>
+                        <button
+                            class="px-4 py-2 rounded-lg font-medium transition-colors whitespace-nowrap shrink-0 self-start sm:self-center disabled:opacity-50 disabled:cursor-not-allowed"
+                            style:background-color=move || {
+                                if let Some(limit_info) = rule_limit.get() {
+                                    if limit_info.current_count >= limit_info.max_rules {
+                                        "var(--bg-disabled, #6b7280)"
+                                    } else {
+                                        "var(--accent-primary)"
+                                    }
+                                } else {
+                                    "var(--accent-primary)"
+                                }
+                            }
+                            style:color="var(--text-on-accent)"
+                            style:width="fit-content"
+                            style:max-width="none"
+                            style:flex="0 0 auto"
+                            disabled=move || {
+                                if let Some(limit_info) = rule_limit.get() {
+                                    limit_info.current_count >= limit_info.max_rules
+                                } else {
+                                    false
+                                }
+                            }
+                            on:click=move |_| {
+                                if let Some(limit_info) = rule_limit.get() {
+                                    if limit_info.current_count < limit_info.max_rules {
+                                        editing_rule_id.set(None);
+                                        show_create_modal.set(true);
+                                    }
+                                } else {
+                                    editing_rule_id.set(None);
+                                    show_create_modal.set(true);
+                                }
+                            }
+                        >
+                            "+ Create Rule"
+                        </button>
+                    </div>
+                </div>
+
+                <ApiKeyStatusBanner
+                    status=key_status
+                    on_migrated=move || {
+                        fetch_rules();
+                        fetch_rule_limit();
+                    }
+                />
+
+                <AutomationAnalyticsPanel show=show_analytics />
+
+                <AutomationLogsPanel show=show_logs rules=rules />
+
+                <RecentlyDeletedPanel show=show_deleted />
+
+                <Show when=move || !loading.get()>
+                    <div class="mb-6 p-6 rounded-lg border" style="background-color: var(--bg-card); border-color: var(--border-secondary);">
+                        <h3 class="text-lg font-semibold mb-3" style="color: var(--text-primary); line-height: 1.5;">
+                            "Quick Start Presets"
+                        </h3>
+                        <p class="text-sm mb-4" style="color: var(--text-secondary); line-height: 1.5;">
+                            "Click a preset to quickly create a common automation rule:"
+                        </p>
+                        <div class="grid grid-cols-1 sm:grid-cols-2 lg:grid-cols-3 gap-3">
+                            <button
+                                class="p-4 rounded-lg border text-left transition-colors hover:opacity-90 w-full"
+                                style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary); display: flex; flex-direction: column; align-items: flex-start;"
+                                on:click=move |_| create_preset_rule("Delete Inactive Torrents".to_string(), 30, "Inactive".to_string(), "GreaterThan".to_string(), 0.0, "Delete".to_string())
+                            >
+                                <div class="font-semibold mb-1.5" style="line-height: 1.5;">"Delete Inactive"</div>
+                                <div class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
+                                    "Removes inactive torrents every 30 min"
+                                </div>
+                            </button>
+                            <button
+                                class="p-4 rounded-lg border text-left transition-colors hover:opacity-90 w-full"
+                                style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary); display: flex; flex-direction: column; align-items: flex-start;"
+                                on:click=move |_| create_preset_rule("Delete Stalled Torrents".to_string(), 30, "StalledTime".to_string(), "GreaterThan".to_string(), 1.0, "Delete".to_string())
+                            >
+                                <div class="font-semibold mb-1.5" style="line-height: 1.5;">"Delete Stalled"</div>
+                                <div class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
+                                    "Removes stalled torrents (>1h) every 30 min"
+                                </div>
+                            </button>
+                            <button
+                                class="p-4 rounded-lg border text-left transition-colors hover:opacity-90 w-full"
+                                style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary); display: flex; flex-direction: column; align-items: flex-start;"
+                                on:click=move |_| create_preset_rule("Stop Seeding Low Ratio".to_string(), 30, "SeedingRatio".to_string(), "GreaterThan".to_string(), 1.0, "StopSeeding".to_string())
+                            >
+                                <div class="font-semibold mb-1.5" style="line-height: 1.5;">"Stop Low Ratio"</div>
+                                <div class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
+                                    "Stops seeding when ratio > 1.0"
+                                </div>
+                            </button>
+                            <button
+                                class="p-4 rounded-lg border text-left transition-colors hover:opacity-90 w-full"
+                                style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary); display: flex; flex-direction: column; align-items: flex-start;"
+                                on:click=move |_| create_preset_rule("Stop After Seeding Time".to_string(), 60, "SeedingTime".to_string(), "GreaterThan".to_string(), 48.0, "StopSeeding".to_string())
+                            >
+                                <div class="font-semibold mb-1.5" style="line-height: 1.5;">"Stop After 48h"</div>
+                                <div class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
+                                    "Stops seeding after 48 hours"
+                                </div>
+                            </button>
+                            <button
+                                class="p-4 rounded-lg border text-left transition-colors hover:opacity-90 w-full"
+                                style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary); display: flex; flex-direction: column; align-items: flex-start;"
+                                on:click=move |_| create_preset_rule("Delete Old Torrents".to_string(), 60, "Age".to_string(), "GreaterThan".to_string(), 720.0, "Delete".to_string())
+                            >
+                                <div class="font-semibold mb-1.5" style="line-height: 1.5;">"Delete Old (30 days)"</div>
+                                <div class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
+                                    "Deletes torrents older than 30 days"
+                                </div>
+                            </button>
+                            <button
+                                class="p-4 rounded-lg border text-left transition-colors hover:opacity-90 w-full"
+                                style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary); display: flex; flex-direction: column; align-items: flex-start;"
+                                on:click=move |_| create_preset_rule("Delete Completed".to_string(), 60, "DownloadFinished".to_string(), "Equal".to_string(), 1.0, "Delete".to_string())
+                            >
+                                <div class="font-semibold mb-1.5" style="line-height: 1.5;">"Delete Completed"</div>
+                                <div class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
+                                    "Deletes finished downloads"
+                                </div>
+                            </button>
+                        </div>
+                    </div>
+                </Show>
+
+                <Show when=move || !loading.get() && !templates.get().is_empty()>
+                    <div class="mb-6 p-6 rounded-lg border" style="background-color: var(--bg-card); border-color: var(--border-secondary);">
+                        <h3 class="text-lg font-semibold mb-3" style="color: var(--text-primary); line-height: 1.5;">
+                            "Rule Templates"
+                        </h3>
+                        <p class="text-sm mb-4" style="color: var(--text-secondary); line-height: 1.5;">
+                            "Pick a template and fill in a few values; the conditions and operators are set up for you:"
+                        </p>
+                        <div class="grid grid-cols-1 sm:grid-cols-2 lg:grid-cols-4 gap-3">
+                            <For
+                                each=move || templates.get()
+                                key=|template| template.id.clone()
+                                children=move |template| {
+                                    let template_id = template.id.clone();
+                                    let is_selected = move || selected_template.get().as_deref() == Some(template_id.as_str());
+                                    let name = template.name.clone();
+                                    let description = template.description.clone();
+                                    view! {
+                                        <button
+                                            class="p-4 rounded-lg border text-left transition-colors hover:opacity-90 w-full"
+                                            style="background-color: var(--bg-secondary); color: var(--text-primary); display: flex; flex-direction: column; align-items: flex-start;"
+                                            style:border-color=move || if is_selected() { "var(--accent-primary)" } else { "var(--border-secondary)" }
+                                            on:click=move |_| select_template(template.clone())
+                                        >
+                                            <div class="font-semibold mb-1.5" style="line-height: 1.5;">{name}</div>
+                                            <div class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
+                                                {description}
+                                            </div>
+                                        </button>
+                                    }
+                                }
+                            />
+                        </div>
+                        {move || {
+                            let template = selected_template.get()
+                                .and_then(|id| templates.get().into_iter().find(|template| template.id == id))?;
+                            Some(view! {
+                                <div class="mt-4 p-4 rounded-lg border space-y-3" style="background-color: var(--bg-secondary); border-color: var(--border-secondary);">
+                                    <div>
+                                        <label class="block text-sm font-medium mb-1" style="color: var(--text-primary);">"Rule name"</label>
+                                        <input
+                                            type="text"
+                                            class="w-full px-3 py-2 rounded-lg border text-sm"
+                                            style="background-color: var(--bg-card); border-color: var(--border-secondary); color: var(--text-primary);"
+                                            prop:value=move || template_name.get()
+                                            on:change=move |ev| template_name.set(event_target_value(&ev))
+                                            disabled=move || creating_from_template.get()
+                                        />
+                                    </div>
+                                    <div class="grid grid-cols-1 sm:grid-cols-3 gap-3">
+                                        {template.parameters.into_iter().map(|parameter| {
+                                            let key = parameter.key.clone();
+                                            let key_for_change = parameter.key.clone();
+                                            let label = match &parameter.unit {
+                                                Some(unit) => format!("{} ({})", parameter.label, unit),
+                                                None => parameter.label.clone(),
+                                            };
+                                            view! {
+                                                <div>
+                                                    <label class="block text-sm font-medium mb-1" style="color: var(--text-primary);">{label}</label>
+                                                    <input
+                                                        type="number"
+                                                        class="w-full px-3 py-2 rounded-lg border text-sm"
+                                                        style="background-color: var(--bg-card); border-color: var(--border-secondary); color: var(--text-primary);"
+                                                        min=parameter.min.to_string()
+                                                        max=parameter.max.to_string()
+                                                        step=parameter.step.to_string()
+                                                        prop:value=move || template_params.get().get(&key).copied().unwrap_or_default().to_string()
+                                                        on:change=move |ev| {
+                                                            if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+                                                                template_params.update(|params| {
+                                                                    params.insert(key_for_change.clone(), value);
+                                                                });
+                                                            }
+                                                        }
+                                                        disabled=move || creating_from_template.get()
+                                                    />
+                                                </div>
+                                            }
+                                        }).collect::<Vec<_>>()}
+                                    </div>
+                                    <div class="flex justify-end">
+                                        <button
+                                            class="px-4 py-2 rounded-lg font-medium transition-colors whitespace-nowrap disabled:opacity-50 disabled:cursor-not-allowed"
+                                            style="background-color: var(--accent-primary); color: var(--text-on-accent);"
+                                            on:click=create_from_template
+                                            disabled=move || creating_from_template.get() || rule_limit.get().map(|limit| limit.current_count >= limit.max_rules).unwrap_or(false)
+                                        >
+                                            {move || if creating_from_template.get() { "Creating..." } else { "Create Rule" }}
+                                        </button>
+                                    </div>
+                                </div>
+                            })
+                        }}
+                    </div>
+                </Show>
+                
+                <Show when=move || error.get().is_some()>
+                    <div class="mb-4 p-4 rounded-lg" style="background-color: var(--bg-error); color: var(--text-error); line-height: 1.5;">
+                        {move || error.get().unwrap_or_default()}
+                    </div>
+                </Show>
+
+                <Show when=move || loading.get()>
+                    <div class="text-center py-8" style="color: var(--text-secondary); line-height: 1.5;">
+                        "Loading automations..."
+                    </div>
+                </Show>
+
+
+                <Show when=move || !loading.get() && !rules.get().is_empty()>
+                    <div class="space-y-4">
+                        <div class="flex items-center justify-between p-4 rounded-lg border mb-4" style="background-color: var(--bg-card); border-color: var(--border-secondary);">
+                                <div class="flex items-center gap-4">
+                                    <div class="flex items-center gap-3">
+                                        <input
+                                            type="checkbox"
+                                            class="w-5 h-5 rounded cursor-pointer"
+                                            style="accent-color: var(--accent-primary);"
+                                            checked=move || {
+                                                let rules_list = rules.get();
+                                                !rules_list.is_empty() && rules_list.iter().all(|rule| {
+                                                    rule.id.map(|id| selected_rules.get().contains(&id)).unwrap_or(false)
+                                                })
+                                            }
+                                            on:change=move |ev| {
+                                                let checked = event_target_checked(&ev);
+                                                let rules_list = rules.get();
+                                                selected_rules.update(|set| {
+                                                    if checked {
+                                                        for rule in &rules_list {
+                                                            if let Some(id) = rule.id {
+                                                                set.insert(id);
+                                                            }
+                                                        }
+                                                    } else {
+                                                        for rule in &rules_list {
+                                                            if let Some(id) = rule.id {
+                                                                set.remove(&id);
+                                                            }
+                                                        }
+                                                    }
+                                                });
+                                            }
+                                        />
+                                        <span class="text-sm font-medium" style="color: var(--text-primary);">
+                                            "Select All"
+                                        </span>
+                                    </div>
+                                    <Show when=move || !selected_rules.get().is_empty()>
+                                        <div class="text-sm" style="color: var(--text-secondary);">
+                                            {move || format!("{} rule(s) selected", selected_rules.get().len())}
+                                        </div>
+                                    </Show>
+                                </div>
+                                <Show when=move || !selected_rules.get().is_empty()>
+                                    <button
+                                    class="px-4 py-2 text-sm font-medium rounded-lg transition-all border"
+                                    style="background-color: var(--bg-error, #ef4444); color: var(--text-on-accent); border-color: var(--bg-error, #ef4444); line-height: 1.5;"
+                                    on:click=move |_| {
+                                        let selected_ids: Vec<i64> = selected_rules.get().into_iter().collect();
+                                        if !selected_ids.is_empty() {
+                                            let selected_ids_clone = selected_ids.clone();
                                             show_confirmation(
                                                 confirmation_state.clone(),
                                                 "Delete Selected Rules".to_string(),
@@ -669,719 +2810,2460 @@ pub fn AutomationsTab() -> impl IntoView {
                                                                             headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
                                                                             headers.set("Content-Type", "application/json").unwrap();
 
-                                                                            let body = serde_json::json!({ "rule_ids": ids_to_delete });
-                                                                            let body_str = serde_json::to_string(&body).unwrap();
-                                                                            let body_js = wasm_bindgen::JsValue::from_str(&body_str);
+                                                                            let body = serde_json::json!({ "rule_ids": ids_to_delete });
+                                                                            let body_str = serde_json::to_string(&body).unwrap();
+                                                                            let body_js = wasm_bindgen::JsValue::from_str(&body_str);
+
+                                                                            let init = {
+                                                                                let mut i = web_sys::RequestInit::new();
+                                                                                i.set_method("POST");
+                                                                                i.set_headers(&headers);
+                                                                                i.set_body(&body_js);
+                                                                                i
+                                                                            };
+
+                                                                            let promise = window.fetch_with_str_and_init("/api/automation/rules/bulk-delete", &init);
+                                                                            let future = wasm_bindgen_futures::JsFuture::from(promise);
+
+                                                                            if let Ok(response) = future.await {
+                                                                                let resp: web_sys::Response = response.dyn_into().unwrap();
+                                                                                if resp.status() == 200 {
+                                                                                    let text_promise = resp.text().unwrap();
+                                                                                    let text_future = wasm_bindgen_futures::JsFuture::from(text_promise);
+                                                                                    if let Ok(text_value) = text_future.await {
+                                                                                        if let Some(text) = text_value.as_string() {
+                                                                                            if let Ok(api_response) = serde_json::from_str::<ApiResponse<serde_json::Value>>(&text) {
+                                                                                                if api_response.success {
+                                                                                                    selected_rules.set(std::collections::HashSet::new());
+                                                                                                    fetch_rules();
+                                                                                                    fetch_rule_limit();
+                                                                                                } else {
+                                                                                                    error.set(Some(api_response.error.unwrap_or_else(|| "Failed to delete rules".to_string())));
+                                                                                                }
+                                                                                            } else {
+                                                                                                error.set(Some("Failed to parse response".to_string()));
+                                                                                            }
+                                                                                        } else {
+                                                                                            error.set(Some("Invalid response format".to_string()));
+                                                                                        }
+                                                                                    } else {
+                                                                                        error.set(Some("Failed to read response".to_string()));
+                                                                                    }
+                                                                                } else {
+                                                                                    error.set(Some(format!("Failed to delete rules: {}", resp.status())));
+                                                                                }
+                                                                            } else {
+                                                                                error.set(Some("Network request failed".to_string()));
+                                                                            }
+                                                                        }
+                                                                    }
+                                                                }
+                                                            }
+                                                        });
+                                                    }
+                                                },
+                                                ConfirmationVariant::Danger,
+                                                Some("Delete".to_string()),
+                                                None
+                                            );
+                                        }
+                                    }
+                                >
+                                        "Delete Selected"
+                                    </button>
+                                </Show>
+                        </div>
+                        {move || {
+                            rules.get().into_iter().map(|rule| {
+                                let rule_id = rule.id;
+                                let rule_revision = rule.revision;
+                                let rule_name_for_delete = rule.name.clone();
+                                let logs_for_rule = move || {
+                                    if let Some(id) = rule_id {
+                                        rule_logs.get().get(&id).cloned().unwrap_or_default()
+                                    } else {
+                                        Vec::new()
+                                    }
+                                };
+                                let format_timestamp = move |timestamp_str: String| -> String {
+                                    #[cfg(feature = "hydrate")]
+                                    {
+                                        if let Ok(parsed) = chrono::DateTime::parse_from_rfc3339(&timestamp_str) {
+                                            let utc_timestamp = parsed.timestamp_millis();
+                                            if let Some(_window) = web_sys::window() {
+                                                let js_date = js_sys::Date::new(&wasm_bindgen::JsValue::from_f64(utc_timestamp as f64));
+                                                let month = js_date.get_month() as u32;
+                                                let day = js_date.get_date() as u32;
+                                                let year = js_date.get_full_year() as u32;
+                                                let hours = js_date.get_hours() as u32;
+                                                let minutes = js_date.get_minutes() as u32;
+                                                let am_pm = if hours < 12 { "AM" } else { "PM" };
+                                                let display_hours = if hours == 0 { 12 } else if hours > 12 { hours - 12 } else { hours };
+                                                let month_names = ["January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December"];
+                                                format!("{} {}, {} at {}:{:02} {}", month_names[month as usize], day, year, display_hours, minutes, am_pm)
+                                            } else {
+                                                timestamp_str
+                                            }
+                                        } else {
+                                            timestamp_str
+                                        }
+                                    }
+                                    #[cfg(not(feature = "hydrate"))]
+                                    {
+                                        timestamp_str
+                                    }
+                                };
+                                let last_run = move || {
+                                    logs_for_rule().first().and_then(|log| log.executed_at.clone())
+                                };
+                                let run_count = move || logs_for_rule().len();
+                                let last_result = move || {
+                                    logs_for_rule().first().map(|log| {
+                                        let total = log.total_items.unwrap_or(log.items_processed);
+                                        (log.success, log.items_processed, total, log.error_message.clone(), log.processed_items.clone(), log.partial.unwrap_or(false))
+                                    })
+                                };
+                                let expanded_items_clone = expanded_items.clone();
+                                let is_expanded = move || {
+                                    if let Some(id) = rule_id {
+                                        expanded_items_clone.get().contains(&id)
+                                    } else {
+                                        false
+                                    }
+                                };
+                                let expanded_items_toggle = expanded_items.clone();
+                                let toggle_expanded = move |_| {
+                                    if let Some(id) = rule_id {
+                                        expanded_items_toggle.update(|set| {
+                                            if set.contains(&id) {
+                                                set.remove(&id);
+                                            } else {
+                                                set.insert(id);
+                                            }
+                                        });
+                                    }
+                                };
+                                let next_run = move || {
+                                    if let Some(id) = rule_id {
+                                        next_run_times.get().get(&id).cloned().flatten()
+                                    } else {
+                                        None
+                                    }
+                                };
+                                let applies_to = format!("Applies to: {}", format_download_types(&rule.download_types));
+                                let conditions_summary = format!("Matches: {}", format_conditions(&rule.conditions));
+                                let event_summary = trigger_events(&rule.trigger_config)
+                                    .map(|events| format!("Runs when a download: {}", format_trigger_events(&events)));
+                                let cron_summary = rule.trigger_config.get("Cron")
+                                    .and_then(CronDraft::from_json)
+                                    .map(|cron| cron.summary());
+                                let cooldown_summary = rule.cooldown.as_ref()
+                                    .and_then(CooldownDraft::from_json)
+                                    .map(|cooldown| cooldown.summary());
+                                let quota_summary = rule.quota.as_ref()
+                                    .and_then(QuotaDraft::from_json)
+                                    .map(|quota| quota.summary());
+                                let window_summary = rule.schedule_window.as_ref()
+                                    .and_then(ScheduleWindowDraft::from_json)
+                                    .map(|window| window.summary());
+                                let rule_id_for_checkbox = rule_id;
+                                let is_selected = move || {
+                                    if let Some(id) = rule_id_for_checkbox {
+                                        selected_rules.get().contains(&id)
+                                    } else {
+                                        false
+                                    }
+                                };
+                                view! {
+                                    <div 
+                                        class="p-4 rounded-lg border transition-colors"
+                                        style="background-color: var(--bg-card); border-color: var(--border-secondary);"
+                                    >
+                                        <div class="flex items-center justify-between">
+                                            <div class="flex-1">
+                                                <div class="flex items-center space-x-3 mb-2">
+                                                    <input
+                                                        type="checkbox"
+                                                        class="w-5 h-5 rounded cursor-pointer"
+                                                        style="accent-color: var(--accent-primary);"
+                                                        checked=move || is_selected()
+                                                        on:change=move |ev| {
+                                                            if let Some(id) = rule_id_for_checkbox {
+                                                                let checked = event_target_checked(&ev);
+                                                                selected_rules.update(|set| {
+                                                                    if checked {
+                                                                        set.insert(id);
+                                                                    } else {
+                                                                        set.remove(&id);
+                                                                    }
+                                                                });
+                                                            }
+                                                        }
+                                                    />
+                                                    <h3 class="text-lg font-semibold" style="color: var(--text-primary); line-height: 1.5;">
+                                                        {rule.name.clone()}
+                                                    </h3>
+                                                    <span 
+                                                        class="px-2 py-1 text-xs rounded"
+                                                        style={move || {
+                                                            if rule.enabled && key_invalid() {
+                                                                "background-color: var(--bg-error); color: var(--text-error);"
+                                                            } else if rule.enabled {
+                                                                "background-color: var(--bg-success); color: var(--text-success);"
+                                                            } else {
+                                                                "background-color: var(--bg-secondary); color: var(--text-secondary);"
+                                                            }
+                                                        }}
+                                                        title=move || (rule.enabled && key_invalid()).then_some("Suspended: TorBox no longer accepts this API key")
+                                                    >
+                                                        {move || if rule.enabled && key_invalid() { "Key invalid" } else if rule.enabled { "Enabled" } else { "Disabled" }}
+                                                    </span>
+                                                </div>
+                                                <div class="space-y-1 mt-2">
+                                                    <p class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
+                                                        {applies_to}
+                                                    </p>
+                                                    <p class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
+                                                        {conditions_summary}
+                                                    </p>
+                                                    {event_summary.map(|summary| view! {
+                                                        <p class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
+                                                            {summary}
+                                                        </p>
+                                                    })}
+                                                    {cron_summary.map(|summary| view! {
+                                                        <p class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
+                                                            {summary}
+                                                        </p>
+                                                    })}
+                                                    {cooldown_summary.map(|summary| view! {
+                                                        <p class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
+                                                            {summary}
+                                                        </p>
+                                                    })}
+                                                    {quota_summary.map(|summary| view! {
+                                                        <p class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
+                                                            {summary}
+                                                        </p>
+                                                    })}
+                                                    {window_summary.map(|summary| view! {
+                                                        <p class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
+                                                            {summary}
+                                                        </p>
+                                                    })}
+                                                    <p class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
+                                                        {move || {
+                                                            let count = run_count();
+                                                            if count > 0 {
+                                                                format!("Ran {} time{}", count, if count == 1 { "" } else { "s" })
+                                                            } else {
+                                                                "Never run".to_string()
+                                                            }
+                                                        }}
+                                                    </p>
+                                                    <Show when=move || last_run().is_some()>
+                                                        <p class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
+                                                            {move || {
+                                                                if let Some(time) = last_run() {
+                                                                    format!("Last run: {}", format_timestamp(time))
+                                                                } else {
+                                                                    String::new()
+                                                                }
+                                                            }}
+                                                        </p>
+                                                    </Show>
+                                                    <Show when=move || rule.enabled && next_run().is_some()>
+                                                        <p class="text-xs" style="color: var(--text-primary); line-height: 1.5;">
+                                                            {move || {
+                                                                if let Some(time) = next_run() {
+                                                                    format!("Next run: {}", format_timestamp(time))
+                                                                } else {
+                                                                    String::new()
+                                                                }
+                                                            }}
+                                                        </p>
+                                                    </Show>
+                                                    <Show when=move || last_result().is_some()>
+                                                        <div class="space-y-2">
+                                                            <p class="text-sm font-medium" style={move || {
+                                                                if let Some((success, _, _, _, _, partial)) = last_result() {
+                                                                    if success && !partial {
+                                                                        "color: var(--text-success); line-height: 1.5;"
+                                                                    } else if partial {
+                                                                        "color: var(--text-warning); line-height: 1.5;"
+                                                                    } else {
+                                                                        "color: var(--text-error); line-height: 1.5;"
+                                                                    }
+                                                                } else {
+                                                                    "color: var(--text-secondary); line-height: 1.5;"
+                                                                }
+                                                            }}>
+                                                                {move || {
+                                                                    if let Some((success, items_processed, total_items, error, processed_items, partial)) = last_result() {
+                                                                        if partial {
+                                                                            format!("{}/{} items processed (partial)", items_processed, total_items)
+                                                                        } else if success {
+                                                                            if total_items > 0 {
+                                                                                format!("{}/{} items processed", items_processed, total_items)
+                                                                            } else {
+                                                                                "No items matched conditions".to_string()
+                                                                            }
+                                                                        } else {
+                                                                            format!("Failed: {}/{} processed - {}", items_processed, total_items, error.unwrap_or_else(|| "Unknown error".to_string()))
+                                                                        }
+                                                                    } else {
+                                                                        String::new()
+                                                                    }
+                                                                }}
+                                                            </p>
+                                                            <Show when=move || {
+                                                                if let Some((_, items_processed, _, _, processed_items, _)) = last_result() {
+                                                                    items_processed > 0 && processed_items.is_some() && !processed_items.as_ref().unwrap().is_empty()
+                                                                } else {
+                                                                    false
+                                                                }
+                                                            }>
+                                                                <div class="mt-1">
+                                                                    <button
+                                                                        class="text-xs cursor-pointer hover:opacity-80 transition-opacity font-medium flex items-center gap-1"
+                                                                        style="color: var(--text-secondary); background: none; border: none; padding: 0;"
+                                                                        on:click=toggle_expanded
+                                                                    >
+                                                                        <span>{move || {
+                                                                            if is_expanded() {
+                                                                                "▼ Hide Items"
+                                                                            } else {
+                                                                                "▶ Show Items"
+                                                                            }
+                                                                        }}</span>
+                                                                        {move || {
+                                                                            if let Some((_, items_processed, total_items, _, _, _)) = last_result() {
+                                                                                format!("({})", items_processed)
+                                                                            } else {
+                                                                                String::new()
+                                                                            }
+                                                                        }}
+                                                                    </button>
+                                                                    <Show when=move || is_expanded()>
+                                                                        <div class="mt-2 p-2 rounded border" style="background-color: var(--bg-tertiary); border-color: var(--border-secondary);">
+                                                                            <div class="space-y-1 max-h-64 overflow-y-auto">
+                                                                                <For
+                                                                                    each=move || {
+                                                                                        if let Some((_, _, _, _, Some(items), _)) = last_result() {
+                                                                                            items.iter().cloned().collect::<Vec<_>>()
+                                                                                        } else {
+                                                                                            Vec::new()
+                                                                                        }
+                                                                                    }
+                                                                                    key=|item| (item.download_type.clone(), item.id)
+                                                                                    children=move |item: ProcessedItem| {
+                                                                                        let item_name = item.name.clone();
+                                                                                        let item_name_title = item.name.clone();
+                                                                                        let item_action = item.action.clone();
+                                                                                        let item_success = item.success;
+                                                                                        let item_error = item.error.clone().unwrap_or_default();
+                                                                                        let item_steps = item.steps.clone();
+                                                                                        view! {
+                                                                                            <div class="space-y-0.5">
+                                                                                            <div class="flex items-center justify-between text-xs" style="color: var(--text-secondary);">
+                                                                                                <span class="truncate flex-1 mr-2" title={item_name_title}>
+                                                                                                    {item_name}
+                                                                                                </span>
+                                                                                                <div class="flex items-center gap-2 shrink-0">
+                                                                                                    <span class="px-1.5 py-0.5 rounded text-xs" style={
+                                                                                                        if item_success {
+                                                                                                            "background-color: var(--bg-success); color: var(--text-success);"
+                                                                                                        } else {
+                                                                                                            "background-color: var(--bg-error); color: var(--text-error);"
+                                                                                                        }
+                                                                                                    }>
+                                                                                                        {item_action}
+                                                                                                    </span>
+                                                                                                    <Show when=move || !item_success>
+                                                                                                        <span class="text-xs" style="color: var(--text-error);" title={item_error.clone()}>
+                                                                                                            "⚠"
+                                                                                                        </span>
+                                                                                                    </Show>
+                                                                                                </div>
+                                                                                            </div>
+                                                                                            {(item_steps.len() > 1).then(|| view! {
+                                                                                                <div class="flex flex-wrap gap-1 pl-2">
+                                                                                                    {item_steps.iter().map(|step| {
+                                                                                                        let title = step.error.clone().or_else(|| step.detail.clone()).unwrap_or_default();
+                                                                                                        view! {
+                                                                                                            <span class="px-1 rounded text-[10px]" title={title} style={
+                                                                                                                if step.success {
+                                                                                                                    "background-color: var(--bg-success); color: var(--text-success);"
+                                                                                                                } else {
+                                                                                                                    "background-color: var(--bg-error); color: var(--text-error);"
+                                                                                                                }
+                                                                                                            }>
+                                                                                                                {format!("{}. {}", step.step, step.action)}
+                                                                                                            </span>
+                                                                                                        }
+                                                                                                    }).collect::<Vec<_>>()}
+                                                                                                </div>
+                                                                                            })}
+                                                                                            </div>
+                                                                                        }
+                                                                                    }
+                                                                                />
+                                                                            </div>
+                                                                        </div>
+                                                                    </Show>
+                                                                </div>
+                                                            </Show>
+                                                        </div>
+                                                    </Show>
+                                                </div>
+                                            </div>
+                                            <div class="flex items-center gap-3 flex-wrap">
+                                                <button
+                                                    class="px-4 py-2 text-sm font-medium rounded-lg transition-all shrink-0 border"
+                                                    style={move || {
+                                                        if rule.enabled {
+                                                            "background-color: var(--bg-secondary); color: var(--text-secondary); border-color: var(--border-secondary); line-height: 1.5;"
+                                                        } else {
+                                                            "background-color: var(--bg-success); color: var(--text-success); border-color: var(--border-success); line-height: 1.5;"
+                                                        }
+                                                    }}
+                                                    on:click=move |_| {
+                                                        if let Some(id) = rule_id {
+                                                            toggle_rule(id, rule.enabled, rule.clone());
+                                                        }
+                                                    }
+                                                >
+                                                    {if rule.enabled { "Disable" } else { "Enable" }}
+                                                </button>
+                                                <button
+                                                    class="px-4 py-2 text-sm font-medium rounded-lg transition-all shrink-0 border hover:opacity-90"
+                                                    style="background-color: var(--bg-secondary); color: var(--text-primary); border-color: var(--border-secondary); line-height: 1.5;"
+                                                    on:click=move |_| {
+                                                        if let Some(id) = rule_id {
+                                                            editing_rule_id.set(Some(id));
+                                                            show_create_modal.set(true);
+                                                        }
+                                                    }
+                                                >
+                                                    "Edit"
+                                                </button>
+                                                <button
+                                                    class="px-4 py-2 text-sm font-medium rounded-lg transition-all shrink-0 border hover:opacity-90"
+                                                    style="background-color: var(--bg-secondary); color: var(--text-primary); border-color: var(--border-secondary); line-height: 1.5;"
+                                                    title=rule_revision.map(|revision| format!("Revision {}", revision)).unwrap_or_default()
+                                                    on:click=move |_| history_rule_id.set(rule_id)
+                                                >
+                                                    "History"
+                                                </button>
+                                                <button
+                                                    class="px-4 py-2 text-sm font-medium rounded-lg transition-all shrink-0 border hover:opacity-90 flex items-center gap-2"
+                                                    style="background-color: var(--accent-primary); color: var(--text-on-accent); border-color: var(--accent-primary); line-height: 1.5;"
+                                                    disabled=move || {
+                                                        if let Some(id) = rule_id {
+                                                            running_rules.get().contains(&id)
+                                                        } else {
+                                                            false
+                                                        }
+                                                    }
+                                                    on:click=move |_| {
+                                                        if let Some(id) = rule_id {
+                                                            let rule_id_clone = id;
+                                                            let running_rules_clone = running_rules.clone();
+                                                            let error_clone = error.clone();
+                                                            let rules_clone = rules.clone();
+                                                            let loading_clone = loading.clone();
+                                                            let rule_limit_clone = rule_limit.clone();
+                                                            #[cfg(feature = "hydrate")]
+                                                            {
+                                                                running_rules_clone.update(|set| {
+                                                                    set.insert(rule_id_clone);
+                                                                });
+                                                                spawn_local(async move {
+                                                                    if let Some(window) = web_sys::window() {
+                                                                        if let Ok(Some(storage)) = window.local_storage() {
+                                                                            if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                                                                                if !api_key.is_empty() {
+                                                                                    let url = format!("/api/automation/rules/{}/run", rule_id_clone);
+                                                                                    let headers = web_sys::Headers::new().unwrap();
+                                                                                    headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+                                                                                    headers.set("Content-Type", "application/json").unwrap();
+
+                                                                                    let init = {
+                                                                                        let mut i = web_sys::RequestInit::new();
+                                                                                        i.set_method("POST");
+                                                                                        i.set_headers(&headers);
+                                                                                        i
+                                                                                    };
+
+                                                                                    let promise = window.fetch_with_str_and_init(&url, &init);
+                                                                                    let future = wasm_bindgen_futures::JsFuture::from(promise);
+
+                                                                                    if let Ok(response) = future.await {
+                                                                                        let resp: web_sys::Response = response.dyn_into().unwrap();
+                                                                                        let text_promise = resp.text().unwrap();
+                                                                                        let text_future = wasm_bindgen_futures::JsFuture::from(text_promise);
+                                                                                        if let Ok(text_value) = text_future.await {
+                                                                                            if let Some(text) = text_value.as_string() {
+                                                                                                if resp.status() == 200 {
+                                                                                                    if let Ok(result) = serde_json::from_str::<serde_json::Value>(&text) {
+                                                                                                        if let Some(data) = result.get("data") {
+                                                                                                            if let Some(items_processed) = data.get("items_processed") {
+                                                                                                                let items_count = items_processed.as_i64().unwrap_or(0);
+                                                                                                                let total_items = data.get("total_items").and_then(|v| v.as_i64()).unwrap_or(items_count);
+                                                                                                                let partial = data.get("partial").and_then(|v| v.as_bool()).unwrap_or(false);
+                                                                                                                if let Some(success) = data.get("success") {
+                                                                                                                    if success.as_bool().unwrap_or(false) && !partial {
+                                                                                                                        if total_items > 0 {
+                                                                                                                            error_clone.set(Some(format!("Rule executed successfully: {}/{} items processed", items_count, total_items)));
+                                                                                                                        } else {
+                                                                                                                            error_clone.set(Some("Rule executed: No items matched conditions".to_string()));
+                                                                                                                        }
+                                                                                                                    } else if partial {
+                                                                                                                        error_clone.set(Some(format!("Rule partially completed: {}/{} items processed", items_count, total_items)));
+                                                                                                                    } else {
+                                                                                                                        if let Some(err_msg) = data.get("error_message") {
+                                                                                                                            error_clone.set(Some(format!("Rule execution had errors: {}", err_msg.as_str().unwrap_or("Unknown error"))));
+                                                                                                                        } else {
+                                                                                                                            error_clone.set(Some("Rule execution failed".to_string()));
+                                                                                                                        }
+                                                                                                                    }
+                                                                                                                }
+                                                                                                            }
+                                                                                                        }
+                                                                                                    }
+                                                                                                    loading_clone.set(true);
+                                                                                                    error_clone.set(None);
+                                                                                                    let fetch_url = "/api/automation/rules";
+                                                                                                    let fetch_headers = web_sys::Headers::new().unwrap();
+                                                                                                    fetch_headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+                                                                                                    let fetch_init = {
+                                                                                                        let mut i = web_sys::RequestInit::new();
+                                                                                                        i.set_method("GET");
+                                                                                                        i.set_headers(&fetch_headers);
+                                                                                                        i
+                                                                                                    };
+                                                                                                    let fetch_promise = window.fetch_with_str_and_init(fetch_url, &fetch_init);
+                                                                                                    let fetch_future = wasm_bindgen_futures::JsFuture::from(fetch_promise);
+                                                                                                    if let Ok(fetch_response) = fetch_future.await {
+                                                                                                        let fetch_resp: web_sys::Response = fetch_response.dyn_into().unwrap();
+                                                                                                        if fetch_resp.status() == 200 {
+                                                                                                            let fetch_text_promise = fetch_resp.text().unwrap();
+                                                                                                            let fetch_text_future = wasm_bindgen_futures::JsFuture::from(fetch_text_promise);
+                                                                                                            if let Ok(fetch_text_value) = fetch_text_future.await {
+                                                                                                                if let Some(fetch_text) = fetch_text_value.as_string() {
+                                                                                                                    if let Ok(fetch_api_response) = serde_json::from_str::<ApiResponse<Vec<AutomationRule>>>(&fetch_text) {
+                                                                                                                        if let Some(fetch_data) = fetch_api_response.data {
+                                                                                                                            rules_clone.set(fetch_data);
+                                                                                                                        }
+                                                                                                                    }
+                                                                                                                }
+                                                                                                            }
+                                                                                                        }
+                                                                                                    }
+                                                                                                    let limit_url = "/api/automation/rules/limit";
+                                                                                                    let limit_headers = web_sys::Headers::new().unwrap();
+                                                                                                    limit_headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+                                                                                                    let limit_init = {
+                                                                                                        let mut i = web_sys::RequestInit::new();
+                                                                                                        i.set_method("GET");
+                                                                                                        i.set_headers(&limit_headers);
+                                                                                                        i
+                                                                                                    };
+                                                                                                    let limit_promise = window.fetch_with_str_and_init(limit_url, &limit_init);
+                                                                                                    let limit_future = wasm_bindgen_futures::JsFuture::from(limit_promise);
+                                                                                                    if let Ok(limit_response) = limit_future.await {
+                                                                                                        let limit_resp: web_sys::Response = limit_response.dyn_into().unwrap();
+                                                                                                        if limit_resp.status() == 200 {
+                                                                                                            let limit_text_promise = limit_resp.text().unwrap();
+                                                                                                            let limit_text_future = wasm_bindgen_futures::JsFuture::from(limit_text_promise);
+                                                                                                            if let Ok(limit_text_value) = limit_text_future.await {
+                                                                                                                if let Some(limit_text) = limit_text_value.as_string() {
+                                                                                                                    if let Ok(limit_api_response) = serde_json::from_str::<ApiResponse<RuleLimitInfo>>(&limit_text) {
+                                                                                                                        if let Some(limit_data) = limit_api_response.data {
+                                                                                                                            rule_limit_clone.set(Some(limit_data));
+                                                                                                                        }
+                                                                                                                    }
+                                                                                                                }
+                                                                                                            }
+                                                                                                        }
+                                                                                                    }
+                                                                                                    loading_clone.set(false);
+                                                                                                } else {
+                                                                                                    error_clone.set(Some(format!("Failed to run rule: {}", resp.status())));
+                                                                                                }
+                                                                                            }
+                                                                                        }
+                                                                                    } else {
+                                                                                        error_clone.set(Some("Network request failed".to_string()));
+                                                                                    }
+                                                                                } else {
+                                                                                    error_clone.set(Some("No API key found".to_string()));
+                                                                                }
+                                                                            } else {
+                                                                                error_clone.set(Some("No API key found".to_string()));
+                                                                            }
+                                                                        } else {
+                                                                            error_clone.set(Some("Storage not available".to_string()));
+                                                                        }
+                                                                    } else {
+                                                                        error_clone.set(Some("Window not available".to_string()));
+                                                                    }
+                                                                    running_rules_clone.update(|set| {
+                                                                        set.remove(&rule_id_clone);
+                                                                    });
+                                                                });
+                                                            }
+                                                        }
+                                                    }
+                                                >
+                                                    <Show when=move || {
+                                                        if let Some(id) = rule_id {
+                                                            running_rules.get().contains(&id)
+                                                        } else {
+                                                            false
+                                                        }
+                                                    }>
+                                                        <LoadingSpinner size=SpinnerSize::Small variant=SpinnerVariant::Default/>
+                                                    </Show>
+                                                    {move || {
+                                                        if let Some(id) = rule_id {
+                                                            if running_rules.get().contains(&id) {
+                                                                "Running..."
+                                                            } else {
+                                                                "Run Now"
+                                                            }
+                                                        } else {
+                                                            "Run Now"
+                                                        }
+                                                    }}
+                                                </button>
+                                                <button
+                                                    class="px-4 py-2 text-sm font-medium rounded-lg transition-all shrink-0 border hover:opacity-90"
+                                                    style="background-color: var(--bg-error); color: var(--text-error); border-color: var(--border-error); line-height: 1.5;"
+                                                    on:click={
+                                                        let rule_name = rule_name_for_delete.clone();
+                                                        let confirmation_state_clone = confirmation_state.clone();
+                                                        let delete_rule_clone = delete_rule.clone();
+                                                        move |_| {
+                                                            if let Some(id) = rule_id {
+                                                                show_confirmation(
+                                                                    confirmation_state_clone.clone(),
+                                                                    "Delete Automation Rule".to_string(),
+                                                                    format!("Are you sure you want to delete the rule \"{}\"? This action cannot be undone.", rule_name),
+                                                                    move || {
+                                                                        delete_rule_clone(id);
+                                                                    },
+                                                                    ConfirmationVariant::Danger,
+                                                                    Some("Delete".to_string()),
+                                                                    Some("Cancel".to_string()),
+                                                                );
+                                                            }
+                                                        }
+                                                    }
+                                                >
+                                                    "Delete"
+                                                </button>
+                                            </div>
+                                        </div>
+                                    </div>
+                                }
+                            }).collect::<Vec<_>>()
+                        }}
+                    </div>
+                </Show>
+
+                <RuleModal 
+                    show=show_create_modal
+                    editing_rule_id=editing_rule_id
+                    rules=rules
+                    preset_data=preset_data
+                    on_save=move || {
+                        show_create_modal.set(false);
+                        editing_rule_id.set(None);
+                        preset_data.set(None);
+                        fetch_rules();
+                        fetch_rule_limit();
+                    }
+                />
+
+                <ImportRulesModal
+                    show=show_import_modal
+                    on_imported=move || {
+                        fetch_rules();
+                        fetch_rule_limit();
+                    }
+                />
+
+                <RuleHistoryModal
+                    rule_id=history_rule_id
+                    rules=rules
+                    on_restored=move || fetch_rules()
+                />
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn RuleModal(
+    show: RwSignal<bool>,
+    editing_rule_id: RwSignal<Option<i64>>,
+    rules: RwSignal<Vec<AutomationRule>>,
+    preset_data: RwSignal<Option<(String, u32, String, String, f64, String)>>,
+    on_save: impl Fn() + 'static + Send + Sync,
+) -> impl IntoView {
+    let rule_name = RwSignal::new(String::new());
+    let rule_enabled = RwSignal::new(true);
+    let download_types = RwSignal::new(vec!["Torrent".to_string()]);
+    let trigger_type = RwSignal::new("interval".to_string());
+    let cron = RwSignal::new(CronDraft::new());
+    let next_runs = RwSignal::new(None::<Result<NextRuns, String>>);
+    let interval_minutes = RwSignal::new(60u32);
+    let trigger_event_list = RwSignal::new(vec!["Finished".to_string()]);
+    let condition_tree = RwSignal::new(ConditionDraft::root_with(ConditionDraft::leaf("SeedingTime", "GreaterThan", 24.0)));
+    let action_steps = RwSignal::new(vec![ActionStepDraft::new("StopSeeding")]);
+    let saving = RwSignal::new(false);
+    let save_error = RwSignal::new(None::<String>);
+    let preview = RwSignal::new(None::<PreviewResult>);
+    let previewing = RwSignal::new(false);
+    let notification = RwSignal::new(None::<NotificationDraft>);
+    let notification_test = RwSignal::new(None::<(bool, String)>);
+    let schedule_window = RwSignal::new(None::<ScheduleWindowDraft>);
+    let quota = RwSignal::new(None::<QuotaDraft>);
+    let cooldown = RwSignal::new(None::<CooldownDraft>);
+
+    Effect::new(move |_| {
+        if show.get() {
+            if let Some(rule_id) = editing_rule_id.get() {
+                if let Some(rule) = rules.get().iter().find(|r| r.id == Some(rule_id)) {
+                    rule_name.set(rule.name.clone());
+                    rule_enabled.set(rule.enabled);
+                    download_types.set(if rule.download_types.is_empty() { vec!["Torrent".to_string()] } else { rule.download_types.clone() });
+                    
+                    if let Ok(trigger) = serde_json::from_value::<serde_json::Map<String, serde_json::Value>>(rule.trigger_config.clone()) {
+                        if trigger.contains_key("Cron") {
+                            trigger_type.set("cron".to_string());
+                            if let Some(draft) = trigger.get("Cron").and_then(CronDraft::from_json) {
+                                cron.set(draft);
+                            }
+                        } else if trigger.contains_key("Interval") {
+                            trigger_type.set("interval".to_string());
+                            if let Some(mins) = trigger.get("Interval").and_then(|v| v.get("minutes")).and_then(|v| v.as_u64()) {
+                                interval_minutes.set(mins as u32);
+                            }
+                        } else if let Some(events) = trigger_events(&rule.trigger_config) {
+                            trigger_type.set("event".to_string());
+                            trigger_event_list.set(events);
+                        }
+                    }
+                    
+                    if let Some(draft) = ConditionDraft::from_json(&rule.conditions) {
+                        condition_tree.set(if matches!(draft.kind, ConditionDraftKind::Group { .. }) && !draft.negated {
+                            draft
+                        } else {
+                            ConditionDraft::root_with(draft)
+                        });
+                    }
+                    
+                    let steps = ActionStepDraft::list_from_json(&rule.action_config);
+                    if !steps.is_empty() {
+                        action_steps.set(steps);
+                    }
+                    notification.set(rule.notification.as_ref().and_then(NotificationDraft::from_json));
+                    schedule_window.set(rule.schedule_window.as_ref().and_then(ScheduleWindowDraft::from_json));
+                    quota.set(rule.quota.as_ref().and_then(QuotaDraft::from_json));
+                    cooldown.set(rule.cooldown.as_ref().and_then(CooldownDraft::from_json));
+                }
+            } else if let Some((name, minutes, cond_type, cond_op, cond_val, act_type)) = preset_data.get() {
+                // Apply preset values
+                rule_name.set(name);
+                rule_enabled.set(true);
+                download_types.set(vec!["Torrent".to_string()]);
+                trigger_type.set("interval".to_string());
+                interval_minutes.set(minutes);
+                condition_tree.set(ConditionDraft::root_with(ConditionDraft::leaf(&cond_type, &cond_op, cond_val)));
+                action_steps.set(vec![ActionStepDraft::new(&act_type)]);
+                notification.set(None);
+                schedule_window.set(None);
+                quota.set(None);
+                cooldown.set(None);
+                preset_data.set(None); // Clear preset after applying
+            } else {
+                rule_name.set(String::new());
+                rule_enabled.set(true);
+                download_types.set(vec!["Torrent".to_string()]);
+                trigger_type.set("interval".to_string());
+                cron.set(CronDraft::new());
+                interval_minutes.set(60);
+                trigger_event_list.set(vec!["Finished".to_string()]);
+                condition_tree.set(ConditionDraft::root_with(ConditionDraft::leaf("SeedingTime", "GreaterThan", 24.0)));
+                action_steps.set(vec![ActionStepDraft::new("StopSeeding")]);
+                notification.set(None);
+                schedule_window.set(None);
+                quota.set(None);
+                cooldown.set(None);
+            }
+            save_error.set(None);
+            preview.set(None);
+            notification_test.set(None);
+            next_runs.set(None);
+        }
+    });
+
+    // Ask the server when the cron trigger being edited would fire, so the
+    // builder and custom expressions are checked by the same parser the
+    // scheduler uses.
+    Effect::new(move |_| {
+        if !show.get() || trigger_type.get() != "cron" {
+            return;
+        }
+        let draft = cron.get();
+        #[cfg(feature = "hydrate")]
+        {
+            let body = serde_json::json!({
+                "trigger_config": { "Cron": draft.to_json() },
+                "count": 5,
+            });
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+                                headers.set("Content-Type", "application/json").unwrap();
+
+                                let body_js = wasm_bindgen::JsValue::from_str(&body.to_string());
+
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("POST");
+                                    i.set_headers(&headers);
+                                    i.set_body(&body_js);
+                                    i
+                                };
+
+                                let promise = window.fetch_with_str_and_init("/api/automation/triggers/next-runs", &init);
+                                let future = wasm_bindgen_futures::JsFuture::from(promise);
+
+                                let result = match future.await {
+                                    Ok(response) => {
+                                        let resp: web_sys::Response = response.dyn_into().unwrap();
+                                        let text = match resp.text() {
+                                            Ok(text_promise) => wasm_bindgen_futures::JsFuture::from(text_promise).await.ok().and_then(|v| v.as_string()).unwrap_or_default(),
+                                            Err(_) => String::new(),
+                                        };
+                                        match serde_json::from_str::<ApiResponse<NextRuns>>(&text) {
+                                            Ok(api_response) if api_response.success => api_response.data.ok_or_else(|| "No schedule returned".to_string()),
+                                            Ok(api_response) => Err(api_response.error.unwrap_or_else(|| "Invalid schedule".to_string())),
+                                            Err(_) => Err(format!("Failed to check schedule: {}", resp.status())),
+                                        }
+                                    }
+                                    Err(_) => Err("Network request failed".to_string()),
+                                };
+                                // A slower response for an earlier edit must not replace a newer one.
+                                if cron.get_untracked() == draft {
+                                    next_runs.set(Some(result));
+                                }
+                            }
+                        }
+                    }
+                }
+            });
+        }
+        #[cfg(not(feature = "hydrate"))]
+        let _ = draft;
+    });
+
+    #[cfg(feature = "hydrate")]
+    let build_request = move || {
+        let trigger_config = if trigger_type.get() == "cron" {
+            serde_json::json!({
+                "Cron": cron.get().to_json()
+            })
+        } else if trigger_type.get() == "event" {
+            serde_json::json!({
+                "OnEvent": {
+                    "events": trigger_event_list.get()
+                }
+            })
+        } else {
+            serde_json::json!({
+                "Interval": {
+                    "minutes": interval_minutes.get()
+                }
+            })
+        };
+
+        CreateRuleRequest {
+            name: rule_name.get(),
+            enabled: Some(rule_enabled.get()),
+            download_types: download_types.get(),
+            trigger_config,
+            conditions: condition_tree.get().to_json(),
+            action_config: ActionStepDraft::list_to_json(&action_steps.get()),
+            notification: notification.get().map(|draft| draft.to_json()),
+            schedule_window: schedule_window.get().map(|draft| draft.to_json()),
+            quota: quota.get().map(|draft| draft.to_json()),
+            cooldown: cooldown.get().map(|draft| draft.to_json()),
+        }
+    };
+
+    let send_test_notification = move |_| {
+        #[cfg(feature = "hydrate")]
+        {
+            let Some(draft) = notification.get() else {
+                return;
+            };
+            notification_test.set(None);
+            let body = serde_json::json!({
+                "rule_name": rule_name.get(),
+                "notification": draft.to_json(),
+            });
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+                                headers.set("Content-Type", "application/json").unwrap();
+
+                                let body_js = wasm_bindgen::JsValue::from_str(&body.to_string());
 
-                                                                            let init = {
-                                                                                let mut i = web_sys::RequestInit::new();
-                                                                                i.set_method("POST");
-                                                                                i.set_headers(&headers);
-                                                                                i.set_body(&body_js);
-                                                                                i
-                                                                            };
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("POST");
+                                    i.set_headers(&headers);
+                                    i.set_body(&body_js);
+                                    i
+                                };
 
-                                                                            let promise = window.fetch_with_str_and_init("/api/automation/rules/bulk-delete", &init);
-                                                                            let future = wasm_bindgen_futures::JsFuture::from(promise);
+                                let promise = window.fetch_with_str_and_init("/api/automation/notifications/test", &init);
+                                let future = wasm_bindgen_futures::JsFuture::from(promise);
 
-                                                                            if let Ok(response) = future.await {
-                                                                                let resp: web_sys::Response = response.dyn_into().unwrap();
-                                                                                if resp.status() == 200 {
-                                                                                    let text_promise = resp.text().unwrap();
-                                                                                    let text_future = wasm_bindgen_futures::JsFuture::from(text_promise);
-                                                                                    if let Ok(text_value) = text_future.await {
-                                                                                        if let Some(text) = text_value.as_string() {
-                                                                                            if let Ok(api_response) = serde_json::from_str::<ApiResponse<serde_json::Value>>(&text) {
-                                                                                                if api_response.success {
-                                                                                                    selected_rules.set(std::collections::HashSet::new());
-                                                                                                    fetch_rules();
-                                                                                                    fetch_rule_limit();
-                                                                                                } else {
-                                                                                                    error.set(Some(api_response.error.unwrap_or_else(|| "Failed to delete rules".to_string())));
-                                                                                                }
-                                                                                            } else {
-                                                                                                error.set(Some("Failed to parse response".to_string()));
-                                                                                            }
-                                                                                        } else {
-                                                                                            error.set(Some("Invalid response format".to_string()));
-                                                                                        }
-                                                                                    } else {
-                                                                                        error.set(Some("Failed to read response".to_string()));
-                                                                                    }
-                                                                                } else {
-                                                                                    error.set(Some(format!("Failed to delete rules: {}", resp.status())));
-                                                                                }
-                                                                            } else {
-                                                                                error.set(Some("Network request failed".to_string()));
-                                                                            }
-                                                                        }
-                                                                    }
-                                                                }
-                                                            }
-                                                        });
-                                                    }
-                                                },
-                                                ConfirmationVariant::Danger,
-                                                Some("Delete".to_string()),
-                                                None
-                                            );
-                                        }
-                                    }
-                                >
-                                        "Delete Selected"
-                                    </button>
-                                </Show>
-                        </div>
-                        {move || {
-                            rules.get().into_iter().map(|rule| {
-                                let rule_id = rule.id;
-                                let rule_name_for_delete = rule.name.clone();
-                                let logs_for_rule = move || {
-                                    if let Some(id) = rule_id {
-                                        rule_logs.get().get(&id).cloned().unwrap_or_default()
-                                    } else {
-                                        Vec::new()
-                                    }
-                                };
-                                let format_timestamp = move |timestamp_str: String| -> String {
-                                    #[cfg(feature = "hydrate")]
-                                    {
-                                        if let Ok(parsed) = chrono::DateTime::parse_from_rfc3339(&timestamp_str) {
-                                            let utc_timestamp = parsed.timestamp_millis();
-                                            if let Some(_window) = web_sys::window() {
-                                                let js_date = js_sys::Date::new(&wasm_bindgen::JsValue::from_f64(utc_timestamp as f64));
-                                                let month = js_date.get_month() as u32;
-                                                let day = js_date.get_date() as u32;
-                                                let year = js_date.get_full_year() as u32;
-                                                let hours = js_date.get_hours() as u32;
-                                                let minutes = js_date.get_minutes() as u32;
-                                                let am_pm = if hours < 12 { "AM" } else { "PM" };
-                                                let display_hours = if hours == 0 { 12 } else if hours > 12 { hours - 12 } else { hours };
-                                                let month_names = ["January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December"];
-                                                format!("{} {}, {} at {}:{:02} {}", month_names[month as usize], day, year, display_hours, minutes, am_pm)
-                                            } else {
-                                                timestamp_str
+                                if let Ok(response) = future.await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                    if let Ok(text_promise) = resp.text() {
+                                        if let Ok(text_value) = wasm_bindgen_futures::JsFuture::from(text_promise).await {
+                                            let text = text_value.as_string().unwrap_or_default();
+                                            match serde_json::from_str::<ApiResponse<serde_json::Value>>(&text) {
+                                                Ok(api_response) if api_response.success => notification_test.set(Some((true, "Test notification sent".to_string()))),
+                                                Ok(api_response) => notification_test.set(Some((false, api_response.error.unwrap_or_else(|| "Test notification failed".to_string())))),
+                                                Err(_) => notification_test.set(Some((false, format!("Test notification failed: {}", resp.status())))),
                                             }
-                                        } else {
-                                            timestamp_str
                                         }
                                     }
-                                    #[cfg(not(feature = "hydrate"))]
-                                    {
-                                        timestamp_str
-                                    }
-                                };
-                                let last_run = move || {
-                                    logs_for_rule().first().and_then(|log| log.executed_at.clone())
-                                };
-                                let run_count = move || logs_for_rule().len();
-                                let last_result = move || {
-                                    logs_for_rule().first().map(|log| {
-                                        let total = log.total_items.unwrap_or(log.items_processed);
-                                        (log.success, log.items_processed, total, log.error_message.clone(), log.processed_items.clone(), log.partial.unwrap_or(false))
-                                    })
-                                };
-                                let expanded_items_clone = expanded_items.clone();
-                                let is_expanded = move || {
-                                    if let Some(id) = rule_id {
-                                        expanded_items_clone.get().contains(&id)
-                                    } else {
-                                        false
-                                    }
+                                } else {
+                                    notification_test.set(Some((false, "Network request failed".to_string())));
+                                }
+                            }
+                        }
+                    }
+                }
+            });
+        }
+    };
+
+    let run_preview = move |_| {
+        #[cfg(feature = "hydrate")]
+        {
+            previewing.set(true);
+            save_error.set(None);
+            let request = build_request();
+            let url = match editing_rule_id.get_untracked() {
+                Some(rule_id) => format!("/api/automation/rules/preview?rule_id={}", rule_id),
+                None => "/api/automation/rules/preview".to_string(),
+            };
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+                                headers.set("Content-Type", "application/json").unwrap();
+
+                                let body = serde_json::to_string(&request).unwrap();
+                                let body_js = wasm_bindgen::JsValue::from_str(&body);
+
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("POST");
+                                    i.set_headers(&headers);
+                                    i.set_body(&body_js);
+                                    i
                                 };
-                                let expanded_items_toggle = expanded_items.clone();
-                                let toggle_expanded = move |_| {
-                                    if let Some(id) = rule_id {
-                                        expanded_items_toggle.update(|set| {
-                                            if set.contains(&id) {
-                                                set.remove(&id);
-                                            } else {
-                                                set.insert(id);
+
+                                let promise = window.fetch_with_str_and_init(&url, &init);
+                                let future = wasm_bindgen_futures::JsFuture::from(promise);
+
+                                if let Ok(response) = future.await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                    if let Ok(text_promise) = resp.text() {
+                                        if let Ok(text_value) = wasm_bindgen_futures::JsFuture::from(text_promise).await {
+                                            let text = text_value.as_string().unwrap_or_default();
+                                            match serde_json::from_str::<ApiResponse<PreviewResult>>(&text) {
+                                                Ok(api_response) if api_response.success => preview.set(api_response.data),
+                                                Ok(api_response) => save_error.set(Some(api_response.error.unwrap_or_else(|| "Preview failed".to_string()))),
+                                                Err(_) => save_error.set(Some(format!("Preview failed: {}", resp.status()))),
                                             }
-                                        });
-                                    }
-                                };
-                                let next_run = move || {
-                                    if let Some(id) = rule_id {
-                                        next_run_times.get().get(&id).cloned().flatten()
-                                    } else {
-                                        None
+                                        }
                                     }
-                                };
-                                let rule_id_for_checkbox = rule_id;
-                                let is_selected = move || {
-                                    if let Some(id) = rule_id_for_checkbox {
-                                        selected_rules.get().contains(&id)
-                                    } else {
-                                        false
+                                } else {
+                                    save_error.set(Some("Network request failed".to_string()));
+                                }
+                            }
+                        }
+                    }
+                }
+                previewing.set(false);
+            });
+        }
+    };
+
+    let on_save_arc = Arc::new(on_save);
+    let on_save_arc_clone = on_save_arc.clone();
+    let editing_rule_id_save = editing_rule_id.clone();
+    let saving_save = saving.clone();
+    let save_error_save = save_error.clone();
+
+    view! {
+        <Show
+            when=move || show.get()
+        >
+            <div 
+                style="position: fixed !important; top: 0 !important; left: 0 !important; right: 0 !important; bottom: 0 !important; width: 100vw !important; height: 100vh !important; background-color: rgba(0, 0, 0, 0.75) !important; backdrop-filter: blur(4px) !important; z-index: 2147483647 !important; display: flex !important; align-items: center !important; justify-content: center !important; padding: 0.5rem !important; overflow-y: auto !important; box-sizing: border-box !important;"
+                on:click=move |_| {
+                    if !saving.get() {
+                        show.set(false);
+                    }
+                }
+            >
+                <div 
+                    class="rounded-xl border shadow-2xl modal-content"
+                    style="background-color: var(--bg-card) !important; border-color: var(--border-secondary) !important; z-index: 2147483647 !important; position: relative !important; width: calc(100% - 1rem) !important; max-width: 48rem !important; max-height: calc(100vh - 1rem) !important; margin: auto !important; overflow: hidden !important; display: flex !important; flex-direction: column !important;"
+                    on:click=|ev| ev.stop_propagation()
+                >
+                    <div class="flex-shrink-0 px-4 sm:px-6 pt-4 sm:pt-6 pb-3 sm:pb-4 border-b" style="border-color: var(--border-secondary);">
+                        <div class="flex items-center justify-between">
+                            <div>
+                                <h3 class="text-xl sm:text-2xl font-bold mb-1" style="color: var(--text-primary); line-height: 1.3;">
+                                    {move || if editing_rule_id.get().is_some() { "Edit Automation Rule" } else { "Create Automation Rule" }}
+                                </h3>
+                                <p class="text-xs sm:text-sm" style="color: var(--text-secondary); line-height: 1.5;">
+                                    {move || if editing_rule_id.get().is_some() { "Modify your automation rule settings" } else { "Set up a new automation to manage your downloads" }}
+                                </p>
+                            </div>
+                            <button
+                                class="flex items-center justify-center w-7 h-7 sm:w-8 sm:h-8 rounded-lg transition-colors shrink-0 hover:bg-opacity-10"
+                                style="color: var(--text-secondary); background-color: var(--bg-secondary);"
+                                on:click=move |_| {
+                                    if !saving.get() {
+                                        show.set(false);
                                     }
-                                };
-                                view! {
-                                    <div 
-                                        class="p-4 rounded-lg border transition-colors"
-                                        style="background-color: var(--bg-card); border-color: var(--border-secondary);"
-                                    >
-                                        <div class="flex items-center justify-between">
-                                            <div class="flex-1">
-                                                <div class="flex items-center space-x-3 mb-2">
-                                                    <input
-                                                        type="checkbox"
-                                                        class="w-5 h-5 rounded cursor-pointer"
-                                                        style="accent-color: var(--accent-primary);"
-                                                        checked=move || is_selected()
-                                                        on:change=move |ev| {
-                                                            if let Some(id) = rule_id_for_checkbox {
+                                }
+                                title="Close"
+                            >
+                                <span class="text-xl leading-none">"×"</span>
+                            </button>
+                        </div>
+                    </div>
+
+                    <div class="flex-1 overflow-y-auto px-4 sm:px-6 md:px-8 py-4 sm:py-6 md:py-8" style="min-height: 0 !important;">
+                        <Show when=move || save_error.get().is_some()>
+                            <div class="mb-6 p-4 rounded-lg border" style="background-color: var(--bg-error); border-color: var(--border-error, #ef4444); color: var(--text-error); line-height: 1.5;">
+                                <div class="flex items-start">
+                                    <span class="text-lg mr-2">"⚠"</span>
+                                    <div class="flex-1">
+                                        <p class="font-medium mb-1">"Error"</p>
+                                        <p class="text-sm">{move || save_error.get().unwrap_or_default()}</p>
+                                    </div>
+                                </div>
+                            </div>
+                        </Show>
+
+                        <div class="space-y-8">
+                            <div class="p-6">
+                                <div class="mb-5">
+                                    <h4 class="text-lg font-bold mb-2" style="color: var(--text-primary); line-height: 1.4;">
+                                        "Basic Information"
+                                    </h4>
+                                    <div class="h-0.5 w-12 rounded-full" style="background-color: var(--accent-primary);"></div>
+                                </div>
+                                <div class="space-y-6">
+                                    <div style="margin-bottom: 1.5rem;">
+                                        <label class="block text-sm font-semibold mb-3" style="color: var(--text-primary); line-height: 1.5;">
+                                            "Rule Name"
+                                        </label>
+                                        <input
+                                            type="text"
+                                            placeholder="e.g., Stop seeding after 48h"
+                                            class="w-full px-4 py-3 rounded-lg border transition-all focus:outline-none focus:ring-2"
+                                            style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary); font-size: 0.9375rem; focus:border-color: var(--accent-primary); focus:ring-color: var(--accent-primary); focus:ring-width: 2px;"
+                                            value=move || rule_name.get()
+                                            on:input=move |ev| rule_name.set(event_target_value(&ev))
+                                            disabled=move || saving.get()
+                                        />
+                                        <p class="text-xs mt-2.5" style="color: var(--text-secondary); line-height: 1.6;">
+                                            "Give your rule a descriptive name to easily identify it later"
+                                        </p>
+                                    </div>
+
+                                    <div class="flex items-center p-4 rounded-lg border" style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); margin-top: 1.5rem;">
+                                        <input
+                                            type="checkbox"
+                                            id="rule-enabled"
+                                            class="w-5 h-5 rounded cursor-pointer"
+                                            style="accent-color: var(--accent-primary);"
+                                            checked=move || rule_enabled.get()
+                                            on:change=move |ev| rule_enabled.set(event_target_checked(&ev))
+                                            disabled=move || saving.get()
+                                        />
+                                        <label for="rule-enabled" class="ml-3.5 text-sm font-medium cursor-pointer" style="color: var(--text-primary); line-height: 1.5;">
+                                            "Enable this rule"
+                                        </label>
+                                    </div>
+
+                                    <div style="margin-top: 1.5rem;">
+                                        <label class="block text-sm font-semibold mb-3" style="color: var(--text-primary); line-height: 1.5;">
+                                            "Download Types"
+                                        </label>
+                                        <div class="flex flex-wrap gap-3">
+                                            {DOWNLOAD_TYPE_OPTIONS.iter().map(|(value, label)| {
+                                                let value = value.to_string();
+                                                let value_for_checked = value.clone();
+                                                view! {
+                                                    <label class="flex items-center gap-2 px-3 py-2 rounded-lg border cursor-pointer text-sm" style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary);">
+                                                        <input
+                                                            type="checkbox"
+                                                            class="w-4 h-4 rounded cursor-pointer"
+                                                            style="accent-color: var(--accent-primary);"
+                                                            checked=move || download_types.get().contains(&value_for_checked)
+                                                            on:change=move |ev| {
                                                                 let checked = event_target_checked(&ev);
-                                                                selected_rules.update(|set| {
+                                                                download_types.update(|types| {
+                                                                    types.retain(|t| t != &value);
                                                                     if checked {
-                                                                        set.insert(id);
-                                                                    } else {
-                                                                        set.remove(&id);
+                                                                        types.push(value.clone());
                                                                     }
                                                                 });
                                                             }
-                                                        }
-                                                    />
-                                                    <h3 class="text-lg font-semibold" style="color: var(--text-primary); line-height: 1.5;">
-                                                        {rule.name.clone()}
-                                                    </h3>
-                                                    <span 
-                                                        class="px-2 py-1 text-xs rounded"
-                                                        style={move || {
-                                                            if rule.enabled {
-                                                                "background-color: var(--bg-success); color: var(--text-success);"
-                                                            } else {
-                                                                "background-color: var(--bg-secondary); color: var(--text-secondary);"
-                                                            }
-                                                        }}
-                                                    >
-                                                        {if rule.enabled { "Enabled" } else { "Disabled" }}
-                                                    </span>
-                                                </div>
-                                                <div class="space-y-1 mt-2">
-                                                    <p class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
-                                                        {move || {
-                                                            let count = run_count();
-                                                            if count > 0 {
-                                                                format!("Ran {} time{}", count, if count == 1 { "" } else { "s" })
-                                                            } else {
-                                                                "Never run".to_string()
-                                                            }
-                                                        }}
-                                                    </p>
-                                                    <Show when=move || last_run().is_some()>
-                                                        <p class="text-xs" style="color: var(--text-secondary); line-height: 1.5;">
-                                                            {move || {
-                                                                if let Some(time) = last_run() {
-                                                                    format!("Last run: {}", format_timestamp(time))
-                                                                } else {
-                                                                    String::new()
-                                                                }
-                                                            }}
-                                                        </p>
-                                                    </Show>
-                                                    <Show when=move || rule.enabled && next_run().is_some()>
-                                                        <p class="text-xs" style="color: var(--text-primary); line-height: 1.5;">
-                                                            {move || {
-                                                                if let Some(time) = next_run() {
-                                                                    format!("Next run: {}", format_timestamp(time))
-                                                                } else {
-                                                                    String::new()
-                                                                }
-                                                            }}
-                                                        </p>
-                                                    </Show>
-                                                    <Show when=move || last_result().is_some()>
-                                                        <div class="space-y-2">
-                                                            <p class="text-sm font-medium" style={move || {
-                                                                if let Some((success, _, _, _, _, partial)) = last_result() {
-                                                                    if success && !partial {
-                                                                        "color: var(--text-success); line-height: 1.5;"
-                                                                    } else if partial {
-                                                                        "color: var(--text-warning); line-height: 1.5;"
-                                                                    } else {
-                                                                        "color: var(--text-error); line-height: 1.5;"
-                                                                    }
-                                                                } else {
-                                                                    "color: var(--text-secondary); line-height: 1.5;"
-                                                                }
-                                                            }}>
-                                                                {move || {
-                                                                    if let Some((success, items_processed, total_items, error, processed_items, partial)) = last_result() {
-                                                                        if partial {
-                                                                            format!("{}/{} items processed (partial)", items_processed, total_items)
-                                                                        } else if success {
-                                                                            if total_items > 0 {
-                                                                                format!("{}/{} items processed", items_processed, total_items)
-                                                                            } else {
-                                                                                "No items matched conditions".to_string()
-                                                                            }
-                                                                        } else {
-                                                                            format!("Failed: {}/{} processed - {}", items_processed, total_items, error.unwrap_or_else(|| "Unknown error".to_string()))
+                                                            disabled=move || saving.get()
+                                                        />
+                                                        {*label}
+                                                    </label>
+                                                }
+                                            }).collect::<Vec<_>>()}
+                                        </div>
+                                        <p class="text-xs mt-2.5" style="color: var(--text-secondary); line-height: 1.6;">
+                                            "Which downloads this rule checks. Seeding and swarm conditions only apply to torrents."
+                                        </p>
+                                    </div>
+                                </div>
+                            </div>
+
+                            <div class="p-6">
+                                <div class="mb-5">
+                                    <h4 class="text-lg font-bold mb-2" style="color: var(--text-primary); line-height: 1.4;">
+                                        "Schedule"
+                                    </h4>
+                                    <div class="h-0.5 w-12 rounded-full" style="background-color: var(--accent-primary);"></div>
+                                    <p class="text-xs mt-3" style="color: var(--text-secondary); line-height: 1.6;">
+                                        "Configure when this rule should automatically run and check your torrents"
+                                    </p>
+                                </div>
+                                <div class="space-y-6">
+                                    <div style="margin-bottom: 1.5rem;">
+                                        <label class="block text-sm font-semibold mb-3" style="color: var(--text-primary); line-height: 1.5;">
+                                            "Trigger Type"
+                                        </label>
+                                        <select
+                                            class="w-full px-4 py-3 rounded-lg border transition-all"
+                                            style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary); font-size: 0.9375rem;"
+                                            on:change=move |ev| trigger_type.set(event_target_value(&ev))
+                                            disabled=move || saving.get()
+                                        >
+                                            <option value="interval" selected=move || trigger_type.get() == "interval">"Interval (minutes)"</option>
+                                            <option value="cron" selected=move || trigger_type.get() == "cron">"Cron Expression"</option>
+                                            <option value="event" selected=move || trigger_type.get() == "event">"Download Events"</option>
+                                        </select>
+                                    </div>
+
+                                    <Show when=move || trigger_type.get() == "event">
+                                        <div style="margin-top: 1.5rem;">
+                                            <label class="block text-sm font-semibold mb-3" style="color: var(--text-primary); line-height: 1.5;">
+                                                "Run when a download..."
+                                            </label>
+                                            <div class="flex flex-wrap gap-4">
+                                                {DOWNLOAD_EVENT_OPTIONS.iter().map(|(value, label)| {
+                                                    let value = value.to_string();
+                                                    let value_for_checked = value.clone();
+                                                    view! {
+                                                        <label class="flex items-center gap-2 text-sm cursor-pointer" style="color: var(--text-primary);">
+                                                            <input
+                                                                type="checkbox"
+                                                                class="w-4 h-4 rounded cursor-pointer"
+                                                                style="accent-color: var(--accent-primary);"
+                                                                checked=move || trigger_event_list.get().contains(&value_for_checked)
+                                                                on:change=move |ev| {
+                                                                    let checked = event_target_checked(&ev);
+                                                                    trigger_event_list.update(|list| {
+                                                                        list.retain(|e| e != &value);
+                                                                        if checked {
+                                                                            list.push(value.clone());
                                                                         }
-                                                                    } else {
-                                                                        String::new()
-                                                                    }
-                                                                }}
-                                                            </p>
-                                                            <Show when=move || {
-                                                                if let Some((_, items_processed, _, _, processed_items, _)) = last_result() {
-                                                                    items_processed > 0 && processed_items.is_some() && !processed_items.as_ref().unwrap().is_empty()
-                                                                } else {
-                                                                    false
+                                                                    });
                                                                 }
-                                                            }>
-                                                                <div class="mt-1">
-                                                                    <button
-                                                                        class="text-xs cursor-pointer hover:opacity-80 transition-opacity font-medium flex items-center gap-1"
-                                                                        style="color: var(--text-secondary); background: none; border: none; padding: 0;"
-                                                                        on:click=toggle_expanded
-                                                                    >
-                                                                        <span>{move || {
-                                                                            if is_expanded() {
-                                                                                "▼ Hide Items"
-                                                                            } else {
-                                                                                "▶ Show Items"
-                                                                            }
-                                                                        }}</span>
-                                                                        {move || {
-                                                                            if let Some((_, items_processed, total_items, _, _, _)) = last_result() {
-                                                                                format!("({})", items_processed)
-                                                                            } else {
-                                                                                String::new()
-                                                                            }
-                                                                        }}
-                                                                    </button>
-                                                                    <Show when=move || is_expanded()>
-                                                                        <div class="mt-2 p-2 rounded border" style="background-color: var(--bg-tertiary); border-color: var(--border-secondary);">
-                                                                            <div class="space-y-1 max-h-64 overflow-y-auto">
-                                                                                <For
-                                                                                    each=move || {
-                                                                                        if let Some((_, _, _, _, Some(items), _)) = last_result() {
-                                                                                            items.iter().cloned().collect::<Vec<_>>()
-                                                                                        } else {
-                                                                                            Vec::new()
-                                                                                        }
-                                                                                    }
-                                                                                    key=|item| item.id
-                                                                                    children=move |item: ProcessedItem| {
-                                                                                        let item_name = item.name.clone();
-                                                                                        let item_name_title = item.name.clone();
-                                                                                        let item_action = item.action.clone();
-                                                                                        let item_success = item.success;
-                                                                                        let item_error = item.error.clone().unwrap_or_default();
-                                                                                        view! {
-                                                                                            <div class="flex items-center justify-between text-xs" style="color: var(--text-secondary);">
-                                                                                                <span class="truncate flex-1 mr-2" title={item_name_title}>
-                                                                                                    {item_name}
-                                                                                                </span>
-                                                                                                <div class="flex items-center gap-2 shrink-0">
-                                                                                                    <span class="px-1.5 py-0.5 rounded text-xs" style={
-                                                                                                        if item_success {
-                                                                                                            "background-color: var(--bg-success); color: var(--text-success);"
-                                                                                                        } else {
-                                                                                                            "background-color: var(--bg-error); color: var(--text-error);"
-                                                                                                        }
-                                                                                                    }>
-                                                                                                        {item_action}
-                                                                                                    </span>
-                                                                                                    <Show when=move || !item_success>
-                                                                                                        <span class="text-xs" style="color: var(--text-error);" title={item_error.clone()}>
-                                                                                                            "⚠"
-                                                                                                        </span>
-                                                                                                    </Show>
-                                                                                                </div>
-                                                                                            </div>
+                                                                disabled=move || saving.get()
+                                                            />
+                                                            {*label}
+                                                        </label>
+                                                    }
+                                                }).collect::<Vec<_>>()}
+                                            </div>
+                                            <p class="text-xs mt-2.5" style="color: var(--text-secondary); line-height: 1.6;">
+                                                "Download lists are checked about once a minute. The rule's conditions are then applied to the downloads that changed."
+                                            </p>
+                                        </div>
+                                    </Show>
+
+                                    <Show when=move || trigger_type.get() == "interval">
+                                        <div style="margin-top: 1.5rem;">
+                                            <label class="block text-sm font-semibold mb-3" style="color: var(--text-primary); line-height: 1.5;">
+                                                "Interval (minutes)"
+                                            </label>
+                                            <input
+                                                type="number"
+                                                min="30"
+                                                class="w-full px-4 py-3 rounded-lg border transition-all"
+                                                style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary); font-size: 0.9375rem;"
+                                                value=move || interval_minutes.get().to_string()
+                                                on:input=move |ev| {
+                                                    if let Ok(val) = event_target_value(&ev).parse::<u32>() {
+                                                        interval_minutes.set(if val < 30 { 30 } else { val });
+                                                    }
+                                                }
+                                                disabled=move || saving.get()
+                                            />
+                                            <p class="text-xs mt-2.5" style="color: var(--text-secondary); line-height: 1.6;">
+                                                "How often to check and run this rule (minimum 30 minutes)"
+                                            </p>
+                                        </div>
+                                    </Show>
+
+                                    <Show when=move || trigger_type.get() == "cron">
+                                        <div style="margin-top: 1.5rem;">
+                                            <label class="block text-sm font-semibold mb-3" style="color: var(--text-primary); line-height: 1.5;">
+                                                "Schedule"
+                                            </label>
+                                            {move || {
+                                                let draft = cron.get();
+                                                let mode = draft.mode.clone();
+                                                view! {
+                                                    <div class="space-y-3">
+                                                        <select
+                                                            class="w-full px-4 py-3 rounded-lg border transition-all"
+                                                            style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary); font-size: 0.9375rem;"
+                                                            on:change=move |ev| {
+                                                                let value = event_target_value(&ev);
+                                                                cron.update(|draft| {
+                                                                    if value == "custom" {
+                                                                        draft.expression = draft.to_expression();
+                                                                    }
+                                                                    draft.mode = value;
+                                                                });
+                                                            }
+                                                            disabled=move || saving.get()
+                                                        >
+                                                            {CRON_MODE_OPTIONS.iter().map(|(value, label)| {
+                                                                let selected = draft.mode == *value;
+                                                                view! { <option value=*value selected=selected>{*label}</option> }
+                                                            }).collect::<Vec<_>>()}
+                                                        </select>
+
+                                                        {(mode == "hourly").then(|| view! {
+                                                            <div class="flex items-center gap-2">
+                                                                <span class="text-sm" style="color: var(--text-secondary);">"At minute"</span>
+                                                                <input
+                                                                    type="number"
+                                                                    min="0"
+                                                                    max="59"
+                                                                    class="px-3 py-2 rounded-lg border text-sm w-24"
+                                                                    style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                                                    value=draft.minute.to_string()
+                                                                    on:change=move |ev| {
+                                                                        if let Ok(minute) = event_target_value(&ev).parse::<u32>() {
+                                                                            cron.update(|draft| draft.minute = minute.min(59));
+                                                                        }
+                                                                    }
+                                                                    disabled=move || saving.get()
+                                                                />
+                                                            </div>
+                                                        })}
+
+                                                        {(mode == "weekly").then(|| view! {
+                                                            <div class="flex flex-wrap gap-4">
+                                                                {WEEKDAY_OPTIONS.iter().map(|(value, label)| {
+                                                                    let value = value.to_string();
+                                                                    let checked = draft.days.contains(&value);
+                                                                    view! {
+                                                                        <label class="flex items-center gap-2 text-sm cursor-pointer" style="color: var(--text-primary);">
+                                                                            <input
+                                                                                type="checkbox"
+                                                                                class="w-4 h-4 rounded cursor-pointer"
+                                                                                style="accent-color: var(--accent-primary);"
+                                                                                checked=checked
+                                                                                on:change=move |ev| {
+                                                                                    let checked = event_target_checked(&ev);
+                                                                                    cron.update(|draft| {
+                                                                                        draft.days.retain(|d| d != &value);
+                                                                                        if checked {
+                                                                                            draft.days.push(value.clone());
                                                                                         }
-                                                                                    }
-                                                                                />
-                                                                            </div>
-                                                                        </div>
-                                                                    </Show>
-                                                                </div>
-                                                            </Show>
+                                                                                    });
+                                                                                }
+                                                                                disabled=move || saving.get()
+                                                                            />
+                                                                            {*label}
+                                                                        </label>
+                                                                    }
+                                                                }).collect::<Vec<_>>()}
+                                                            </div>
+                                                        })}
+
+                                                        {(mode == "monthly").then(|| view! {
+                                                            <div class="flex items-center gap-2">
+                                                                <span class="text-sm" style="color: var(--text-secondary);">"On day"</span>
+                                                                <input
+                                                                    type="number"
+                                                                    min="1"
+                                                                    max="28"
+                                                                    class="px-3 py-2 rounded-lg border text-sm w-24"
+                                                                    style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                                                    value=draft.day_of_month.to_string()
+                                                                    on:change=move |ev| {
+                                                                        if let Ok(day) = event_target_value(&ev).parse::<u32>() {
+                                                                            cron.update(|draft| draft.day_of_month = day.clamp(1, 28));
+                                                                        }
+                                                                    }
+                                                                    disabled=move || saving.get()
+                                                                />
+                                                                <span class="text-sm" style="color: var(--text-secondary);">"of every month"</span>
+                                                            </div>
+                                                        })}
+
+                                                        {matches!(mode.as_str(), "daily" | "weekly" | "monthly").then(|| view! {
+                                                            <div class="flex items-center gap-2">
+                                                                <span class="text-sm" style="color: var(--text-secondary);">"At"</span>
+                                                                <input
+                                                                    type="time"
+                                                                    class="px-3 py-2 rounded-lg border text-sm"
+                                                                    style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                                                    value=format!("{:02}:{:02}", draft.hour, draft.minute)
+                                                                    on:change=move |ev| {
+                                                                        let value = event_target_value(&ev);
+                                                                        let time = value.split_once(':').and_then(|(hour, minute)| Some((hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?)));
+                                                                        if let Some((hour, minute)) = time {
+                                                                            cron.update(|draft| {
+                                                                                draft.hour = hour.min(23);
+                                                                                draft.minute = minute.min(59);
+                                                                            });
+                                                                        }
+                                                                    }
+                                                                    disabled=move || saving.get()
+                                                                />
+                                                            </div>
+                                                        })}
+
+                                                        {(mode == "custom").then(|| view! {
+                                                            <div>
+                                                                <input
+                                                                    type="text"
+                                                                    placeholder="0 0 3 * * MON-FRI"
+                                                                    class="w-full px-4 py-3 rounded-lg border transition-all font-mono text-sm"
+                                                                    style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                                                    value=draft.expression.clone()
+                                                                    on:change=move |ev| cron.update(|draft| draft.expression = event_target_value(&ev))
+                                                                    disabled=move || saving.get()
+                                                                />
+                                                                <p class="text-xs mt-2.5" style="color: var(--text-secondary); line-height: 1.6;">
+                                                                    "Format: sec min hour day-of-month month day-of-week (e.g., '0 0 * * * *' = every hour at minute 0). Day-of-week numbers start at 1 for Sunday, so prefer names like MON-FRI."
+                                                                </p>
+                                                            </div>
+                                                        })}
+
+                                                        <input
+                                                            type="text"
+                                                            placeholder="Europe/Berlin"
+                                                            class="w-full px-4 py-3 rounded-lg border transition-all text-sm"
+                                                            style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                                            value=draft.timezone.clone()
+                                                            on:change=move |ev| cron.update(|draft| draft.timezone = event_target_value(&ev))
+                                                            disabled=move || saving.get()
+                                                        />
+                                                        <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
+                                                            {format!("{}. Times are read in this IANA timezone, so the rule follows daylight saving changes.", draft.summary())}
+                                                        </p>
+                                                    </div>
+                                                }
+                                            }}
+                                            <div class="p-3.5 rounded-lg mt-3" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
+                                                {move || match next_runs.get() {
+                                                    Some(Ok(next)) if next.runs.is_empty() => view! {
+                                                        <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">"This schedule never fires"</p>
+                                                    }.into_any(),
+                                                    Some(Ok(next)) => view! {
+                                                        <div class="space-y-1">
+                                                            <p class="text-xs font-semibold" style="color: var(--text-primary); line-height: 1.6;">
+                                                                {format!("Next runs ({})", next.timezone)}
+                                                            </p>
+                                                            {next.runs.iter().map(|run| {
+                                                                let label = chrono::DateTime::parse_from_rfc3339(run)
+                                                                    .map(|time| time.format("%a %b %-d %Y, %H:%M").to_string())
+                                                                    .unwrap_or_else(|_| run.clone());
+                                                                view! {
+                                                                    <p class="text-xs font-mono" style="color: var(--text-secondary); line-height: 1.6;">{label}</p>
+                                                                }
+                                                            }).collect::<Vec<_>>()}
                                                         </div>
-                                                    </Show>
-                                                </div>
+                                                    }.into_any(),
+                                                    Some(Err(e)) => view! {
+                                                        <p class="text-xs" style="color: var(--text-error); line-height: 1.6;">{e}</p>
+                                                    }.into_any(),
+                                                    None => view! {
+                                                        <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">"Checking schedule..."</p>
+                                                    }.into_any(),
+                                                }}
                                             </div>
-                                            <div class="flex items-center gap-3 flex-wrap">
-                                                <button
-                                                    class="px-4 py-2 text-sm font-medium rounded-lg transition-all shrink-0 border"
-                                                    style={move || {
-                                                        if rule.enabled {
-                                                            "background-color: var(--bg-secondary); color: var(--text-secondary); border-color: var(--border-secondary); line-height: 1.5;"
-                                                        } else {
-                                                            "background-color: var(--bg-success); color: var(--text-success); border-color: var(--border-success); line-height: 1.5;"
+                                        </div>
+                                    </Show>
+
+                                    <div style="margin-top: 1.5rem;">
+                                        <div class="flex items-center">
+                                            <input
+                                                type="checkbox"
+                                                id="rule-schedule-window"
+                                                class="w-5 h-5 rounded cursor-pointer"
+                                                style="accent-color: var(--accent-primary);"
+                                                checked=move || schedule_window.get().is_some()
+                                                on:change=move |ev| schedule_window.set(event_target_checked(&ev).then(ScheduleWindowDraft::new))
+                                                disabled=move || saving.get()
+                                            />
+                                            <label for="rule-schedule-window" class="ml-3.5 text-sm font-medium cursor-pointer" style="color: var(--text-primary); line-height: 1.5;">
+                                                "Only act during a schedule window"
+                                            </label>
+                                        </div>
+                                        {move || schedule_window.get().map(|draft| view! {
+                                            <div class="space-y-3 mt-3">
+                                                <div class="flex flex-wrap gap-4">
+                                                    {WEEKDAY_OPTIONS.iter().map(|(value, label)| {
+                                                        let value = value.to_string();
+                                                        let checked = draft.days.contains(&value);
+                                                        view! {
+                                                            <label class="flex items-center gap-2 text-sm cursor-pointer" style="color: var(--text-primary);">
+                                                                <input
+                                                                    type="checkbox"
+                                                                    class="w-4 h-4 rounded cursor-pointer"
+                                                                    style="accent-color: var(--accent-primary);"
+                                                                    checked=checked
+                                                                    on:change=move |ev| {
+                                                                        let checked = event_target_checked(&ev);
+                                                                        schedule_window.update(|window| {
+                                                                            if let Some(window) = window {
+                                                                                window.days.retain(|d| d != &value);
+                                                                                if checked {
+                                                                                    window.days.push(value.clone());
+                                                                                }
+                                                                            }
+                                                                        });
+                                                                    }
+                                                                    disabled=move || saving.get()
+                                                                />
+                                                                {*label}
+                                                            </label>
                                                         }
-                                                    }}
-                                                    on:click=move |_| {
-                                                        if let Some(id) = rule_id {
-                                                            toggle_rule(id, rule.enabled, rule.clone());
+                                                    }).collect::<Vec<_>>()}
+                                                </div>
+                                                {draft.ranges.iter().cloned().enumerate().map(|(idx, (start, end))| view! {
+                                                    <div class="flex items-center gap-2">
+                                                        <input
+                                                            type="time"
+                                                            class="px-3 py-2 rounded-lg border text-sm"
+                                                            style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                                            value=start
+                                                            on:change=move |ev| schedule_window.update(|window| {
+                                                                if let Some(range) = window.as_mut().and_then(|w| w.ranges.get_mut(idx)) {
+                                                                    range.0 = event_target_value(&ev);
+                                                                }
+                                                            })
+                                                            disabled=move || saving.get()
+                                                        />
+                                                        <span class="text-sm" style="color: var(--text-secondary);">"to"</span>
+                                                        <input
+                                                            type="time"
+                                                            class="px-3 py-2 rounded-lg border text-sm"
+                                                            style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                                            value=end
+                                                            on:change=move |ev| schedule_window.update(|window| {
+                                                                if let Some(range) = window.as_mut().and_then(|w| w.ranges.get_mut(idx)) {
+                                                                    range.1 = event_target_value(&ev);
+                                                                }
+                                                            })
+                                                            disabled=move || saving.get()
+                                                        />
+                                                        <button
+                                                            class="px-2 py-1 rounded text-xs"
+                                                            style="background-color: var(--bg-tertiary); color: var(--text-secondary); border: 1px solid var(--border-secondary);"
+                                                            on:click=move |_| schedule_window.update(|window| {
+                                                                if let Some(window) = window {
+                                                                    if idx < window.ranges.len() {
+                                                                        window.ranges.remove(idx);
+                                                                    }
+                                                                }
+                                                            })
+                                                            disabled=move || saving.get()
+                                                        >
+                                                            "Remove"
+                                                        </button>
+                                                    </div>
+                                                }).collect::<Vec<_>>()}
+                                                <button
+                                                    class="px-3 py-1.5 rounded-lg text-xs font-semibold transition-all"
+                                                    style="background-color: var(--bg-tertiary); color: var(--text-primary); border: 1px solid var(--border-secondary);"
+                                                    on:click=move |_| schedule_window.update(|window| {
+                                                        if let Some(window) = window {
+                                                            window.ranges.push(("00:00".to_string(), "06:00".to_string()));
                                                         }
-                                                    }
+                                                    })
+                                                    disabled=move || saving.get()
                                                 >
-                                                    {if rule.enabled { "Disable" } else { "Enable" }}
+                                                    "+ Add time range"
                                                 </button>
-                                                <button
-                                                    class="px-4 py-2 text-sm font-medium rounded-lg transition-all shrink-0 border hover:opacity-90"
-                                                    style="background-color: var(--bg-secondary); color: var(--text-primary); border-color: var(--border-secondary); line-height: 1.5;"
-                                                    on:click=move |_| {
-                                                        if let Some(id) = rule_id {
-                                                            editing_rule_id.set(Some(id));
-                                                            show_create_modal.set(true);
+                                                <input
+                                                    type="text"
+                                                    placeholder="Europe/Berlin"
+                                                    class="w-full px-4 py-3 rounded-lg border transition-all text-sm"
+                                                    style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                                    value=draft.timezone.clone()
+                                                    on:change=move |ev| schedule_window.update(|window| {
+                                                        if let Some(window) = window {
+                                                            window.timezone = event_target_value(&ev);
+                                                        }
+                                                    })
+                                                    disabled=move || saving.get()
+                                                />
+                                                <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
+                                                    {format!("{}. No days means every day, no time ranges means all day; a range ending before it starts runs past midnight. Scheduled and event runs outside the window are skipped and logged; \"Run Now\" ignores the window.", draft.summary())}
+                                                </p>
+                                            </div>
+                                        })}
+                                    </div>
+                                </div>
+                            </div>
+
+                            <div class="p-6">
+                                <div class="mb-5">
+                                    <h4 class="text-lg font-bold mb-2" style="color: var(--text-primary); line-height: 1.4;">
+                                        "Conditions"
+                                    </h4>
+                                    <div class="h-0.5 w-12 rounded-full" style="background-color: var(--accent-primary);"></div>
+                                    <p class="text-xs mt-3" style="color: var(--text-secondary); line-height: 1.6;">
+                                        "Define what criteria downloads must meet for this rule to apply. Combine conditions with AND/OR groups and tick NOT to invert a condition or group."
+                                    </p>
+                                </div>
+                                <div class="space-y-4">
+                                    {move || render_condition_node(condition_tree, condition_tree.get(), Vec::new(), saving)}
+                                    <div class="p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
+                                        <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
+                                            {move || format!("Matches: {}", condition_tree.get().summary(false))}
+                                        </p>
+                                    </div>
+                                </div>
+                            </div>
+
+                            <div class="p-6">
+                                <div class="mb-5">
+                                    <h4 class="text-lg font-bold mb-2" style="color: var(--text-primary); line-height: 1.4;">
+                                        "Cooldown"
+                                    </h4>
+                                    <div class="h-0.5 w-12 rounded-full" style="background-color: var(--accent-primary);"></div>
+                                </div>
+                                <div class="space-y-3">
+                                    <div class="flex items-center">
+                                        <input
+                                            type="checkbox"
+                                            id="rule-cooldown"
+                                            class="w-5 h-5 rounded cursor-pointer"
+                                            style="accent-color: var(--accent-primary);"
+                                            checked=move || cooldown.get().is_some()
+                                            on:change=move |ev| cooldown.set(event_target_checked(&ev).then(CooldownDraft::new))
+                                            disabled=move || saving.get()
+                                        />
+                                        <label for="rule-cooldown" class="ml-3.5 text-sm font-medium cursor-pointer" style="color: var(--text-primary); line-height: 1.5;">
+                                            "Don't keep acting on the same download"
+                                        </label>
+                                    </div>
+                                    {move || cooldown.get().map(|draft| view! {
+                                        <div class="space-y-3">
+                                            <div class="flex flex-wrap items-center gap-2">
+                                                <span class="text-sm" style="color: var(--text-secondary);">"Skip downloads this rule acted on within"</span>
+                                                <input
+                                                    type="number"
+                                                    min="0.25"
+                                                    step="0.25"
+                                                    placeholder="off"
+                                                    class="w-24 px-3 py-2 rounded-lg border text-sm"
+                                                    style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                                    value=draft.hours.map(|hours| hours.to_string()).unwrap_or_default()
+                                                    on:change=move |ev| {
+                                                        let hours = event_target_value(&ev).trim().parse::<f64>().ok();
+                                                        cooldown.update(|c| if let Some(c) = c { c.hours = hours; });
+                                                    }
+                                                    disabled=move || saving.get()
+                                                />
+                                                <span class="text-sm" style="color: var(--text-secondary);">"hours"</span>
+                                            </div>
+                                            <div class="flex flex-wrap items-center gap-2">
+                                                <span class="text-sm" style="color: var(--text-secondary);">"Stop after acting on a download"</span>
+                                                <input
+                                                    type="number"
+                                                    min="1"
+                                                    step="1"
+                                                    placeholder="off"
+                                                    class="w-24 px-3 py-2 rounded-lg border text-sm"
+                                                    style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                                    value=draft.max_actions.map(|max| max.to_string()).unwrap_or_default()
+                                                    on:change=move |ev| {
+                                                        let max = event_target_value(&ev).trim().parse::<u32>().ok();
+                                                        cooldown.update(|c| if let Some(c) = c { c.max_actions = max; });
+                                                    }
+                                                    disabled=move || saving.get()
+                                                />
+                                                <span class="text-sm" style="color: var(--text-secondary);">"times"</span>
+                                            </div>
+                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
+                                                {format!("{}. Only this rule's own successful actions count; use the History conditions to look at what every rule did.", draft.summary())}
+                                            </p>
+                                        </div>
+                                    })}
+                                </div>
+                            </div>
+
+                            <div class="p-6">
+                                <div class="mb-5">
+                                    <h4 class="text-lg font-bold mb-2" style="color: var(--text-primary); line-height: 1.4;">
+                                        "Storage Quota"
+                                    </h4>
+                                    <div class="h-0.5 w-12 rounded-full" style="background-color: var(--accent-primary);"></div>
+                                </div>
+                                <div class="space-y-3">
+                                    <div class="flex items-center">
+                                        <input
+                                            type="checkbox"
+                                            id="rule-quota"
+                                            class="w-5 h-5 rounded cursor-pointer"
+                                            style="accent-color: var(--accent-primary);"
+                                            checked=move || quota.get().is_some()
+                                            on:change=move |ev| quota.set(event_target_checked(&ev).then(QuotaDraft::new))
+                                            disabled=move || saving.get()
+                                        />
+                                        <label for="rule-quota" class="ml-3.5 text-sm font-medium cursor-pointer" style="color: var(--text-primary); line-height: 1.5;">
+                                            "Only act on as many downloads as it takes to stay under a limit"
+                                        </label>
+                                    </div>
+                                    {move || quota.get().map(|draft| {
+                                        let selected_sort = draft.sort_by.clone();
+                                        let selected_kind = draft.target_kind.clone();
+                                        view! {
+                                            <div class="space-y-3">
+                                                <div class="flex flex-wrap items-center gap-2">
+                                                    <span class="text-sm" style="color: var(--text-secondary);">"Keep under"</span>
+                                                    <input
+                                                        type="number"
+                                                        min="0"
+                                                        step="1"
+                                                        class="w-28 px-3 py-2 rounded-lg border text-sm"
+                                                        style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                                        value=draft.target.to_string()
+                                                        on:change=move |ev| {
+                                                            if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+                                                                quota.update(|q| if let Some(q) = q { q.target = value; });
+                                                            }
+                                                        }
+                                                        disabled=move || saving.get()
+                                                    />
+                                                    <select
+                                                        class="px-3 py-2 rounded-lg border text-sm"
+                                                        style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                                        on:change=move |ev| {
+                                                            let kind = event_target_value(&ev);
+                                                            quota.update(|q| if let Some(q) = q { q.target_kind = kind; });
+                                                        }
+                                                        disabled=move || saving.get()
+                                                    >
+                                                        <option value="TotalSize" selected=selected_kind == "TotalSize">"GB in total"</option>
+                                                        <option value="ItemCount" selected=selected_kind == "ItemCount">"downloads"</option>
+                                                    </select>
+                                                    <select
+                                                        class="px-3 py-2 rounded-lg border text-sm"
+                                                        style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                                        on:change=move |ev| {
+                                                            let sort_by = event_target_value(&ev);
+                                                            quota.update(|q| if let Some(q) = q { q.sort_by = sort_by; });
                                                         }
+                                                        disabled=move || saving.get()
+                                                    >
+                                                        {QUOTA_SORT_OPTIONS.iter().map(|(value, label)| view! {
+                                                            <option value=*value selected=selected_sort == *value>{*label}</option>
+                                                        }).collect::<Vec<_>>()}
+                                                    </select>
+                                                </div>
+                                                <div class="flex flex-wrap items-center gap-2">
+                                                    <span class="text-sm" style="color: var(--text-secondary);">"At most"</span>
+                                                    <input
+                                                        type="number"
+                                                        min="1"
+                                                        step="1"
+                                                        placeholder="no limit"
+                                                        class="w-28 px-3 py-2 rounded-lg border text-sm"
+                                                        style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                                        value=draft.max_items_per_run.map(|max| max.to_string()).unwrap_or_default()
+                                                        on:change=move |ev| {
+                                                            let max = event_target_value(&ev).trim().parse::<u32>().ok();
+                                                            quota.update(|q| if let Some(q) = q { q.max_items_per_run = max; });
+                                                        }
+                                                        disabled=move || saving.get()
+                                                    />
+                                                    <span class="text-sm" style="color: var(--text-secondary);">"downloads per run"</span>
+                                                </div>
+                                                <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
+                                                    {format!("{}. The total counts every download of the selected types; only downloads matching the conditions are picked, in the chosen order, until the total is under the limit. Needs a cron or interval trigger and a Delete step.", draft.summary())}
+                                                </p>
+                                            </div>
+                                        }
+                                    })}
+                                </div>
+                            </div>
+
+                            <div class="p-6">
+                                <div class="mb-5">
+                                    <h4 class="text-lg font-bold mb-2" style="color: var(--text-primary); line-height: 1.4;">
+                                        "Actions"
+                                    </h4>
+                                    <div class="h-0.5 w-12 rounded-full" style="background-color: var(--accent-primary);"></div>
+                                    <p class="text-xs mt-3" style="color: var(--text-secondary); line-height: 1.6;">
+                                        "Steps run in order on every matching download. Each step's failure policy decides whether to continue, skip the rest of that download's steps, or stop the whole run."
+                                    </p>
+                                </div>
+                                {move || render_action_steps(action_steps, saving)}
+                            </div>
+
+                            <div class="p-6">
+                                <div class="mb-5">
+                                    <h4 class="text-lg font-bold mb-2" style="color: var(--text-primary); line-height: 1.4;">
+                                        "Notifications"
+                                    </h4>
+                                    <div class="h-0.5 w-12 rounded-full" style="background-color: var(--accent-primary);"></div>
+                                </div>
+                                <div class="space-y-3">
+                                    <div class="flex items-center">
+                                        <input
+                                            type="checkbox"
+                                            id="rule-notify"
+                                            class="w-5 h-5 rounded cursor-pointer"
+                                            style="accent-color: var(--accent-primary);"
+                                            checked=move || notification.get().is_some()
+                                            on:change=move |ev| {
+                                                notification.set(event_target_checked(&ev).then(NotificationDraft::new));
+                                                notification_test.set(None);
+                                            }
+                                            disabled=move || saving.get()
+                                        />
+                                        <label for="rule-notify" class="ml-3.5 text-sm font-medium cursor-pointer" style="color: var(--text-primary); line-height: 1.5;">
+                                            "Notify on completion"
+                                        </label>
+                                    </div>
+                                    {move || notification.get().map(|draft| view! {
+                                        <div class="space-y-2">
+                                            {notification_inputs(
+                                                draft,
+                                                true,
+                                                move |field, value| notification.update(|n| {
+                                                    if let Some(n) = n {
+                                                        n.set(field, value);
                                                     }
+                                                }),
+                                                saving,
+                                            )}
+                                            <div class="flex items-center gap-3">
+                                                <button
+                                                    class="px-3 py-1.5 rounded-lg text-xs font-semibold transition-all"
+                                                    style="background-color: var(--bg-tertiary); color: var(--text-primary); border: 1px solid var(--border-secondary);"
+                                                    on:click=send_test_notification
+                                                    disabled=move || saving.get()
                                                 >
-                                                    "Edit"
+                                                    "Send Test"
                                                 </button>
-                                                <button
-                                                    class="px-4 py-2 text-sm font-medium rounded-lg transition-all shrink-0 border hover:opacity-90 flex items-center gap-2"
-                                                    style="background-color: var(--accent-primary); color: var(--text-on-accent); border-color: var(--accent-primary); line-height: 1.5;"
-                                                    disabled=move || {
-                                                        if let Some(id) = rule_id {
-                                                            running_rules.get().contains(&id)
-                                                        } else {
-                                                            false
+                                                {move || notification_test.get().map(|(ok, message)| view! {
+                                                    <span class="text-xs" style={if ok { "color: var(--text-success);" } else { "color: var(--text-error);" }}>
+                                                        {message}
+                                                    </span>
+                                                })}
+                                            </div>
+                                        </div>
+                                    })}
+                                </div>
+                            </div>
+
+                            <Show when=move || preview.get().is_some()>
+                                <div class="p-6">
+                                    <div class="mb-5">
+                                        <h4 class="text-lg font-bold mb-2" style="color: var(--text-primary); line-height: 1.4;">
+                                            "Preview"
+                                        </h4>
+                                        <div class="h-0.5 w-12 rounded-full" style="background-color: var(--accent-primary);"></div>
+                                        <p class="text-xs mt-3" style="color: var(--text-secondary); line-height: 1.6;">
+                                            {move || preview.get().map(|p| {
+                                                let mut text = match &p.quota {
+                                                    Some(quota) => {
+                                                        let mut text = format!(
+                                                            "{} downloads use {}. {} of {} matching downloads would be picked, leaving {} downloads using {}.",
+                                                            quota.total_count,
+                                                            format_bytes(quota.total_size),
+                                                            p.matched_count,
+                                                            quota.matching,
+                                                            quota.projected_count,
+                                                            format_bytes(quota.projected_size),
+                                                        );
+                                                        if !quota.target_reached {
+                                                            text.push_str(if quota.capped {
+                                                                " The per-run limit stops it short of the target; later runs continue."
+                                                            } else {
+                                                                " Not enough downloads match to reach the target."
+                                                            });
                                                         }
+                                                        text.push_str(" No actions were run.");
+                                                        text
                                                     }
-                                                    on:click=move |_| {
-                                                        if let Some(id) = rule_id {
-                                                            let rule_id_clone = id;
-                                                            let running_rules_clone = running_rules.clone();
-                                                            let error_clone = error.clone();
-                                                            let rules_clone = rules.clone();
-                                                            let loading_clone = loading.clone();
-                                                            let rule_limit_clone = rule_limit.clone();
-                                                            #[cfg(feature = "hydrate")]
-                                                            {
-                                                                running_rules_clone.update(|set| {
-                                                                    set.insert(rule_id_clone);
-                                                                });
-                                                                spawn_local(async move {
-                                                                    if let Some(window) = web_sys::window() {
-                                                                        if let Ok(Some(storage)) = window.local_storage() {
-                                                                            if let Ok(Some(api_key)) = storage.get_item("api_key") {
-                                                                                if !api_key.is_empty() {
-                                                                                    let url = format!("/api/automation/rules/{}/run", rule_id_clone);
-                                                                                    let headers = web_sys::Headers::new().unwrap();
-                                                                                    headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
-                                                                                    headers.set("Content-Type", "application/json").unwrap();
+                                                    None => format!("{} of {} downloads currently match. No actions were run.", p.matched_count, p.total_items),
+                                                };
+                                                if p.on_cooldown > 0 {
+                                                    text.push_str(&format!(" {} more match but are on cooldown.", p.on_cooldown));
+                                                }
+                                                if p.truncated {
+                                                    text.push_str(&format!(" Showing the first {}.", p.matched_items.len()));
+                                                }
+                                                text
+                                            }).unwrap_or_default()}
+                                        </p>
+                                    </div>
+                                    <div class="space-y-2 max-h-80 overflow-y-auto">
+                                        {move || preview.get().map(|p| p.matched_items.into_iter().map(|item| {
+                                            let item_title = item.name.clone();
+                                            view! {
+                                                <div class="p-3 rounded-lg border text-xs space-y-1" style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-secondary);">
+                                                    <div class="flex items-center justify-between gap-2">
+                                                        <span class="truncate font-semibold" style="color: var(--text-primary);" title={item_title}>{item.name}</span>
+                                                        <span class="shrink-0">{format!("{} · {} · {}", item.download_type, format_bytes(item.size), item.download_state)}</span>
+                                                    </div>
+                                                    {item.conditions.into_iter().map(|measurement| {
+                                                        let value = match &measurement.value {
+                                                            serde_json::Value::Null => "n/a".to_string(),
+                                                            serde_json::Value::Number(n) => n.as_f64().map(|v| format!("{:.2}", v)).unwrap_or_else(|| n.to_string()),
+                                                            serde_json::Value::String(text) => text.clone(),
+                                                            other => other.to_string(),
+                                                        };
+                                                        view! {
+                                                            <div class="flex items-center justify-between gap-2">
+                                                                <span class="truncate">{format!("{}. {}", measurement.index, measurement.label)}</span>
+                                                                <span class="shrink-0" style={if measurement.matched { "color: var(--text-success);" } else { "color: var(--text-secondary);" }}>
+                                                                    {format!("{} {}", value, if measurement.matched { "✓" } else { "✗" })}
+                                                                </span>
+                                                            </div>
+                                                        }
+                                                    }).collect::<Vec<_>>()}
+                                                </div>
+                                            }
+                                        }).collect::<Vec<_>>())}
+                                    </div>
+                                </div>
+                            </Show>
+                        </div>
+                    </div>
+
+                    <div class="flex-shrink-0 px-4 sm:px-6 md:px-8 py-4 sm:py-5 md:py-6 border-t flex flex-row justify-end gap-3 sm:gap-4" style="border-color: var(--border-primary); background-color: var(--bg-secondary);">
+                        <button
+                            class="px-6 py-3 rounded-lg transition-all font-semibold text-sm"
+                            style="background-color: var(--bg-tertiary); color: var(--text-primary); border: 1px solid var(--border-secondary); line-height: 1.5; display: inline-flex !important; align-items: center; justify-content: center; width: auto !important; min-width: 100px; max-width: none !important; flex: 0 0 auto !important;"
+                            on:click=run_preview
+                            disabled=move || saving.get() || previewing.get()
+                        >
+                            {move || if previewing.get() { "Previewing..." } else { "Preview" }}
+                        </button>
+                        <button
+                            class="px-6 py-3 rounded-lg transition-all font-semibold text-sm"
+                            style="background-color: var(--bg-tertiary); color: var(--text-primary); border: 1px solid var(--border-secondary); line-height: 1.5; display: inline-flex !important; align-items: center; justify-content: center; width: auto !important; min-width: 100px; max-width: none !important; flex: 0 0 auto !important;"
+                            on:click=move |_| {
+                                if !saving.get() {
+                                    show.set(false);
+                                }
+                            }
+                            disabled=move || saving.get()
+                        >
+                            "Cancel"
+                        </button>
+                        <button
+                            class="px-6 py-3 rounded-lg font-semibold transition-all text-sm"
+                            style="background-color: var(--accent-primary); color: var(--text-on-accent); line-height: 1.5; display: inline-flex !important; align-items: center; justify-content: center; box-shadow: var(--shadow-md); width: auto !important; min-width: 100px; max-width: none !important; flex: 0 0 auto !important;"
+                                on:click={
+                                    let on_save_for_click = on_save_arc_clone.clone();
+                                    move |_| {
+                                        #[cfg(feature = "hydrate")]
+                                        {
+                                            saving_save.set(true);
+                                            save_error_save.set(None);
+                                            let editing_rule_id_clone = editing_rule_id_save.clone();
+                                            let saving_clone = saving_save.clone();
+                                            let save_error_clone = save_error_save.clone();
+                                            let on_save_clone = on_save_for_click.clone();
+                                        
+                                        spawn_local(async move {
+                                            if let Some(window) = web_sys::window() {
+                                                if let Ok(Some(storage)) = window.local_storage() {
+                                                    if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                                                        if !api_key.is_empty() {
+                                                            let request = build_request();
 
-                                                                                    let init = {
-                                                                                        let mut i = web_sys::RequestInit::new();
-                                                                                        i.set_method("POST");
-                                                                                        i.set_headers(&headers);
-                                                                                        i
-                                                                                    };
+                                                            let url = if editing_rule_id_clone.get().is_some() {
+                                                                format!("/api/automation/rules/{}", editing_rule_id_clone.get().unwrap())
+                                                            } else {
+                                                                "/api/automation/rules".to_string()
+                                                            };
 
-                                                                                    let promise = window.fetch_with_str_and_init(&url, &init);
-                                                                                    let future = wasm_bindgen_futures::JsFuture::from(promise);
+                                                            let method = if editing_rule_id_clone.get().is_some() { "PUT" } else { "POST" };
 
-                                                                                    if let Ok(response) = future.await {
-                                                                                        let resp: web_sys::Response = response.dyn_into().unwrap();
-                                                                                        let text_promise = resp.text().unwrap();
-                                                                                        let text_future = wasm_bindgen_futures::JsFuture::from(text_promise);
-                                                                                        if let Ok(text_value) = text_future.await {
-                                                                                            if let Some(text) = text_value.as_string() {
-                                                                                                if resp.status() == 200 {
-                                                                                                    if let Ok(result) = serde_json::from_str::<serde_json::Value>(&text) {
-                                                                                                        if let Some(data) = result.get("data") {
-                                                                                                            if let Some(items_processed) = data.get("items_processed") {
-                                                                                                                let items_count = items_processed.as_i64().unwrap_or(0);
-                                                                                                                let total_items = data.get("total_items").and_then(|v| v.as_i64()).unwrap_or(items_count);
-                                                                                                                let partial = data.get("partial").and_then(|v| v.as_bool()).unwrap_or(false);
-                                                                                                                if let Some(success) = data.get("success") {
-                                                                                                                    if success.as_bool().unwrap_or(false) && !partial {
-                                                                                                                        if total_items > 0 {
-                                                                                                                            error_clone.set(Some(format!("Rule executed successfully: {}/{} items processed", items_count, total_items)));
-                                                                                                                        } else {
-                                                                                                                            error_clone.set(Some("Rule executed: No items matched conditions".to_string()));
-                                                                                                                        }
-                                                                                                                    } else if partial {
-                                                                                                                        error_clone.set(Some(format!("Rule partially completed: {}/{} items processed", items_count, total_items)));
-                                                                                                                    } else {
-                                                                                                                        if let Some(err_msg) = data.get("error_message") {
-                                                                                                                            error_clone.set(Some(format!("Rule execution had errors: {}", err_msg.as_str().unwrap_or("Unknown error"))));
-                                                                                                                        } else {
-                                                                                                                            error_clone.set(Some("Rule execution failed".to_string()));
-                                                                                                                        }
-                                                                                                                    }
-                                                                                                                }
-                                                                                                            }
-                                                                                                        }
-                                                                                                    }
-                                                                                                    loading_clone.set(true);
-                                                                                                    error_clone.set(None);
-                                                                                                    let fetch_url = "/api/automation/rules";
-                                                                                                    let fetch_headers = web_sys::Headers::new().unwrap();
-                                                                                                    fetch_headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
-                                                                                                    let fetch_init = {
-                                                                                                        let mut i = web_sys::RequestInit::new();
-                                                                                                        i.set_method("GET");
-                                                                                                        i.set_headers(&fetch_headers);
-                                                                                                        i
-                                                                                                    };
-                                                                                                    let fetch_promise = window.fetch_with_str_and_init(fetch_url, &fetch_init);
-                                                                                                    let fetch_future = wasm_bindgen_futures::JsFuture::from(fetch_promise);
-                                                                                                    if let Ok(fetch_response) = fetch_future.await {
-                                                                                                        let fetch_resp: web_sys::Response = fetch_response.dyn_into().unwrap();
-                                                                                                        if fetch_resp.status() == 200 {
-                                                                                                            let fetch_text_promise = fetch_resp.text().unwrap();
-                                                                                                            let fetch_text_future = wasm_bindgen_futures::JsFuture::from(fetch_text_promise);
-                                                                                                            if let Ok(fetch_text_value) = fetch_text_future.await {
-                                                                                                                if let Some(fetch_text) = fetch_text_value.as_string() {
-                                                                                                                    if let Ok(fetch_api_response) = serde_json::from_str::<ApiResponse<Vec<AutomationRule>>>(&fetch_text) {
-                                                                                                                        if let Some(fetch_data) = fetch_api_response.data {
-                                                                                                                            rules_clone.set(fetch_data);
-                                                                                                                        }
-                                                                                                                    }
-                                                                                                                }
-                                                                                                            }
-                                                                                                        }
-                                                                                                    }
-                                                                                                    let limit_url = "/api/automation/rules/limit";
-                                                                                                    let limit_headers = web_sys::Headers::new().unwrap();
-                                                                                                    limit_headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
-                                                                                                    let limit_init = {
-                                                                                                        let mut i = web_sys::RequestInit::new();
-                                                                                                        i.set_method("GET");
-                                                                                                        i.set_headers(&limit_headers);
-                                                                                                        i
-                                                                                                    };
-                                                                                                    let limit_promise = window.fetch_with_str_and_init(limit_url, &limit_init);
-                                                                                                    let limit_future = wasm_bindgen_futures::JsFuture::from(limit_promise);
-                                                                                                    if let Ok(limit_response) = limit_future.await {
-                                                                                                        let limit_resp: web_sys::Response = limit_response.dyn_into().unwrap();
-                                                                                                        if limit_resp.status() == 200 {
-                                                                                                            let limit_text_promise = limit_resp.text().unwrap();
-                                                                                                            let limit_text_future = wasm_bindgen_futures::JsFuture::from(limit_text_promise);
-                                                                                                            if let Ok(limit_text_value) = limit_text_future.await {
-                                                                                                                if let Some(limit_text) = limit_text_value.as_string() {
-                                                                                                                    if let Ok(limit_api_response) = serde_json::from_str::<ApiResponse<RuleLimitInfo>>(&limit_text) {
-                                                                                                                        if let Some(limit_data) = limit_api_response.data {
-                                                                                                                            rule_limit_clone.set(Some(limit_data));
-                                                                                                                        }
-                                                                                                                    }
-                                                                                                                }
-                                                                                                            }
-                                                                                                        }
-                                                                                                    }
-                                                                                                    loading_clone.set(false);
-                                                                                                } else {
-                                                                                                    error_clone.set(Some(format!("Failed to run rule: {}", resp.status())));
-                                                                                                }
-                                                                                            }
-                                                                                        }
-                                                                                    } else {
-                                                                                        error_clone.set(Some("Network request failed".to_string()));
-                                                                                    }
+                                                            let headers = web_sys::Headers::new().unwrap();
+                                                            headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+                                                            headers.set("Content-Type", "application/json").unwrap();
+                                                            
+                                                            let body = serde_json::to_string(&request).unwrap();
+                                                            let body_js = wasm_bindgen::JsValue::from_str(&body);
+                                                            
+                                                            let init = {
+                                                                let mut i = web_sys::RequestInit::new();
+                                                                i.set_method(method);
+                                                                i.set_headers(&headers);
+                                                                i.set_body(&body_js);
+                                                                i
+                                                            };
+                                                            
+                                                            let promise = window.fetch_with_str_and_init(&url, &init);
+                                                            let future = wasm_bindgen_futures::JsFuture::from(promise);
+                                                            
+                                                            if let Ok(response) = future.await {
+                                                                let resp: web_sys::Response = response.dyn_into().unwrap();
+                                                                if resp.status() == 200 || resp.status() == 201 {
+                                                                    saving_clone.set(false);
+                                                                    on_save_clone();
+                                                                } else {
+                                                                    let text_promise = resp.text().unwrap();
+                                                                    let text_future = wasm_bindgen_futures::JsFuture::from(text_promise);
+                                                                    if let Ok(text_value) = text_future.await {
+                                                                        if let Some(text) = text_value.as_string() {
+                                                                            if let Ok(api_response) = serde_json::from_str::<ApiResponse<serde_json::Value>>(&text) {
+                                                                                if let Some(err_msg) = api_response.error {
+                                                                                    save_error_clone.set(Some(err_msg));
                                                                                 } else {
-                                                                                    error_clone.set(Some("No API key found".to_string()));
+                                                                                    save_error_clone.set(Some(format!("Failed to save: {}", text)));
                                                                                 }
                                                                             } else {
-                                                                                error_clone.set(Some("No API key found".to_string()));
+                                                                                save_error_clone.set(Some(format!("Failed to save: {}", text)));
                                                                             }
                                                                         } else {
-                                                                            error_clone.set(Some("Storage not available".to_string()));
+                                                                            save_error_clone.set(Some(format!("Failed to save: {}", resp.status())));
                                                                         }
                                                                     } else {
-                                                                        error_clone.set(Some("Window not available".to_string()));
+                                                                        save_error_clone.set(Some(format!("Failed to save: {}", resp.status())));
                                                                     }
-                                                                    running_rules_clone.update(|set| {
-                                                                        set.remove(&rule_id_clone);
-                                                                    });
-                                                                });
-                                                            }
-                                                        }
-                                                    }
-                                                >
-                                                    <Show when=move || {
-                                                        if let Some(id) = rule_id {
-                                                            running_rules.get().contains(&id)
-                                                        } else {
-                                                            false
-                                                        }
-                                                    }>
-                                                        <LoadingSpinner size=SpinnerSize::Small variant=SpinnerVariant::Default/>
-                                                    </Show>
-                                                    {move || {
-                                                        if let Some(id) = rule_id {
-                                                            if running_rules.get().contains(&id) {
-                                                                "Running..."
+                                                                    saving_clone.set(false);
+                                                                }
                                                             } else {
-                                                                "Run Now"
+                                                                save_error_clone.set(Some("Network request failed".to_string()));
+                                                                saving_clone.set(false);
                                                             }
-                                                        } else {
-                                                            "Run Now"
                                                         }
-                                                    }}
-                                                </button>
-                                                <button
-                                                    class="px-4 py-2 text-sm font-medium rounded-lg transition-all shrink-0 border hover:opacity-90"
-                                                    style="background-color: var(--bg-error); color: var(--text-error); border-color: var(--border-error); line-height: 1.5;"
-                                                    on:click={
-                                                        let rule_name = rule_name_for_delete.clone();
-                                                        let confirmation_state_clone = confirmation_state.clone();
-                                                        let delete_rule_clone = delete_rule.clone();
-                                                        move |_| {
-                                                            if let Some(id) = rule_id {
-                                                                show_confirmation(
-                                                                    confirmation_state_clone.clone(),
-                                                                    "Delete Automation Rule".to_string(),
-                                                                    format!("Are you sure you want to delete the rule \"{}\"? This action cannot be undone.", rule_name),
-                                                                    move || {
-                                                                        delete_rule_clone(id);
-                                                                    },
-                                                                    ConfirmationVariant::Danger,
-                                                                    Some("Delete".to_string()),
-                                                                    Some("Cancel".to_string()),
-                                                                );
-                                                            }
+                                                    }
+                                                }
+                                            }
+                                        });
+                                    }
+                                }
+                            }
+                            disabled=move || saving_save.get()
+                            >
+                                {move || if saving_save.get() { "Saving..." } else { "Save" }}
+                            </button>
+                        </div>
+                    </div>
+                </div>
+        </Show>
+    }
+}
+
+#[component]
+fn ImportRulesModal(
+    show: RwSignal<bool>,
+    on_imported: impl Fn() + 'static + Send + Sync,
+) -> impl IntoView {
+    let content = RwSignal::new(String::new());
+    let on_conflict = RwSignal::new("skip".to_string());
+    let importing = RwSignal::new(false);
+    let import_error = RwSignal::new(None::<String>);
+    let report = RwSignal::new(None::<ImportReport>);
+    let on_imported = StoredValue::new(on_imported);
+
+    Effect::new(move |_| {
+        if show.get() {
+            content.set(String::new());
+            on_conflict.set("skip".to_string());
+            import_error.set(None);
+            report.set(None);
+        }
+    });
+
+    let load_file = move |ev: leptos::ev::Event| {
+        #[cfg(feature = "hydrate")]
+        {
+            let file = ev.target()
+                .and_then(|target| target.dyn_into::<web_sys::HtmlInputElement>().ok())
+                .and_then(|input| input.files())
+                .and_then(|files| files.get(0));
+            if let Some(file) = file {
+                spawn_local(async move {
+                    match wasm_bindgen_futures::JsFuture::from(file.text()).await {
+                        Ok(text) => content.set(text.as_string().unwrap_or_default()),
+                        Err(_) => import_error.set(Some(format!("Failed to read {}", file.name()))),
+                    }
+                });
+            }
+        }
+        #[cfg(not(feature = "hydrate"))]
+        let _ = ev;
+    };
+
+    let run_import = move |_| {
+        #[cfg(feature = "hydrate")]
+        {
+            if content.get().trim().is_empty() {
+                import_error.set(Some("Paste a rule bundle or choose a file to import".to_string()));
+                return;
+            }
+            importing.set(true);
+            import_error.set(None);
+            report.set(None);
+            let body = serde_json::json!({
+                "content": content.get(),
+                "on_conflict": on_conflict.get(),
+            });
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+                                headers.set("Content-Type", "application/json").unwrap();
+
+                                let body_js = wasm_bindgen::JsValue::from_str(&body.to_string());
+
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("POST");
+                                    i.set_headers(&headers);
+                                    i.set_body(&body_js);
+                                    i
+                                };
+
+                                let promise = window.fetch_with_str_and_init("/api/automation/rules/import", &init);
+                                let future = wasm_bindgen_futures::JsFuture::from(promise);
+
+                                if let Ok(response) = future.await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                    if let Ok(text_promise) = resp.text() {
+                                        if let Ok(text_value) = wasm_bindgen_futures::JsFuture::from(text_promise).await {
+                                            let text = text_value.as_string().unwrap_or_default();
+                                            match serde_json::from_str::<ApiResponse<ImportReport>>(&text) {
+                                                Ok(api_response) if api_response.success => {
+                                                    if let Some(data) = api_response.data {
+                                                        if data.imported + data.replaced > 0 {
+                                                            on_imported.with_value(|on_imported| on_imported());
                                                         }
+                                                        report.set(Some(data));
                                                     }
-                                                >
-                                                    "Delete"
-                                                </button>
-                                            </div>
-                                        </div>
-                                    </div>
+                                                }
+                                                Ok(api_response) => import_error.set(Some(api_response.error.unwrap_or_else(|| "Import failed".to_string()))),
+                                                Err(_) => import_error.set(Some(format!("Import failed: {}", resp.status()))),
+                                            }
+                                        }
+                                    }
+                                } else {
+                                    import_error.set(Some("Network request failed".to_string()));
                                 }
-                            }).collect::<Vec<_>>()
-                        }}
-                    </div>
-                </Show>
+                            }
+                        }
+                    }
+                }
+                importing.set(false);
+            });
+        }
+    };
 
-                <RuleModal 
-                    show=show_create_modal
-                    editing_rule_id=editing_rule_id
-                    rules=rules
-                    preset_data=preset_data
-                    on_save=move || {
-                        show_create_modal.set(false);
-                        editing_rule_id.set(None);
-                        preset_data.set(None);
-                        fetch_rules();
-                        fetch_rule_limit();
+    view! {
+        <Show when=move || show.get()>
+            <div
+                style="position: fixed !important; top: 0 !important; left: 0 !important; right: 0 !important; bottom: 0 !important; width: 100vw !important; height: 100vh !important; background-color: rgba(0, 0, 0, 0.75) !important; backdrop-filter: blur(4px) !important; z-index: 2147483647 !important; display: flex !important; align-items: center !important; justify-content: center !important; padding: 0.5rem !important; overflow-y: auto !important; box-sizing: border-box !important;"
+                on:click=move |_| {
+                    if !importing.get() {
+                        show.set(false);
                     }
-                />
+                }
+            >
+                <div
+                    class="rounded-xl border shadow-2xl modal-content"
+                    style="background-color: var(--bg-card) !important; border-color: var(--border-secondary) !important; z-index: 2147483647 !important; position: relative !important; width: calc(100% - 1rem) !important; max-width: 40rem !important; max-height: calc(100vh - 1rem) !important; margin: auto !important; overflow: hidden !important; display: flex !important; flex-direction: column !important;"
+                    on:click=|ev| ev.stop_propagation()
+                >
+                    <div class="flex-shrink-0 px-4 sm:px-6 pt-4 sm:pt-6 pb-3 sm:pb-4 border-b" style="border-color: var(--border-secondary);">
+                        <div class="flex items-center justify-between">
+                            <div>
+                                <h3 class="text-xl sm:text-2xl font-bold mb-1" style="color: var(--text-primary); line-height: 1.3;">
+                                    "Import Automation Rules"
+                                </h3>
+                                <p class="text-xs sm:text-sm" style="color: var(--text-secondary); line-height: 1.5;">
+                                    "Load a JSON or YAML bundle exported from another account. Bundles don't carry notification header values, so enter those again after importing."
+                                </p>
+                            </div>
+                            <button
+                                class="flex items-center justify-center w-7 h-7 sm:w-8 sm:h-8 rounded-lg transition-colors shrink-0 hover:bg-opacity-10"
+                                style="color: var(--text-secondary); background-color: var(--bg-secondary);"
+                                on:click=move |_| {
+                                    if !importing.get() {
+                                        show.set(false);
+                                    }
+                                }
+                                title="Close"
+                            >
+                                <span class="text-xl leading-none">"×"</span>
+                            </button>
+                        </div>
+                    </div>
+
+                    <div class="flex-1 overflow-y-auto px-4 sm:px-6 py-4 space-y-4">
+                        <Show when=move || import_error.get().is_some()>
+                            <div class="p-4 rounded-lg border" style="background-color: var(--bg-error); border-color: var(--border-error, #ef4444); color: var(--text-error); line-height: 1.5;">
+                                <div class="flex items-start">
+                                    <span class="text-lg mr-2">"⚠"</span>
+                                    <div class="flex-1">
+                                        <p class="font-medium mb-1">"Error"</p>
+                                        <p class="text-sm">{move || import_error.get().unwrap_or_default()}</p>
+                                    </div>
+                                </div>
+                            </div>
+                        </Show>
+
+                        <input
+                            type="file"
+                            accept=".json,.yaml,.yml,application/json,application/yaml"
+                            class="text-sm"
+                            style="color: var(--text-secondary);"
+                            on:change=load_file
+                            disabled=move || importing.get()
+                        />
+                        <textarea
+                            class="w-full h-56 px-3 py-2 rounded-lg border text-xs font-mono"
+                            style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                            placeholder="...or paste a rule bundle here"
+                            prop:value=move || content.get()
+                            on:change=move |ev| content.set(event_target_value(&ev))
+                            disabled=move || importing.get()
+                        ></textarea>
+                        <div>
+                            <label class="block text-sm font-medium mb-2" style="color: var(--text-primary);">
+                                "When a rule with the same name exists"
+                            </label>
+                            <select
+                                class="px-3 py-2 rounded-lg border text-sm"
+                                style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                on:change=move |ev| on_conflict.set(event_target_value(&ev))
+                                disabled=move || importing.get()
+                            >
+                                {IMPORT_CONFLICT_OPTIONS.iter().map(|(option_value, option_label)| {
+                                    view! {
+                                        <option value=*option_value selected=move || on_conflict.get() == *option_value>{*option_label}</option>
+                                    }
+                                }).collect::<Vec<_>>()}
+                            </select>
+                        </div>
+
+                        {move || report.get().map(|report| view! {
+                            <div class="p-4 rounded-lg border" style="background-color: var(--bg-secondary); border-color: var(--border-secondary);">
+                                <p class="text-sm font-medium mb-2" style="color: var(--text-primary);">
+                                    {format!("{} imported, {} replaced, {} skipped", report.imported, report.replaced, report.skipped)}
+                                </p>
+                                <ul class="space-y-1">
+                                    {report.results.into_iter().map(|result| {
+                                        let ok = result.status == "imported" || result.status == "replaced";
+                                        view! {
+                                            <li class="text-xs" style={if ok { "color: var(--text-success);" } else { "color: var(--text-error);" }}>
+                                                {format!("{} {} — {}", if ok { "✓" } else { "✗" }, result.name, result.status.replace('_', " "))}
+                                                {result.message.map(|message| format!(": {}", message))}
+                                            </li>
+                                        }
+                                    }).collect::<Vec<_>>()}
+                                </ul>
+                            </div>
+                        })}
+                    </div>
+
+                    <div class="flex-shrink-0 px-4 sm:px-6 md:px-8 py-4 sm:py-5 border-t flex flex-row justify-end gap-3 sm:gap-4" style="border-color: var(--border-primary); background-color: var(--bg-secondary);">
+                        <button
+                            class="px-6 py-3 rounded-lg transition-all font-semibold text-sm"
+                            style="background-color: var(--bg-tertiary); color: var(--text-primary); border: 1px solid var(--border-secondary); line-height: 1.5; display: inline-flex !important; align-items: center; justify-content: center; width: auto !important; min-width: 100px; max-width: none !important; flex: 0 0 auto !important;"
+                            on:click=move |_| {
+                                if !importing.get() {
+                                    show.set(false);
+                                }
+                            }
+                            disabled=move || importing.get()
+                        >
+                            "Close"
+                        </button>
+                        <button
+                            class="px-6 py-3 rounded-lg font-semibold transition-all text-sm disabled:opacity-50"
+                            style="background-color: var(--accent-primary); color: var(--text-on-accent); line-height: 1.5; display: inline-flex !important; align-items: center; justify-content: center; box-shadow: var(--shadow-md); width: auto !important; min-width: 100px; max-width: none !important; flex: 0 0 auto !important;"
+                            on:click=run_import
+                            disabled=move || importing.get()
+                        >
+                            {move || if importing.get() { "Importing..." } else { "Import" }}
+                        </button>
+                    </div>
+                </div>
             </div>
-        </div>
+        </Show>
+    }
+}
+
+/// Short display of a changed value; `None` is a field the revision does not have.
+fn format_change_value(value: &Option<serde_json::Value>) -> String {
+    match value {
+        Some(serde_json::Value::String(text)) => text.clone(),
+        Some(value) => value.to_string(),
+        None => "(unset)".to_string(),
     }
 }
 
 #[component]
-fn RuleModal(
-    show: RwSignal<bool>,
-    editing_rule_id: RwSignal<Option<i64>>,
+fn RuleHistoryModal(
+    rule_id: RwSignal<Option<i64>>,
     rules: RwSignal<Vec<AutomationRule>>,
-    preset_data: RwSignal<Option<(String, u32, String, String, f64, String)>>,
-    on_save: impl Fn() + 'static + Send + Sync,
+    on_restored: impl Fn() + 'static + Send + Sync,
 ) -> impl IntoView {
-    let rule_name = RwSignal::new(String::new());
-    let rule_enabled = RwSignal::new(true);
-    let trigger_type = RwSignal::new("interval".to_string());
-    let cron_expression = RwSignal::new("0 * * * *".to_string());
-    let interval_minutes = RwSignal::new(60u32);
-    let condition_type = RwSignal::new("SeedingTime".to_string());
-    let condition_operator = RwSignal::new("GreaterThan".to_string());
-    let condition_value = RwSignal::new(24.0f64);
-    let action_type = RwSignal::new("StopSeeding".to_string());
-    let saving = RwSignal::new(false);
-    let save_error = RwSignal::new(None::<String>);
+    let revisions = RwSignal::new(Vec::<RuleRevision>::new());
+    let selected = RwSignal::new(None::<i64>);
+    let diff = RwSignal::new(None::<RevisionDiff>);
+    let loading = RwSignal::new(false);
+    let restoring = RwSignal::new(false);
+    let history_error = RwSignal::new(None::<String>);
+    let on_restored = StoredValue::new(on_restored);
+
+    let rule_name = move || {
+        let id = rule_id.get();
+        rules.get().into_iter().find(|rule| rule.id.is_some() && rule.id == id).map(|rule| rule.name).unwrap_or_default()
+    };
+    let current_revision = move || revisions.get().first().map(|revision| revision.revision);
+
+    let fetch_revisions = move |id: i64| {
+        #[cfg(feature = "hydrate")]
+        {
+            loading.set(true);
+            history_error.set(None);
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let url = format!("/api/automation/rules/{}/revisions", id);
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("GET");
+                                    i.set_headers(&headers);
+                                    i
+                                };
+
+                                let promise = window.fetch_with_str_and_init(&url, &init);
+                                if let Ok(response) = wasm_bindgen_futures::JsFuture::from(promise).await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                    if let Ok(text_promise) = resp.text() {
+                                        if let Ok(text_value) = wasm_bindgen_futures::JsFuture::from(text_promise).await {
+                                            let text = text_value.as_string().unwrap_or_default();
+                                            if rule_id.get_untracked() == Some(id) {
+                                                match serde_json::from_str::<ApiResponse<Vec<RuleRevision>>>(&text) {
+                                                    Ok(api_response) if api_response.success => revisions.set(api_response.data.unwrap_or_default()),
+                                                    Ok(api_response) => history_error.set(Some(api_response.error.unwrap_or_else(|| "Failed to load revisions".to_string()))),
+                                                    Err(_) => history_error.set(Some(format!("Failed to load revisions: {}", resp.status()))),
+                                                }
+                                            }
+                                        }
+                                    }
+                                } else {
+                                    history_error.set(Some("Network request failed".to_string()));
+                                }
+                            }
+                        }
+                    }
+                }
+                loading.set(false);
+            });
+        }
+        #[cfg(not(feature = "hydrate"))]
+        let _ = id;
+    };
 
     Effect::new(move |_| {
-        if show.get() {
-            if let Some(rule_id) = editing_rule_id.get() {
-                if let Some(rule) = rules.get().iter().find(|r| r.id == Some(rule_id)) {
-                    rule_name.set(rule.name.clone());
-                    rule_enabled.set(rule.enabled);
-                    
-                    if let Ok(trigger) = serde_json::from_value::<serde_json::Map<String, serde_json::Value>>(rule.trigger_config.clone()) {
-                        if trigger.contains_key("Cron") {
-                            trigger_type.set("cron".to_string());
-                            if let Some(expr) = trigger.get("Cron").and_then(|v| v.get("expression")).and_then(|v| v.as_str()) {
-                                cron_expression.set(expr.to_string());
+        revisions.set(Vec::new());
+        selected.set(None);
+        diff.set(None);
+        history_error.set(None);
+        if let Some(id) = rule_id.get() {
+            fetch_revisions(id);
+        }
+    });
+
+    // Compares the selected revision with the current one.
+    let select_revision = move |revision: i64| {
+        selected.set(Some(revision));
+        diff.set(None);
+        #[cfg(feature = "hydrate")]
+        {
+            let Some(id) = rule_id.get_untracked() else {
+                return;
+            };
+            history_error.set(None);
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let url = format!("/api/automation/rules/{}/revisions/diff?from={}", id, revision);
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("GET");
+                                    i.set_headers(&headers);
+                                    i
+                                };
+
+                                let promise = window.fetch_with_str_and_init(&url, &init);
+                                if let Ok(response) = wasm_bindgen_futures::JsFuture::from(promise).await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                    if let Ok(text_promise) = resp.text() {
+                                        if let Ok(text_value) = wasm_bindgen_futures::JsFuture::from(text_promise).await {
+                                            let text = text_value.as_string().unwrap_or_default();
+                                            if selected.get_untracked() == Some(revision) {
+                                                match serde_json::from_str::<ApiResponse<RevisionDiff>>(&text) {
+                                                    Ok(api_response) if api_response.success => diff.set(api_response.data),
+                                                    Ok(api_response) => history_error.set(Some(api_response.error.unwrap_or_else(|| "Failed to compare revisions".to_string()))),
+                                                    Err(_) => history_error.set(Some(format!("Failed to compare revisions: {}", resp.status()))),
+                                                }
+                                            }
+                                        }
+                                    }
+                                } else {
+                                    history_error.set(Some("Network request failed".to_string()));
+                                }
                             }
-                        } else if trigger.contains_key("Interval") {
-                            trigger_type.set("interval".to_string());
-                            if let Some(mins) = trigger.get("Interval").and_then(|v| v.get("minutes")).and_then(|v| v.as_u64()) {
-                                interval_minutes.set(mins as u32);
+                        }
+                    }
+                }
+            });
+        }
+    };
+
+    let restore_revision = move |revision: i64| {
+        #[cfg(feature = "hydrate")]
+        {
+            let Some(id) = rule_id.get_untracked() else {
+                return;
+            };
+            restoring.set(true);
+            history_error.set(None);
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let url = format!("/api/automation/rules/{}/revisions/{}/restore", id, revision);
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("POST");
+                                    i.set_headers(&headers);
+                                    i
+                                };
+
+                                let promise = window.fetch_with_str_and_init(&url, &init);
+                                if let Ok(response) = wasm_bindgen_futures::JsFuture::from(promise).await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                    if let Ok(text_promise) = resp.text() {
+                                        if let Ok(text_value) = wasm_bindgen_futures::JsFuture::from(text_promise).await {
+                                            let text = text_value.as_string().unwrap_or_default();
+                                            match serde_json::from_str::<ApiResponse<AutomationRule>>(&text) {
+                                                Ok(api_response) if api_response.success => {
+                                                    on_restored.with_value(|on_restored| on_restored());
+                                                    selected.set(None);
+                                                    diff.set(None);
+                                                    fetch_revisions(id);
+                                                }
+                                                Ok(api_response) => history_error.set(Some(api_response.error.unwrap_or_else(|| "Restore failed".to_string()))),
+                                                Err(_) => history_error.set(Some(format!("Restore failed: {}", resp.status()))),
+                                            }
+                                        }
+                                    }
+                                } else {
+                                    history_error.set(Some("Network request failed".to_string()));
+                                }
                             }
                         }
                     }
-                    
-                    if let Some(condition) = rule.conditions.first().and_then(|c| serde_json::from_value::<serde_json::Map<String, serde_json::Value>>(c.clone()).ok()) {
-                        if let Some(ct) = condition.get("type").and_then(|v| v.as_str()) {
-                            condition_type.set(ct.to_string());
-                        }
-                        if let Some(op) = condition.get("operator").and_then(|v| v.as_str()) {
-                            condition_operator.set(op.to_string());
-                        }
-                        if let Some(val) = condition.get("value").and_then(|v| v.as_f64()) {
-                            condition_value.set(val);
-                        }
-                    }
-                    
-                    if let Ok(action) = serde_json::from_value::<serde_json::Map<String, serde_json::Value>>(rule.action_config.clone()) {
-                        if let Some(at) = action.get("action_type").and_then(|v| v.as_str()) {
-                            action_type.set(at.to_string());
-                        }
-                    }
                 }
-            } else if let Some((name, minutes, cond_type, cond_op, cond_val, act_type)) = preset_data.get() {
-                // Apply preset values
-                rule_name.set(name);
-                rule_enabled.set(true);
-                trigger_type.set("interval".to_string());
-                interval_minutes.set(minutes);
-                condition_type.set(cond_type);
-                condition_operator.set(cond_op);
-                condition_value.set(cond_val);
-                action_type.set(act_type);
-                preset_data.set(None); // Clear preset after applying
-            } else {
-                rule_name.set(String::new());
-                rule_enabled.set(true);
-                trigger_type.set("interval".to_string());
-                cron_expression.set("0 * * * *".to_string());
-                interval_minutes.set(60);
-                condition_type.set("SeedingTime".to_string());
-                condition_operator.set("GreaterThan".to_string());
-                condition_value.set(24.0);
-                action_type.set("StopSeeding".to_string());
-            }
-            save_error.set(None);
+                restoring.set(false);
+            });
         }
-    });
+        #[cfg(not(feature = "hydrate"))]
+        let _ = (revision, on_restored);
+    };
 
-    let on_save_arc = Arc::new(on_save);
-    let on_save_arc_clone = on_save_arc.clone();
-    let rule_name_save = rule_name.clone();
-    let rule_enabled_save = rule_enabled.clone();
-    let trigger_type_save = trigger_type.clone();
-    let cron_expression_save = cron_expression.clone();
-    let interval_minutes_save = interval_minutes.clone();
-    let condition_type_save = condition_type.clone();
-    let condition_operator_save = condition_operator.clone();
-    let condition_value_save = condition_value.clone();
-    let action_type_save = action_type.clone();
-    let editing_rule_id_save = editing_rule_id.clone();
-    let saving_save = saving.clone();
-    let save_error_save = save_error.clone();
+    let close = move || {
+        if !restoring.get_untracked() {
+            rule_id.set(None);
+        }
+    };
 
     view! {
-        <Show
-            when=move || show.get()
-        >
-            <div 
+        <Show when=move || rule_id.get().is_some()>
+            <div
                 style="position: fixed !important; top: 0 !important; left: 0 !important; right: 0 !important; bottom: 0 !important; width: 100vw !important; height: 100vh !important; background-color: rgba(0, 0, 0, 0.75) !important; backdrop-filter: blur(4px) !important; z-index: 2147483647 !important; display: flex !important; align-items: center !important; justify-content: center !important; padding: 0.5rem !important; overflow-y: auto !important; box-sizing: border-box !important;"
-                on:click=move |_| {
-                    if !saving.get() {
-                        show.set(false);
-                    }
-                }
+                on:click=move |_| close()
             >
-                <div 
+                <div
                     class="rounded-xl border shadow-2xl modal-content"
                     style="background-color: var(--bg-card) !important; border-color: var(--border-secondary) !important; z-index: 2147483647 !important; position: relative !important; width: calc(100% - 1rem) !important; max-width: 48rem !important; max-height: calc(100vh - 1rem) !important; margin: auto !important; overflow: hidden !important; display: flex !important; flex-direction: column !important;"
                     on:click=|ev| ev.stop_propagation()
@@ -1390,20 +5272,16 @@ fn RuleModal(
                         <div class="flex items-center justify-between">
                             <div>
                                 <h3 class="text-xl sm:text-2xl font-bold mb-1" style="color: var(--text-primary); line-height: 1.3;">
-                                    {move || if editing_rule_id.get().is_some() { "Edit Automation Rule" } else { "Create Automation Rule" }}
+                                    "Rule History"
                                 </h3>
                                 <p class="text-xs sm:text-sm" style="color: var(--text-secondary); line-height: 1.5;">
-                                    {move || if editing_rule_id.get().is_some() { "Modify your automation rule settings" } else { "Set up a new automation to manage your torrents" }}
+                                    {move || format!("Every saved version of \"{}\". Select one to compare it with the current rule.", rule_name())}
                                 </p>
                             </div>
                             <button
                                 class="flex items-center justify-center w-7 h-7 sm:w-8 sm:h-8 rounded-lg transition-colors shrink-0 hover:bg-opacity-10"
                                 style="color: var(--text-secondary); background-color: var(--bg-secondary);"
-                                on:click=move |_| {
-                                    if !saving.get() {
-                                        show.set(false);
-                                    }
-                                }
+                                on:click=move |_| close()
                                 title="Close"
                             >
                                 <span class="text-xl leading-none">"×"</span>
@@ -1411,559 +5289,950 @@ fn RuleModal(
                         </div>
                     </div>
 
-                    <div class="flex-1 overflow-y-auto px-4 sm:px-6 md:px-8 py-4 sm:py-6 md:py-8" style="min-height: 0 !important;">
-                        <Show when=move || save_error.get().is_some()>
-                            <div class="mb-6 p-4 rounded-lg border" style="background-color: var(--bg-error); border-color: var(--border-error, #ef4444); color: var(--text-error); line-height: 1.5;">
-                                <div class="flex items-start">
-                                    <span class="text-lg mr-2">"⚠"</span>
-                                    <div class="flex-1">
-                                        <p class="font-medium mb-1">"Error"</p>
-                                        <p class="text-sm">{move || save_error.get().unwrap_or_default()}</p>
-                                    </div>
-                                </div>
-                            </div>
-                        </Show>
+                    <div class="flex-1 overflow-y-auto px-4 sm:px-6 py-4 space-y-4">
+                        <Show when=move || history_error.get().is_some()>
+                            <p class="text-sm" style="color: var(--text-error);">{move || history_error.get().unwrap_or_default()}</p>
+                        </Show>
+
+                        <Show when=move || loading.get()>
+                            <div class="flex justify-center py-4">
+                                <LoadingSpinner size=SpinnerSize::Medium variant=SpinnerVariant::Accent/>
+                            </div>
+                        </Show>
+
+                        <ul class="space-y-2">
+                            {move || revisions.get().into_iter().map(|revision| {
+                                let number = revision.revision;
+                                let is_current = current_revision() == Some(number);
+                                view! {
+                                    <li
+                                        class="p-3 rounded-lg border cursor-pointer"
+                                        style=move || format!(
+                                            "background-color: var(--bg-secondary); border-color: {};",
+                                            if selected.get() == Some(number) { "var(--accent-primary)" } else { "var(--border-secondary)" }
+                                        )
+                                        on:click=move |_| {
+                                            if !is_current {
+                                                select_revision(number);
+                                            }
+                                        }
+                                    >
+                                        <div class="flex flex-wrap items-center gap-x-3 gap-y-1 text-xs">
+                                            <span class="font-semibold" style="color: var(--text-primary);">{format!("rev {}", number)}</span>
+                                            <span style="color: var(--text-secondary);">{format!("{} UTC", revision.created_at.clone().unwrap_or_default())}</span>
+                                            <span style="color: var(--text-primary);">{revision.action.clone()}</span>
+                                            {revision.client_ip.clone().map(|ip| view! {
+                                                <span style="color: var(--text-secondary);">{format!("from {}", ip)}</span>
+                                            })}
+                                            {is_current.then(|| view! {
+                                                <span style="color: var(--text-success);">"current"</span>
+                                            })}
+                                        </div>
+                                    </li>
+                                }
+                            }).collect::<Vec<_>>()}
+                        </ul>
+
+                        {move || diff.get().map(|diff| {
+                            let from = diff.from;
+                            view! {
+                                <div class="p-4 rounded-lg border" style="background-color: var(--bg-secondary); border-color: var(--border-secondary);">
+                                    <div class="flex items-center justify-between gap-3 mb-2">
+                                        <p class="text-sm font-medium" style="color: var(--text-primary);">
+                                            {format!("Changes from rev {} to rev {}", diff.from, diff.to)}
+                                        </p>
+                                        <button
+                                            class="px-3 py-2 rounded-lg text-sm font-medium transition-colors whitespace-nowrap border disabled:opacity-50 disabled:cursor-not-allowed"
+                                            style="background-color: var(--accent-primary); color: var(--text-on-accent); border-color: var(--accent-primary);"
+                                            title="Save this revision as the rule's newest version"
+                                            disabled=move || restoring.get()
+                                            on:click=move |_| restore_revision(from)
+                                        >
+                                            {move || if restoring.get() { "Restoring...".to_string() } else { format!("Restore rev {}", from) }}
+                                        </button>
+                                    </div>
+                                    {if diff.changes.is_empty() {
+                                        view! { <p class="text-xs" style="color: var(--text-secondary);">"No differences"</p> }.into_any()
+                                    } else {
+                                        view! {
+                                            <ul class="space-y-1">
+                                                {diff.changes.into_iter().map(|change| view! {
+                                                    <li class="text-xs font-mono break-all">
+                                                        <span style="color: var(--text-primary);">{change.path.clone()}</span>
+                                                        <span style="color: var(--text-error);">{format!(" {}", format_change_value(&change.before))}</span>
+                                                        <span style="color: var(--text-secondary);">" → "</span>
+                                                        <span style="color: var(--text-success);">{format_change_value(&change.after)}</span>
+                                                    </li>
+                                                }).collect::<Vec<_>>()}
+                                            </ul>
+                                        }.into_any()
+                                    }}
+                                </div>
+                            }
+                        })}
+                    </div>
+                </div>
+            </div>
+        </Show>
+    }
+}
+
+#[component]
+fn AutomationAnalyticsPanel(show: RwSignal<bool>) -> impl IntoView {
+    let days = RwSignal::new(30u32);
+    let analytics = RwSignal::new(None::<AutomationAnalytics>);
+    let loading = RwSignal::new(false);
+    let analytics_error = RwSignal::new(None::<String>);
+
+    Effect::new(move |_| {
+        let range = days.get();
+        if !show.get() {
+            return;
+        }
+        #[cfg(feature = "hydrate")]
+        {
+            loading.set(true);
+            analytics_error.set(None);
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("GET");
+                                    i.set_headers(&headers);
+                                    i
+                                };
+
+                                let url = format!("/api/automation/analytics?days={}", range);
+                                let promise = window.fetch_with_str_and_init(&url, &init);
+                                if let Ok(response) = wasm_bindgen_futures::JsFuture::from(promise).await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                    if let Ok(text_promise) = resp.text() {
+                                        if let Ok(text_value) = wasm_bindgen_futures::JsFuture::from(text_promise).await {
+                                            let text = text_value.as_string().unwrap_or_default();
+                                            match serde_json::from_str::<ApiResponse<AutomationAnalytics>>(&text) {
+                                                Ok(api_response) if api_response.success => analytics.set(api_response.data),
+                                                Ok(api_response) => analytics_error.set(Some(api_response.error.unwrap_or_else(|| "Failed to load analytics".to_string()))),
+                                                Err(_) => analytics_error.set(Some(format!("Failed to load analytics: {}", resp.status()))),
+                                            }
+                                        }
+                                    }
+                                } else {
+                                    analytics_error.set(Some("Network request failed".to_string()));
+                                }
+                            }
+                        }
+                    }
+                }
+                loading.set(false);
+            });
+        }
+        #[cfg(not(feature = "hydrate"))]
+        let _ = range;
+    });
+
+    let stat_card = |label: &'static str, value: String| view! {
+        <div class="p-4 rounded-lg border" style="background-color: var(--bg-secondary); border-color: var(--border-secondary);">
+            <div class="text-xs mb-1" style="color: var(--text-secondary);">{label}</div>
+            <div class="text-xl font-semibold" style="color: var(--text-primary);">{value}</div>
+        </div>
+    };
+
+    view! {
+        <Show when=move || show.get()>
+            <div class="mb-6 p-6 rounded-lg border" style="background-color: var(--bg-card); border-color: var(--border-secondary);">
+                <div class="flex flex-col sm:flex-row items-start sm:items-center justify-between gap-3 mb-4">
+                    <h3 class="text-lg font-semibold" style="color: var(--text-primary); line-height: 1.5;">
+                        "Automation Analytics"
+                    </h3>
+                    <select
+                        class="px-3 py-2 rounded-lg border text-sm"
+                        style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                        on:change=move |ev| days.set(event_target_value(&ev).parse().unwrap_or(30))
+                        disabled=move || loading.get()
+                    >
+                        {ANALYTICS_RANGE_OPTIONS.iter().map(|(option_days, option_label)| {
+                            let option_days = *option_days;
+                            view! {
+                                <option value=option_days.to_string() selected=move || days.get() == option_days>{*option_label}</option>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+
+                <Show when=move || analytics_error.get().is_some()>
+                    <p class="text-sm mb-4" style="color: var(--text-error);">{move || analytics_error.get().unwrap_or_default()}</p>
+                </Show>
+
+                <Show when=move || loading.get() && analytics.get().is_none()>
+                    <div class="flex justify-center py-8">
+                        <LoadingSpinner size=SpinnerSize::Medium variant=SpinnerVariant::Accent/>
+                    </div>
+                </Show>
+
+                {move || analytics.get().map(|data| {
+                    let busiest_day = data.runs_per_day.iter().map(|day| day.runs).max().unwrap_or(0).max(1);
+                    let busiest_action = data.actions.iter().map(|action| action.succeeded + action.failed).max().unwrap_or(0).max(1);
+                    let skipped = (data.skipped_runs > 0).then(|| format!("{} runs skipped (previous run still in progress or outside the schedule window)", data.skipped_runs));
+                    view! {
+                        <div class="grid grid-cols-2 lg:grid-cols-5 gap-3 mb-6">
+                            {stat_card("Runs", data.total_runs.to_string())}
+                            {stat_card("Success rate", format!("{:.1}%", data.success_rate * 100.0))}
+                            {stat_card("Partial rate", format!("{:.1}%", data.partial_rate * 100.0))}
+                            {stat_card("Items processed", data.items_processed.to_string())}
+                            {stat_card("Storage reclaimed", format_bytes(data.reclaimed_bytes))}
+                        </div>
+                        {skipped.map(|note| view! {
+                            <p class="text-xs mb-4" style="color: var(--text-secondary);">{note}</p>
+                        })}
+
+                        <h4 class="text-sm font-semibold mb-2" style="color: var(--text-primary);">"Runs per day"</h4>
+                        <div class="flex items-end gap-px h-32 mb-2 p-2 rounded-lg" style="background-color: var(--bg-secondary);">
+                            {data.runs_per_day.into_iter().map(|day| {
+                                let height = |count: i64| format!("{}%", count as f64 * 100.0 / busiest_day as f64);
+                                let title = format!("{}: {} runs ({} successful, {} partial, {} failed), {} items", day.date, day.runs, day.successful, day.partial, day.failed, day.items_processed);
+                                view! {
+                                    <div class="flex-1 h-full flex flex-col justify-end" title=title>
+                                        <div style:height=height(day.failed) style="background-color: var(--text-error);"></div>
+                                        <div style:height=height(day.partial) style="background-color: var(--accent-primary); opacity: 0.6;"></div>
+                                        <div style:height=height(day.successful) style="background-color: var(--text-success);"></div>
+                                    </div>
+                                }
+                            }).collect::<Vec<_>>()}
+                        </div>
+                        <div class="flex gap-4 text-xs mb-6" style="color: var(--text-secondary);">
+                            <span><span style="color: var(--text-success);">"■"</span>" Successful"</span>
+                            <span><span style="color: var(--accent-primary); opacity: 0.6;">"■"</span>" Partial"</span>
+                            <span><span style="color: var(--text-error);">"■"</span>" Failed"</span>
+                        </div>
+
+                        <div class="grid grid-cols-1 lg:grid-cols-2 gap-6">
+                            <div>
+                                <h4 class="text-sm font-semibold mb-2" style="color: var(--text-primary);">"Actions"</h4>
+                                <Show
+                                    when={
+                                        let empty = data.actions.is_empty();
+                                        move || !empty
+                                    }
+                                    fallback=|| view! { <p class="text-sm" style="color: var(--text-secondary);">"No actions in this period"</p> }
+                                >
+                                    <div class="space-y-2">
+                                        {data.actions.clone().into_iter().map(|action| {
+                                            let width = |count: i64| format!("{}%", count as f64 * 100.0 / busiest_action as f64);
+                                            view! {
+                                                <div>
+                                                    <div class="flex justify-between text-xs mb-1" style="color: var(--text-secondary);">
+                                                        <span style="color: var(--text-primary);">{action.action.clone()}</span>
+                                                        <span>{format!("{} succeeded, {} failed", action.succeeded, action.failed)}</span>
+                                                    </div>
+                                                    <div class="flex h-2 rounded overflow-hidden" style="background-color: var(--bg-tertiary);">
+                                                        <div style:width=width(action.succeeded) style="background-color: var(--text-success);"></div>
+                                                        <div style:width=width(action.failed) style="background-color: var(--text-error);"></div>
+                                                    </div>
+                                                </div>
+                                            }
+                                        }).collect::<Vec<_>>()}
+                                    </div>
+                                </Show>
+                            </div>
+                            <div>
+                                <h4 class="text-sm font-semibold mb-2" style="color: var(--text-primary);">"Most failing items"</h4>
+                                <Show
+                                    when={
+                                        let empty = data.failing_items.is_empty();
+                                        move || !empty
+                                    }
+                                    fallback=|| view! { <p class="text-sm" style="color: var(--text-secondary);">"No failed items in this period"</p> }
+                                >
+                                    <ul class="space-y-2">
+                                        {data.failing_items.clone().into_iter().map(|item| {
+                                            let detail = match (item.download_type.as_deref(), item.last_error.as_deref()) {
+                                                (Some(download_type), Some(error)) => format!("{} #{} — {}", download_type, item.id, error),
+                                                (Some(download_type), None) => format!("{} #{}", download_type, item.id),
+                                                (None, Some(error)) => format!("#{} — {}", item.id, error),
+                                                (None, None) => format!("#{}", item.id),
+                                            };
+                                            view! {
+                                                <li class="text-xs">
+                                                    <div class="flex justify-between gap-2">
+                                                        <span class="truncate" style="color: var(--text-primary);" title=item.name.clone()>{item.name.clone()}</span>
+                                                        <span class="shrink-0" style="color: var(--text-error);">{format!("{} failures", item.failures)}</span>
+                                                    </div>
+                                                    <div class="truncate" style="color: var(--text-secondary);" title=detail.clone()>{detail.clone()}</div>
+                                                </li>
+                                            }
+                                        }).collect::<Vec<_>>()}
+                                    </ul>
+                                </Show>
+                            </div>
+                        </div>
+                    }
+                })}
+            </div>
+        </Show>
+    }
+}
+
+#[component]
+fn AutomationLogsPanel(show: RwSignal<bool>, rules: RwSignal<Vec<AutomationRule>>) -> impl IntoView {
+    let filter = RwSignal::new(LogFilterDraft::default());
+    let logs = RwSignal::new(Vec::<ExecutionLog>::new());
+    let next_cursor = RwSignal::new(None::<i64>);
+    let loading = RwSignal::new(false);
+    let exporting = RwSignal::new(false);
+    let logs_error = RwSignal::new(None::<String>);
+
+    // Loads the first page for the current filters, or the page after `cursor`.
+    let fetch_logs = move |cursor: Option<i64>| {
+        #[cfg(feature = "hydrate")]
+        {
+            loading.set(true);
+            logs_error.set(None);
+            let draft = filter.get_untracked();
+            let mut url = format!("/api/automation/logs?{}", draft.to_query());
+            if let Some(cursor) = cursor {
+                url.push_str(&format!("&cursor={}", cursor));
+            }
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("GET");
+                                    i.set_headers(&headers);
+                                    i
+                                };
+
+                                let promise = window.fetch_with_str_and_init(&url, &init);
+                                if let Ok(response) = wasm_bindgen_futures::JsFuture::from(promise).await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                    if let Ok(text_promise) = resp.text() {
+                                        if let Ok(text_value) = wasm_bindgen_futures::JsFuture::from(text_promise).await {
+                                            let text = text_value.as_string().unwrap_or_default();
+                                            // Results for filters that have since changed are dropped.
+                                            if filter.get_untracked() == draft {
+                                                match serde_json::from_str::<ApiResponse<LogPage>>(&text) {
+                                                    Ok(api_response) if api_response.success => {
+                                                        if let Some(page) = api_response.data {
+                                                            if cursor.is_some() {
+                                                                logs.update(|logs| logs.extend(page.logs));
+                                                            } else {
+                                                                logs.set(page.logs);
+                                                            }
+                                                            next_cursor.set(page.next_cursor);
+                                                        }
+                                                    }
+                                                    Ok(api_response) => logs_error.set(Some(api_response.error.unwrap_or_else(|| "Failed to load logs".to_string()))),
+                                                    Err(_) => logs_error.set(Some(format!("Failed to load logs: {}", resp.status()))),
+                                                }
+                                            }
+                                        }
+                                    }
+                                } else {
+                                    logs_error.set(Some("Network request failed".to_string()));
+                                }
+                            }
+                        }
+                    }
+                }
+                loading.set(false);
+            });
+        }
+        #[cfg(not(feature = "hydrate"))]
+        let _ = cursor;
+    };
+
+    Effect::new(move |_| {
+        filter.track();
+        if show.get() {
+            fetch_logs(None);
+        }
+    });
+
+    let export_logs = move |format: &'static str| {
+        #[cfg(feature = "hydrate")]
+        {
+            exporting.set(true);
+            logs_error.set(None);
+            let url = format!("/api/automation/logs/export?format={}&{}", format, filter.get_untracked().to_query());
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("GET");
+                                    i.set_headers(&headers);
+                                    i
+                                };
+
+                                let promise = window.fetch_with_str_and_init(&url, &init);
+                                if let Ok(response) = wasm_bindgen_futures::JsFuture::from(promise).await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                    if let Ok(text_promise) = resp.text() {
+                                        if let Ok(text_value) = wasm_bindgen_futures::JsFuture::from(text_promise).await {
+                                            let text = text_value.as_string().unwrap_or_default();
+                                            match serde_json::from_str::<ApiResponse<LogExport>>(&text) {
+                                                Ok(api_response) => match api_response.data {
+                                                    Some(export) => download_file(&window, &export.filename, &export.content_type, &export.content),
+                                                    None => logs_error.set(Some(api_response.error.unwrap_or_else(|| "Failed to export logs".to_string()))),
+                                                },
+                                                Err(_) => logs_error.set(Some(format!("Failed to export logs: {}", resp.status()))),
+                                            }
+                                        }
+                                    }
+                                } else {
+                                    logs_error.set(Some("Network request failed".to_string()));
+                                }
+                            }
+                        }
+                    }
+                }
+                exporting.set(false);
+            });
+        }
+        #[cfg(not(feature = "hydrate"))]
+        let _ = format;
+    };
+
+    let input_style = "background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);";
+
+    view! {
+        <Show when=move || show.get()>
+            <div class="mb-6 p-6 rounded-lg border" style="background-color: var(--bg-card); border-color: var(--border-secondary);">
+                <div class="flex flex-col sm:flex-row items-start sm:items-center justify-between gap-3 mb-4">
+                    <h3 class="text-lg font-semibold" style="color: var(--text-primary); line-height: 1.5;">
+                        "Execution Logs"
+                    </h3>
+                    <div class="flex items-center gap-2">
+                        <button
+                            class="px-3 py-2 rounded-lg text-sm font-medium transition-colors whitespace-nowrap border disabled:opacity-50 disabled:cursor-not-allowed"
+                            style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                            title="Export the runs matching these filters, one row per processed download"
+                            disabled=move || exporting.get() || logs.get().is_empty()
+                            on:click=move |_| export_logs("csv")
+                        >
+                            "Export CSV"
+                        </button>
+                        <button
+                            class="px-3 py-2 rounded-lg text-sm font-medium transition-colors whitespace-nowrap border disabled:opacity-50 disabled:cursor-not-allowed"
+                            style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                            title="Export the runs matching these filters as JSON"
+                            disabled=move || exporting.get() || logs.get().is_empty()
+                            on:click=move |_| export_logs("json")
+                        >
+                            "Export JSON"
+                        </button>
+                    </div>
+                </div>
+
+                <div class="grid grid-cols-1 sm:grid-cols-2 lg:grid-cols-5 gap-3 mb-4">
+                    <select
+                        class="px-3 py-2 rounded-lg border text-sm"
+                        style=input_style
+                        on:change=move |ev| {
+                            let rule_id = event_target_value(&ev).parse::<i64>().ok();
+                            filter.update(|filter| filter.rule_id = rule_id);
+                        }
+                    >
+                        <option value="" selected=move || filter.get().rule_id.is_none()>"All rules"</option>
+                        {move || rules.get().into_iter().filter_map(|rule| {
+                            let id = rule.id?;
+                            Some(view! {
+                                <option value=id.to_string() selected=move || filter.get().rule_id == Some(id)>{rule.name.clone()}</option>
+                            })
+                        }).collect::<Vec<_>>()}
+                    </select>
+                    <input
+                        type="date"
+                        class="px-3 py-2 rounded-lg border text-sm"
+                        style=input_style
+                        title="From (UTC)"
+                        on:change=move |ev| filter.update(|filter| filter.from = event_target_value(&ev))
+                    />
+                    <input
+                        type="date"
+                        class="px-3 py-2 rounded-lg border text-sm"
+                        style=input_style
+                        title="To, inclusive (UTC)"
+                        on:change=move |ev| filter.update(|filter| filter.to = event_target_value(&ev))
+                    />
+                    <select
+                        class="px-3 py-2 rounded-lg border text-sm"
+                        style=input_style
+                        on:change=move |ev| filter.update(|filter| filter.status = event_target_value(&ev))
+                    >
+                        {LOG_STATUS_OPTIONS.iter().map(|(value, label)| {
+                            let value = *value;
+                            view! {
+                                <option value=value selected=move || filter.get().status == value>{*label}</option>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </select>
+                    <input
+                        type="text"
+                        placeholder="Download name contains..."
+                        class="px-3 py-2 rounded-lg border text-sm"
+                        style=input_style
+                        on:change=move |ev| filter.update(|filter| filter.item = event_target_value(&ev))
+                    />
+                </div>
+
+                <Show when=move || logs_error.get().is_some()>
+                    <p class="text-sm mb-4" style="color: var(--text-error);">{move || logs_error.get().unwrap_or_default()}</p>
+                </Show>
 
-                        <div class="space-y-8">
-                            <div class="p-6">
-                                <div class="mb-5">
-                                    <h4 class="text-lg font-bold mb-2" style="color: var(--text-primary); line-height: 1.4;">
-                                        "Basic Information"
-                                    </h4>
-                                    <div class="h-0.5 w-12 rounded-full" style="background-color: var(--accent-primary);"></div>
-                                </div>
-                                <div class="space-y-6">
-                                    <div style="margin-bottom: 1.5rem;">
-                                        <label class="block text-sm font-semibold mb-3" style="color: var(--text-primary); line-height: 1.5;">
-                                            "Rule Name"
-                                        </label>
-                                        <input
-                                            type="text"
-                                            placeholder="e.g., Stop seeding after 48h"
-                                            class="w-full px-4 py-3 rounded-lg border transition-all focus:outline-none focus:ring-2"
-                                            style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary); font-size: 0.9375rem; focus:border-color: var(--accent-primary); focus:ring-color: var(--accent-primary); focus:ring-width: 2px;"
-                                            value=move || rule_name.get()
-                                            on:input=move |ev| rule_name.set(event_target_value(&ev))
-                                            disabled=move || saving.get()
-                                        />
-                                        <p class="text-xs mt-2.5" style="color: var(--text-secondary); line-height: 1.6;">
-                                            "Give your rule a descriptive name to easily identify it later"
-                                        </p>
-                                    </div>
+                <Show when=move || !loading.get() && logs_error.get().is_none() && logs.get().is_empty()>
+                    <p class="text-sm" style="color: var(--text-secondary);">"No runs match these filters"</p>
+                </Show>
 
-                                    <div class="flex items-center p-4 rounded-lg border" style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); margin-top: 1.5rem;">
-                                        <input
-                                            type="checkbox"
-                                            id="rule-enabled"
-                                            class="w-5 h-5 rounded cursor-pointer"
-                                            style="accent-color: var(--accent-primary);"
-                                            checked=move || rule_enabled.get()
-                                            on:change=move |ev| rule_enabled.set(event_target_checked(&ev))
-                                            disabled=move || saving.get()
-                                        />
-                                        <label for="rule-enabled" class="ml-3.5 text-sm font-medium cursor-pointer" style="color: var(--text-primary); line-height: 1.5;">
-                                            "Enable this rule"
-                                        </label>
-                                    </div>
+                <div class="space-y-2">
+                    {move || logs.get().into_iter().map(|log| {
+                        let (status, color) = if log.partial.unwrap_or(false) {
+                            ("Partial", "var(--text-warning)")
+                        } else if log.success {
+                            ("Success", "var(--text-success)")
+                        } else if log.error_message.as_deref().map(|message| message.starts_with("skipped:")).unwrap_or(false) {
+                            ("Skipped", "var(--text-secondary)")
+                        } else {
+                            ("Failed", "var(--text-error)")
+                        };
+                        let counts = match log.total_items {
+                            Some(total) => format!("{}/{} items", log.items_processed, total),
+                            None => format!("{} items", log.items_processed),
+                        };
+                        let items = log.processed_items.clone().unwrap_or_default();
+                        view! {
+                            <div class="p-3 rounded-lg border" style="background-color: var(--bg-secondary); border-color: var(--border-secondary);">
+                                <div class="flex flex-wrap items-center gap-x-3 gap-y-1 text-xs">
+                                    <span style="color: var(--text-secondary);">{format!("{} UTC", log.executed_at.clone().unwrap_or_default())}</span>
+                                    <span class="font-semibold" style="color: var(--text-primary);">{log.rule_name.clone()}</span>
+                                    <span style=format!("color: {};", color)>{status}</span>
+                                    <span style="color: var(--text-secondary);">{format!("{} · {}", log.execution_type, counts)}</span>
+                                    {log.rule_revision.map(|revision| view! {
+                                        <span style="color: var(--text-secondary);" title="Revision of the rule that ran">{format!("rev {}", revision)}</span>
+                                    })}
                                 </div>
+                                {log.error_message.clone().map(|message| view! {
+                                    <p class="text-xs mt-1" style="color: var(--text-secondary);">{message}</p>
+                                })}
+                                {(!items.is_empty()).then(|| view! {
+                                    <ul class="mt-2 space-y-0.5">
+                                        {items.into_iter().map(|item| {
+                                            let outcome = match &item.error {
+                                                Some(error) => format!("{} failed: {}", item.action, error),
+                                                None if item.success => item.action.clone(),
+                                                None => format!("{} failed", item.action),
+                                            };
+                                            view! {
+                                                <li class="text-xs flex justify-between gap-2">
+                                                    <span class="truncate" style="color: var(--text-primary);" title=item.name.clone()>{item.name.clone()}</span>
+                                                    <span class="shrink-0" style={if item.success { "color: var(--text-secondary);" } else { "color: var(--text-error);" }}>{outcome}</span>
+                                                </li>
+                                            }
+                                        }).collect::<Vec<_>>()}
+                                    </ul>
+                                })}
                             </div>
+                        }
+                    }).collect::<Vec<_>>()}
+                </div>
 
-                            <div class="p-6">
-                                <div class="mb-5">
-                                    <h4 class="text-lg font-bold mb-2" style="color: var(--text-primary); line-height: 1.4;">
-                                        "Schedule"
-                                    </h4>
-                                    <div class="h-0.5 w-12 rounded-full" style="background-color: var(--accent-primary);"></div>
-                                    <p class="text-xs mt-3" style="color: var(--text-secondary); line-height: 1.6;">
-                                        "Configure when this rule should automatically run and check your torrents"
-                                    </p>
-                                </div>
-                                <div class="space-y-6">
-                                    <div style="margin-bottom: 1.5rem;">
-                                        <label class="block text-sm font-semibold mb-3" style="color: var(--text-primary); line-height: 1.5;">
-                                            "Trigger Type"
-                                        </label>
-                                        <select
-                                            class="w-full px-4 py-3 rounded-lg border transition-all"
-                                            style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary); font-size: 0.9375rem;"
-                                            on:change=move |ev| trigger_type.set(event_target_value(&ev))
-                                            disabled=move || saving.get()
-                                        >
-                                            <option value="interval" selected=move || trigger_type.get() == "interval">"Interval (minutes)"</option>
-                                            <option value="cron" selected=move || trigger_type.get() == "cron">"Cron Expression"</option>
-                                        </select>
-                                    </div>
+                <Show when=move || loading.get()>
+                    <div class="flex justify-center py-4">
+                        <LoadingSpinner size=SpinnerSize::Medium variant=SpinnerVariant::Accent/>
+                    </div>
+                </Show>
 
-                                    <Show when=move || trigger_type.get() == "interval">
-                                        <div style="margin-top: 1.5rem;">
-                                            <label class="block text-sm font-semibold mb-3" style="color: var(--text-primary); line-height: 1.5;">
-                                                "Interval (minutes)"
-                                            </label>
-                                            <input
-                                                type="number"
-                                                min="30"
-                                                class="w-full px-4 py-3 rounded-lg border transition-all"
-                                                style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary); font-size: 0.9375rem;"
-                                                value=move || interval_minutes.get().to_string()
-                                                on:input=move |ev| {
-                                                    if let Ok(val) = event_target_value(&ev).parse::<u32>() {
-                                                        interval_minutes.set(if val < 30 { 30 } else { val });
-                                                    }
-                                                }
-                                                disabled=move || saving.get()
-                                            />
-                                            <p class="text-xs mt-2.5" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "How often to check and run this rule (minimum 30 minutes)"
-                                            </p>
-                                        </div>
-                                    </Show>
+                <Show when=move || !loading.get() && next_cursor.get().is_some()>
+                    <div class="flex justify-center mt-4">
+                        <button
+                            class="px-4 py-2 rounded-lg text-sm font-medium transition-colors border"
+                            style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                            on:click=move |_| fetch_logs(next_cursor.get_untracked())
+                        >
+                            "Load more"
+                        </button>
+                    </div>
+                </Show>
+            </div>
+        </Show>
+    }
+}
 
-                                    <Show when=move || trigger_type.get() == "cron">
-                                        <div style="margin-top: 1.5rem;">
-                                            <label class="block text-sm font-semibold mb-3" style="color: var(--text-primary); line-height: 1.5;">
-                                                "Cron Expression"
-                                            </label>
-                                            <input
-                                                type="text"
-                                                placeholder="0 * * * *"
-                                                class="w-full px-4 py-3 rounded-lg border transition-all font-mono text-sm"
-                                                style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary);"
-                                                value=move || cron_expression.get()
-                                                on:input=move |ev| cron_expression.set(event_target_value(&ev))
-                                                disabled=move || saving.get()
-                                            />
-                                            <p class="text-xs mt-2.5" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Format: sec min hour day month day-of-week (e.g., '0 0 * * *' = every hour at minute 0)"
-                                            </p>
-                                        </div>
-                                    </Show>
-                                </div>
-                            </div>
+#[component]
+fn RecentlyDeletedPanel(show: RwSignal<bool>) -> impl IntoView {
+    let deleted = RwSignal::new(Vec::<DeletedTorrent>::new());
+    let loading = RwSignal::new(false);
+    let busy_id = RwSignal::new(None::<i64>);
+    let deleted_error = RwSignal::new(None::<String>);
 
-                            <div class="p-6">
-                                <div class="mb-5">
-                                    <h4 class="text-lg font-bold mb-2" style="color: var(--text-primary); line-height: 1.4;">
-                                        "Condition"
-                                    </h4>
-                                    <div class="h-0.5 w-12 rounded-full" style="background-color: var(--accent-primary);"></div>
-                                    <p class="text-xs mt-3" style="color: var(--text-secondary); line-height: 1.6;">
-                                        "Define what criteria torrents must meet for this rule to apply. Currently, only one condition is supported per rule."
-                                    </p>
-                                </div>
-                                <div class="space-y-6">
-                                    <div style="margin-bottom: 1.5rem;">
-                                        <label class="block text-sm font-semibold mb-3" style="color: var(--text-primary); line-height: 1.5;">
-                                            "Condition Type"
-                                        </label>
-                                        <select
-                                            class="w-full px-4 py-3 rounded-lg border transition-all"
-                                            style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary); font-size: 0.9375rem;"
-                                            on:change=move |ev| condition_type.set(event_target_value(&ev))
-                                            disabled=move || saving.get()
-                                        >
-                                        <optgroup label="Time-Based">
-                                            <option value="SeedingTime" selected=move || condition_type.get() == "SeedingTime">"Seeding Time (hours)"</option>
-                                            <option value="StalledTime" selected=move || condition_type.get() == "StalledTime">"Stalled Time (hours)"</option>
-                                            <option value="Age" selected=move || condition_type.get() == "Age">"Age (hours)"</option>
-                                            <option value="ExpiresAt" selected=move || condition_type.get() == "ExpiresAt">"Expires At (hours remaining)"</option>
-                                            <option value="ETA" selected=move || condition_type.get() == "ETA">"ETA (hours)"</option>
-                                        </optgroup>
-                                        <optgroup label="Performance">
-                                            <option value="SeedingRatio" selected=move || condition_type.get() == "SeedingRatio">"Seeding Ratio"</option>
-                                            <option value="DownloadSpeed" selected=move || condition_type.get() == "DownloadSpeed">"Download Speed (bytes/sec)"</option>
-                                            <option value="UploadSpeed" selected=move || condition_type.get() == "UploadSpeed">"Upload Speed (bytes/sec)"</option>
-                                            <option value="Progress" selected=move || condition_type.get() == "Progress">"Progress (%)"</option>
-                                            <option value="Availability" selected=move || condition_type.get() == "Availability">"Availability (0-1)"</option>
-                                        </optgroup>
-                                        <optgroup label="Network">
-                                            <option value="Seeds" selected=move || condition_type.get() == "Seeds">"Seeds (count)"</option>
-                                            <option value="Peers" selected=move || condition_type.get() == "Peers">"Peers (count)"</option>
-                                        </optgroup>
-                                        <optgroup label="Size & Data">
-                                            <option value="FileSize" selected=move || condition_type.get() == "FileSize">"File Size (GB)"</option>
-                                            <option value="TotalUploaded" selected=move || condition_type.get() == "TotalUploaded">"Total Uploaded (GB)"</option>
-                                            <option value="TotalDownloaded" selected=move || condition_type.get() == "TotalDownloaded">"Total Downloaded (GB)"</option>
-                                        </optgroup>
-                                        <optgroup label="Status">
-                                            <option value="DownloadState" selected=move || condition_type.get() == "DownloadState">"Download State"</option>
-                                            <option value="Inactive" selected=move || condition_type.get() == "Inactive">"Inactive"</option>
-                                            <option value="DownloadFinished" selected=move || condition_type.get() == "DownloadFinished">"Download Finished"</option>
-                                            <option value="Cached" selected=move || condition_type.get() == "Cached">"Cached"</option>
-                                            <option value="DownloadPresent" selected=move || condition_type.get() == "DownloadPresent">"Download Present"</option>
-                                        </optgroup>
-                                        <optgroup label="Properties">
-                                            <option value="Private" selected=move || condition_type.get() == "Private">"Private"</option>
-                                            <option value="LongTermSeeding" selected=move || condition_type.get() == "LongTermSeeding">"Long Term Seeding"</option>
-                                            <option value="SeedTorrent" selected=move || condition_type.get() == "SeedTorrent">"Seed Torrent"</option>
-                                            <option value="TorrentFile" selected=move || condition_type.get() == "TorrentFile">"Has Torrent File"</option>
-                                            <option value="AllowZipped" selected=move || condition_type.get() == "AllowZipped">"Allow Zipped"</option>
-                                            <option value="HasMagnet" selected=move || condition_type.get() == "HasMagnet">"Has Magnet Link"</option>
-                                        </optgroup>
-                                    </select>
-                                    </div>
-                                    <Show when=move || condition_type.get() == "SeedingTime">
-                                        <div class="mt-3 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Hours since the torrent started seeding (from cached_at timestamp)"
-                                            </p>
-                                        </div>
-                                    </Show>
-                                    <Show when=move || condition_type.get() == "SeedingRatio">
-                                        <div class="mt-3 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Upload/download ratio (e.g., 1.0 = uploaded as much as downloaded)"
-                                            </p>
-                                        </div>
-                                    </Show>
-                                    <Show when=move || condition_type.get() == "StalledTime">
-                                        <div class="mt-3 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Hours since last update (indicates stalled torrents)"
-                                            </p>
-                                        </div>
-                                    </Show>
-                                    <Show when=move || condition_type.get() == "Age">
-                                        <div class="mt-3 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Hours since torrent was created"
-                                            </p>
-                                        </div>
-                                    </Show>
-                                    <Show when=move || condition_type.get() == "Inactive">
-                                        <div class="mt-3 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Torrent is not active or in error/failed state (use value 1.0 for Equal)"
-                                            </p>
-                                        </div>
-                                    </Show>
-                                    <Show when=move || condition_type.get() == "DownloadFinished">
-                                        <div class="mt-3 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Download is complete (use value 1.0 for Equal)"
-                                            </p>
-                                        </div>
-                                    </Show>
-                                    <Show when=move || condition_type.get() == "Cached">
-                                        <div class="mt-3 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Torrent is cached on server (use value 1.0 for Equal)"
-                                            </p>
-                                        </div>
-                                    </Show>
-                                    <Show when=move || condition_type.get() == "DownloadState">
-                                        <div class="mt-3 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "State: 0=downloading, 1=uploading, 2=stopped, 3=cached (use Equal operator)"
-                                            </p>
-                                        </div>
-                                    </Show>
-                                    <Show when=move || condition_type.get() == "Progress">
-                                        <div class="mt-3 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Download progress percentage (0-100)"
-                                            </p>
-                                        </div>
-                                    </Show>
-                                    <Show when=move || condition_type.get() == "FileSize">
-                                        <div class="mt-3 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Torrent size in gigabytes"
-                                            </p>
-                                        </div>
-                                    </Show>
-                                    <Show when=move || condition_type.get() == "ETA">
-                                        <div class="mt-3 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Estimated time to completion in hours"
-                                            </p>
-                                        </div>
-                                    </Show>
-                                    <Show when=move || condition_type.get() == "Availability">
-                                        <div class="mt-3 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Torrent availability (0.0 to 1.0, where 1.0 = fully available)"
-                                            </p>
-                                        </div>
-                                    </Show>
-                                    <Show when=move || condition_type.get() == "ExpiresAt">
-                                        <div class="mt-3 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Hours until torrent expires (only for torrents with expiration)"
-                                            </p>
-                                        </div>
-                                    </Show>
+    Effect::new(move |_| {
+        if !show.get() {
+            return;
+        }
+        #[cfg(feature = "hydrate")]
+        {
+            loading.set(true);
+            deleted_error.set(None);
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("GET");
+                                    i.set_headers(&headers);
+                                    i
+                                };
+
+                                let promise = window.fetch_with_str_and_init("/api/automation/deleted", &init);
+                                if let Ok(response) = wasm_bindgen_futures::JsFuture::from(promise).await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                    if let Ok(text_promise) = resp.text() {
+                                        if let Ok(text_value) = wasm_bindgen_futures::JsFuture::from(text_promise).await {
+                                            let text = text_value.as_string().unwrap_or_default();
+                                            match serde_json::from_str::<ApiResponse<Vec<DeletedTorrent>>>(&text) {
+                                                Ok(api_response) if api_response.success => deleted.set(api_response.data.unwrap_or_default()),
+                                                Ok(api_response) => deleted_error.set(Some(api_response.error.unwrap_or_else(|| "Failed to load deleted torrents".to_string()))),
+                                                Err(_) => deleted_error.set(Some(format!("Failed to load deleted torrents: {}", resp.status()))),
+                                            }
+                                        }
+                                    }
+                                } else {
+                                    deleted_error.set(Some("Network request failed".to_string()));
+                                }
+                            }
+                        }
+                    }
+                }
+                loading.set(false);
+            });
+        }
+    });
+
+    // Adds the torrent again, or with `readd` false drops it from the archive.
+    let run_action = move |id: i64, readd: bool| {
+        #[cfg(feature = "hydrate")]
+        {
+            busy_id.set(Some(id));
+            deleted_error.set(None);
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let (method, url) = if readd {
+                                    ("POST", format!("/api/automation/deleted/{}/readd", id))
+                                } else {
+                                    ("DELETE", format!("/api/automation/deleted/{}", id))
+                                };
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method(method);
+                                    i.set_headers(&headers);
+                                    i
+                                };
 
-                                    <div class="grid grid-cols-1 md:grid-cols-2 gap-5" style="margin-top: 1.5rem;">
-                                        <div>
-                                            <label class="block text-sm font-semibold mb-3" style="color: var(--text-primary); line-height: 1.5;">
-                                                "Operator"
-                                            </label>
-                                            <select
-                                                class="w-full px-4 py-3 rounded-lg border transition-all"
-                                                style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary); font-size: 0.9375rem;"
-                                                on:change=move |ev| condition_operator.set(event_target_value(&ev))
-                                                disabled=move || saving.get()
-                                            >
-                                                <option value="GreaterThan" selected=move || condition_operator.get() == "GreaterThan">"Greater Than (>)"</option>
-                                                <option value="LessThan" selected=move || condition_operator.get() == "LessThan">"Less Than (<)"</option>
-                                                <option value="GreaterThanOrEqual" selected=move || condition_operator.get() == "GreaterThanOrEqual">"Greater Than Or Equal (≥)"</option>
-                                                <option value="LessThanOrEqual" selected=move || condition_operator.get() == "LessThanOrEqual">"Less Than Or Equal (≤)"</option>
-                                                <option value="Equal" selected=move || condition_operator.get() == "Equal">"Equal (=)"</option>
-                                            </select>
-                                        </div>
+                                let promise = window.fetch_with_str_and_init(&url, &init);
+                                if let Ok(response) = wasm_bindgen_futures::JsFuture::from(promise).await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                    if let Ok(text_promise) = resp.text() {
+                                        if let Ok(text_value) = wasm_bindgen_futures::JsFuture::from(text_promise).await {
+                                            let text = text_value.as_string().unwrap_or_default();
+                                            match serde_json::from_str::<ApiResponse<DeletedTorrent>>(&text) {
+                                                Ok(api_response) if api_response.success => match api_response.data {
+                                                    Some(updated) => deleted.update(|deleted| {
+                                                        if let Some(entry) = deleted.iter_mut().find(|entry| entry.id == Some(id)) {
+                                                            *entry = updated;
+                                                        }
+                                                    }),
+                                                    None => deleted.update(|deleted| deleted.retain(|entry| entry.id != Some(id))),
+                                                },
+                                                Ok(api_response) => deleted_error.set(Some(api_response.error.unwrap_or_else(|| "Request failed".to_string()))),
+                                                Err(_) => deleted_error.set(Some(format!("Request failed: {}", resp.status()))),
+                                            }
+                                        }
+                                    }
+                                } else {
+                                    deleted_error.set(Some("Network request failed".to_string()));
+                                }
+                            }
+                        }
+                    }
+                }
+                busy_id.set(None);
+            });
+        }
+        #[cfg(not(feature = "hydrate"))]
+        let _ = (id, readd);
+    };
 
-                                        <div>
-                                            <label class="block text-sm font-semibold mb-3" style="color: var(--text-primary); line-height: 1.5;">
-                                                "Value"
-                                            </label>
-                                            <input
-                                                type="number"
-                                                step="0.1"
-                                                class="w-full px-4 py-3 rounded-lg border transition-all"
-                                                style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary); font-size: 0.9375rem;"
-                                                value=move || condition_value.get().to_string()
-                                                on:input=move |ev| {
-                                                    if let Ok(val) = event_target_value(&ev).parse::<f64>() {
-                                                        condition_value.set(val);
-                                                    }
-                                                }
-                                                disabled=move || saving.get()
-                                            />
-                                        </div>
-                                    </div>
-                                </div>
-                            </div>
+    view! {
+        <Show when=move || show.get()>
+            <div class="mb-6 p-6 rounded-lg border" style="background-color: var(--bg-card); border-color: var(--border-secondary);">
+                <div class="mb-4">
+                    <h3 class="text-lg font-semibold" style="color: var(--text-primary); line-height: 1.5;">
+                        "Recently Deleted"
+                    </h3>
+                    <p class="text-sm" style="color: var(--text-secondary);">
+                        "Torrents deleted by rules or from the downloads table, kept so they can be added again from their magnet."
+                    </p>
+                </div>
 
-                            <div class="p-6">
-                                <div class="mb-5">
-                                    <h4 class="text-lg font-bold mb-2" style="color: var(--text-primary); line-height: 1.4;">
-                                        "Action"
-                                    </h4>
-                                    <div class="h-0.5 w-12 rounded-full" style="background-color: var(--accent-primary);"></div>
-                                    <p class="text-xs mt-3" style="color: var(--text-secondary); line-height: 1.6;">
-                                        "What action should be performed on torrents that match the condition?"
-                                    </p>
+                <Show when=move || deleted_error.get().is_some()>
+                    <p class="text-sm mb-4" style="color: var(--text-error);">{move || deleted_error.get().unwrap_or_default()}</p>
+                </Show>
+
+                <Show when=move || loading.get()>
+                    <div class="flex justify-center py-8">
+                        <LoadingSpinner size=SpinnerSize::Medium variant=SpinnerVariant::Accent/>
+                    </div>
+                </Show>
+
+                <Show when=move || !loading.get() && deleted_error.get().is_none() && deleted.get().is_empty()>
+                    <p class="text-sm" style="color: var(--text-secondary);">"No deleted torrents"</p>
+                </Show>
+
+                <div class="space-y-2">
+                    {move || deleted.get().into_iter().filter_map(|entry| {
+                        let id = entry.id?;
+                        let deleted_by = entry.rule_name.clone().map(|name| format!("by rule '{}'", name)).unwrap_or_else(|| "manually".to_string());
+                        let summary = format!(
+                            "{} · {} file{} · deleted {} UTC {}",
+                            format_bytes(entry.size),
+                            entry.files.len(),
+                            if entry.files.len() == 1 { "" } else { "s" },
+                            entry.deleted_at.clone().unwrap_or_default(),
+                            deleted_by
+                        );
+                        let readded = entry.readded_at.clone().map(|readded_at| match entry.readded_torrent_id {
+                            Some(torrent_id) => format!("Re-added {} UTC as torrent {}", readded_at, torrent_id),
+                            None => format!("Re-added {} UTC", readded_at),
+                        });
+                        Some(view! {
+                            <div class="p-3 rounded-lg border flex flex-col sm:flex-row sm:items-center justify-between gap-3" style="background-color: var(--bg-secondary); border-color: var(--border-secondary);">
+                                <div class="min-w-0">
+                                    <p class="text-sm font-medium truncate" style="color: var(--text-primary);" title=entry.name.clone()>{entry.name.clone()}</p>
+                                    <p class="text-xs" style="color: var(--text-secondary);">{summary}</p>
+                                    {readded.map(|readded| view! {
+                                        <p class="text-xs" style="color: var(--text-success);">{readded}</p>
+                                    })}
                                 </div>
-                                <div>
-                                    <select
-                                        class="w-full px-4 py-3 rounded-lg border transition-all"
-                                        style="background-color: var(--bg-tertiary); border-color: var(--border-secondary); color: var(--text-primary); font-size: 0.9375rem;"
-                                        on:change=move |ev| action_type.set(event_target_value(&ev))
-                                        disabled=move || saving.get()
+                                <div class="flex items-center gap-2 shrink-0">
+                                    <button
+                                        class="px-3 py-1.5 rounded-lg text-sm font-medium transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+                                        style="background-color: var(--accent-primary); color: var(--text-on-accent);"
+                                        disabled=move || busy_id.get().is_some()
+                                        on:click=move |_| run_action(id, true)
                                     >
-                                <optgroup label="Control">
-                                    <option value="StopSeeding" selected=move || action_type.get() == "StopSeeding">"Stop Seeding"</option>
-                                    <option value="Stop" selected=move || action_type.get() == "Stop">"Stop"</option>
-                                    <option value="Resume" selected=move || action_type.get() == "Resume">"Resume"</option>
-                                    <option value="Restart" selected=move || action_type.get() == "Restart">"Restart"</option>
-                                    <option value="ForceStart" selected=move || action_type.get() == "ForceStart">"Force Start"</option>
-                                    <option value="Reannounce" selected=move || action_type.get() == "Reannounce">"Reannounce"</option>
-                                </optgroup>
-                                <optgroup label="Remove">
-                                    <option value="Delete" selected=move || action_type.get() == "Delete">"Delete"</option>
-                                </optgroup>
-                            </select>
-                                    <Show when=move || action_type.get() == "StopSeeding">
-                                        <div class="mt-4 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Stops seeding for completed/seeding torrents. Use for torrents that have finished downloading."
-                                            </p>
-                                        </div>
-                                    </Show>
-                                    <Show when=move || action_type.get() == "Stop">
-                                        <div class="mt-4 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Stops active torrents (downloads or uploads). Use for torrents currently downloading or uploading."
-                                            </p>
-                                        </div>
-                                    </Show>
-                                    <Show when=move || action_type.get() == "Resume">
-                                        <div class="mt-4 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Resumes a stopped torrent. Use to restart paused or stopped downloads."
-                                            </p>
-                                        </div>
-                                    </Show>
-                                    <Show when=move || action_type.get() == "Restart">
-                                        <div class="mt-4 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Restarts a torrent from the beginning. Use to reset and restart downloads."
-                                            </p>
-                                        </div>
-                                    </Show>
-                                    <Show when=move || action_type.get() == "ForceStart">
-                                        <div class="mt-4 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Forces a torrent to start immediately. Use to start queued or stopped torrents."
-                                            </p>
-                                        </div>
-                                    </Show>
-                                    <Show when=move || action_type.get() == "Reannounce">
-                                        <div class="mt-4 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Reannounces to tracker. Use for stalled torrents to refresh tracker connection."
-                                            </p>
-                                        </div>
-                                    </Show>
-                                    <Show when=move || action_type.get() == "Delete">
-                                        <div class="mt-4 p-3.5 rounded-lg" style="background-color: var(--bg-tertiary); border: 1px solid var(--border-secondary);">
-                                            <p class="text-xs" style="color: var(--text-secondary); line-height: 1.6;">
-                                                "Permanently deletes the torrent and its files. This action cannot be undone."
-                                            </p>
-                                        </div>
-                                    </Show>
+                                        "Re-add"
+                                    </button>
+                                    <button
+                                        class="px-3 py-1.5 rounded-lg text-sm font-medium transition-colors border disabled:opacity-50 disabled:cursor-not-allowed"
+                                        style="background-color: var(--bg-secondary); border-color: var(--border-secondary); color: var(--text-primary);"
+                                        title="Remove from this list without adding it again"
+                                        disabled=move || busy_id.get().is_some()
+                                        on:click=move |_| run_action(id, false)
+                                    >
+                                        "Forget"
+                                    </button>
                                 </div>
                             </div>
-                        </div>
-                    </div>
+                        })
+                    }).collect::<Vec<_>>()}
+                </div>
+            </div>
+        </Show>
+    }
+}
 
-                    <div class="flex-shrink-0 px-4 sm:px-6 md:px-8 py-4 sm:py-5 md:py-6 border-t flex flex-row justify-end gap-3 sm:gap-4" style="border-color: var(--border-primary); background-color: var(--bg-secondary);">
-                        <button
-                            class="px-6 py-3 rounded-lg transition-all font-semibold text-sm"
-                            style="background-color: var(--bg-tertiary); color: var(--text-primary); border: 1px solid var(--border-secondary); line-height: 1.5; display: inline-flex !important; align-items: center; justify-content: center; width: auto !important; min-width: 100px; max-width: none !important; flex: 0 0 auto !important;"
-                            on:click=move |_| {
-                                if !saving.get() {
-                                    show.set(false);
-                                }
-                            }
-                            disabled=move || saving.get()
-                        >
-                            "Cancel"
-                        </button>
-                        <button
-                            class="px-6 py-3 rounded-lg font-semibold transition-all text-sm"
-                            style="background-color: var(--accent-primary); color: var(--text-on-accent); line-height: 1.5; display: inline-flex !important; align-items: center; justify-content: center; box-shadow: var(--shadow-md); width: auto !important; min-width: 100px; max-width: none !important; flex: 0 0 auto !important;"
-                                on:click={
-                                    let on_save_for_click = on_save_arc_clone.clone();
-                                    move |_| {
-                                        #[cfg(feature = "hydrate")]
-                                        {
-                                            saving_save.set(true);
-                                            save_error_save.set(None);
-                                            let rule_name_clone = rule_name_save.clone();
-                                            let rule_enabled_clone = rule_enabled_save.clone();
-                                            let trigger_type_clone = trigger_type_save.clone();
-                                            let cron_expression_clone = cron_expression_save.clone();
-                                            let interval_minutes_clone = interval_minutes_save.clone();
-                                            let condition_type_clone = condition_type_save.clone();
-                                            let condition_operator_clone = condition_operator_save.clone();
-                                            let condition_value_clone = condition_value_save.clone();
-                                            let action_type_clone = action_type_save.clone();
-                                            let editing_rule_id_clone = editing_rule_id_save.clone();
-                                            let saving_clone = saving_save.clone();
-                                            let save_error_clone = save_error_save.clone();
-                                            let on_save_clone = on_save_for_click.clone();
-                                        
-                                        spawn_local(async move {
-                                            if let Some(window) = web_sys::window() {
-                                                if let Ok(Some(storage)) = window.local_storage() {
-                                                    if let Ok(Some(api_key)) = storage.get_item("api_key") {
-                                                        if !api_key.is_empty() {
-                                                            let trigger_config = if trigger_type_clone.get() == "cron" {
-                                                                serde_json::json!({
-                                                                    "Cron": {
-                                                                        "expression": cron_expression_clone.get()
-                                                                    }
-                                                                })
-                                                            } else {
-                                                                serde_json::json!({
-                                                                    "Interval": {
-                                                                        "minutes": interval_minutes_clone.get()
-                                                                    }
-                                                                })
-                                                            };
+#[component]
+fn ApiKeyStatusBanner(
+    status: RwSignal<Option<ApiKeyStatus>>,
+    on_migrated: impl Fn() + 'static + Send + Sync,
+) -> impl IntoView {
+    let migrating = RwSignal::new(None::<String>);
+    let migrate_error = RwSignal::new(None::<String>);
+    let on_migrated = StoredValue::new(on_migrated);
 
-                                                            let conditions = vec![serde_json::json!({
-                                                                "type": condition_type_clone.get(),
-                                                                "operator": condition_operator_clone.get(),
-                                                                "value": condition_value_clone.get()
-                                                            })];
+    #[cfg(feature = "hydrate")]
+    {
+        spawn_local(async move {
+            if let Some(window) = web_sys::window() {
+                if let Ok(Some(storage)) = window.local_storage() {
+                    if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                        if !api_key.is_empty() {
+                            let headers = web_sys::Headers::new().unwrap();
+                            headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
 
-                                                            let action_config = serde_json::json!({
-                                                                "action_type": action_type_clone.get(),
-                                                                "params": serde_json::Value::Null
-                                                            });
+                            let init = {
+                                let mut i = web_sys::RequestInit::new();
+                                i.set_method("GET");
+                                i.set_headers(&headers);
+                                i
+                            };
 
-                                                            let request = CreateRuleRequest {
-                                                                name: rule_name_clone.get(),
-                                                                enabled: Some(rule_enabled_clone.get()),
-                                                                trigger_config,
-                                                                conditions,
-                                                                action_config,
-                                                            };
+                            let promise = window.fetch_with_str_and_init("/api/automation/keys/status", &init);
+                            if let Ok(response) = wasm_bindgen_futures::JsFuture::from(promise).await {
+                                let resp: web_sys::Response = response.dyn_into().unwrap();
+                                if let Ok(text_promise) = resp.text() {
+                                    if let Ok(text_value) = wasm_bindgen_futures::JsFuture::from(text_promise).await {
+                                        let text = text_value.as_string().unwrap_or_default();
+                                        if let Ok(api_response) = serde_json::from_str::<ApiResponse<ApiKeyStatus>>(&text) {
+                                            status.set(api_response.data);
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+    }
 
-                                                            let url = if editing_rule_id_clone.get().is_some() {
-                                                                format!("/api/automation/rules/{}", editing_rule_id_clone.get().unwrap())
-                                                            } else {
-                                                                "/api/automation/rules".to_string()
-                                                            };
+    let migrate = move |old_api_key_hash: String| {
+        #[cfg(feature = "hydrate")]
+        {
+            migrating.set(Some(old_api_key_hash.clone()));
+            migrate_error.set(None);
+            let body = serde_json::json!({ "old_api_key_hash": old_api_key_hash });
+            spawn_local(async move {
+                if let Some(window) = web_sys::window() {
+                    if let Ok(Some(storage)) = window.local_storage() {
+                        if let Ok(Some(api_key)) = storage.get_item("api_key") {
+                            if !api_key.is_empty() {
+                                let headers = web_sys::Headers::new().unwrap();
+                                headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
+                                headers.set("Content-Type", "application/json").unwrap();
 
-                                                            let method = if editing_rule_id_clone.get().is_some() { "PUT" } else { "POST" };
+                                let body_js = wasm_bindgen::JsValue::from_str(&body.to_string());
 
-                                                            let headers = web_sys::Headers::new().unwrap();
-                                                            headers.set("Authorization", &format!("Bearer {}", api_key)).unwrap();
-                                                            headers.set("Content-Type", "application/json").unwrap();
-                                                            
-                                                            let body = serde_json::to_string(&request).unwrap();
-                                                            let body_js = wasm_bindgen::JsValue::from_str(&body);
-                                                            
-                                                            let init = {
-                                                                let mut i = web_sys::RequestInit::new();
-                                                                i.set_method(method);
-                                                                i.set_headers(&headers);
-                                                                i.set_body(&body_js);
-                                                                i
-                                                            };
-                                                            
-                                                            let promise = window.fetch_with_str_and_init(&url, &init);
-                                                            let future = wasm_bindgen_futures::JsFuture::from(promise);
-                                                            
-                                                            if let Ok(response) = future.await {
-                                                                let resp: web_sys::Response = response.dyn_into().unwrap();
-                                                                if resp.status() == 200 || resp.status() == 201 {
-                                                                    saving_clone.set(false);
-                                                                    on_save_clone();
-                                                                } else {
-                                                                    let text_promise = resp.text().unwrap();
-                                                                    let text_future = wasm_bindgen_futures::JsFuture::from(text_promise);
-                                                                    if let Ok(text_value) = text_future.await {
-                                                                        if let Some(text) = text_value.as_string() {
-                                                                            if let Ok(api_response) = serde_json::from_str::<ApiResponse<serde_json::Value>>(&text) {
-                                                                                if let Some(err_msg) = api_response.error {
-                                                                                    save_error_clone.set(Some(err_msg));
-                                                                                } else {
-                                                                                    save_error_clone.set(Some(format!("Failed to save: {}", text)));
-                                                                                }
-                                                                            } else {
-                                                                                save_error_clone.set(Some(format!("Failed to save: {}", text)));
-                                                                            }
-                                                                        } else {
-                                                                            save_error_clone.set(Some(format!("Failed to save: {}", resp.status())));
-                                                                        }
-                                                                    } else {
-                                                                        save_error_clone.set(Some(format!("Failed to save: {}", resp.status())));
-                                                                    }
-                                                                    saving_clone.set(false);
-                                                                }
-                                                            } else {
-                                                                save_error_clone.set(Some("Network request failed".to_string()));
-                                                                saving_clone.set(false);
-                                                            }
-                                                        }
-                                                    }
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("POST");
+                                    i.set_headers(&headers);
+                                    i.set_body(&body_js);
+                                    i
+                                };
+
+                                let promise = window.fetch_with_str_and_init("/api/automation/keys/migrate", &init);
+                                if let Ok(response) = wasm_bindgen_futures::JsFuture::from(promise).await {
+                                    let resp: web_sys::Response = response.dyn_into().unwrap();
+                                    if let Ok(text_promise) = resp.text() {
+                                        if let Ok(text_value) = wasm_bindgen_futures::JsFuture::from(text_promise).await {
+                                            let text = text_value.as_string().unwrap_or_default();
+                                            match serde_json::from_str::<ApiResponse<ApiKeyStatus>>(&text) {
+                                                Ok(api_response) if api_response.success => {
+                                                    status.set(api_response.data);
+                                                    on_migrated.with_value(|on_migrated| on_migrated());
                                                 }
+                                                Ok(api_response) => migrate_error.set(Some(api_response.error.unwrap_or_else(|| "Failed to move rules".to_string()))),
+                                                Err(_) => migrate_error.set(Some(format!("Failed to move rules: {}", resp.status()))),
                                             }
-                                        });
+                                        }
                                     }
+                                } else {
+                                    migrate_error.set(Some("Network request failed".to_string()));
                                 }
                             }
-                            disabled=move || saving_save.get()
-                            >
-                                {move || if saving_save.get() { "Saving..." } else { "Save" }}
-                            </button>
-                        </div>
+                        }
+                    }
+                }
+                migrating.set(None);
+            });
+        }
+        #[cfg(not(feature = "hydrate"))]
+        {
+            let _ = (old_api_key_hash, on_migrated);
+        }
+    };
+
+    view! {
+        <Show when=move || status.get().map(|status| status.suspended_at.is_some()).unwrap_or(false)>
+            <div class="mb-6 p-4 rounded-lg border" style="background-color: var(--bg-error); border-color: var(--border-error, #ef4444); color: var(--text-error); line-height: 1.5;">
+                <div class="flex items-start">
+                    <span class="text-lg mr-2">"⚠"</span>
+                    <div class="flex-1">
+                        <p class="font-medium mb-1">"Key invalid: your rules are suspended"</p>
+                        <p class="text-sm">
+                            {move || status.get().map(|status| format!(
+                                "TorBox rejected this API key {} times in a row{}. Sign out and sign in with your current API key to resume your rules; they move over to the new key automatically.",
+                                status.auth_failures,
+                                status.last_auth_error.map(|error| format!(" ({})", error)).unwrap_or_default(),
+                            )).unwrap_or_default()}
+                        </p>
                     </div>
                 </div>
+            </div>
+        </Show>
+
+        <Show when=move || status.get().map(|status| status.migrated_rules > 0).unwrap_or(false)>
+            <div class="mb-6 p-4 rounded-lg border" style="background-color: var(--bg-success); border-color: var(--border-secondary); color: var(--text-success); line-height: 1.5;">
+                {move || status.get().map(|status| format!(
+                    "Moved {} rule{} from your previous API key to this one.",
+                    status.migrated_rules,
+                    if status.migrated_rules == 1 { "" } else { "s" },
+                )).unwrap_or_default()}
+            </div>
+        </Show>
+
+        <Show when=move || status.get().map(|status| !status.other_keys.is_empty()).unwrap_or(false)>
+            <div class="mb-6 p-4 rounded-lg border" style="background-color: var(--bg-card); border-color: var(--border-secondary); line-height: 1.5;">
+                <p class="font-medium mb-1" style="color: var(--text-primary);">"Rules on another API key"</p>
+                <p class="text-sm mb-3" style="color: var(--text-secondary);">
+                    "These rules belong to another API key of your TorBox account. Move them here if that key was regenerated."
+                </p>
+                <Show when=move || migrate_error.get().is_some()>
+                    <p class="text-sm mb-3" style="color: var(--text-error);">{move || migrate_error.get().unwrap_or_default()}</p>
+                </Show>
+                <div class="space-y-2">
+                    {move || status.get().map(|status| status.other_keys).unwrap_or_default().into_iter().map(|key| {
+                        let hash = key.api_key_hash.clone();
+                        let hash_for_busy = key.api_key_hash.clone();
+                        view! {
+                            <div class="flex items-center justify-between gap-3 p-3 rounded-lg" style="background-color: var(--bg-secondary);">
+                                <div class="text-sm" style="color: var(--text-primary);">
+                                    {format!("Key …{}: {} rule{}", &key.api_key_hash[key.api_key_hash.len().saturating_sub(8)..], key.rule_count, if key.rule_count == 1 { "" } else { "s" })}
+                                    {key.suspended_at.map(|_| view! {
+                                        <span class="ml-2 px-2 py-1 text-xs rounded" style="background-color: var(--bg-error); color: var(--text-error);">"Key invalid"</span>
+                                    })}
+                                </div>
+                                <button
+                                    class="px-3 py-2 rounded-lg text-sm font-medium transition-colors whitespace-nowrap disabled:opacity-50 disabled:cursor-not-allowed"
+                                    style="background-color: var(--accent-primary); color: var(--text-on-accent);"
+                                    disabled=move || migrating.get().is_some()
+                                    on:click=move |_| migrate(hash.clone())
+                                >
+                                    {move || if migrating.get().as_deref() == Some(hash_for_busy.as_str()) { "Moving..." } else { "Move to this key" }}
+                                </button>
+                            </div>
+                        }
+                    }).collect_view()}
+                </div>
+            </div>
         </Show>
     }
 }
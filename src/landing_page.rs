@@ -4,7 +4,7 @@ use leptos::task::spawn_local;
 use web_sys;
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen;
-use crate::api::{RequestHandler, ApiError};
+use crate::api::{RequestHandler, ApiError, format_api_error};
 use crate::notifications::use_notification;
 
 #[component]
@@ -84,6 +84,24 @@ pub fn LandingPage() -> impl IntoView {
                                     notifications_local.warning("Failed to save API key to storage".to_string());
                                 }
                             }
+
+                            // Reactivates automation rules suspended for this key, or moves
+                            // over those of the key it replaced. Failing here must not block
+                            // the sign-in itself.
+                            let headers = web_sys::Headers::new().ok().filter(|headers| {
+                                headers.set("Authorization", &format!("Bearer {}", key)).is_ok()
+                            });
+                            if let Some(headers) = headers {
+                                let init = {
+                                    let mut i = web_sys::RequestInit::new();
+                                    i.set_method("POST");
+                                    i.set_headers(&headers);
+                                    i
+                                };
+                                let promise = window.fetch_with_str_and_init("/api/automation/keys/sign-in", &init);
+                                let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+                            }
+
                             window.location().set_href("/dashboard");
                         } else {
                             is_connecting_local.set(false);
@@ -100,8 +118,7 @@ pub fn LandingPage() -> impl IntoView {
                         is_connecting_local.set(false);
                         error_message_local.set("Invalid Key".to_string());
                         let error_msg = match e {
-                            ApiError::NetworkError => "Network error. Please check your connection and try again.".to_string(),
-                            ApiError::ServerError => "Server error. Please try again later.".to_string(),
+                            ApiError::NetworkError | ApiError::ServerError(_) => format_api_error(&e),
                             _ => format!("Failed to validate API key: {}", e),
                         };
                         notifications_local.error(error_msg);
@@ -11,7 +11,7 @@ async fn main() {
     use leptos_axum::{generate_route_list, LeptosRoutes};
     use std::sync::Arc;
     use torbox_companion::app::*;
-    use torbox_companion::automation::{Database, AutomationScheduler, create_routes};
+    use torbox_companion::automation::{Database, AutomationScheduler, EventWatcher, TransferTracker, create_routes};
     use torbox_companion::logging::logging_middleware;
 
     let conf = get_configuration(Some("Cargo.toml")).unwrap();
@@ -27,6 +27,48 @@ async fn main() {
     let db_path = std::env::var("TORBOX_DB_PATH")
         .unwrap_or_else(|_| "data/torbox.db".to_string());
 
+    let master_key = torbox_companion::automation::encryption::MasterKey::from_env()
+        .expect("Failed to load master key");
+
+    if std::env::args().nth(1).as_deref() == Some("rotate-keys") {
+        let database = Database::new(&db_path, 1, master_key)
+            .await
+            .expect("Failed to initialize database");
+        match database.rotate_data_key().await {
+            Ok(report) => {
+                log!("Rotated to data key {}: re-encrypted {} API keys, pruned {} retired keys", 
+                     report.data_key_id, report.reencrypted, report.pruned_keys);
+                if report.remaining > 0 {
+                    log!("{} API keys are still on an older data key; run rotate-keys again once they can be decrypted", report.remaining);
+                }
+            }
+            Err(e) => {
+                log!("Key rotation failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if std::env::args().nth(1).as_deref() == Some("migrate-rules") {
+        let args: Vec<String> = std::env::args().skip(2).collect();
+        let [old_hash, new_hash] = args.as_slice() else {
+            log!("Usage: torbox-companion migrate-rules <old-api-key-hash> <new-api-key-hash>");
+            std::process::exit(2);
+        };
+        let database = Database::new(&db_path, 1, master_key)
+            .await
+            .expect("Failed to initialize database");
+        match database.migrate_rules(old_hash, new_hash).await {
+            Ok(moved) => log!("Moved {} rules to the new API key; restart the server to schedule them", moved),
+            Err(e) => {
+                log!("Rule migration failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     let max_rules_per_user = std::env::var("TORBOX_MAX_RULES_PER_USER")
         .unwrap_or_else(|_| "100".to_string())
         .parse::<i64>()
@@ -37,17 +79,61 @@ async fn main() {
         .parse::<i64>()
         .unwrap_or(90);
 
+    let deleted_retention_days = std::env::var("TORBOX_DELETED_RETENTION_DAYS")
+        .unwrap_or_else(|_| "30".to_string())
+        .parse::<i64>()
+        .unwrap_or(30);
+
     let rule_execution_timeout_secs = std::env::var("TORBOX_RULE_EXECUTION_TIMEOUT_SECS")
         .unwrap_or_else(|_| "130".to_string())
         .parse::<u64>()
         .unwrap_or(130);
 
+    let max_concurrent_rule_runs = std::env::var("TORBOX_MAX_CONCURRENT_RULE_RUNS")
+        .unwrap_or_else(|_| "4".to_string())
+        .parse::<usize>()
+        .unwrap_or(4);
+
+    let event_poll_interval_secs = std::env::var("TORBOX_EVENT_POLL_INTERVAL_SECS")
+        .unwrap_or_else(|_| "60".to_string())
+        .parse::<u64>()
+        .unwrap_or(60)
+        .max(30);
+
+    let transfer_poll_interval_secs = std::env::var("TORBOX_TRANSFER_POLL_INTERVAL_SECS")
+        .unwrap_or_else(|_| "120".to_string())
+        .parse::<u64>()
+        .unwrap_or(120)
+        .max(30);
+
+    let api_rate_limit_per_minute = std::env::var("TORBOX_API_RATE_LIMIT_PER_MINUTE")
+        .unwrap_or_else(|_| "120".to_string())
+        .parse::<u32>()
+        .unwrap_or(120)
+        .max(1);
+
+    let api_rate_limit_burst = std::env::var("TORBOX_API_RATE_LIMIT_BURST")
+        .unwrap_or_else(|_| "20".to_string())
+        .parse::<u32>()
+        .unwrap_or(20)
+        .max(1);
+
+    let db_read_connections = std::env::var("TORBOX_DB_READ_CONNECTIONS")
+        .unwrap_or_else(|_| "4".to_string())
+        .parse::<usize>()
+        .unwrap_or(4)
+        .clamp(1, 32);
+
+    torbox_companion::api::rate_limiter::init(api_rate_limit_per_minute, api_rate_limit_burst);
+    log!("TorBox API rate limit: {} requests/minute per API key (burst {})", api_rate_limit_per_minute, api_rate_limit_burst);
+
     log!("Initializing automation database at: {}", db_path);
-    log!("Configuration: max_rules_per_user={}, log_retention_days={}, execution_timeout_secs={}", 
-         max_rules_per_user, log_retention_days, rule_execution_timeout_secs);
+    log!("API key encryption: {}", if master_key.is_some() { "data keys wrapped by master key" } else { "no master key configured" });
+    log!("Configuration: max_rules_per_user={}, log_retention_days={}, deleted_retention_days={}, execution_timeout_secs={}, max_concurrent_rule_runs={}, db_read_connections={}", 
+         max_rules_per_user, log_retention_days, deleted_retention_days, rule_execution_timeout_secs, max_concurrent_rule_runs, db_read_connections);
     
     let database = Arc::new(
-        Database::new(&db_path)
+        Database::new(&db_path, db_read_connections, master_key)
             .await
             .expect("Failed to initialize database")
     );
@@ -68,12 +154,22 @@ async fn main() {
                     log!("Failed to cleanup old logs: {}", e);
                 }
             }
+            match database_for_cleanup.cleanup_deleted_torrents(deleted_retention_days).await {
+                Ok(count) => {
+                    if count > 0 {
+                        log!("Removed {} archived torrents deleted more than {} days ago", count, deleted_retention_days);
+                    }
+                }
+                Err(e) => {
+                    log!("Failed to cleanup deleted torrents: {}", e);
+                }
+            }
         }
     });
 
     log!("Initializing automation scheduler...");
     let scheduler = Arc::new(
-        AutomationScheduler::new(database.clone(), rule_execution_timeout_secs)
+        AutomationScheduler::new(database.clone(), rule_execution_timeout_secs, max_concurrent_rule_runs)
             .await
             .expect("Failed to initialize scheduler")
     );
@@ -81,6 +177,9 @@ async fn main() {
     scheduler.start().await.expect("Failed to start scheduler");
     log!("Automation scheduler started");
 
+    Arc::new(EventWatcher::new(database.clone(), scheduler.limiter(), event_poll_interval_secs)).start();
+    Arc::new(TransferTracker::new(database.clone(), transfer_poll_interval_secs)).start();
+
     let mut app = Router::new()
         .leptos_routes(&leptos_options, routes, {
             let leptos_options = leptos_options.clone();
@@ -127,7 +226,7 @@ async fn main() {
         log!("Shutting down server...");
     };
 
-    axum::serve(listener, app.into_make_service())
+    axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
         .with_graceful_shutdown(shutdown)
         .await
         .unwrap();
@@ -0,0 +1,57 @@
+#![cfg(feature = "ssr")]
+
+use serde_json::json;
+use torbox_companion::automation::bundle::{BundleFormat, BundledRule, RuleBundle};
+use torbox_companion::automation::types::AutomationRule;
+
+fn rule_with_secrets() -> AutomationRule {
+    serde_json::from_value(json!({
+        "id": 7,
+        "api_key_hash": "fixture-hash",
+        "name": "Upload and tell",
+        "enabled": true,
+        "trigger_config": {"Interval": {"minutes": 60}},
+        "conditions": [],
+        "action_config": {"steps": [
+            {"action_type": "Notify", "params": {
+                "url": "https://hooks.example.com/step",
+                "headers": {"Authorization": "Bearer step-secret"}
+            }},
+            {"action_type": "Delete", "params": null}
+        ]},
+        "notification": {
+            "url": "https://hooks.example.com/rule",
+            "headers": {"X-Api-Key": "rule-secret"}
+        },
+        "created_at": null,
+        "updated_at": null
+    }))
+    .unwrap()
+}
+
+#[test]
+fn export_leaves_out_secrets() {
+    let bundle = RuleBundle::from_rules(&[rule_with_secrets()]);
+    let content = bundle.serialize(BundleFormat::Json).unwrap();
+
+    for secret in ["step-secret", "rule-secret", "fixture-hash"] {
+        assert!(!content.contains(secret), "{} in {}", secret, content);
+    }
+    for kept in ["Authorization", "X-Api-Key", "https://hooks.example.com/rule", "Delete"] {
+        assert!(content.contains(kept), "{} missing from {}", kept, content);
+    }
+}
+
+#[test]
+fn imported_bundle_reports_blank_secrets() {
+    let exported = RuleBundle::from_rules(&[rule_with_secrets()])
+        .serialize(BundleFormat::Yaml)
+        .unwrap();
+    let imported = RuleBundle::parse(&exported).unwrap();
+    assert!(imported.rules[0].has_blank_secrets());
+
+    let mut untouched = rule_with_secrets();
+    untouched.action_config.steps.truncate(1);
+    let bundled = BundledRule::from(&untouched);
+    assert!(!bundled.has_blank_secrets());
+}
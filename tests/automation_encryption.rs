@@ -0,0 +1,191 @@
+#![cfg(feature = "ssr")]
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key};
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use torbox_companion::automation::encryption::{
+    EncryptionService, MasterKey, ALLOW_UNWRAPPED_DATA_KEY_VAR, CIPHER_BOUND, CIPHER_LEGACY,
+};
+use torbox_companion::automation::Database;
+
+const DATA_KEY: [u8; 32] = [7; 32];
+
+fn master(byte: &str) -> MasterKey {
+    MasterKey::from_hex(&byte.repeat(32)).unwrap()
+}
+
+/// A database path of its own for each test, in a directory that starts out empty.
+fn database_path(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("torbox-encryption-{}-{}", std::process::id(), test));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir.join("torbox.db")
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Runtime::new().unwrap().block_on(future)
+}
+
+fn open(path: &PathBuf, master_key: Option<MasterKey>) -> Result<Database, String> {
+    block_on(Database::new(path, 1, master_key))
+}
+
+async fn service_with(data_key: [u8; 32]) -> EncryptionService {
+    let service = EncryptionService::new();
+    service.initialize(1, HashMap::from([(1, data_key)])).await;
+    service
+}
+
+#[test]
+fn wrapped_data_key_round_trips() {
+    let master_key = master("a1");
+    let (wrapped, nonce) = master_key.wrap(&DATA_KEY).unwrap();
+
+    assert_ne!(wrapped.as_slice(), DATA_KEY.as_slice());
+    assert_eq!(master_key.unwrap(&wrapped, &nonce).unwrap(), DATA_KEY);
+
+    let other = master("b2");
+    assert_ne!(other.id(), master_key.id());
+    assert!(other.unwrap(&wrapped, &nonce).is_err());
+
+    let mut tampered = wrapped.clone();
+    tampered[0] ^= 1;
+    assert!(master_key.unwrap(&tampered, &nonce).is_err());
+}
+
+#[test]
+fn bound_ciphertext_only_decrypts_under_its_hash() {
+    block_on(async {
+        let service = service_with(DATA_KEY).await;
+        let encrypted = service.encrypt_api_key("api-key", "hash-a").await.unwrap();
+
+        let decrypt = |cipher_version, api_key_hash| {
+            service.decrypt_api_key(encrypted.data_key_id, cipher_version, api_key_hash, &encrypted.ciphertext, &encrypted.nonce)
+        };
+        assert_eq!(decrypt(CIPHER_BOUND, "hash-a").await.unwrap(), "api-key");
+        assert!(decrypt(CIPHER_BOUND, "hash-b").await.is_err());
+        assert!(decrypt(CIPHER_LEGACY, "hash-a").await.is_err());
+    });
+}
+
+#[test]
+fn legacy_ciphertext_still_decrypts() {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&DATA_KEY));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, b"api-key".as_slice()).unwrap();
+
+    block_on(async {
+        let service = service_with(DATA_KEY).await;
+        let decrypted = service.decrypt_api_key(1, CIPHER_LEGACY, "any-hash", &ciphertext, &nonce).await;
+        assert_eq!(decrypted.unwrap(), "api-key");
+    });
+}
+
+#[test]
+fn legacy_rows_are_bound_on_startup() {
+    let path = database_path("legacy-rows");
+    let master_key = master("c3");
+    drop(open(&path, Some(master_key.clone())).unwrap());
+
+    // Write a row the way releases before `CIPHER_BOUND` did.
+    let conn = Connection::open(&path).unwrap();
+    let (data_key_id, key_material, wrap_nonce): (i64, Vec<u8>, Vec<u8>) = conn
+        .query_row("SELECT id, key_material, wrap_nonce FROM data_keys WHERE active", [], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .unwrap();
+    let data_key = master_key.unwrap(&key_material, &wrap_nonce).unwrap();
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&data_key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, b"legacy-api-key".as_slice()).unwrap();
+    let api_key_hash = EncryptionService::hash_api_key("legacy-api-key");
+    conn.execute(
+        "INSERT INTO api_keys (api_key_hash, encrypted_api_key, nonce, data_key_id, cipher_version) VALUES (?, ?, ?, ?, ?)",
+        params![api_key_hash, ciphertext, nonce.to_vec(), data_key_id, CIPHER_LEGACY],
+    )
+    .unwrap();
+
+    let database = open(&path, Some(master_key)).unwrap();
+    assert_eq!(block_on(database.get_api_key(&api_key_hash)).unwrap(), "legacy-api-key");
+    let cipher_version: i64 = conn
+        .query_row("SELECT cipher_version FROM api_keys WHERE api_key_hash = ?", [&api_key_hash], |row| row.get(0))
+        .unwrap();
+    assert_eq!(cipher_version, CIPHER_BOUND);
+}
+
+#[test]
+fn wrong_or_missing_master_key_is_rejected() {
+    let path = database_path("wrong-master-key");
+    drop(open(&path, Some(master("d4"))).unwrap());
+
+    let error = open(&path, Some(master("e5"))).err().unwrap();
+    assert!(error.contains("is not the one"), "{}", error);
+
+    let error = open(&path, None).err().unwrap();
+    assert!(error.contains("protected by a master key"), "{}", error);
+
+    assert!(open(&path, Some(master("d4"))).is_ok());
+}
+
+#[test]
+fn rotation_reencrypts_every_row_and_prunes_the_retired_key() {
+    let path = database_path("rotation");
+    let database = open(&path, Some(master("f6"))).unwrap();
+    let api_keys = ["first-api-key", "second-api-key", "third-api-key"];
+    let hashes: Vec<String> = api_keys
+        .iter()
+        .map(|api_key| block_on(database.save_api_key(api_key)).unwrap())
+        .collect();
+
+    let conn = Connection::open(&path).unwrap();
+    let active = |conn: &Connection| -> i64 {
+        conn.query_row("SELECT id FROM data_keys WHERE active", [], |row| row.get(0)).unwrap()
+    };
+    let retired = active(&conn);
+
+    let report = block_on(database.rotate_data_key()).unwrap();
+    assert_ne!(report.data_key_id, retired);
+    assert_eq!(report.data_key_id, active(&conn));
+    assert_eq!(report.reencrypted, api_keys.len());
+    assert_eq!(report.remaining, 0);
+    assert_eq!(report.pruned_keys, 1);
+
+    let (data_keys, rows_on_other_keys): (i64, i64) = conn
+        .query_row(
+            "SELECT (SELECT COUNT(*) FROM data_keys), (SELECT COUNT(*) FROM api_keys WHERE data_key_id IS NOT ?)",
+            [report.data_key_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!((data_keys, rows_on_other_keys), (1, 0));
+
+    drop(database);
+    let database = open(&path, Some(master("f6"))).unwrap();
+    for (api_key, hash) in api_keys.iter().zip(&hashes) {
+        assert_eq!(&block_on(database.get_api_key(hash)).unwrap(), api_key);
+    }
+}
+
+#[test]
+fn new_database_needs_a_master_key_or_an_explicit_opt_out() {
+    let path = database_path("opt-out");
+
+    let error = open(&path, None).err().unwrap();
+    assert!(error.contains(ALLOW_UNWRAPPED_DATA_KEY_VAR), "{}", error);
+
+    std::env::set_var(ALLOW_UNWRAPPED_DATA_KEY_VAR, "true");
+    let opened = open(&path, None);
+    std::env::remove_var(ALLOW_UNWRAPPED_DATA_KEY_VAR);
+    let hash = block_on(opened.unwrap().save_api_key("unwrapped-api-key")).unwrap();
+
+    // Setting a master key later wraps the key that was created in the clear.
+    let database = open(&path, Some(master("a7"))).unwrap();
+    assert_eq!(block_on(database.get_api_key(&hash)).unwrap(), "unwrapped-api-key");
+    let unwrapped: i64 = Connection::open(&path)
+        .unwrap()
+        .query_row("SELECT COUNT(*) FROM data_keys WHERE wrap_nonce IS NULL", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(unwrapped, 0);
+}
@@ -0,0 +1,100 @@
+#![cfg(feature = "ssr")]
+
+mod common;
+
+use serde_json::json;
+use torbox_companion::automation::history::{self, ActionTally, ItemHistory, RuleTally};
+use torbox_companion::automation::items::DownloadItem;
+use torbox_companion::automation::types::{ActionType, CooldownConfig};
+
+const HOUR: i64 = 3600;
+const NOW: i64 = 1_768_564_800;
+
+fn torrent(id: i32, hash: &str) -> DownloadItem {
+    common::torrent(id, json!({"hash": hash}))
+}
+
+/// History in which the rule acted on download `id` `runs` times, last `hours_ago`.
+fn rule_history(id: i32, hash: Option<&str>, runs: i64, hours_ago: i64) -> ItemHistory {
+    let tally = RuleTally { download_hash: hash.map(str::to_string), runs, last_at: NOW - hours_ago * HOUR };
+    ItemHistory::new(vec![], vec![(id, tally)])
+}
+
+fn cooldown(hours: Option<f64>, max_actions: Option<u32>) -> CooldownConfig {
+    CooldownConfig { hours, max_actions }
+}
+
+#[test]
+fn hours_keep_the_rule_off_recent_downloads() {
+    let item = torrent(1, "aaa");
+    let cooldown = cooldown(Some(6.0), None);
+
+    assert!(rule_history(1, Some("aaa"), 1, 5).on_cooldown(&cooldown, &item, NOW));
+    assert!(!rule_history(1, Some("aaa"), 1, 6).on_cooldown(&cooldown, &item, NOW));
+    assert!(!rule_history(1, Some("aaa"), 5, 24).on_cooldown(&cooldown, &item, NOW));
+}
+
+#[test]
+fn max_actions_counts_every_run() {
+    let item = torrent(1, "aaa");
+    let cooldown = cooldown(None, Some(3));
+
+    assert!(!rule_history(1, Some("aaa"), 2, 1000).on_cooldown(&cooldown, &item, NOW));
+    assert!(rule_history(1, Some("aaa"), 3, 1000).on_cooldown(&cooldown, &item, NOW));
+
+    // Runs recorded with and without a hash add up.
+    let history = ItemHistory::new(
+        vec![],
+        vec![
+            (1, RuleTally { download_hash: Some("aaa".to_string()), runs: 2, last_at: NOW - 1000 * HOUR }),
+            (1, RuleTally { download_hash: None, runs: 1, last_at: NOW - 900 * HOUR }),
+        ],
+    );
+    assert!(history.on_cooldown(&cooldown, &item, NOW));
+}
+
+#[test]
+fn either_limit_is_enough() {
+    let item = torrent(1, "aaa");
+    let cooldown = cooldown(Some(6.0), Some(3));
+
+    assert!(rule_history(1, Some("aaa"), 1, 1).on_cooldown(&cooldown, &item, NOW));
+    assert!(rule_history(1, Some("aaa"), 3, 100).on_cooldown(&cooldown, &item, NOW));
+    assert!(!rule_history(1, Some("aaa"), 2, 100).on_cooldown(&cooldown, &item, NOW));
+}
+
+#[test]
+fn reused_ids_with_another_hash_are_not_on_cooldown() {
+    let cooldown = cooldown(Some(6.0), Some(1));
+
+    assert!(!rule_history(1, Some("old"), 5, 1).on_cooldown(&cooldown, &torrent(1, "new"), NOW));
+    // Without a recorded hash the id alone decides.
+    assert!(rule_history(1, None, 1, 1).on_cooldown(&cooldown, &torrent(1, "new"), NOW));
+    assert!(!rule_history(2, None, 1, 1).on_cooldown(&cooldown, &torrent(1, "new"), NOW));
+}
+
+#[test]
+fn downloads_without_history_are_not_on_cooldown() {
+    let cooldown = cooldown(Some(6.0), Some(1));
+
+    assert!(!ItemHistory::default().on_cooldown(&cooldown, &torrent(1, "aaa"), NOW));
+
+    // Actions of other rules do not count towards this rule's cooldown.
+    let others = ItemHistory::new(
+        vec![(1, ActionTally { download_hash: Some("aaa".to_string()), action_type: ActionType::Stop, count: 4, last_at: NOW })],
+        vec![],
+    );
+    assert!(!others.on_cooldown(&cooldown, &torrent(1, "aaa"), NOW));
+    assert_eq!(others.action_count(&torrent(1, "aaa"), Some(&ActionType::Stop)), 4);
+    assert_eq!(others.last_action_at(&torrent(1, "aaa")), Some(NOW));
+}
+
+#[test]
+fn validate_cooldown_needs_a_sensible_limit() {
+    assert!(history::validate_cooldown(&cooldown(Some(12.0), None)).is_ok());
+    assert!(history::validate_cooldown(&cooldown(None, Some(2))).is_ok());
+    assert!(history::validate_cooldown(&cooldown(None, None)).is_err());
+    assert!(history::validate_cooldown(&cooldown(Some(0.0), None)).is_err());
+    assert!(history::validate_cooldown(&cooldown(Some(f64::NAN), None)).is_err());
+    assert!(history::validate_cooldown(&cooldown(None, Some(0))).is_err());
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "ssr")]
+
+use torbox_companion::automation::logs::LogExportFormat;
+use torbox_companion::automation::types::{DownloadType, ExecutionLog, ProcessedItem};
+
+fn log(id: i64, rule_name: &str, processed_items: Option<Vec<ProcessedItem>>) -> ExecutionLog {
+    ExecutionLog {
+        id: Some(id),
+        rule_id: 3,
+        rule_name: rule_name.to_string(),
+        api_key_hash: "fixture-hash".to_string(),
+        execution_type: "scheduled".to_string(),
+        items_processed: processed_items.as_ref().map_or(0, |items| items.len() as i32),
+        total_items: Some(10),
+        success: true,
+        error_message: None,
+        processed_items,
+        executed_at: Some("2026-01-16 12:00:00".to_string()),
+        partial: None,
+        rule_revision: Some(1),
+    }
+}
+
+fn item(id: i32, name: &str, error: Option<&str>) -> ProcessedItem {
+    ProcessedItem {
+        id,
+        download_type: Some(DownloadType::Torrent),
+        name: name.to_string(),
+        action: "Delete".to_string(),
+        success: error.is_none(),
+        error: error.map(str::to_string),
+        steps: vec![],
+        size: Some(1024),
+        hash: None,
+    }
+}
+
+fn rows(csv: &str) -> Vec<&str> {
+    assert!(csv.ends_with("\r\n"), "{:?}", csv);
+    csv.trim_end_matches("\r\n").split("\r\n").collect()
+}
+
+#[test]
+fn one_row_per_processed_item() {
+    let logs = [log(1, "Cleanup", Some(vec![item(10, "first", None), item(11, "second", None)]))];
+    let csv = LogExportFormat::Csv.serialize(&logs).unwrap();
+    let rows = rows(&csv);
+
+    assert_eq!(rows.len(), 3);
+    assert!(rows[0].starts_with("log_id,executed_at,rule_id,"));
+    assert_eq!(
+        rows[1],
+        "1,2026-01-16 12:00:00,3,Cleanup,scheduled,success,2,10,,10,torrent,first,1024,Delete,true,"
+    );
+    assert!(rows[2].starts_with("1,2026-01-16 12:00:00,3,Cleanup,"));
+    assert!(rows[2].contains(",11,torrent,second,"));
+}
+
+#[test]
+fn runs_without_items_leave_the_item_columns_empty() {
+    let mut failed = log(2, "Cleanup", None);
+    failed.success = false;
+    failed.error_message = Some("API unavailable".to_string());
+    let csv = LogExportFormat::Csv.serialize(&[failed]).unwrap();
+    let rows = rows(&csv);
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1], "2,2026-01-16 12:00:00,3,Cleanup,scheduled,failed,0,10,API unavailable,,,,,,,");
+    assert_eq!(rows[1].split(',').count(), rows[0].split(',').count());
+}
+
+#[test]
+fn fields_with_separators_quotes_or_newlines_are_quoted() {
+    let logs = [log(
+        3,
+        "Cleanup, nightly",
+        Some(vec![item(12, "The \"Best\" Show", Some("line one\nline two"))]),
+    )];
+    let csv = LogExportFormat::Csv.serialize(&logs).unwrap();
+
+    assert!(csv.contains(",\"Cleanup, nightly\","), "{}", csv);
+    assert!(csv.contains(",\"The \"\"Best\"\" Show\","), "{}", csv);
+    assert!(csv.contains(",false,\"line one\nline two\"\r\n"), "{}", csv);
+    // Plain fields are left as they are.
+    assert!(csv.contains(",scheduled,success,"), "{}", csv);
+}
+
+#[test]
+fn export_formats_are_looked_up_by_name() {
+    assert_eq!(LogExportFormat::from_name("CSV").unwrap(), LogExportFormat::Csv);
+    assert_eq!(LogExportFormat::from_name("json").unwrap(), LogExportFormat::Json);
+    assert!(LogExportFormat::from_name("xlsx").is_err());
+}
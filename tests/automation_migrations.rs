@@ -0,0 +1,166 @@
+#![cfg(feature = "ssr")]
+
+use rusqlite::Connection;
+use std::collections::{BTreeMap, BTreeSet};
+use torbox_companion::automation::migrations::{self, MIGRATIONS};
+
+const FIXTURES: [(&str, &str); 5] = [
+    ("v0_original", include_str!("fixtures/automation_db/v0_original.sql")),
+    ("v0_log_details", include_str!("fixtures/automation_db/v0_log_details.sql")),
+    ("v0_download_types", include_str!("fixtures/automation_db/v0_download_types.sql")),
+    ("v0_notifications", include_str!("fixtures/automation_db/v0_notifications.sql")),
+    ("v0_transfers", include_str!("fixtures/automation_db/v0_transfers.sql")),
+];
+
+/// Columns of every table and the set of indexes. Column order is ignored because
+/// `ALTER TABLE ADD COLUMN` always appends.
+fn schema(conn: &Connection) -> (BTreeMap<String, BTreeSet<String>>, BTreeSet<String>) {
+    let mut tables = BTreeMap::new();
+    let names: Vec<String> = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    for name in names {
+        let columns: BTreeSet<String> = conn
+            .prepare("SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?)")
+            .unwrap()
+            .query_map([&name], |row| {
+                Ok(format!(
+                    "{} {} notnull={} default={:?} pk={}",
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, i64>(4)?,
+                ))
+            })
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        tables.insert(name, columns);
+    }
+
+    let indexes = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'index' AND name NOT LIKE 'sqlite_%'")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    (tables, indexes)
+}
+
+fn fresh_database() -> Connection {
+    let mut conn = Connection::open_in_memory().unwrap();
+    migrations::run(&mut conn).unwrap();
+    conn
+}
+
+fn recorded_versions(conn: &Connection) -> Vec<u32> {
+    conn.prepare("SELECT version FROM schema_version ORDER BY version")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+#[test]
+fn migration_versions_are_sequential() {
+    for (index, migration) in MIGRATIONS.iter().enumerate() {
+        assert_eq!(migration.version as usize, index + 1, "{}", migration.description);
+    }
+}
+
+#[test]
+fn fresh_database_reaches_latest_version() {
+    let conn = fresh_database();
+    assert_eq!(migrations::current_version(&conn).unwrap(), migrations::latest_version());
+    assert_eq!(recorded_versions(&conn), (1..=migrations::latest_version()).collect::<Vec<_>>());
+}
+
+#[test]
+fn fixtures_upgrade_to_current_schema() {
+    let expected = schema(&fresh_database());
+
+    for (name, fixture) in FIXTURES {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(fixture).unwrap();
+
+        let previous = migrations::run(&mut conn).unwrap_or_else(|e| panic!("{}: {}", name, e));
+        assert_eq!(previous, 0, "{}", name);
+        assert_eq!(migrations::current_version(&conn).unwrap(), migrations::latest_version(), "{}", name);
+        assert_eq!(schema(&conn), expected, "{}", name);
+
+        let (rule_name, log_count): (String, i64) = conn
+            .query_row(
+                "SELECT name, (SELECT COUNT(*) FROM rule_execution_log) FROM automation_rules WHERE id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(rule_name, "Fixture rule", "{}", name);
+        assert_eq!(log_count, 1, "{}", name);
+    }
+}
+
+#[test]
+fn upgrade_keeps_existing_column_values() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(FIXTURES[4].1).unwrap();
+    migrations::run(&mut conn).unwrap();
+
+    let download_types: Option<String> = conn
+        .query_row("SELECT download_types FROM automation_rules WHERE id = 1", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(download_types.as_deref(), Some("[\"torrents\",\"usenet\"]"));
+
+    let (total_items, status): (Option<i64>, String) = conn
+        .query_row(
+            "SELECT (SELECT total_items FROM rule_execution_log), status FROM transfer_jobs WHERE job_id = 7",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(total_items, Some(3));
+    assert_eq!(status, "pending");
+
+    let (data_key_id, cipher_version, key_length): (Option<i64>, i64, i64) = conn
+        .query_row(
+            "SELECT data_key_id, cipher_version, (SELECT length(key_material) FROM data_keys WHERE active)
+             FROM api_keys WHERE api_key_hash = 'fixture-hash'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .unwrap();
+    assert_eq!(data_key_id, Some(1));
+    assert_eq!(cipher_version, 0);
+    assert_eq!(key_length, 32);
+}
+
+#[test]
+fn running_twice_is_a_no_op() {
+    let mut conn = fresh_database();
+    let before = schema(&conn);
+
+    assert_eq!(migrations::run(&mut conn).unwrap(), migrations::latest_version());
+    assert_eq!(schema(&conn), before);
+    assert_eq!(recorded_versions(&conn).len(), MIGRATIONS.len());
+}
+
+#[test]
+fn refuses_database_from_newer_build() {
+    let mut conn = fresh_database();
+    conn.execute(
+        "INSERT INTO schema_version (version, description) VALUES (?, 'from the future')",
+        [migrations::latest_version() + 1],
+    )
+    .unwrap();
+
+    let error = migrations::run(&mut conn).unwrap_err();
+    assert!(error.contains("newer than this build"), "{}", error);
+}
@@ -0,0 +1,208 @@
+#![cfg(feature = "ssr")]
+
+use axum::body::Bytes;
+use axum::http::{HeaderMap, StatusCode, Uri};
+use axum::Router;
+use reqwest::Url;
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use torbox_companion::automation::notifier::{self, NotificationEvent};
+use torbox_companion::automation::types::{NotificationConfig, NotificationFormat, NotifyOn};
+
+#[derive(Debug, Clone)]
+struct Received {
+    path: String,
+    headers: HeaderMap,
+    body: serde_json::Value,
+}
+
+/// A webhook endpoint on a loopback port that records every request and
+/// answers with `status`.
+struct StandIn {
+    url: String,
+    received: Arc<Mutex<Vec<Received>>>,
+}
+
+impl StandIn {
+    async fn start(status: StatusCode) -> Self {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let app = Router::new().fallback({
+            let received = received.clone();
+            move |uri: Uri, headers: HeaderMap, body: Bytes| {
+                let received = received.clone();
+                async move {
+                    received.lock().unwrap().push(Received {
+                        path: uri.path().to_string(),
+                        headers,
+                        body: serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null),
+                    });
+                    status
+                }
+            }
+        });
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        Self { url, received }
+    }
+
+    fn only_request(&self) -> Received {
+        let received = self.received.lock().unwrap();
+        assert_eq!(received.len(), 1, "expected exactly one request, got {:?}", *received);
+        received[0].clone()
+    }
+}
+
+/// The stand-in listens on loopback, which notifications only reach when the
+/// operator allows private targets.
+fn run<F: Future>(test: F) -> F::Output {
+    std::env::set_var(notifier::ALLOW_PRIVATE_TARGETS_VAR, "true");
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(test)
+}
+
+fn config(url: String, format: NotificationFormat) -> NotificationConfig {
+    NotificationConfig {
+        url,
+        format,
+        notify_on: NotifyOn::AfterRun,
+        headers: HashMap::new(),
+        payload_template: None,
+    }
+}
+
+#[test]
+fn generic_webhook_posts_the_event_with_custom_headers() {
+    run(async {
+        let stand_in = StandIn::start(StatusCode::OK).await;
+        let mut config = config(format!("{}/hooks/torbox", stand_in.url), NotificationFormat::Generic);
+        config.headers.insert("X-Token".to_string(), "secret".to_string());
+
+        notifier::send(&config, &NotificationEvent::test("Clean up")).await.unwrap();
+
+        let request = stand_in.only_request();
+        assert_eq!(request.path, "/hooks/torbox");
+        assert_eq!(request.headers.get("x-token").unwrap(), "secret");
+        assert_eq!(request.body["event"], "test");
+        assert_eq!(request.body["rule_name"], "Clean up");
+        assert_eq!(request.body["items"][0]["name"], "Example download");
+    });
+}
+
+#[test]
+fn payload_template_placeholders_are_filled() {
+    run(async {
+        let stand_in = StandIn::start(StatusCode::OK).await;
+        let mut config = config(stand_in.url.clone(), NotificationFormat::Generic);
+        config.payload_template = Some(serde_json::json!({
+            "text": "{{rule_name}} finished with {{status}}",
+            "fields": ["{{items_processed}}/{{total_items}}"],
+            "count": 3,
+        }));
+
+        notifier::send(&config, &NotificationEvent::test("Clean up")).await.unwrap();
+
+        let request = stand_in.only_request();
+        assert_eq!(request.body, serde_json::json!({
+            "text": "Clean up finished with success",
+            "fields": ["1/1"],
+            "count": 3,
+        }));
+    });
+}
+
+#[test]
+fn discord_and_slack_get_their_message_fields() {
+    run(async {
+        let stand_in = StandIn::start(StatusCode::NO_CONTENT).await;
+        let event = NotificationEvent::test("Clean up");
+
+        notifier::send(&config(stand_in.url.clone(), NotificationFormat::Discord), &event).await.unwrap();
+        notifier::send(&config(stand_in.url.clone(), NotificationFormat::Slack), &event).await.unwrap();
+
+        let received = stand_in.received.lock().unwrap();
+        let discord = received[0].body["content"].as_str().unwrap();
+        let slack = received[1].body["text"].as_str().unwrap();
+        assert!(discord.starts_with("**Test notification for 'Clean up'**"), "{}", discord);
+        assert!(slack.starts_with("*Test notification for 'Clean up'*"), "{}", slack);
+        assert!(slack.contains("✓ Example download"), "{}", slack);
+    });
+}
+
+#[test]
+fn ntfy_posts_to_the_server_root_under_its_base_path() {
+    run(async {
+        let stand_in = StandIn::start(StatusCode::OK).await;
+        let event = NotificationEvent::test("Clean up");
+
+        notifier::send(&config(format!("{}/alerts", stand_in.url), NotificationFormat::Ntfy), &event).await.unwrap();
+        notifier::send(&config(format!("{}/ntfy/alerts", stand_in.url), NotificationFormat::Ntfy), &event).await.unwrap();
+
+        let received = stand_in.received.lock().unwrap();
+        assert_eq!(received[0].path, "/");
+        assert_eq!(received[1].path, "/ntfy/");
+        for request in received.iter() {
+            assert_eq!(request.body["topic"], "alerts");
+            assert_eq!(request.body["priority"], 3);
+        }
+    });
+}
+
+#[test]
+fn error_statuses_fail_the_send() {
+    run(async {
+        let stand_in = StandIn::start(StatusCode::INTERNAL_SERVER_ERROR).await;
+
+        let error = notifier::send(&config(stand_in.url.clone(), NotificationFormat::Gotify), &NotificationEvent::test("Clean up"))
+            .await
+            .unwrap_err();
+
+        assert!(error.contains("500"), "{}", error);
+        assert_eq!(stand_in.only_request().body["priority"], 5);
+    });
+}
+
+#[test]
+fn redirects_are_not_followed() {
+    run(async {
+        let stand_in = StandIn::start(StatusCode::TEMPORARY_REDIRECT).await;
+
+        let error = notifier::send(&config(stand_in.url.clone(), NotificationFormat::Generic), &NotificationEvent::test("Clean up"))
+            .await
+            .unwrap_err();
+
+        assert!(error.contains("307"), "{}", error);
+        stand_in.only_request();
+    });
+}
+
+#[test]
+fn private_targets_are_refused_unless_allowed() {
+    run(async {
+        for target in ["http://127.0.0.1:8080/hook", "http://localhost/hook", "http://10.0.0.5/hook", "http://169.254.169.254/latest", "http://[::1]/hook"] {
+            let url = Url::parse(target).unwrap();
+            let error = notifier::resolve_target(&url, false).await.unwrap_err();
+            assert!(error.contains(notifier::ALLOW_PRIVATE_TARGETS_VAR), "{}: {}", target, error);
+            assert!(notifier::resolve_target(&url, true).await.is_ok(), "{}", target);
+        }
+    });
+}
+
+#[test]
+fn private_address_ranges() {
+    let private = ["127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "100.64.0.1", "0.0.0.0", "::1", "fd00::1", "fe80::1", "::ffff:192.168.1.1"];
+    let public = ["1.1.1.1", "100.128.0.1", "2606:4700:4700::1111", "::ffff:8.8.8.8"];
+
+    for ip in private {
+        assert!(notifier::is_private_address(ip.parse::<IpAddr>().unwrap()), "{}", ip);
+    }
+    for ip in public {
+        assert!(!notifier::is_private_address(ip.parse::<IpAddr>().unwrap()), "{}", ip);
+    }
+}
@@ -0,0 +1,135 @@
+#![cfg(feature = "ssr")]
+
+mod common;
+
+use serde_json::json;
+use torbox_companion::automation::items::DownloadItem;
+use torbox_companion::automation::quota;
+use torbox_companion::automation::types::{QuotaConfig, QuotaSortKey, QuotaTarget};
+
+const GB: i64 = 1024 * 1024 * 1024;
+
+/// A finished torrent of `size_gb`, added on day `day` of January 2026.
+fn torrent(id: i32, size_gb: i64, day: u32, ratio: f32) -> DownloadItem {
+    let created_at = format!("2026-01-{:02}T12:00:00Z", day);
+    common::torrent(
+        id,
+        json!({"size": size_gb * GB, "created_at": created_at, "updated_at": created_at, "ratio": ratio}),
+    )
+}
+
+fn library() -> Vec<DownloadItem> {
+    vec![
+        torrent(1, 40, 5, 2.0),
+        torrent(2, 10, 1, 0.5),
+        torrent(3, 30, 3, 0.1),
+        torrent(4, 20, 2, 1.0),
+    ]
+}
+
+fn config(sort_by: QuotaSortKey, target: QuotaTarget, max_items_per_run: Option<u32>) -> QuotaConfig {
+    QuotaConfig { sort_by, target, max_items_per_run }
+}
+
+fn picked(selection: &quota::QuotaSelection) -> Vec<i32> {
+    selection.items.iter().map(|item| item.id()).collect()
+}
+
+#[test]
+fn picks_the_fewest_items_in_sort_order() {
+    let all = library();
+
+    // 100 GB in total; the two oldest (10 + 20 GB) get it down to 70 GB.
+    let selection = quota::select(
+        &config(QuotaSortKey::Age, QuotaTarget::TotalSize { gb: 75.0 }, None),
+        &all,
+        all.iter().collect(),
+    );
+    assert_eq!(picked(&selection), vec![2, 4]);
+    assert_eq!(selection.status.total_size, 100 * GB);
+    assert_eq!(selection.status.projected_size, 70 * GB);
+    assert_eq!(selection.status.projected_count, 2);
+    assert!(selection.status.target_reached);
+    assert!(!selection.status.capped);
+
+    // Largest first, one 40 GB download is enough for the same target.
+    let selection = quota::select(
+        &config(QuotaSortKey::Size, QuotaTarget::TotalSize { gb: 75.0 }, None),
+        &all,
+        all.iter().collect(),
+    );
+    assert_eq!(picked(&selection), vec![1]);
+
+    let selection = quota::select(
+        &config(QuotaSortKey::Ratio, QuotaTarget::ItemCount { count: 2 }, None),
+        &all,
+        all.iter().collect(),
+    );
+    assert_eq!(picked(&selection), vec![3, 2]);
+    assert_eq!(selection.status.projected_count, 2);
+}
+
+#[test]
+fn only_matching_items_are_picked_but_all_count_towards_the_total() {
+    let all = library();
+    let matching = all.iter().filter(|item| item.id() != 2).collect();
+
+    let selection = quota::select(
+        &config(QuotaSortKey::Age, QuotaTarget::TotalSize { gb: 75.0 }, None),
+        &all,
+        matching,
+    );
+    assert_eq!(picked(&selection), vec![4, 3]);
+    assert_eq!(selection.status.matching, 3);
+    assert_eq!(selection.status.projected_size, 50 * GB);
+}
+
+#[test]
+fn nothing_is_picked_when_already_under_the_target() {
+    let all = library();
+
+    for target in [QuotaTarget::TotalSize { gb: 100.0 }, QuotaTarget::ItemCount { count: 4 }] {
+        let selection = quota::select(&config(QuotaSortKey::Age, target, None), &all, all.iter().collect());
+        assert!(selection.items.is_empty());
+        assert!(selection.status.target_reached);
+        assert_eq!(selection.status.projected_size, selection.status.total_size);
+    }
+}
+
+#[test]
+fn max_items_per_run_caps_the_selection() {
+    let all = library();
+
+    let selection = quota::select(
+        &config(QuotaSortKey::Age, QuotaTarget::TotalSize { gb: 20.0 }, Some(2)),
+        &all,
+        all.iter().collect(),
+    );
+    assert_eq!(picked(&selection), vec![2, 4]);
+    assert!(selection.status.capped);
+    assert!(!selection.status.target_reached);
+
+    // A cap that is never hit is not reported.
+    let selection = quota::select(
+        &config(QuotaSortKey::Age, QuotaTarget::TotalSize { gb: 75.0 }, Some(2)),
+        &all,
+        all.iter().collect(),
+    );
+    assert!(!selection.status.capped);
+    assert!(selection.status.target_reached);
+}
+
+#[test]
+fn target_out_of_reach_picks_every_matching_item() {
+    let all = library();
+    let matching = all.iter().filter(|item| item.id() == 2).collect();
+
+    let selection = quota::select(
+        &config(QuotaSortKey::Age, QuotaTarget::TotalSize { gb: 10.0 }, None),
+        &all,
+        matching,
+    );
+    assert_eq!(picked(&selection), vec![2]);
+    assert!(!selection.status.target_reached);
+    assert!(!selection.status.capped);
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "ssr")]
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use chrono_tz::Tz;
+use torbox_companion::automation::schedule::{self, LocalSchedule};
+use torbox_companion::automation::types::TriggerConfig;
+
+fn local_schedule(expression: &str, timezone: Tz) -> LocalSchedule {
+    let (job, local) = schedule::job_cron(expression, timezone).unwrap();
+    assert_eq!(job, "0 * * * * *");
+    local.unwrap()
+}
+
+/// The minute ticks in `[from, from + hours)` on which `schedule` is due.
+fn due_ticks(schedule: &LocalSchedule, from: DateTime<Utc>, hours: i64) -> Vec<DateTime<Utc>> {
+    (0..hours * 60)
+        .map(|minute| from + Duration::minutes(minute))
+        .filter(|tick| schedule.is_due(*tick))
+        .collect()
+}
+
+fn utc(month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, month, day, hour, minute, 0).unwrap()
+}
+
+#[test]
+fn parse_cron_names_the_field_at_fault() {
+    let cases = [
+        ("61 * * * * *", "Invalid seconds field '61'"),
+        ("0 60 * * * *", "Invalid minutes field '60'"),
+        ("0 0 25 * * *", "Invalid hours field '25'"),
+        ("0 0 0 32 * *", "Invalid day of month field '32'"),
+        ("0 0 0 * 13 *", "Invalid month field '13'"),
+        ("0 0 0 * * FUNDAY", "Invalid day of week field 'FUNDAY'"),
+    ];
+    for (expression, expected) in cases {
+        let error = schedule::parse_cron(expression).unwrap_err();
+        assert!(error.starts_with(expected), "{}: {}", expression, error);
+    }
+}
+
+#[test]
+fn parse_cron_rejects_wrong_field_counts_and_impossible_dates() {
+    let error = schedule::parse_cron("0 * * * *").unwrap_err();
+    assert!(error.contains("found 5"), "{}", error);
+    assert!(error.contains("leading seconds field"), "{}", error);
+
+    let error = schedule::parse_cron("0 0 0 0 * * * *").unwrap_err();
+    assert!(error.contains("found 8"), "{}", error);
+    assert!(!error.contains("leading seconds field"), "{}", error);
+
+    assert!(schedule::parse_cron("   ").unwrap_err().contains("cannot be empty"));
+
+    let error = schedule::parse_cron("0 0 0 30 2 *").unwrap_err();
+    assert!(error.contains("never fires"), "{}", error);
+
+    assert!(schedule::parse_cron("0 */15 9-17 * * MON-FRI").is_ok());
+}
+
+#[test]
+fn utc_expressions_go_to_the_scheduler_unchanged() {
+    let (job, local) = schedule::job_cron("0 30 2 * * *", Tz::UTC).unwrap();
+    assert_eq!(job, "0 30 2 * * *");
+    assert!(local.is_none());
+
+    let (job, _) = schedule::job_cron("15 30 2 * * *", Tz::Europe__Berlin).unwrap();
+    assert_eq!(job, "15 * * * * *");
+}
+
+#[test]
+fn is_due_follows_local_time() {
+    let schedule = local_schedule("0 30 9 * * *", Tz::America__New_York);
+
+    // 09:30 in New York is 14:30 UTC in winter and 13:30 UTC in summer.
+    assert_eq!(due_ticks(&schedule, utc(1, 15, 0, 0), 24), vec![utc(1, 15, 14, 30)]);
+    assert_eq!(due_ticks(&schedule, utc(7, 15, 0, 0), 24), vec![utc(7, 15, 13, 30)]);
+}
+
+#[test]
+fn is_due_allows_a_late_tick() {
+    let schedule = local_schedule("0 0 12 * * *", Tz::Europe__Berlin);
+    let noon = utc(1, 15, 11, 0);
+
+    assert!(schedule.is_due(noon));
+    assert!(schedule.is_due(noon + Duration::seconds(1)));
+    assert!(!schedule.is_due(noon + Duration::seconds(2)));
+    assert!(!schedule.is_due(noon - Duration::seconds(1)));
+}
+
+#[test]
+fn skipped_local_time_does_not_fire() {
+    // Berlin skips from 02:00 to 03:00 on 29 March 2026.
+    let schedule = local_schedule("0 30 2 * * *", Tz::Europe__Berlin);
+
+    assert!(due_ticks(&schedule, utc(3, 28, 22, 0), 24).is_empty());
+    assert_eq!(due_ticks(&schedule, utc(3, 29, 22, 0), 24), vec![utc(3, 30, 0, 30)]);
+}
+
+#[test]
+fn repeated_local_time_fires_twice() {
+    // Berlin goes from 03:00 back to 02:00 on 25 October 2026.
+    let schedule = local_schedule("0 30 2 * * *", Tz::Europe__Berlin);
+
+    assert_eq!(
+        due_ticks(&schedule, utc(10, 24, 22, 0), 24),
+        vec![utc(10, 25, 0, 30), utc(10, 25, 1, 30)]
+    );
+}
+
+#[test]
+fn next_runs_are_in_the_rule_timezone() {
+    let trigger = TriggerConfig::Cron {
+        expression: "0 0 8 * * *".to_string(),
+        timezone: Some("Asia/Tokyo".to_string()),
+    };
+    let (timezone, runs) = schedule::next_runs(&trigger, utc(1, 15, 0, 0), 2).unwrap();
+
+    assert_eq!(timezone, Tz::Asia__Tokyo);
+    let runs: Vec<DateTime<Utc>> = runs.iter().map(|run| run.with_timezone(&Utc)).collect();
+    assert_eq!(runs, vec![utc(1, 15, 23, 0), utc(1, 16, 23, 0)]);
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "ssr")]
+
+use chrono::{DateTime, TimeZone, Utc};
+use chrono_tz::Europe::Berlin;
+use torbox_companion::automation::types::{ScheduleWindow, TimeRange, Weekday};
+use torbox_companion::automation::window;
+
+fn window(days: Vec<Weekday>, ranges: &[(&str, &str)]) -> ScheduleWindow {
+    ScheduleWindow {
+        days,
+        ranges: ranges
+            .iter()
+            .map(|(start, end)| TimeRange { start: start.to_string(), end: end.to_string() })
+            .collect(),
+        timezone: "Europe/Berlin".to_string(),
+    }
+}
+
+/// Berlin wall-clock time on a day of January 2026; the 16th is a Friday.
+fn berlin(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Berlin.with_ymd_and_hms(2026, 1, day, hour, minute, 0).unwrap().with_timezone(&Utc)
+}
+
+#[test]
+fn range_within_a_day() {
+    let window = window(vec![], &[("09:00", "17:30")]);
+
+    assert!(!window::is_open(&window, berlin(16, 8, 59)).unwrap());
+    assert!(window::is_open(&window, berlin(16, 9, 0)).unwrap());
+    assert!(window::is_open(&window, berlin(16, 17, 29)).unwrap());
+    assert!(!window::is_open(&window, berlin(16, 17, 30)).unwrap());
+}
+
+#[test]
+fn times_are_read_in_the_window_timezone() {
+    let window = window(vec![], &[("09:00", "10:00")]);
+
+    // 09:30 in Berlin is 08:30 UTC in winter.
+    assert!(window::is_open(&window, Utc.with_ymd_and_hms(2026, 1, 16, 8, 30, 0).unwrap()).unwrap());
+    assert!(!window::is_open(&window, Utc.with_ymd_and_hms(2026, 1, 16, 9, 30, 0).unwrap()).unwrap());
+}
+
+#[test]
+fn range_past_midnight_belongs_to_the_day_it_starts() {
+    let window = window(vec![Weekday::Friday], &[("22:00", "02:00")]);
+
+    assert!(!window::is_open(&window, berlin(16, 21, 59)).unwrap());
+    assert!(window::is_open(&window, berlin(16, 22, 0)).unwrap());
+    assert!(window::is_open(&window, berlin(16, 23, 59)).unwrap());
+    // Early Saturday still belongs to Friday's range.
+    assert!(window::is_open(&window, berlin(17, 0, 0)).unwrap());
+    assert!(window::is_open(&window, berlin(17, 1, 59)).unwrap());
+    assert!(!window::is_open(&window, berlin(17, 2, 0)).unwrap());
+    // Saturday's own evening is not in the window.
+    assert!(!window::is_open(&window, berlin(17, 23, 0)).unwrap());
+    // Early Friday belongs to Thursday, which is not in the window.
+    assert!(!window::is_open(&window, berlin(16, 1, 0)).unwrap());
+}
+
+#[test]
+fn days_without_ranges_are_open_all_day() {
+    let window = window(vec![Weekday::Saturday, Weekday::Sunday], &[]);
+
+    assert!(!window::is_open(&window, berlin(16, 23, 59)).unwrap());
+    assert!(window::is_open(&window, berlin(17, 0, 0)).unwrap());
+    assert!(window::is_open(&window, berlin(18, 23, 59)).unwrap());
+    assert!(!window::is_open(&window, berlin(19, 0, 0)).unwrap());
+}
+
+#[test]
+fn range_may_end_at_midnight() {
+    let window = window(vec![], &[("18:00", "24:00")]);
+
+    assert!(window::is_open(&window, berlin(16, 23, 59)).unwrap());
+    assert!(!window::is_open(&window, berlin(17, 0, 0)).unwrap());
+    assert!(window::validate(&window).is_ok());
+}
+
+#[test]
+fn validate_rejects_bad_windows() {
+    let cases = [
+        (window(vec![], &[]), "at least one day"),
+        (window(vec![], &[("10:00", "10:00")]), "the same"),
+        (window(vec![], &[("24:00", "02:00")]), "before 24:00"),
+        (window(vec![], &[("9am", "17:00")]), "Invalid time"),
+        (window(vec![], &[("10:60", "11:00")]), "Invalid time"),
+    ];
+    for (window, expected) in cases {
+        let error = window::validate(&window).unwrap_err();
+        assert!(error.contains(expected), "{}", error);
+    }
+
+    let mut unknown_zone = window(vec![Weekday::Monday], &[]);
+    unknown_zone.timezone = "Mars/Olympus_Mons".to_string();
+    assert!(window::validate(&unknown_zone).unwrap_err().contains("Unknown timezone"));
+}
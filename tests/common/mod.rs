@@ -0,0 +1,42 @@
+use serde_json::{json, Value};
+use torbox_companion::automation::items::DownloadItem;
+
+/// A finished, seeding torrent as TorBox lists it. `fields` replaces any of the
+/// defaults, so a test only spells out what it depends on.
+pub fn torrent(id: i32, fields: Value) -> DownloadItem {
+    let mut torrent = json!({
+        "id": id,
+        "auth_id": "fixture",
+        "server": 1,
+        "hash": format!("hash-{}", id),
+        "name": format!("Torrent {}", id),
+        "size": 1024,
+        "active": true,
+        "created_at": "2026-01-01T12:00:00Z",
+        "updated_at": "2026-01-01T12:00:00Z",
+        "download_state": "uploading",
+        "seeds": 0,
+        "peers": 0,
+        "ratio": 1.0,
+        "progress": 1.0,
+        "download_speed": 0,
+        "upload_speed": 0,
+        "eta": 0,
+        "torrent_file": false,
+        "download_present": true,
+        "availability": 1.0,
+        "download_finished": true,
+        "total_uploaded": 0,
+        "total_downloaded": 0,
+        "cached": true,
+        "owner": "fixture",
+        "seed_torrent": true,
+        "allow_zipped": true,
+        "long_term_seeding": false,
+        "private": false
+    });
+    if let (Value::Object(torrent), Value::Object(fields)) = (&mut torrent, fields) {
+        torrent.extend(fields);
+    }
+    DownloadItem::Torrent(serde_json::from_value(torrent).unwrap())
+}
@@ -0,0 +1,56 @@
+-- Layout before execution logs recorded processed items, totals or partial runs.
+CREATE TABLE server_key (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    encryption_key BLOB NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE api_keys (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    api_key_hash TEXT UNIQUE NOT NULL,
+    encrypted_api_key BLOB NOT NULL,
+    nonce BLOB NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    last_used_at DATETIME
+);
+
+CREATE TABLE automation_rules (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    api_key_hash TEXT NOT NULL,
+    name TEXT NOT NULL,
+    enabled BOOLEAN DEFAULT true,
+    trigger_config TEXT NOT NULL,
+    conditions TEXT NOT NULL,
+    action_config TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (api_key_hash) REFERENCES api_keys(api_key_hash)
+);
+
+CREATE TABLE rule_execution_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    rule_id INTEGER NOT NULL,
+    rule_name TEXT NOT NULL,
+    api_key_hash TEXT NOT NULL,
+    execution_type TEXT NOT NULL,
+    items_processed INTEGER DEFAULT 0,
+    success BOOLEAN DEFAULT true,
+    error_message TEXT,
+    executed_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (rule_id) REFERENCES automation_rules(id)
+);
+
+
+CREATE INDEX idx_rules_api_key_hash ON automation_rules(api_key_hash);
+CREATE INDEX idx_rules_enabled ON automation_rules(enabled);
+CREATE INDEX idx_logs_rule_id ON rule_execution_log(rule_id);
+CREATE INDEX idx_logs_api_key_hash ON rule_execution_log(api_key_hash);
+CREATE INDEX idx_logs_executed_at ON rule_execution_log(executed_at);
+
+
+INSERT INTO server_key (id, encryption_key) VALUES (1, zeroblob(32));
+INSERT INTO api_keys (api_key_hash, encrypted_api_key, nonce) VALUES ('fixture-hash', x'00', x'00');
+
+INSERT INTO automation_rules (api_key_hash, name, trigger_config, conditions, action_config) VALUES ('fixture-hash', 'Fixture rule', '{"type":"interval","interval_minutes":60}', '[]', '{"action_type":"StopSeeding"}');
+
+INSERT INTO rule_execution_log (rule_id, rule_name, api_key_hash, execution_type, items_processed) VALUES (1, 'Fixture rule', 'fixture-hash', 'scheduled', 2);